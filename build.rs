@@ -0,0 +1,8 @@
+// Generates the gRPC service from proto/scalper.proto. protoc comes from protoc-bin-vendored, so
+// building needs no system install.
+fn main() -> Result<(), Box<dyn std::error::Error>> {
+    std::env::set_var("PROTOC", protoc_bin_vendored::protoc_bin_path()?);
+    println!("cargo:rerun-if-changed=proto/scalper.proto");
+    tonic_build::configure().build_client(false).compile_protos(&["proto/scalper.proto"], &["proto"])?;
+    Ok(())
+}
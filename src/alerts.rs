@@ -0,0 +1,83 @@
+use std::collections::HashSet;
+use serde::{Deserialize, Serialize};
+
+// --- 🚨 ALERT RULES ---
+
+#[derive(Clone, Copy, Debug, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AlertCondition {
+    /// Price moves from one side of `level` to the other.
+    PriceCrosses { level: f64 },
+    /// Open position is down more than `pct` percent from entry.
+    DrawdownPct { pct: f64 },
+    /// No successful price tick for `secs` seconds.
+    NoTickSecs { secs: u64 },
+}
+
+impl AlertCondition {
+    pub fn validate(&self) -> Result<(), String> {
+        let ok = match *self {
+            AlertCondition::PriceCrosses { level } => level > 0.0,
+            AlertCondition::DrawdownPct { pct } => pct > 0.0 && pct < 100.0,
+            AlertCondition::NoTickSecs { secs } => secs > 0,
+        };
+        if ok { Ok(()) } else { Err(format!("Invalid alert condition: {:?}", self)) }
+    }
+}
+
+fn default_enabled() -> bool { true }
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct AlertRule {
+    #[serde(default)]
+    pub id: u32,
+    pub condition: AlertCondition,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+}
+
+/// Snapshot of the loop state that rules are evaluated against.
+pub struct AlertInputs {
+    pub price: Option<f64>,
+    pub unrealized_pct: f64,
+    pub secs_since_tick: u64,
+}
+
+/// Edge-triggered evaluation: a rule fires once when it becomes true and re-arms when it clears.
+#[derive(Default)]
+pub struct AlertEngine {
+    firing: HashSet<u32>,
+    last_price: Option<f64>,
+}
+
+impl AlertEngine {
+    pub fn evaluate(&mut self, rules: &[AlertRule], inputs: &AlertInputs) -> Vec<String> {
+        let mut fired = Vec::new();
+
+        for rule in rules.iter().filter(|r| r.enabled) {
+            let message = match rule.condition {
+                AlertCondition::PriceCrosses { level } => match (self.last_price, inputs.price) {
+                    (Some(prev), Some(now)) if prev < level && now >= level => Some(format!("Price crossed above ${:.2}", level)),
+                    (Some(prev), Some(now)) if prev > level && now <= level => Some(format!("Price crossed below ${:.2}", level)),
+                    _ => None,
+                },
+                AlertCondition::DrawdownPct { pct } => (inputs.unrealized_pct <= -pct)
+                    .then(|| format!("Position drawdown {:.2}% exceeds {:.2}%", inputs.unrealized_pct, pct)),
+                AlertCondition::NoTickSecs { secs } => (inputs.secs_since_tick >= secs)
+                    .then(|| format!("No price tick for {}s", inputs.secs_since_tick)),
+            };
+
+            match message {
+                // Crossings are discrete events, so they always fire
+                Some(msg) if matches!(rule.condition, AlertCondition::PriceCrosses { .. }) => fired.push(msg),
+                Some(msg) => {
+                    if self.firing.insert(rule.id) { fired.push(msg); }
+                },
+                None => { self.firing.remove(&rule.id); },
+            }
+        }
+
+        if inputs.price.is_some() { self.last_price = inputs.price; }
+        fired
+    }
+}
@@ -0,0 +1,137 @@
+use std::collections::HashMap;
+use std::io::Write;
+use std::sync::OnceLock;
+use axum::body::Bytes;
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+use axum::response::{IntoResponse, Response};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use sha2::{Digest, Sha256};
+
+// --- 🗜️ DASHBOARD ASSETS ---
+// Pages, styles and scripts are compiled in and compressed (brotli and gzip) once at startup, so a
+// request is a map lookup. Styles and scripts are served under content-hashed names and cached by
+// the browser for a year; a page references them by that name and is revalidated with its ETag,
+// so a new build is picked up on the next load and an unchanged one costs a 304.
+
+// (name, content type, body); pages refer to the others as {{name}}
+const SOURCES: [(&str, &str, &str); 11] = [
+    ("dashboard.css", "text/css; charset=utf-8", include_str!("assets/dashboard.css")),
+    ("dashboard.js", "text/javascript; charset=utf-8", include_str!("assets/dashboard.js")),
+    ("backtests.css", "text/css; charset=utf-8", include_str!("assets/backtests.css")),
+    ("backtests.js", "text/javascript; charset=utf-8", include_str!("assets/backtests.js")),
+    ("public.css", "text/css; charset=utf-8", include_str!("assets/public.css")),
+    ("public.js", "text/javascript; charset=utf-8", include_str!("assets/public.js")),
+    ("login.css", "text/css; charset=utf-8", include_str!("assets/login.css")),
+    ("dashboard.html", "text/html; charset=utf-8", include_str!("assets/dashboard.html")),
+    ("backtests.html", "text/html; charset=utf-8", include_str!("assets/backtests.html")),
+    ("public.html", "text/html; charset=utf-8", include_str!("assets/public.html")),
+    ("login.html", "text/html; charset=utf-8", include_str!("assets/login.html")),
+];
+const IMMUTABLE: &str = "public, max-age=31536000, immutable";
+
+struct Asset {
+    content_type: &'static str,
+    hashed: bool, // Served under /assets/ with a content-hashed name
+    identity: Bytes,
+    gzip: Bytes,
+    brotli: Bytes,
+    etag: String,
+}
+
+#[derive(Clone, Copy)]
+enum Encoding {
+    Identity,
+    Gzip,
+    Brotli,
+}
+
+static ASSETS: OnceLock<HashMap<String, Asset>> = OnceLock::new();
+
+fn compress(raw: &[u8]) -> (Bytes, Bytes) {
+    let mut gz = GzEncoder::new(Vec::new(), Compression::best());
+    let _ = gz.write_all(raw);
+    let gzip = gz.finish().unwrap_or_default();
+    let mut brotli = Vec::new();
+    {
+        let mut w = brotli::CompressorWriter::new(&mut brotli, 4096, 11, 22);
+        let _ = w.write_all(raw);
+    }
+    (Bytes::from(gzip), Bytes::from(brotli))
+}
+
+fn build() -> HashMap<String, Asset> {
+    let mut assets = HashMap::new();
+    let mut names: Vec<(String, String)> = Vec::new(); // (placeholder, hashed path), filled before the pages that use them
+    for (name, content_type, body) in SOURCES {
+        let page = content_type.starts_with("text/html");
+        let mut body = body.to_string();
+        for (placeholder, path) in &names {
+            body = body.replace(placeholder.as_str(), path);
+        }
+        let digest = hex::encode(&Sha256::digest(body.as_bytes())[..8]);
+        let (gzip, brotli) = compress(body.as_bytes());
+        let key = match name.rsplit_once('.') {
+            Some((stem, ext)) if !page => format!("{}.{}.{}", stem, digest, ext),
+            _ => name.to_string(),
+        };
+        if !page {
+            names.push((format!("{{{{{}}}}}", name), format!("/assets/{}", key)));
+        }
+        assets.insert(key, Asset { content_type, hashed: !page, identity: Bytes::from(body), gzip, brotli, etag: digest });
+    }
+    assets
+}
+
+/// Compresses everything up front; otherwise the first request does it.
+pub fn init() {
+    ASSETS.get_or_init(build);
+}
+
+fn accepted(headers: &HeaderMap) -> Encoding {
+    let offered = headers.get(header::ACCEPT_ENCODING).and_then(|v| v.to_str().ok()).unwrap_or("");
+    let accepts = |wanted: &str| offered.split(',').any(|part| {
+        let mut parts = part.trim().split(';');
+        parts.next() == Some(wanted) && !parts.any(|p| p.trim().replace(' ', "") == "q=0")
+    });
+    if accepts("br") { Encoding::Brotli } else if accepts("gzip") { Encoding::Gzip } else { Encoding::Identity }
+}
+
+fn respond(asset: &Asset, headers: &HeaderMap) -> Response {
+    let encoding = accepted(headers);
+    let (body, coding) = match encoding {
+        Encoding::Brotli => (asset.brotli.clone(), Some("br")),
+        Encoding::Gzip => (asset.gzip.clone(), Some("gzip")),
+        Encoding::Identity => (asset.identity.clone(), None),
+    };
+    // Each encoding is a different representation, so it gets its own tag
+    let etag = format!("\"{}-{}\"", asset.etag, coding.unwrap_or("id"));
+    let mut out = HeaderMap::new();
+    out.insert(header::ETAG, HeaderValue::from_str(&etag).unwrap());
+    out.insert(header::VARY, HeaderValue::from_static("Accept-Encoding"));
+    out.insert(header::CACHE_CONTROL, HeaderValue::from_static(if asset.hashed { IMMUTABLE } else { "no-cache" }));
+    if headers.get(header::IF_NONE_MATCH).is_some_and(|tag| tag.as_bytes() == etag.as_bytes()) {
+        return (StatusCode::NOT_MODIFIED, out).into_response();
+    }
+    out.insert(header::CONTENT_TYPE, HeaderValue::from_static(asset.content_type));
+    if let Some(coding) = coding {
+        out.insert(header::CONTENT_ENCODING, HeaderValue::from_static(coding));
+    }
+    (out, body).into_response()
+}
+
+/// A page by file name (e.g. "dashboard.html").
+pub fn page(name: &str, headers: &HeaderMap) -> Response {
+    match ASSETS.get_or_init(build).get(name) {
+        Some(asset) if !asset.hashed => respond(asset, headers),
+        _ => StatusCode::NOT_FOUND.into_response(),
+    }
+}
+
+/// A style or script by its hashed name, as linked from the pages.
+pub fn file(name: &str, headers: &HeaderMap) -> Response {
+    match ASSETS.get_or_init(build).get(name) {
+        Some(asset) if asset.hashed => respond(asset, headers),
+        _ => StatusCode::NOT_FOUND.into_response(),
+    }
+}
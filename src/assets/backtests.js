@@ -0,0 +1,115 @@
+const COLORS = ["#2196F3", "#4CAF50", "#FF9800", "#E91E63", "#9C27B0", "#00BCD4"];
+const PARAMS = ["rsi_buy", "rsi_sell", "rsi_crash", "trailing_stop_pct", "trade_capital", "hard_stop_pct", "break_even_pct", "max_hold_mins", "reentry_pct", "reentry_rsi"];
+const METRICS = ["candles", "trades", "win_rate", "net_pnl", "gross_pnl", "avg_trade", "max_drawdown"];
+let runs = [];
+let selected = new Set();
+
+// Times are shown in the [calendar] zone, read from /api/stats
+let utcOffsetMins = 0;
+function fmtTime(ts, withDate) {
+    const d = new Date(new Date(ts).getTime() + utcOffsetMins * 60000);
+    return withDate ? d.toLocaleString(undefined, { timeZone: "UTC" }) : d.toLocaleTimeString(undefined, { timeZone: "UTC" });
+}
+function fmt(v) { return Number.isInteger(v) ? v : v.toFixed(4); }
+function plClass(v) { return v >= 0 ? "pos" : "neg"; }
+
+async function loadRuns() {
+    try {
+        let stats = await fetch(window.location.origin + '/api/stats');
+        if (stats.ok) utcOffsetMins = (await stats.json()).utc_offset_mins || 0;
+        let res = await fetch(window.location.origin + '/api/backtests?t=' + Date.now());
+        runs = await res.json();
+        renderRuns();
+        renderCompare();
+    } catch (e) { console.error("Backtests Error:", e); }
+}
+
+async function runBacktest() {
+    const body = {};
+    PARAMS.forEach(p => { const v = document.getElementById(p).value; if (v !== "") body[p] = parseFloat(v); });
+    const days = document.getElementById('recorded_days').value;
+    if (days !== "") body.recorded_days = parseInt(days);
+    document.getElementById('run_status').innerText = "Running...";
+    try {
+        let res = await fetch(window.location.origin + '/api/backtests', {
+            method: "POST", headers: { "Content-Type": "application/json" }, body: JSON.stringify(body)
+        });
+        if (!res.ok) throw new Error(await res.text());
+        let run = await res.json();
+        selected.add(run.id);
+        document.getElementById('run_status').innerText = "Run #" + run.id + " complete";
+        await loadRuns();
+    } catch (e) { document.getElementById('run_status').innerText = "Failed: " + e.message; }
+}
+
+function toggleRun(id) {
+    if (selected.has(id)) selected.delete(id); else selected.add(id);
+    renderCompare();
+}
+
+function renderRuns() {
+    let html = "";
+    runs.forEach(r => {
+        html += `<tr>
+            <td><input type="checkbox" style="width:auto" ${selected.has(r.id) ? "checked" : ""} onchange="toggleRun(${r.id})"></td>
+            <td>#${r.id}</td>
+            <td>${fmtTime(r.created, true)}</td>
+            <td>${r.metrics.trades}</td>
+            <td class="${plClass(r.metrics.net_pnl)}">$${r.metrics.net_pnl.toFixed(2)}</td>
+            <td><a href="#" onclick="showTrades(${r.id}); return false;">trades</a></td>
+        </tr>`;
+    });
+    document.getElementById('runs_body').innerHTML = html || `<tr><td colspan="6">No runs yet</td></tr>`;
+}
+
+function renderCompare() {
+    const chosen = runs.filter(r => selected.has(r.id));
+    let html = "<tr><th></th>" + chosen.map((r, i) => `<th style="color:${COLORS[i % COLORS.length]}">#${r.id}</th>`).join("") + "</tr>";
+    PARAMS.forEach(p => { html += `<tr><td class="label">${p}</td>` + chosen.map(r => `<td>${fmt(r.params[p])}</td>`).join("") + "</tr>"; });
+    METRICS.forEach(m => { html += `<tr><td class="label">${m}</td>` + chosen.map(r => `<td>${fmt(r.metrics[m])}</td>`).join("") + "</tr>"; });
+    document.getElementById('compare').innerHTML = chosen.length ? html : "<tr><td>Select runs to compare</td></tr>";
+    drawEquity(chosen);
+}
+
+function drawEquity(chosen) {
+    const canvas = document.getElementById('equity');
+    const ctx = canvas.getContext('2d');
+    canvas.width = canvas.clientWidth;
+    ctx.clearRect(0, 0, canvas.width, canvas.height);
+    const points = chosen.flatMap(r => r.equity);
+    if (!points.length) return;
+
+    const minT = Math.min(...points.map(p => p[0])), maxT = Math.max(...points.map(p => p[0]));
+    const minV = Math.min(0, ...points.map(p => p[1])), maxV = Math.max(0, ...points.map(p => p[1]));
+    const x = t => (maxT === minT ? 0 : (t - minT) / (maxT - minT)) * (canvas.width - 10) + 5;
+    const y = v => canvas.height - 5 - (maxV === minV ? 0.5 : (v - minV) / (maxV - minV)) * (canvas.height - 10);
+
+    ctx.strokeStyle = "#333";
+    ctx.beginPath(); ctx.moveTo(0, y(0)); ctx.lineTo(canvas.width, y(0)); ctx.stroke();
+    chosen.forEach((r, i) => {
+        ctx.strokeStyle = COLORS[i % COLORS.length];
+        ctx.beginPath();
+        r.equity.forEach((p, j) => { if (j === 0) ctx.moveTo(x(p[0]), y(p[1])); else ctx.lineTo(x(p[0]), y(p[1])); });
+        ctx.stroke();
+    });
+}
+
+async function showTrades(id) {
+    try {
+        let res = await fetch(window.location.origin + '/api/backtests/' + id);
+        let run = await res.json();
+        let html = "";
+        run.trades.forEach(t => {
+            html += `<tr>
+                <td>${fmtTime(t.entry_time, true)}</td>
+                <td>${fmtTime(t.exit_time)}</td>
+                <td>$${t.entry_price.toFixed(2)}</td>
+                <td>$${t.exit_price.toFixed(2)}</td>
+                <td class="${plClass(t.profit)}">$${t.profit.toFixed(2)}</td>
+                <td>${t.reason}</td>
+            </tr>`;
+        });
+        document.getElementById('trades_title').innerText = "Trades - Run #" + id;
+        document.getElementById('trades_body').innerHTML = html || `<tr><td colspan="6">No trades</td></tr>`;
+    } catch (e) { console.error("Trades Error:", e); }
+}
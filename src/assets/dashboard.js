@@ -0,0 +1,360 @@
+// 🛑 SAFETY: Check element existence to prevent crashes on old cached HTML
+function safeSetText(id, val) {
+    const el = document.getElementById(id);
+    if(el) el.innerText = val;
+}
+function safeSetClass(id, val) {
+    const el = document.getElementById(id);
+    if(el) el.className = val;
+}
+
+// Amounts are in the pair's quote currency, written like the server writes them (currency.rs)
+let currencies = { base: "BTC", quote: "USDT", reporting: "USDT", rate: 1 };
+function fmtMoney(v, code) {
+    code = code || currencies.quote;
+    const sign = v < 0 ? "-" : "";
+    const abs = Math.abs(v).toFixed(2);
+    if (["USD", "USDT", "USDC"].includes(code)) return sign + "$" + abs;
+    if (code === "INR") return sign + "₹" + abs;
+    return sign + abs + " " + code;
+}
+// The reporting currency's figure for a quote amount, when it is another currency and its rate is known
+function fmtReported(v) {
+    if (currencies.reporting === currencies.quote || currencies.rate === null) return "";
+    return "≈ " + fmtMoney(v * currencies.rate, currencies.reporting);
+}
+
+// Times are shown in the [calendar] zone, whatever the browser's is
+let utcOffsetMins = 0;
+// For text from the DB or the bot going into innerHTML, attributes included
+function escapeHtml(text) {
+    return String(text).replace(/[&<>"']/g, c => ({ "&": "&amp;", "<": "&lt;", ">": "&gt;", '"': "&quot;", "'": "&#39;" })[c]);
+}
+function fmtTime(ts, withDate) {
+    const d = new Date(new Date(ts).getTime() + utcOffsetMins * 60000);
+    return withDate ? d.toLocaleString(undefined, { timeZone: "UTC" }) : d.toLocaleTimeString(undefined, { timeZone: "UTC" });
+}
+
+async function updateStats() {
+    try {
+        // FIX: Use absolute URL to prevent "Request cannot be constructed from a URL that includes credentials" error
+        // Revalidated with the ETag on every poll; an unchanged state comes back as a bodiless 304
+        const url = window.location.origin + '/api/stats';
+        let res = await fetch(url, { cache: "no-cache" });
+        let data = await res.json();
+        utcOffsetMins = data.utc_offset_mins || 0;
+        currencies = data.currencies;
+
+        safeSetText('price', fmtMoney(data.price));
+        safeSetText('status', data.status);
+        safeSetClass('status', "status-badge " + (/HALTED|OFFLINE|DEGRADED|THROTTLED/.test(data.status) ? "halted" : data.status.includes("IDLE") ? "idle" : "active"));
+        const linkColors = { healthy: "#4CAF50", degraded: "#FF9800", disconnected: "#F44336" };
+        for (const [id, link] of [['net_feed', data.network.feed], ['net_api', data.network.private_api]]) {
+            const el = document.getElementById(id);
+            if(el) { el.innerText = link; el.style.color = linkColors[link]; }
+        }
+        const riskBar = document.getElementById('risk_bar');
+        if(riskBar) riskBar.style.display = data.risk.halted ? "block" : "none";
+        safeSetText('risk_reason', data.risk.halted || "");
+        const notice = document.getElementById('config_notice');
+        if(notice) { notice.style.display = data.config_notice ? "block" : "none"; notice.innerText = "⚠️ " + (data.config_notice || ""); }
+        const warmup = document.getElementById('warmup_notice');
+        if(warmup) { warmup.style.display = data.warmup ? "block" : "none"; warmup.innerText = "⏳ Indicators warming up, entries blocked for " + data.warmup + " more candle(s)"; }
+
+        const held = data.slots.filter(s => s.state === "in_position");
+        safeSetText('entry', data.entry_price > 0 ? fmtMoney(data.entry_price) + (held.length > 1 ? " (" + held.length + " slots)" : "") : "--");
+        const entryEl = document.getElementById('entry');
+        if(entryEl) entryEl.title = held.map(s => s.quantity.toFixed(5) + " @ " + fmtMoney(s.entry_price) + ", peak " + fmtMoney(s.highest_price)).join("\n");
+        const stopNames = { trailing: "trailing", break_even: "break-even", hard: "hard" };
+        safeSetText('stop', data.stop ? fmtMoney(data.stop.price) + " (" + stopNames[data.stop.kind] + ")" : "--");
+        const left = data.exit_at ? Math.max(0, Math.round((data.exit_at - Date.now()) / 1000)) : null;
+        safeSetText('time_left', left === null ? "--" : Math.floor(left / 60) + ":" + String(left % 60).padStart(2, "0"));
+
+        const pl = data.unrealized_pl;
+        safeSetText('unrealized', pl.toFixed(2) + "%");
+        safeSetClass('unrealized', "value " + (pl >= 0 ? "pos" : "neg"));
+
+        safeSetText('realized', fmtMoney(data.realized_pl));
+        safeSetText('realized_gross', fmtMoney(data.gross_pl));
+        safeSetText('realized_reported', fmtReported(data.realized_pl));
+        safeSetText('rsi', data.rsi.toFixed(2));
+        safeSetText('strategy_name', data.strategy);
+
+        safeSetText('bb_low', fmtMoney(data.bb_lower));
+        safeSetText('bb_high', fmtMoney(data.bb_upper));
+
+        const r = data.resources;
+        safeSetText('res_cpu', r.cpu_pct.toFixed(1) + "%");
+        safeSetText('res_rss', r.rss_mb.toFixed(1) + " MB");
+        safeSetText('res_fds', r.open_fds);
+        safeSetText('res_db', r.db_size_mb.toFixed(2) + " MB");
+        safeSetText('res_temp', r.soc_temp_c === null ? "--" : r.soc_temp_c.toFixed(1) + "°C");
+        safeSetClass('res_temp', "value " + (r.throttled ? "neg" : ""));
+        safeSetText('res_throttle', r.throttled ? "THROTTLED" : "OK");
+        safeSetClass('res_throttle', "value " + (r.throttled ? "neg" : "pos"));
+
+        safeSetText('usdt_label', currencies.quote + " Available");
+        safeSetText('btc_label', currencies.base + " Available");
+        safeSetText('usdt', fmtMoney(data.wallet_usdt));
+        safeSetText('btc', data.wallet_btc.toFixed(5) + " " + currencies.base);
+        // Every asset on the account, on hover
+        const balances = Object.entries(data.balances).map(([asset, amount]) => asset + ": " + amount).join("\n");
+        for (const id of ['usdt', 'btc']) { const el = document.getElementById(id); if(el) el.title = balances; }
+        // The quote in each of the account's wallets, available / locked
+        const walletsEl = document.getElementById('wallets');
+        if(walletsEl) walletsEl.innerHTML = data.wallets.map(w => w.error
+            ? `<div class="val-box"><div class="label">${w.wallet}</div><div class="value neg" title="${w.error}">unavailable</div></div>`
+            : `<div class="val-box"><div class="label">${w.wallet} (locked)</div><div class="value">${fmtMoney(w.available)} <span style="color:#888">(${fmtMoney(w.locked)})</span></div></div>`).join("");
+
+        let logHtml = "";
+        data.logs.forEach(line => { logHtml += `<div>> ${line}</div>`; });
+        const logsEl = document.getElementById('logs');
+        if(logsEl) logsEl.innerHTML = logHtml;
+
+    } catch (e) { console.error("Update Error:", e); }
+}
+// Trade history (client-side filtering & paging)
+const TRADES_PER_PAGE = 10;
+let allTrades = [];
+let tradePage = 0;
+
+function renderTrades() {
+    const side = document.getElementById('trade_filter').value;
+    const rows = allTrades.filter(t => side === "all" || t.action === side);
+    const pages = Math.max(1, Math.ceil(rows.length / TRADES_PER_PAGE));
+    if (tradePage >= pages) tradePage = pages - 1;
+
+    let html = "";
+    rows.slice(tradePage * TRADES_PER_PAGE, (tradePage + 1) * TRADES_PER_PAGE).forEach(t => {
+        const pl = t.action === "sell" ? t.profit : null;
+        const plCls = pl === null ? "" : (pl >= 0 ? "pos" : "neg");
+        const gross = t.action === "sell" ? t.gross_profit : null;
+        const context = [
+            `signal ${fmtMoney(t.signal_price)}`,
+            `RSI ${t.rsi.toFixed(1)}`,
+            `BB ${fmtMoney(t.bb_lower)} - ${fmtMoney(t.bb_upper)}`,
+            t.stop_price === null ? null : `stop ${fmtMoney(t.stop_price)}`,
+            t.spread_bps === null ? null : `spread ${t.spread_bps.toFixed(1)} bps`,
+            t.mode,
+        ].filter(Boolean).join(" | ");
+        html += `<tr>
+            <td>${fmtTime(t.timestamp)}</td>
+            <td>${escapeHtml(t.action.toUpperCase())}</td>
+            <td>${fmtMoney(t.price)}</td>
+            <td>${t.quantity.toFixed(5)}</td>
+            <td class="${plCls}">${pl === null ? "--" : fmtMoney(pl)}</td>
+            <td>${gross === null ? "--" : fmtMoney(gross)}</td>
+            <td title="${escapeHtml(context)}">${escapeHtml(t.reason)}</td>
+        </tr>`;
+    });
+    const body = document.getElementById('trades_body');
+    if(body) body.innerHTML = html || `<tr><td colspan="7">No trades yet</td></tr>`;
+    safeSetText('trade_page', `Page ${tradePage + 1} / ${pages}`);
+}
+
+function changeTradePage(delta) {
+    tradePage = Math.max(0, tradePage + delta);
+    renderTrades();
+}
+
+async function updateTrades() {
+    try {
+        const url = window.location.origin + '/api/trades?t=' + Date.now();
+        let res = await fetch(url);
+        allTrades = await res.json();
+        renderTrades();
+    } catch (e) { console.error("Trades Error:", e); }
+}
+
+// Strategy selector (loaded once so polling never clobbers edits)
+let strategyInfo = null;
+
+async function loadStrategy() {
+    try {
+        let res = await fetch(window.location.origin + '/api/strategy?t=' + Date.now());
+        strategyInfo = await res.json();
+        const sel = document.getElementById('strategy_select');
+        sel.innerHTML = strategyInfo.available.map(s => `<option value="${s.name}">${s.name}</option>`).join("");
+        sel.value = strategyInfo.active;
+        renderStrategyParams();
+    } catch (e) { console.error("Strategy Error:", e); }
+}
+
+function renderStrategyParams() {
+    const name = document.getElementById('strategy_select').value;
+    const info = strategyInfo.available.find(s => s.name === name);
+    safeSetText('strategy_desc', info.description);
+    document.getElementById('strategy_params').innerHTML = info.params.map(p => `
+        <div><div class="label">${p.label}</div>
+        <input id="param_${p.key}" type="number" min="${p.min}" max="${p.max}" step="${p.step}" value="${strategyInfo.params[p.key]}"></div>
+    `).join("");
+}
+
+async function applyStrategy() {
+    const name = document.getElementById('strategy_select').value;
+    const info = strategyInfo.available.find(s => s.name === name);
+    const body = Object.assign({}, strategyInfo.params, { name: name });
+    info.params.forEach(p => { body[p.key] = parseFloat(document.getElementById('param_' + p.key).value); });
+    try {
+        let res = await fetch(window.location.origin + '/api/strategy', {
+            method: "POST", headers: { "Content-Type": "application/json" }, body: JSON.stringify(body)
+        });
+        if (!res.ok) throw new Error(await res.text());
+        strategyInfo = await res.json();
+        safeSetText('strategy_msg', "Saved");
+    } catch (e) { safeSetText('strategy_msg', e.message); }
+}
+
+// Alert rules management
+const ALERT_TYPES = {
+    price_crosses: { field: "level", text: v => `Price crosses $${v}` },
+    drawdown_pct: { field: "pct", text: v => `Drawdown > ${v}%` },
+    no_tick_secs: { field: "secs", text: v => `No tick for ${v}s` },
+};
+let alertRules = [];
+
+async function loadAlerts() {
+    try {
+        let res = await fetch(window.location.origin + '/api/alerts?t=' + Date.now());
+        alertRules = await res.json();
+        let html = "";
+        alertRules.forEach(r => {
+            const t = ALERT_TYPES[r.condition.type];
+            html += `<tr>
+                <td><input type="checkbox" ${r.enabled ? "checked" : ""} onchange="toggleAlert(${r.id}, this.checked)"></td>
+                <td>${t.text(r.condition[t.field])}</td>
+                <td style="text-align:right;"><button onclick="deleteAlert(${r.id})">✕</button></td>
+            </tr>`;
+        });
+        const body = document.getElementById('alerts_body');
+        if(body) body.innerHTML = html || `<tr><td colspan="3">No alert rules</td></tr>`;
+    } catch (e) { console.error("Alerts Error:", e); }
+}
+
+async function alertRequest(method, path, body) {
+    let res = await fetch(window.location.origin + path, {
+        method: method, headers: { "Content-Type": "application/json" }, body: body ? JSON.stringify(body) : undefined
+    });
+    if (!res.ok) throw new Error(await res.text());
+}
+
+async function addAlert() {
+    const type = document.getElementById('alert_type').value;
+    const condition = { type: type };
+    condition[ALERT_TYPES[type].field] = parseFloat(document.getElementById('alert_value').value);
+    try {
+        await alertRequest("POST", "/api/alerts", { condition: condition });
+        safeSetText('alert_msg', "");
+        loadAlerts();
+    } catch (e) { safeSetText('alert_msg', e.message); }
+}
+
+async function toggleAlert(id, enabled) {
+    const rule = alertRules.find(r => r.id === id);
+    try { await alertRequest("PUT", "/api/alerts/" + id, { condition: rule.condition, enabled: enabled }); }
+    catch (e) { safeSetText('alert_msg', e.message); }
+    loadAlerts();
+}
+
+async function deleteAlert(id) {
+    try { await alertRequest("DELETE", "/api/alerts/" + id); }
+    catch (e) { safeSetText('alert_msg', e.message); }
+    loadAlerts();
+}
+
+async function rearmRisk() {
+    if (!confirm("Re-arm trading after the risk halt?")) return;
+    try {
+        let res = await fetch(window.location.origin + '/api/risk/rearm', { method: "POST" });
+        if (!res.ok) throw new Error(await res.text());
+        updateStats();
+    } catch (e) { safeSetText('risk_reason', e.message); }
+}
+
+async function pauseTrading() {
+    if (!confirm("Pause new entries until re-armed?")) return;
+    try {
+        let res = await fetch(window.location.origin + '/api/risk/pause', { method: "POST" });
+        if (!res.ok) throw new Error(await res.text());
+        safeSetText('control_msg', "");
+        updateStats();
+    } catch (e) { safeSetText('control_msg', e.message); }
+}
+
+async function closePosition() {
+    if (!confirm("Sell the open position now?")) return;
+    try {
+        let res = await fetch(window.location.origin + '/api/position/close', { method: "POST" });
+        if (!res.ok) throw new Error(await res.text());
+        safeSetText('control_msg', "Closing on the next tick");
+    } catch (e) { safeSetText('control_msg', e.message); }
+}
+
+async function updatePerformance() {
+    try {
+        let res = await fetch(window.location.origin + '/api/performance?t=' + Date.now());
+        let p = await res.json();
+        const opt = v => v === null ? "--" : v.toFixed(2);
+        safeSetText('perf_sharpe', opt(p.sharpe) + " / " + opt(p.sortino));
+        safeSetText('perf_pf', opt(p.profit_factor));
+        safeSetText('perf_exp', fmtMoney(p.expectancy));
+        safeSetClass('perf_exp', "value " + (p.expectancy >= 0 ? "pos" : "neg"));
+        safeSetText('perf_dd', fmtMoney(p.max_drawdown));
+        safeSetText('perf_wr', p.win_rate.toFixed(1) + "% (" + p.closed_trades + ")");
+        safeSetText('perf_hold', (p.avg_holding_secs / 60).toFixed(1) + " min");
+        safeSetText('perf_rolling', p.closed_trades === 0 ? "--" : p.rolling_win_rate.toFixed(1) + "% / " + fmtMoney(p.rolling_expectancy));
+        safeSetClass('perf_rolling', "value " + (p.rolling_expectancy >= 0 ? "pos" : "neg"));
+        safeSetText('perf_streak', p.streak === 0 ? "--" : (p.streak > 0 ? p.streak + " wins" : -p.streak + " losses"));
+        safeSetClass('perf_streak', "value " + (p.streak >= 0 ? "pos" : "neg"));
+        safeSetText('perf_avg', fmtMoney(p.avg_win) + " / " + fmtMoney(p.avg_loss));
+    } catch (e) { console.error("Performance Error:", e); }
+}
+
+async function updateBook() {
+    try {
+        let res = await fetch(window.location.origin + '/api/orderbook?t=' + Date.now());
+        let b = await res.json();
+        const maxQty = Math.max(...b.bids.map(l => l.qty), ...b.asks.map(l => l.qty), 0);
+        const rows = (levels, color, side) => levels.map(l => `
+            <div class="book-row">
+                <div class="book-bar" style="background:${color}; ${side}:0; width:${maxQty ? l.qty / maxQty * 100 : 0}%;"></div>
+                <span style="color:${color}">${l.price.toFixed(2)}</span><span>${l.qty.toFixed(5)}</span>
+            </div>`).join("");
+        const bidsEl = document.getElementById('book_bids');
+        const asksEl = document.getElementById('book_asks');
+        if(bidsEl) bidsEl.innerHTML = rows(b.bids, "#4CAF50", "right") || "--";
+        if(asksEl) asksEl.innerHTML = rows(b.asks, "#F44336", "left") || "--";
+        safeSetText('book_spread', b.spread === null ? "--" : fmtMoney(b.spread) + " (" + b.spread_bps.toFixed(1) + " bps)");
+        safeSetText('book_imbalance', !b.synced ? "resyncing" : b.imbalance === null ? "--" : (b.imbalance * 100).toFixed(0) + "%");
+    } catch (e) { console.error("Order Book Error:", e); }
+}
+
+async function loadUser() {
+    try {
+        let res = await fetch(window.location.origin + '/api/me?t=' + Date.now());
+        let me = await res.json();
+        const el = document.getElementById('user_bar');
+        if (el && me.auth_enabled && me.username) {
+            el.innerHTML = `👤 ${me.username} (${me.role}) · <form method="post" action="/logout" style="display:inline;"><button class="link-btn">Log out</button></form>`;
+        }
+    } catch (e) { console.error("User Error:", e); }
+}
+
+async function updateActivity() {
+    try {
+        let res = await fetch(window.location.origin + '/api/activity?t=' + Date.now());
+        let rows = await res.json();
+        let html = "";
+        rows.slice(0, 10).forEach(a => {
+            html += `<tr><td>${fmtTime(a.timestamp, true)}</td><td>${a.username}</td><td>${a.action}</td></tr>`;
+        });
+        const body = document.getElementById('activity_body');
+        if(body) body.innerHTML = html || `<tr><td colspan="3">No activity yet</td></tr>`;
+    } catch (e) { console.error("Activity Error:", e); }
+}
+
+setInterval(updateStats, 2000);
+setInterval(updateActivity, 15000);
+setInterval(updateBook, 2000);
+setInterval(updatePerformance, 15000);
+setInterval(updateTrades, 5000);
@@ -0,0 +1,19 @@
+function setText(id, val) { const el = document.getElementById(id); if(el) el.innerText = val; }
+function setClass(id, val) { const el = document.getElementById(id); if(el) el.className = val; }
+
+async function update() {
+    try {
+        let res = await fetch(window.location.origin + '/api/public?t=' + Date.now());
+        let d = await res.json();
+        setText('pair', d.pair);
+        setText('price', "$" + d.price.toFixed(2));
+        setText('status', d.status);
+        setClass('status', "status-badge " + (d.status.includes("IDLE") ? "idle" : "active"));
+        setText('rsi', d.rsi.toFixed(2));
+        setText('unrealized', d.unrealized_pl.toFixed(2) + "%");
+        setClass('unrealized', "value " + (d.unrealized_pl >= 0 ? "pos" : "neg"));
+        setText('realized', d.realized_pl_pct.toFixed(2) + "%");
+        setClass('realized', "value " + (d.realized_pl_pct >= 0 ? "pos" : "neg"));
+    } catch (e) { console.error("Update Error:", e); }
+}
+setInterval(update, 2000);
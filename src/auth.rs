@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+#[cfg(feature = "web")]
+use axum::{
+    extract::{Request, State},
+    http::{header, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Redirect, Response},
+};
+use chrono::Utc;
+use parking_lot::RwLock;
+use rand::RngCore;
+use serde::{Deserialize, Serialize};
+#[cfg(feature = "web")]
+use subtle::ConstantTimeEq;
+
+#[cfg(feature = "web")]
+use crate::{runtime, web::AppState, DbManager};
+
+// --- 🔐 DASHBOARD LOGIN & ROLES ---
+
+pub const SESSION_COOKIE: &str = "scalper_session";
+const SESSION_TTL_SECS: i64 = 24 * 60 * 60;
+const BCRYPT_COST: u32 = 10;
+// Reads only admins may make: the whole state, config included
+const ADMIN_ONLY: [&str; 1] = ["/api/snapshot"];
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Role {
+    Admin,  // Full control
+    Viewer, // Read-only
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct UserConfig {
+    pub username: String,
+    pub password_hash: String, // bcrypt, generate with `coindcx_scalper hash-password <password>`
+    pub role: Role,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Session {
+    pub username: String,
+    pub role: Role,
+    pub expires_at: i64, // Unix seconds
+}
+
+pub type SharedSessions = Arc<RwLock<HashMap<String, Session>>>;
+
+pub fn hash_password(password: &str) -> Result<String, String> {
+    bcrypt::hash(password, BCRYPT_COST).map_err(|e| e.to_string())
+}
+
+pub fn verify_password(password: &str, hash: &str) -> bool {
+    bcrypt::verify(password, hash).unwrap_or(false)
+}
+
+fn new_token() -> String {
+    let mut bytes = [0u8; 32];
+    rand::thread_rng().fill_bytes(&mut bytes);
+    hex::encode(bytes)
+}
+
+/// Checks credentials against the configured users and opens a session.
+#[cfg(feature = "web")]
+pub fn login(app: &AppState, username: &str, password: &str) -> Option<(String, Session)> {
+    let user = app.config.read().users.iter().find(|u| u.username == username).cloned()?;
+    if !verify_password(password, &user.password_hash) { return None; }
+
+    let token = new_token();
+    let session = Session { username: user.username, role: user.role, expires_at: Utc::now().timestamp() + SESSION_TTL_SECS };
+    let mut sessions = app.sessions.write();
+    sessions.retain(|_, s| s.expires_at > Utc::now().timestamp());
+    sessions.insert(token.clone(), session.clone());
+    Some((token, session))
+}
+
+pub fn session_cookie(token: &str, max_age: i64) -> String {
+    format!("{}={}; Path=/; HttpOnly; SameSite=Strict; Max-Age={}", SESSION_COOKIE, token, max_age)
+}
+
+pub fn new_session_cookie(token: &str) -> String {
+    session_cookie(token, SESSION_TTL_SECS)
+}
+
+#[cfg(feature = "web")]
+pub fn token_from_request(req: &Request) -> Option<String> {
+    req.headers().get_all(header::COOKIE).iter()
+        .filter_map(|v| v.to_str().ok())
+        .flat_map(|v| v.split(';'))
+        .filter_map(|pair| pair.trim().split_once('='))
+        .find(|(name, _)| *name == SESSION_COOKIE)
+        .map(|(_, value)| value.to_string())
+}
+
+// Bearer METRICS_TOKEN, when that is set
+#[cfg(feature = "web")]
+fn scrape_authorized(req: &Request) -> bool {
+    let Some(token) = std::env::var("METRICS_TOKEN").ok().filter(|t| !t.is_empty()) else { return false };
+    let sent = req.headers().get(header::AUTHORIZATION).map(|v| v.as_bytes()).unwrap_or_default();
+    // Constant time, so the response time says nothing about how much of the token matched
+    bool::from(sent.ct_eq(format!("Bearer {}", token).as_bytes()))
+}
+
+/// Drops sessions that are expired or whose user was removed or changed role; returns how many.
+pub fn revalidate_sessions(sessions: &SharedSessions, users: &[UserConfig]) -> usize {
+    let now = Utc::now().timestamp();
+    let mut sessions = sessions.write();
+    let before = sessions.len();
+    sessions.retain(|_, s| s.expires_at > now && users.iter().any(|u| u.username == s.username && u.role == s.role));
+    before - sessions.len()
+}
+
+#[cfg(feature = "web")]
+pub fn current_session(app: &AppState, token: Option<&str>) -> Option<Session> {
+    let token = token?;
+    app.sessions.read().get(token).filter(|s| s.expires_at > Utc::now().timestamp()).cloned()
+}
+
+/// Login gate for the private dashboard. Disabled when no users are configured.
+/// Viewers may only read; every mutating request is recorded in the activity log.
+#[cfg(feature = "web")]
+pub async fn require_login(State(app): State<AppState>, mut req: Request, next: Next) -> Response {
+    let path = req.uri().path().to_string();
+    // Styles and scripts hold nothing private, and the login page needs them
+    if path == "/login" || path.starts_with("/assets/") || app.config.read().users.is_empty() {
+        return next.run(req).await;
+    }
+    // A scraper has no session: /metrics (balances and P&L) also takes the METRICS_TOKEN bearer token
+    if path == "/metrics" && scrape_authorized(&req) {
+        return next.run(req).await;
+    }
+
+    let session = match current_session(&app, token_from_request(&req).as_deref()) {
+        Some(s) => s,
+        None if path.starts_with("/api/") => return StatusCode::UNAUTHORIZED.into_response(),
+        None => return Redirect::to("/login").into_response(),
+    };
+
+    let method = req.method().clone();
+    let mutating = method != Method::GET && method != Method::HEAD;
+    if ADMIN_ONLY.contains(&path.as_str()) && session.role != Role::Admin {
+        return (StatusCode::FORBIDDEN, "Admins only").into_response();
+    }
+    if mutating && session.role != Role::Admin {
+        let action = format!("DENIED {} {}", method, path);
+        let _ = runtime::db(move || DbManager::log_activity(&session.username, &action)).await;
+        return (StatusCode::FORBIDDEN, "Viewer accounts are read-only").into_response();
+    }
+
+    let username = session.username.clone();
+    req.extensions_mut().insert(session);
+    let response = next.run(req).await;
+    if mutating && path != "/logout" {
+        let action = format!("{} {} -> {}", method, path, response.status().as_u16());
+        let _ = runtime::db(move || DbManager::log_activity(&username, &action)).await;
+    }
+    response
+}
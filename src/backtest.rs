@@ -0,0 +1,163 @@
+use serde::{Deserialize, Serialize};
+
+use crate::indicators::IndicatorPipeline;
+use crate::strategy::{self, ReentryBand, Strategy, StrategyParams};
+use crate::{money, Candle, FEE_RATE};
+
+// --- 🧪 BACKTESTER ---
+// Trades on candle closes like the paper broker does: buying half the spread above the close,
+// selling half below, and paying the fee on both sides. Gross P&L is the same trades at the
+// closes themselves.
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BacktestTrade {
+    pub entry_time: i64,
+    pub exit_time: i64,
+    pub entry_price: f64, // Fill prices, across the spread
+    pub exit_price: f64,
+    pub quantity: f64,
+    pub profit: f64,       // Net of the spread and fees
+    #[serde(default)]
+    pub gross_profit: f64, // Close to close
+    pub reason: String,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+pub struct BacktestMetrics {
+    pub candles: usize,
+    pub trades: usize,
+    pub wins: usize,
+    pub win_rate: f64,
+    pub net_pnl: f64,
+    #[serde(default)]
+    pub gross_pnl: f64,
+    pub avg_trade: f64,
+    pub max_drawdown: f64,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct BacktestRun {
+    pub id: i64,
+    pub created: String,
+    pub strategy: String,
+    pub params: StrategyParams,
+    pub metrics: BacktestMetrics,
+    pub equity: Vec<(i64, f64)>, // (candle time, cumulative realized net P&L)
+    pub trades: Vec<BacktestTrade>,
+}
+
+/// A position the backtest holds.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Held {
+    pub entry_time: i64,
+    pub entry_close: f64,
+    pub entry_price: f64, // Across the spread
+    pub highest_price: f64,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Step {
+    Entered { reason: &'static str },
+    Exited { reason: &'static str, held: Held },
+}
+
+/// The backtest's decisions one candle at a time: the indicator pipeline and its warm-up, the
+/// re-entry band, and the exit chain (hard and break-even stops, then the time exit, then the
+/// strategy's own exits). `run` books what it decides; the strategy harness asserts on it.
+pub struct Stepper<'a> {
+    strategy: &'a dyn Strategy,
+    pipeline: IndicatorPipeline,
+    reentry: ReentryBand,
+    half_spread: f64,
+    pub position: Option<Held>,
+}
+
+impl<'a> Stepper<'a> {
+    pub fn new(strategy: &'a dyn Strategy, spread_bps: f64) -> Self {
+        Stepper { strategy, pipeline: IndicatorPipeline::new(), reentry: ReentryBand::default(), half_spread: spread_bps.max(0.0) / 20_000.0, position: None }
+    }
+
+    /// Trades the candle's close: an entry, an exit (with the position it closed) or neither.
+    pub fn step(&mut self, c: &Candle) -> Option<Step> {
+        let params = self.strategy.params();
+        let mut ind = self.pipeline.next(c.close);
+        self.reentry.apply(params, &mut ind);
+
+        match self.position.as_mut() {
+            None => {
+                let reason = self.pipeline.ready().then(|| self.strategy.entry_signal(&ind)).flatten()?;
+                let entry_price = c.close * (1.0 + self.half_spread);
+                self.position = Some(Held { entry_time: c.time, entry_close: c.close, entry_price, highest_price: c.close });
+                Some(Step::Entered { reason })
+            },
+            Some(held) => {
+                if c.close > held.highest_price { held.highest_price = c.close; }
+                let reason = strategy::protective_exit(params, c.close, held.entry_price, held.highest_price)
+                    .or_else(|| strategy::time_exit(params, c.time - held.entry_time))
+                    .or_else(|| self.strategy.exit_reason(&ind, held.highest_price))?;
+                let held = self.position.take()?;
+                self.reentry.exited(c.close, ind.rsi);
+                Some(Step::Exited { reason, held })
+            },
+        }
+    }
+}
+
+/// Replays candles (oldest first) through the same strategy the live loop uses, crossing a
+/// bid/ask spread of `spread_bps` on every fill.
+pub fn run(candles: &[Candle], strategy: &dyn Strategy, spread_bps: f64) -> BacktestRun {
+    let params: &StrategyParams = strategy.params();
+    let mut stepper = Stepper::new(strategy, spread_bps);
+    let half_spread = stepper.half_spread;
+    let fee_rate = money::float(FEE_RATE);
+
+    let mut trades = Vec::new();
+    let mut equity = Vec::with_capacity(candles.len());
+    let mut realized = 0.0;
+    let mut gross_pnl = 0.0;
+    let mut peak = 0.0;
+    let mut max_drawdown: f64 = 0.0;
+
+    let mut quantity = 0.0;
+
+    for c in candles {
+        match stepper.step(c) {
+            Some(Step::Entered { .. }) => {
+                quantity = stepper.position.map_or(0.0, |held| params.trade_capital / held.entry_price);
+            },
+            Some(Step::Exited { reason, held }) => {
+                let exit_price = c.close * (1.0 - half_spread);
+                let profit = (exit_price - held.entry_price) * quantity - (held.entry_price + exit_price) * quantity * fee_rate;
+                let gross_profit = (c.close - held.entry_close) * quantity;
+                realized += profit;
+                gross_pnl += gross_profit;
+                trades.push(BacktestTrade {
+                    entry_time: held.entry_time, exit_time: c.time, entry_price: held.entry_price, exit_price,
+                    quantity, profit, gross_profit, reason: reason.to_string(),
+                });
+            },
+            None => {},
+        }
+
+        if realized > peak { peak = realized; }
+        max_drawdown = max_drawdown.max(peak - realized);
+        equity.push((c.time, realized));
+    }
+
+    let wins = trades.iter().filter(|t| t.profit > 0.0).count();
+    let metrics = BacktestMetrics {
+        candles: candles.len(),
+        trades: trades.len(),
+        wins,
+        win_rate: if trades.is_empty() { 0.0 } else { wins as f64 / trades.len() as f64 * 100.0 },
+        net_pnl: realized,
+        gross_pnl,
+        avg_trade: if trades.is_empty() { 0.0 } else { realized / trades.len() as f64 },
+        max_drawdown,
+    };
+
+    BacktestRun {
+        id: 0, created: String::new(), strategy: strategy.name().to_string(),
+        params: *params, metrics, equity, trades,
+    }
+}
@@ -0,0 +1,280 @@
+use std::env;
+use std::fs;
+use std::io::Write;
+use std::path::Path;
+use std::time::{Duration, SystemTime};
+use chrono::{DateTime, Utc};
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use hmac::{Hmac, Mac};
+use reqwest::Url;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use tokio::time::sleep;
+
+use crate::config::SharedConfig;
+use crate::{add_log, runtime, DbManager, SharedState, DB_FILE};
+
+// --- ☁️ OFFSITE BACKUP ---
+// Everything the bot knows lives on one SD card. Every interval_hours a consistent copy of the
+// database (VACUUM INTO, gzipped) and any new or grown file in the listed directories (tick files,
+// exports) are pushed to an S3-compatible bucket (MinIO, Backblaze B2, AWS), signed with SigV4 and
+// addressed path-style. Objects older than retention_days are deleted from the bucket. Whether a
+// backup is due is read from the bucket itself, so restarts neither skip nor repeat one.
+// Credentials come from S3_ACCESS_KEY_ID / S3_SECRET_ACCESS_KEY.
+
+const CHECK_SECS: u64 = 300;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BackupConfig {
+    pub enabled: bool,
+    pub endpoint: String, // e.g. https://s3.us-west-004.backblazeb2.com or http://minio:9000
+    pub bucket: String,
+    pub region: String,
+    pub prefix: String, // Key prefix, so several bots can share a bucket
+    pub interval_hours: u64,
+    pub retention_days: u32, // 0 = keep everything
+    pub dirs: Vec<String>,   // Mirrored file by file, without subdirectories
+}
+
+impl Default for BackupConfig {
+    fn default() -> Self {
+        BackupConfig {
+            enabled: false,
+            endpoint: String::new(),
+            bucket: String::new(),
+            region: "us-east-1".to_string(),
+            prefix: "scalper".to_string(),
+            interval_hours: 24,
+            retention_days: 30,
+            dirs: vec!["ticks".to_string(), "exports".to_string()],
+        }
+    }
+}
+
+struct Object {
+    key: String,
+    size: u64,
+    modified: DateTime<Utc>,
+}
+
+struct Bucket {
+    client: reqwest::Client,
+    base: Url, // endpoint/bucket/
+    region: String,
+    access_key: String,
+    secret_key: String,
+}
+
+fn sha256_hex(data: &[u8]) -> String {
+    hex::encode(Sha256::digest(data))
+}
+
+fn hmac(key: &[u8], data: &str) -> Vec<u8> {
+    let mut mac = Hmac::<Sha256>::new_from_slice(key).expect("Invalid Key");
+    mac.update(data.as_bytes());
+    mac.finalize().into_bytes().to_vec()
+}
+
+// RFC 3986 encoding as SigV4 wants it; '/' is kept in paths
+fn encode(value: &str, keep_slash: bool) -> String {
+    value.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'_' | b'.' | b'~' => (b as char).to_string(),
+        b'/' if keep_slash => "/".to_string(),
+        _ => format!("%{:02X}", b),
+    }).collect()
+}
+
+/// The text of every `<name>` element, in order. The list response is flat enough not to need an
+/// XML parser.
+fn elements<'a>(xml: &'a str, name: &str) -> Vec<&'a str> {
+    let (open, close) = (format!("<{}>", name), format!("</{}>", name));
+    let mut found = Vec::new();
+    let mut rest = xml;
+    while let Some(start) = rest.find(&open) {
+        let after = &rest[start + open.len()..];
+        let Some(end) = after.find(&close) else { break };
+        found.push(&after[..end]);
+        rest = &after[end + close.len()..];
+    }
+    found
+}
+
+fn unescape(text: &str) -> String {
+    text.replace("&lt;", "<").replace("&gt;", ">").replace("&quot;", "\"").replace("&apos;", "'").replace("&amp;", "&")
+}
+
+impl Bucket {
+    fn new(cfg: &BackupConfig) -> Result<Self, String> {
+        let (Ok(access_key), Ok(secret_key)) = (env::var("S3_ACCESS_KEY_ID"), env::var("S3_SECRET_ACCESS_KEY")) else {
+            return Err("S3_ACCESS_KEY_ID / S3_SECRET_ACCESS_KEY are not set".to_string());
+        };
+        if cfg.bucket.is_empty() { return Err("no bucket configured".to_string()); }
+        let base = Url::parse(&format!("{}/{}/", cfg.endpoint.trim_end_matches('/'), cfg.bucket))
+            .map_err(|e| format!("bad endpoint {}: {}", cfg.endpoint, e))?;
+        let client = reqwest::Client::builder().timeout(Duration::from_secs(300)).build().map_err(|e| e.to_string())?;
+        Ok(Bucket { client, base, region: cfg.region.clone(), access_key, secret_key })
+    }
+
+    /// Sends a SigV4-signed request; `query` must already be sorted by name.
+    async fn send(&self, method: &str, key: &str, query: &[(&str, &str)], body: Vec<u8>) -> Result<String, String> {
+        let mut url = self.base.join(&encode(key, true)).map_err(|e| e.to_string())?;
+        let canonical_query = query.iter().map(|(k, v)| format!("{}={}", encode(k, false), encode(v, false))).collect::<Vec<_>>().join("&");
+        if !canonical_query.is_empty() { url.set_query(Some(&canonical_query)); }
+        let host = match (url.host_str(), url.port()) {
+            (Some(host), Some(port)) => format!("{}:{}", host, port),
+            (Some(host), None) => host.to_string(),
+            _ => return Err("endpoint has no host".to_string()),
+        };
+
+        let now = Utc::now();
+        let (amz_date, date) = (now.format("%Y%m%dT%H%M%SZ").to_string(), now.format("%Y%m%d").to_string());
+        let payload_hash = sha256_hex(&body);
+        let canonical = format!(
+            "{}\n{}\n{}\nhost:{}\nx-amz-content-sha256:{}\nx-amz-date:{}\n\nhost;x-amz-content-sha256;x-amz-date\n{}",
+            method, url.path(), canonical_query, host, payload_hash, amz_date, payload_hash
+        );
+        let scope = format!("{}/{}/s3/aws4_request", date, self.region);
+        let to_sign = format!("AWS4-HMAC-SHA256\n{}\n{}\n{}", amz_date, scope, sha256_hex(canonical.as_bytes()));
+        let mut signing_key = hmac(format!("AWS4{}", self.secret_key).as_bytes(), &date);
+        for part in [self.region.as_str(), "s3", "aws4_request"] {
+            signing_key = hmac(&signing_key, part);
+        }
+        let signature = hex::encode(hmac(&signing_key, &to_sign));
+        let authorization = format!(
+            "AWS4-HMAC-SHA256 Credential={}/{}, SignedHeaders=host;x-amz-content-sha256;x-amz-date, Signature={}",
+            self.access_key, scope, signature
+        );
+
+        let method = reqwest::Method::from_bytes(method.as_bytes()).map_err(|e| e.to_string())?;
+        let res = self.client.request(method, url)
+            .header("x-amz-date", amz_date)
+            .header("x-amz-content-sha256", payload_hash)
+            .header("Authorization", authorization)
+            .body(body)
+            .send().await.map_err(|e| e.to_string())?;
+        let status = res.status();
+        let text = res.text().await.map_err(|e| e.to_string())?;
+        if !status.is_success() {
+            let reason = elements(&text, "Message").first().map(|m| unescape(m)).unwrap_or_else(|| status.to_string());
+            return Err(format!("{} {}: {}", status.as_u16(), if key.is_empty() { "list" } else { key }, reason));
+        }
+        Ok(text)
+    }
+
+    async fn list(&self, prefix: &str) -> Result<Vec<Object>, String> {
+        let mut objects = Vec::new();
+        let mut token: Option<String> = None;
+        loop {
+            let mut query = vec![];
+            if let Some(token) = token.as_deref() { query.push(("continuation-token", token)); }
+            query.push(("list-type", "2"));
+            query.push(("prefix", prefix));
+            let xml = self.send("GET", "", &query, Vec::new()).await?;
+            for entry in elements(&xml, "Contents") {
+                let key = elements(entry, "Key").first().map(|k| unescape(k)).unwrap_or_default();
+                let size = elements(entry, "Size").first().and_then(|s| s.parse().ok()).unwrap_or(0);
+                let modified = elements(entry, "LastModified").first()
+                    .and_then(|t| DateTime::parse_from_rfc3339(t).ok())
+                    .map_or(DateTime::<Utc>::MIN_UTC, |t| t.with_timezone(&Utc));
+                objects.push(Object { key, size, modified });
+            }
+            token = elements(&xml, "NextContinuationToken").first().map(|t| unescape(t));
+            if elements(&xml, "IsTruncated").first() != Some(&"true") || token.is_none() { break; }
+        }
+        Ok(objects)
+    }
+}
+
+// The database as of now, consistent even while the bot writes to it
+fn snapshot() -> Result<Vec<u8>, String> {
+    let path = format!("{}.backup", DB_FILE);
+    let _ = fs::remove_file(&path);
+    DbManager::snapshot_to(&path).map_err(|e| format!("snapshot: {}", e))?;
+    let raw = fs::read(&path).map_err(|e| e.to_string());
+    let _ = fs::remove_file(&path);
+    let mut gz = GzEncoder::new(Vec::new(), Compression::default());
+    gz.write_all(&raw?).map_err(|e| e.to_string())?;
+    gz.finish().map_err(|e| e.to_string())
+}
+
+// (key suffix, path, size) of every file in the listed directories changed within the retention
+fn local_files(cfg: &BackupConfig) -> Vec<(String, String, u64)> {
+    let cutoff = (cfg.retention_days > 0)
+        .then(|| SystemTime::now() - Duration::from_secs(u64::from(cfg.retention_days) * 86_400));
+    let mut files = Vec::new();
+    for dir in &cfg.dirs {
+        let Ok(entries) = fs::read_dir(dir) else { continue };
+        for entry in entries.flatten() {
+            let Ok(meta) = entry.metadata() else { continue };
+            if !meta.is_file() { continue; }
+            if let (Some(cutoff), Ok(modified)) = (cutoff, meta.modified()) {
+                if modified < cutoff { continue; }
+            }
+            let name = entry.file_name().to_string_lossy().into_owned();
+            let dir_name = Path::new(dir).file_name().map_or(dir.clone(), |d| d.to_string_lossy().into_owned());
+            files.push((format!("{}/{}", dir_name, name), entry.path().to_string_lossy().into_owned(), meta.len()));
+        }
+    }
+    files
+}
+
+async fn run(bucket: &Bucket, cfg: &BackupConfig, objects: &[Object]) -> Result<String, String> {
+    let prefix = cfg.prefix.trim_matches('/');
+    let db = runtime::db(snapshot).await?;
+    let db_size = db.len();
+    let db_key = format!("{}/db/{}-{}.gz", prefix, DB_FILE, Utc::now().format("%Y%m%d-%H%M%S"));
+    bucket.send("PUT", &db_key, &[], db).await?;
+
+    // A file is sent again when its size changed: today's tick file grows all day
+    let mut uploaded = 0;
+    for (suffix, path, size) in local_files(cfg) {
+        let key = format!("{}/files/{}", prefix, suffix);
+        if objects.iter().any(|o| o.key == key && o.size == size) { continue; }
+        let body = tokio::fs::read(&path).await.map_err(|e| format!("{}: {}", path, e))?;
+        bucket.send("PUT", &key, &[], body).await?;
+        uploaded += 1;
+    }
+
+    let mut deleted = 0;
+    if cfg.retention_days > 0 {
+        let cutoff = Utc::now() - chrono::Duration::days(i64::from(cfg.retention_days));
+        for object in objects.iter().filter(|o| o.modified < cutoff) {
+            bucket.send("DELETE", &object.key, &[], Vec::new()).await?;
+            deleted += 1;
+        }
+    }
+    Ok(format!("database {:.1} MB, {} file(s) uploaded, {} expired", db_size as f64 / 1024.0 / 1024.0, uploaded, deleted))
+}
+
+pub async fn monitor(state: SharedState, config: SharedConfig) {
+    let mut failed = false;
+    loop {
+        let cfg = config.read().backup.clone();
+        if cfg.enabled {
+            let result = async {
+                let bucket = Bucket::new(&cfg)?;
+                let prefix = format!("{}/", cfg.prefix.trim_matches('/'));
+                let objects = bucket.list(&prefix).await?;
+                let last = objects.iter().filter(|o| o.key.starts_with(&format!("{}db/", prefix))).map(|o| o.modified).max();
+                let due = last.is_none_or(|at| Utc::now() - at >= chrono::Duration::hours(cfg.interval_hours.max(1) as i64));
+                if due { run(&bucket, &cfg, &objects).await.map(Some) } else { Ok(None) }
+            }.await;
+            match result {
+                Ok(Some(summary)) => {
+                    failed = false;
+                    add_log(&state, format!("☁️ Backup uploaded: {}", summary));
+                }
+                Ok(None) => {}
+                Err(e) => {
+                    eprintln!("Backup Error: {}", e);
+                    // Logged once per outage, it is retried every check
+                    if !failed { add_log(&state, format!("⚠️ Backup failed: {}", e)); }
+                    failed = true;
+                }
+            }
+        }
+        sleep(Duration::from_secs(CHECK_SECS)).await;
+    }
+}
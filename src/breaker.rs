@@ -0,0 +1,151 @@
+use std::collections::VecDeque;
+use std::fmt;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+// --- ⚡ CIRCUIT BREAKER (exchange API failures) ---
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TripAction {
+    #[default]
+    Hold,    // Keep the position, just stop placing orders
+    Flatten, // Try to close the position when the breaker trips
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BreakerConfig {
+    pub max_consecutive_failures: u32,
+    pub window: usize,          // Recent calls considered for the error rate
+    pub max_error_rate: f64,    // 0.0 - 1.0 within the window
+    pub base_backoff_secs: u64, // First probe delay, doubled on each failed probe
+    pub max_backoff_secs: u64,
+    pub on_trip: TripAction,
+}
+
+impl Default for BreakerConfig {
+    fn default() -> Self {
+        BreakerConfig {
+            max_consecutive_failures: 5,
+            window: 20,
+            max_error_rate: 0.5,
+            base_backoff_secs: 10,
+            max_backoff_secs: 300,
+            on_trip: TripAction::Hold,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum BreakerState {
+    Closed,
+    Open { until: Instant },
+    HalfOpen,
+}
+
+pub struct CircuitBreaker {
+    pub cfg: BreakerConfig,
+    state: BreakerState,
+    consecutive_failures: u32,
+    recent: VecDeque<bool>, // true = failure
+    failed_probes: u32,
+    pub trips: u32,
+}
+
+impl CircuitBreaker {
+    pub fn new(cfg: BreakerConfig) -> Self {
+        CircuitBreaker {
+            cfg, state: BreakerState::Closed, consecutive_failures: 0,
+            recent: VecDeque::new(), failed_probes: 0, trips: 0,
+        }
+    }
+
+    /// Whether an API call may go out now. An expired Open state lets exactly one probe through.
+    pub fn allow_request(&mut self) -> bool {
+        match self.state {
+            BreakerState::Closed | BreakerState::HalfOpen => true,
+            BreakerState::Open { until } if Instant::now() >= until => {
+                self.state = BreakerState::HalfOpen;
+                true
+            },
+            BreakerState::Open { .. } => false,
+        }
+    }
+
+    /// New orders are only allowed while fully closed.
+    pub fn trading_allowed(&self) -> bool {
+        self.state == BreakerState::Closed
+    }
+
+    fn push(&mut self, failed: bool) {
+        self.recent.push_back(failed);
+        while self.recent.len() > self.cfg.window.max(1) { self.recent.pop_front(); }
+    }
+
+    /// Returns true if this success closed a previously open breaker.
+    pub fn record_success(&mut self) -> bool {
+        self.push(false);
+        self.consecutive_failures = 0;
+        let recovered = self.state != BreakerState::Closed;
+        if recovered {
+            // Start the error-rate window fresh after recovery
+            self.recent.clear();
+            self.failed_probes = 0;
+            self.state = BreakerState::Closed;
+        }
+        recovered
+    }
+
+    /// Returns true if this failure tripped the breaker (Closed -> Open).
+    pub fn record_failure(&mut self) -> bool {
+        self.push(true);
+        self.consecutive_failures += 1;
+
+        match self.state {
+            BreakerState::HalfOpen => {
+                self.failed_probes += 1;
+                self.open();
+                false
+            },
+            BreakerState::Open { .. } => false,
+            BreakerState::Closed => {
+                let failures = self.recent.iter().filter(|f| **f).count();
+                let window_full = self.recent.len() >= self.cfg.window.max(1);
+                let error_rate = failures as f64 / self.recent.len() as f64;
+                if self.consecutive_failures >= self.cfg.max_consecutive_failures
+                    || (window_full && error_rate > self.cfg.max_error_rate)
+                {
+                    self.trips += 1;
+                    self.open();
+                    true
+                } else {
+                    false
+                }
+            },
+        }
+    }
+
+    fn open(&mut self) {
+        let backoff = self.cfg.base_backoff_secs.saturating_mul(1 << self.failed_probes.min(16));
+        let backoff = backoff.min(self.cfg.max_backoff_secs).max(1);
+        self.state = BreakerState::Open { until: Instant::now() + Duration::from_secs(backoff) };
+    }
+
+    /// Written straight into the caller's buffer, nothing is formatted unless it is displayed.
+    pub fn status(&self) -> BreakerState {
+        self.state
+    }
+}
+
+impl fmt::Display for BreakerState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BreakerState::Closed => f.write_str("closed"),
+            BreakerState::HalfOpen => f.write_str("half_open"),
+            BreakerState::Open { until } => {
+                write!(f, "open (probe in {}s)", until.saturating_duration_since(Instant::now()).as_secs())
+            },
+        }
+    }
+}
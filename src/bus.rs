@@ -0,0 +1,278 @@
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use async_nats::jetstream;
+use chrono::Utc;
+use rdkafka::producer::{FutureProducer, FutureRecord};
+use rdkafka::ClientConfig;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{sleep, timeout};
+
+use crate::executor::{Fill, OrderKind, OrderRequest};
+use crate::{simulating, BotState, PAIR};
+
+// --- 📡 EVENT BUS ---
+// Signals, orders, fills and state transitions (position, risk halt, circuit breaker) are
+// published to NATS JetStream or Kafka as JSON, so other instances and downstream analytics can
+// consume a durable, ordered record of what the bot did. Every event carries the instance name and
+// a sequence number that increases by one per event, so a consumer can spot gaps. A single
+// background task publishes them in order and waits for the broker's ack; while the broker is
+// unreachable events are held (up to queue_size) and retried from the oldest. Ticks are not
+// published, the recorder and the gRPC feed cover those.
+
+const CHANNEL_SIZE: usize = 1024;
+const RETRY_AFTER: Duration = Duration::from_secs(5);
+const SEND_TIMEOUT: Duration = Duration::from_secs(10);
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Backend {
+    #[default]
+    None,
+    Nats,
+    Kafka,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BusConfig {
+    pub backend: Backend,
+    pub url: String,      // nats://host:4222, or Kafka bootstrap servers (host1:9092,host2:9092)
+    pub subject: String,  // NATS: subjects are <subject>.<instance>.<type>; Kafka: the topic
+    pub stream: String,   // JetStream stream created over <subject>.> if missing; empty = plain NATS, no persistence
+    pub instance: String, // Tells instances apart; empty = the pair
+    pub queue_size: usize,
+}
+
+impl Default for BusConfig {
+    fn default() -> Self {
+        BusConfig {
+            backend: Backend::None,
+            url: String::new(),
+            subject: "scalper".to_string(),
+            stream: "SCALPER".to_string(),
+            instance: String::new(),
+            queue_size: 10_000,
+        }
+    }
+}
+
+#[derive(Clone, Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BusEvent {
+    Signal { side: &'static str, price: f64, rsi: f64, reason: &'static str },
+    Order { side: &'static str, kind: &'static str, price: Decimal, qty: Decimal, reason: &'static str },
+    Fill { side: &'static str, price: Decimal, qty: Decimal, reason: &'static str },
+    OrderFailed { side: &'static str, price: Decimal, qty: Decimal, reason: &'static str, error: String },
+    Transition { machine: &'static str, from: String, to: String, reason: String }, // position / risk / breaker
+}
+
+impl BusEvent {
+    fn name(&self) -> &'static str {
+        match self {
+            BusEvent::Signal { .. } => "signal",
+            BusEvent::Order { .. } => "order",
+            BusEvent::Fill { .. } => "fill",
+            BusEvent::OrderFailed { .. } => "order_failed",
+            BusEvent::Transition { .. } => "transition",
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct Envelope {
+    instance: String,
+    seq: u64,
+    time: i64, // Unix millis
+    pair: &'static str,
+    simulation: bool,
+    #[serde(flatten)]
+    event: BusEvent,
+}
+
+enum Msg {
+    Event(i64, BusEvent),
+    Flush(oneshot::Sender<()>),
+}
+
+static QUEUE: OnceLock<mpsc::Sender<Msg>> = OnceLock::new();
+static DROPPED: AtomicU64 = AtomicU64::new(0);
+
+pub fn start(cfg: BusConfig) {
+    if cfg.backend == Backend::None { return; }
+    let (tx, rx) = mpsc::channel(CHANNEL_SIZE);
+    if QUEUE.set(tx).is_err() { return; }
+    tokio::spawn(publish_loop(rx, cfg));
+}
+
+/// Queues an event, stamped with the current time; never waits.
+pub fn publish(event: BusEvent) {
+    let Some(queue) = QUEUE.get() else { return };
+    if queue.try_send(Msg::Event(Utc::now().timestamp_millis(), event)).is_err() {
+        DROPPED.fetch_add(1, Ordering::Relaxed);
+    }
+}
+
+/// Waits (up to `wait`) until everything queued so far has reached the broker.
+pub async fn flush(wait: Duration) {
+    let Some(queue) = QUEUE.get() else { return };
+    let (done, flushed) = oneshot::channel();
+    let _ = timeout(wait, async {
+        if queue.send(Msg::Flush(done)).await.is_ok() { let _ = flushed.await; }
+    }).await;
+}
+
+fn kind_name(kind: OrderKind) -> (&'static str, &'static str) {
+    match kind {
+        OrderKind::Entry { reason } => ("entry", reason),
+        OrderKind::Exit { reason } => ("exit", reason),
+        OrderKind::Flatten { reason } => ("flatten", reason),
+    }
+}
+
+pub fn order_sent(request: &OrderRequest) {
+    let (kind, reason) = kind_name(request.kind);
+    publish(BusEvent::Order { side: request.side, kind, price: request.price, qty: request.qty, reason });
+}
+
+/// Publishes the order's fill or failure, and the position change it made, if any (flat to open
+/// or back, not a partial fill).
+pub fn order_done(request: &OrderRequest, result: &Result<Fill, String>, before: BotState, after: BotState) {
+    let (_, reason) = kind_name(request.kind);
+    publish(match result {
+        Ok(fill) => BusEvent::Fill { side: request.side, price: fill.price, qty: fill.qty, reason },
+        Err(e) => BusEvent::OrderFailed { side: request.side, price: request.price, qty: request.qty, reason, error: e.clone() },
+    });
+    let name = |s: BotState| match s {
+        BotState::Idle => "idle",
+        BotState::InPosition { .. } => "in_position",
+    };
+    if name(before) != name(after) {
+        transition("position", name(before), name(after), reason);
+    }
+}
+
+pub fn transition(machine: &'static str, from: &str, to: &str, reason: &str) {
+    publish(BusEvent::Transition { machine, from: from.to_string(), to: to.to_string(), reason: reason.to_string() });
+}
+
+enum Nats {
+    Core(async_nats::Client),
+    JetStream(Box<jetstream::Context>), // Only the stream's ack means an event is stored
+}
+
+enum Sink {
+    Nats(Option<Nats>), // Connected on first use and after a failure
+    Kafka(FutureProducer),
+}
+
+async fn connect_nats(cfg: &BusConfig) -> Result<Nats, String> {
+    let client = async_nats::connect(&cfg.url).await.map_err(|e| e.to_string())?;
+    if cfg.stream.is_empty() { return Ok(Nats::Core(client)); }
+    let js = jetstream::new(client);
+    js.get_or_create_stream(jetstream::stream::Config {
+        name: cfg.stream.clone(),
+        subjects: vec![format!("{}.>", cfg.subject)],
+        ..Default::default()
+    }).await.map_err(|e| format!("stream {}: {}", cfg.stream, e))?;
+    Ok(Nats::JetStream(Box::new(js)))
+}
+
+impl Sink {
+    async fn send(&mut self, cfg: &BusConfig, instance: &str, envelope: &Envelope) -> Result<(), String> {
+        let payload = serde_json::to_vec(envelope).map_err(|e| e.to_string())?;
+        match self {
+            Sink::Nats(conn) => {
+                let nats = match conn {
+                    Some(nats) => nats,
+                    None => conn.insert(connect_nats(cfg).await?),
+                };
+                let subject = format!("{}.{}.{}", cfg.subject, instance, envelope.event.name());
+                let result = match nats {
+                    Nats::Core(client) => client.publish(subject, payload.into()).await.map_err(|e| e.to_string()),
+                    Nats::JetStream(js) => match js.publish(subject, payload.into()).await {
+                        Ok(ack) => match timeout(SEND_TIMEOUT, ack).await {
+                            Ok(acked) => acked.map(|_| ()).map_err(|e| e.to_string()),
+                            Err(_) => Err("no ack from JetStream".to_string()),
+                        },
+                        Err(e) => Err(e.to_string()),
+                    },
+                };
+                if result.is_err() { *conn = None; }
+                result
+            },
+            Sink::Kafka(producer) => {
+                // Keyed by instance: one partition per instance keeps its events in order
+                let record = FutureRecord::to(&cfg.subject).key(instance).payload(&payload);
+                producer.send(record, SEND_TIMEOUT).await.map(|_| ()).map_err(|(e, _)| e.to_string())
+            },
+        }
+    }
+}
+
+async fn publish_loop(mut rx: mpsc::Receiver<Msg>, cfg: BusConfig) {
+    let mut sink = match cfg.backend {
+        Backend::Nats => Sink::Nats(None),
+        Backend::Kafka => {
+            let producer = ClientConfig::new()
+                .set("bootstrap.servers", &cfg.url)
+                .set("enable.idempotence", "true") // Retries can't duplicate or reorder
+                .create();
+            match producer {
+                Ok(producer) => Sink::Kafka(producer),
+                Err(e) => return eprintln!("❌ Event bus disabled: Kafka: {}", e),
+            }
+        },
+        Backend::None => return,
+    };
+    let instance = if cfg.instance.is_empty() { PAIR.to_string() } else { cfg.instance.clone() };
+    let mut seq = 0;
+    let mut pending: VecDeque<Envelope> = VecDeque::new();
+    let mut waiting: Vec<oneshot::Sender<()>> = Vec::new();
+    loop {
+        // Waits for the next event, or while events are held, at most until the next retry
+        let first = if pending.is_empty() {
+            let Some(msg) = rx.recv().await else { return };
+            Some(msg)
+        } else {
+            tokio::select! {
+                msg = rx.recv() => match msg {
+                    Some(msg) => Some(msg),
+                    None => return,
+                },
+                _ = sleep(RETRY_AFTER) => None,
+            }
+        };
+        for msg in first.into_iter().chain(std::iter::from_fn(|| rx.try_recv().ok())) {
+            match msg {
+                Msg::Event(time, event) => {
+                    seq += 1;
+                    pending.push_back(Envelope { instance: instance.clone(), seq, time, pair: PAIR, simulation: simulating(), event });
+                },
+                Msg::Flush(done) => waiting.push(done),
+            }
+        }
+        while pending.len() > cfg.queue_size {
+            pending.pop_front();
+            DROPPED.fetch_add(1, Ordering::Relaxed);
+        }
+        let dropped = DROPPED.swap(0, Ordering::Relaxed);
+        if dropped > 0 { eprintln!("Event Bus: {} events dropped", dropped); }
+
+        while let Some(envelope) = pending.front() {
+            match sink.send(&cfg, &instance, envelope).await {
+                Ok(()) => { pending.pop_front(); },
+                Err(e) => {
+                    eprintln!("Event Bus Error ({:?}): {} ({} events held)", cfg.backend, e, pending.len());
+                    break;
+                },
+            }
+        }
+        if pending.is_empty() {
+            for done in waiting.drain(..) { let _ = done.send(()); }
+        }
+    }
+}
@@ -0,0 +1,442 @@
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use futures_util::{SinkExt, StreamExt};
+use rust_decimal::Decimal;
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use tokio::sync::mpsc;
+use tokio::time::{sleep, timeout_at, Instant};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::executor::Fill;
+use crate::feed::{self, Flow, StreamTick};
+use crate::keystore::{self, Credentials};
+use crate::orderbook::DepthResponse;
+use crate::reconcile::Execution;
+use crate::risk::WalletBalance;
+use crate::symbols::{self, Environment};
+use crate::userstream::{self, AccountEvent};
+use crate::{money, ratelimit, Candle};
+
+// --- 🟡 BYBIT (USDT PERPETUALS) ---
+// Runs the bot on a Bybit linear perpetual instead of CoinDCX spot: candles and the order book over
+// the v5 REST API, trades over the public WebSocket, and orders, balances, leverage and the
+// position through the signed v5 API (HMAC of timestamp + key + recv window + query or body).
+// The position is one-way and the bot only goes long, so every sell is reduce-only: it can close
+// the position but never open a short. Orders are limit IOC and their fill is read back from the
+// order's status. Credentials come from BYBIT_API_KEY / BYBIT_API_SECRET, or the keystore. With
+// environment = "testnet" everything goes to Bybit's testnet, with BYBIT_TESTNET_API_KEY /
+// BYBIT_TESTNET_API_SECRET. Read at startup.
+
+const REST_URL: &str = "https://api.bybit.com";
+const STREAM_URL: &str = "wss://stream.bybit.com/v5/public/linear";
+const WARM_URL: &str = "https://api.bybit.com/v5/market/time";
+const TESTNET_REST_URL: &str = "https://api-testnet.bybit.com";
+const TESTNET_STREAM_URL: &str = "wss://stream-testnet.bybit.com/v5/public/linear";
+const PRIVATE_URL: &str = "wss://stream.bybit.com/v5/private";
+const TESTNET_PRIVATE_URL: &str = "wss://stream-testnet.bybit.com/v5/private";
+const TESTNET_WARM_URL: &str = "https://api-testnet.bybit.com/v5/market/time";
+const PING_EVERY: Duration = Duration::from_secs(20);
+const ORDER_POLL: Duration = Duration::from_millis(200);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct BybitConfig {
+    pub enabled: bool,
+    pub environment: Environment, // mainnet, or testnet (api-testnet.bybit.com, testnet keys)
+    pub leverage: f64,       // Set on the symbol at startup (live only)
+    pub recv_window_ms: u64, // How long a signed request stays valid
+}
+
+impl Default for BybitConfig {
+    fn default() -> Self {
+        BybitConfig { enabled: false, environment: Environment::Mainnet, leverage: 1.0, recv_window_ms: 5000 }
+    }
+}
+
+static CONFIG: OnceLock<BybitConfig> = OnceLock::new();
+
+/// Selects Bybit for the rest of the process when enabled; called before anything talks to an exchange.
+pub fn configure(cfg: BybitConfig) {
+    if !cfg.enabled { return; }
+    if cfg.environment == Environment::Testnet { println!("🧪 Bybit testnet: {}", TESTNET_REST_URL); }
+    let _ = CONFIG.set(cfg);
+}
+
+pub fn enabled() -> bool {
+    CONFIG.get().is_some()
+}
+
+fn config() -> &'static BybitConfig {
+    CONFIG.get_or_init(BybitConfig::default)
+}
+
+pub fn environment() -> Environment {
+    CONFIG.get().map_or(Environment::Mainnet, |cfg| cfg.environment)
+}
+
+fn testnet() -> bool {
+    environment() == Environment::Testnet
+}
+
+fn rest_url() -> &'static str {
+    if testnet() { TESTNET_REST_URL } else { REST_URL }
+}
+
+/// Cheap public endpoint for keeping the order connection warm.
+pub fn warm_url() -> &'static str {
+    if testnet() { TESTNET_WARM_URL } else { WARM_URL }
+}
+
+fn symbol() -> &'static str {
+    symbols::active().order_code
+}
+
+fn credentials() -> Credentials {
+    keystore::credentials().unwrap_or_else(|| match testnet() {
+        true => Credentials::from_env("BYBIT_TESTNET_API_KEY", "BYBIT_TESTNET_API_SECRET", None),
+        false => Credentials::from_env("BYBIT_API_KEY", "BYBIT_API_SECRET", None),
+    })
+}
+
+fn signed_headers(payload: &str) -> HeaderMap {
+    let Credentials { key, secret, .. } = credentials();
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis().to_string();
+    let recv_window = config().recv_window_ms.to_string();
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("Invalid Key");
+    mac.update(format!("{}{}{}{}", timestamp, key, recv_window, payload).as_bytes());
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    headers.insert("X-BAPI-API-KEY", HeaderValue::from_str(&key).unwrap_or(HeaderValue::from_static("")));
+    headers.insert("X-BAPI-TIMESTAMP", HeaderValue::from_str(&timestamp).unwrap());
+    headers.insert("X-BAPI-RECV-WINDOW", HeaderValue::from_str(&recv_window).unwrap());
+    headers.insert("X-BAPI-SIGN", HeaderValue::from_str(&hex::encode(mac.finalize().into_bytes())).unwrap());
+    headers
+}
+
+// Every v5 response: retCode 0 or an error with retMsg, even on HTTP 200
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Reply<T> {
+    ret_code: i64,
+    ret_msg: String,
+    result: Option<T>,
+}
+
+async fn decode<T: DeserializeOwned>(res: reqwest::Response) -> Result<T, String> {
+    // Only the traded venue's budget is tracked (the spread monitor polls the others)
+    if enabled() { ratelimit::observe(res.status(), res.headers()); }
+    let status = res.status();
+    let reply: Reply<T> = res.json().await.map_err(|e| format!("HTTP {}: {}", status, e))?;
+    match (reply.ret_code, reply.result) {
+        (0, Some(result)) => Ok(result),
+        (code, _) => Err(format!("Bybit {}: {}", code, reply.ret_msg)),
+    }
+}
+
+async fn public<T: DeserializeOwned>(client: &reqwest::Client, path: &str, query: &[(&str, &str)], timeout: Duration) -> Result<T, String> {
+    let res = client.get(format!("{}{}", rest_url(), path)).query(query).timeout(timeout).send().await.map_err(|e| e.to_string())?;
+    decode(res).await
+}
+
+async fn signed_get<T: DeserializeOwned>(client: &reqwest::Client, path: &str, query: &[(&str, &str)], timeout: Duration) -> Result<T, String> {
+    let query = query_string(query);
+    let res = client.get(format!("{}{}?{}", rest_url(), path, query)).headers(signed_headers(&query)).timeout(timeout).send().await
+        .map_err(|e| e.to_string())?;
+    decode(res).await
+}
+
+async fn signed_post<T: DeserializeOwned>(client: &reqwest::Client, path: &str, body: Value, timeout: Duration) -> Result<T, String> {
+    let body = body.to_string();
+    let res = client.post(format!("{}{}", rest_url(), path)).headers(signed_headers(&body)).body(body).timeout(timeout).send().await
+        .map_err(|e| e.to_string())?;
+    decode(res).await
+}
+
+// The signature covers the query string exactly as sent
+fn query_string(query: &[(&str, &str)]) -> String {
+    let mut url = reqwest::Url::parse(REST_URL).unwrap();
+    url.query_pairs_mut().extend_pairs(query);
+    url.query().unwrap_or("").to_string()
+}
+
+fn num(v: &Value) -> f64 {
+    v.as_str().and_then(|s| s.parse().ok()).or_else(|| v.as_f64()).unwrap_or(0.0)
+}
+
+#[derive(Deserialize)]
+struct List {
+    list: Vec<Value>,
+}
+
+/// 1m candles, newest first like CoinDCX's.
+pub async fn candles(client: &reqwest::Client, limit: u32, timeout: Duration) -> Result<Vec<Candle>, String> {
+    let limit = limit.min(1000).to_string();
+    let symbol = symbol();
+    let klines: List = public(client, "/v5/market/kline", &[("category", "linear"), ("symbol", symbol), ("interval", "1"), ("limit", &limit)], timeout).await?;
+    // [start, open, high, low, close, volume, turnover]
+    Ok(klines.list.iter().map(|k| Candle { time: num(&k[0]) as i64, open: num(&k[1]), high: num(&k[2]), low: num(&k[3]), close: num(&k[4]) }).collect())
+}
+
+pub async fn latest_price(client: &reqwest::Client, timeout: Duration) -> Result<Option<f64>, String> {
+    last_price(client, symbol(), timeout).await
+}
+
+/// Last traded price of any linear symbol.
+pub async fn last_price(client: &reqwest::Client, symbol: &str, timeout: Duration) -> Result<Option<f64>, String> {
+    let tickers: List = public(client, "/v5/market/tickers", &[("category", "linear"), ("symbol", symbol)], timeout).await?;
+    Ok(tickers.list.first().map(|t| num(&t["lastPrice"])).filter(|p| *p > 0.0))
+}
+
+/// Order book snapshot in CoinDCX's shape, so the local book is built the same way.
+pub async fn depth(client: &reqwest::Client) -> Result<DepthResponse, String> {
+    let book: Value = public(client, "/v5/market/orderbook", &[("category", "linear"), ("symbol", symbol()), ("limit", "50")], Duration::from_secs(5)).await?;
+    let levels = |side: &Value| side.as_array().into_iter().flatten()
+        .filter_map(|l| Some((l[0].as_str()?.to_string(), l[1].as_str()?.to_string())))
+        .collect();
+    Ok(DepthResponse { bids: levels(&book["b"]), asks: levels(&book["a"]), vs: None })
+}
+
+/// Public trades into the trading loop, like the CoinDCX stream. Returns Ok once the receiver is gone.
+pub async fn stream_trades(tx: &mpsc::Sender<StreamTick>) -> Result<(), String> {
+    let (ws, _) = connect_async(if testnet() { TESTNET_STREAM_URL } else { STREAM_URL }).await.map_err(|e| e.to_string())?;
+    let (mut write, mut read) = ws.split();
+    let topic = format!("publicTrade.{}", symbol());
+    write.send(Message::Text(json!({ "op": "subscribe", "args": [topic] }).to_string())).await.map_err(|e| e.to_string())?;
+    let mut next_ping = Instant::now() + PING_EVERY;
+    loop {
+        let msg = match timeout_at(next_ping, read.next()).await {
+            Err(_) => {
+                write.send(Message::Text(r#"{"op":"ping"}"#.to_string())).await.map_err(|e| e.to_string())?;
+                next_ping = Instant::now() + PING_EVERY;
+                continue;
+            },
+            Ok(None) => return Err("connection closed".to_string()),
+            Ok(Some(msg)) => msg.map_err(|e| e.to_string())?,
+        };
+        let text = match msg {
+            Message::Text(text) => text,
+            Message::Close(_) => return Err("closed by server".to_string()),
+            _ => continue,
+        };
+        let event: Value = serde_json::from_str(&text).unwrap_or_default();
+        if event["op"] == "subscribe" && event["success"] == false {
+            return Err(format!("subscribe to {} refused: {}", topic, event["ret_msg"]));
+        }
+        if event["topic"] != topic.as_str() { continue; }
+        // Oldest first within a message
+        for trade in event["data"].as_array().into_iter().flatten() {
+            let price = num(&trade["p"]);
+            if price <= 0.0 { continue; }
+            if let Flow::Stop = feed::deliver(tx, price) { return Ok(()); }
+        }
+    }
+}
+
+/// The account's private stream: authenticates (HMAC of "GET/realtime" + expiry), then order
+/// updates, the USDT wallet and the position size go to `on_event`. Returns Err when it drops.
+pub async fn stream_account(on_event: &mut impl FnMut(AccountEvent)) -> Result<(), String> {
+    let (ws, _) = connect_async(if testnet() { TESTNET_PRIVATE_URL } else { PRIVATE_URL }).await.map_err(|e| e.to_string())?;
+    let (mut write, mut read) = ws.split();
+    let Credentials { key, secret, .. } = credentials();
+    let expires = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() + 10_000;
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("Invalid Key");
+    mac.update(format!("GET/realtime{}", expires).as_bytes());
+    let auth = json!({ "op": "auth", "args": [key, expires, hex::encode(mac.finalize().into_bytes())] });
+    write.send(Message::Text(auth.to_string())).await.map_err(|e| e.to_string())?;
+    let mut next_ping = Instant::now() + PING_EVERY;
+    loop {
+        let msg = match timeout_at(next_ping, read.next()).await {
+            Err(_) => {
+                write.send(Message::Text(r#"{"op":"ping"}"#.to_string())).await.map_err(|e| e.to_string())?;
+                next_ping = Instant::now() + PING_EVERY;
+                continue;
+            },
+            Ok(None) => return Err("connection closed".to_string()),
+            Ok(Some(msg)) => msg.map_err(|e| e.to_string())?,
+        };
+        let text = match msg {
+            Message::Text(text) => text,
+            Message::Close(_) => return Err("closed by server".to_string()),
+            _ => continue,
+        };
+        let event: Value = serde_json::from_str(&text).unwrap_or_default();
+        match (event["op"].as_str(), event["topic"].as_str()) {
+            (Some("auth"), _) if event["success"] == false => return Err(format!("auth refused: {}", event["ret_msg"])),
+            (Some("auth"), _) => {
+                let subscribe = json!({ "op": "subscribe", "args": ["order", "wallet", "position"] });
+                write.send(Message::Text(subscribe.to_string())).await.map_err(|e| e.to_string())?;
+            },
+            (Some("subscribe"), _) if event["success"] == false => return Err(format!("subscribe refused: {}", event["ret_msg"])),
+            (Some("subscribe"), _) => on_event(AccountEvent::Ready),
+            (_, Some("order")) => for order in event["data"].as_array().into_iter().flatten() {
+                if order["symbol"] != symbol() { continue; }
+                let (Some(link_id), Some(outcome)) = (order["orderLinkId"].as_str(), outcome(order)) else { continue };
+                on_event(AccountEvent::Order { client_id: link_id.to_string(), outcome });
+            },
+            (_, Some("wallet")) => {
+                let quote = symbols::active().quote();
+                let usdt = event["data"].as_array().into_iter().flatten()
+                    .find_map(|account| account["coin"].as_array()?.iter().find(|c| c["coin"] == quote).map(|c| money::from_value(&c["walletBalance"])));
+                if usdt.is_some() { on_event(AccountEvent::Balances { quote: usdt, base: None }); }
+            },
+            (_, Some("position")) => for p in event["data"].as_array().into_iter().flatten() {
+                if p["symbol"] != symbol() { continue; }
+                let size = if p["side"] == "Sell" { -money::from_value(&p["size"]) } else { money::from_value(&p["size"]) };
+                on_event(AccountEvent::Balances { quote: None, base: Some(size) });
+            },
+            _ => {}, // Pongs
+        }
+    }
+}
+
+/// Checks the symbol is trading and hands its order size and price steps to the registry.
+pub async fn instrument(client: &reqwest::Client) -> Result<String, String> {
+    let symbol = symbol();
+    let info: List = public(client, "/v5/market/instruments-info", &[("category", "linear"), ("symbol", symbol)], Duration::from_secs(10)).await?;
+    let item = info.list.first().ok_or_else(|| format!("{} not listed", symbol))?;
+    if item["status"] != "Trading" {
+        return Err(format!("{} status is {}", symbol, item["status"]));
+    }
+    let (lot, tick) = (&item["lotSizeFilter"], money::from_value(&item["priceFilter"]["tickSize"]));
+    let qty_step = money::from_value(&lot["qtyStep"]);
+    symbols::refine(qty_step, tick, money::from_value(&lot["minOrderQty"]), money::from_value(&lot["minNotionalValue"]));
+    Ok(format!("{} trading (qty step {}, tick {})", symbol, qty_step, tick))
+}
+
+/// The unified account's USDT (wallet balance, of it the margin in use) and the open long's size,
+/// which stand in for CoinDCX's USDT and BTC balances.
+pub async fn wallet(client: &reqwest::Client, timeout: Duration) -> Result<(WalletBalance, Decimal), String> {
+    let quote = symbols::active().quote();
+    let wallet: List = signed_get(client, "/v5/account/wallet-balance", &[("accountType", "UNIFIED"), ("coin", quote)], timeout).await?;
+    let coin = wallet.list.first().and_then(|account| account["coin"].as_array()?.iter().find(|c| c["coin"] == quote).cloned()).unwrap_or_default();
+    let balance = money::from_value(&coin["walletBalance"]);
+    let locked = money::from_value(&coin["totalPositionIM"]) + money::from_value(&coin["totalOrderIM"]) + money::from_value(&coin["locked"]);
+    Ok((WalletBalance::new("unified", (balance - locked).max(Decimal::ZERO), locked), position(client, timeout).await?))
+}
+
+/// USDT in the funding account, which the bot can't trade with until it is transferred.
+pub async fn funding(client: &reqwest::Client, timeout: Duration) -> WalletBalance {
+    let quote = symbols::active().quote();
+    let result: Result<Value, String> = signed_get(client, "/v5/asset/transfer/query-account-coins-balance", &[("accountType", "FUND"), ("coin", quote)], timeout).await;
+    match result {
+        Ok(result) => {
+            let coin = result["balance"].as_array().and_then(|coins| coins.iter().find(|c| c["coin"] == quote).cloned()).unwrap_or_default();
+            let (balance, free) = (money::from_value(&coin["walletBalance"]), money::from_value(&coin["transferBalance"]));
+            WalletBalance::new("funding", free, (balance - free).max(Decimal::ZERO))
+        },
+        Err(e) => WalletBalance::failed("funding", e),
+    }
+}
+
+/// Size of the open position (positive long, negative short).
+pub async fn position(client: &reqwest::Client, timeout: Duration) -> Result<Decimal, String> {
+    let positions: List = signed_get(client, "/v5/position/list", &[("category", "linear"), ("symbol", symbol())], timeout).await?;
+    Ok(positions.list.iter().map(|p| if p["side"] == "Sell" { -money::from_value(&p["size"]) } else { money::from_value(&p["size"]) }).sum())
+}
+
+/// Executions on the symbol since `since_ms` that did not come from the bot's orders.
+pub async fn fills(client: &reqwest::Client, since_ms: i64, timeout: Duration) -> Result<Vec<Execution>, String> {
+    let since = since_ms.to_string();
+    let executions: List = signed_get(client, "/v5/execution/list", &[("category", "linear"), ("symbol", symbol()), ("startTime", &since)], timeout).await?;
+    Ok(executions.list.iter()
+        .filter(|e| e["execType"] == "Trade" && !e["orderLinkId"].as_str().unwrap_or("").starts_with("scalper-"))
+        .map(|e| Execution { sell: e["side"] == "Sell", fill: Fill { price: money::from_value(&e["execPrice"]), qty: money::from_value(&e["execQty"]) } })
+        .collect())
+}
+
+/// Sets the configured leverage on the symbol; also proves the key can trade.
+pub async fn set_leverage(client: &reqwest::Client) -> Result<String, String> {
+    let leverage = config().leverage.to_string();
+    let body = json!({ "category": "linear", "symbol": symbol(), "buyLeverage": leverage, "sellLeverage": leverage });
+    match signed_post::<Value>(client, "/v5/position/set-leverage", body, Duration::from_secs(10)).await {
+        Ok(_) => Ok(format!("leverage {}x", leverage)),
+        // 110043: already at that leverage
+        Err(e) if e.starts_with("Bybit 110043") => Ok(format!("leverage {}x", leverage)),
+        Err(e) => Err(e),
+    }
+}
+
+fn order_body(side: &str, order_type: &str, qty: Decimal, extra: Value) -> Value {
+    let market = symbols::active();
+    let mut body = json!({
+        "category": "linear", "symbol": symbol(), "side": if side == "buy" { "Buy" } else { "Sell" },
+        "orderType": order_type, "qty": market.qty(qty), "positionIdx": 0,
+        // Long only: a sell can only close the position, never open a short
+        "reduceOnly": side == "sell",
+    });
+    if let (Some(body), Value::Object(extra)) = (body.as_object_mut(), extra) {
+        for (k, v) in extra {
+            let v = match (k.as_str(), &v) {
+                ("price" | "triggerPrice", Value::Number(_)) => Value::String(market.price(money::from_value(&v))),
+                _ => v,
+            };
+            body.insert(k, v);
+        }
+    }
+    body
+}
+
+/// Limit IOC order; waits for its final status and returns what filled.
+pub async fn place(client: &reqwest::Client, side: &str, price: Decimal, qty: Decimal, wait: Duration) -> Result<Fill, String> {
+    let deadline = Instant::now() + wait;
+    ratelimit::order_permit().await?;
+    let link_id = format!("scalper-{}", SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis());
+    let body = order_body(side, "Limit", qty, json!({ "price": price, "timeInForce": "IOC", "orderLinkId": link_id }));
+    let watch = userstream::watch(&link_id);
+    signed_post::<Value>(client, "/v5/order/create", body, wait).await.map_err(|e| format!("order rejected: {}", e))?;
+    if let Some(outcome) = userstream::report(watch, deadline).await { return outcome; }
+    loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() { return Err(format!("order {} status unknown after {:?}", link_id, wait)); }
+        let orders: List = signed_get(client, "/v5/order/realtime", &[("category", "linear"), ("orderLinkId", &link_id)], left).await?;
+        if let Some(outcome) = orders.list.first().and_then(outcome) { return outcome; }
+        sleep(ORDER_POLL).await;
+    }
+}
+
+// An order's outcome once it is final, the same from the REST order and the order stream
+fn outcome(order: &Value) -> Option<Result<Fill, String>> {
+    let filled = money::from_value(&order["cumExecQty"]);
+    let avg_price = money::from_value(&order["avgPrice"]);
+    match order["orderStatus"].as_str().unwrap_or("") {
+        "Filled" => Some(Ok(Fill { price: avg_price, qty: filled })),
+        "Cancelled" | "PartiallyFilledCanceled" | "Deactivated" if filled > Decimal::ZERO => Some(Ok(Fill { price: avg_price, qty: filled })),
+        "Cancelled" | "PartiallyFilledCanceled" | "Deactivated" => Some(Err("not filled (IOC canceled)".to_string())),
+        "Rejected" => Some(Err(format!("order rejected: {}", order["rejectReason"]))),
+        _ => None,
+    }
+}
+
+// Blocking versions for the panic hook, which runs outside the async runtime
+fn blocking_post(path: &str, body: Value) -> Result<Value, String> {
+    let body = body.to_string();
+    let client = reqwest::blocking::Client::builder().timeout(Duration::from_secs(5)).build().map_err(|e| e.to_string())?;
+    let reply: Reply<Value> = client.post(format!("{}{}", rest_url(), path)).headers(signed_headers(&body)).body(body).send()
+        .map_err(|e| e.to_string())?.json().map_err(|e| e.to_string())?;
+    match (reply.ret_code, reply.result) {
+        (0, Some(result)) => Ok(result),
+        (code, _) => Err(format!("Bybit {}: {}", code, reply.ret_msg)),
+    }
+}
+
+/// Reduce-only stop-market sell, triggered when the last price falls to `stop_price`; returns its order id.
+pub fn place_stop(stop_price: Decimal, quantity: Decimal) -> Result<String, String> {
+    let body = order_body("sell", "Market", quantity, json!({ "triggerPrice": stop_price, "triggerDirection": 2 }));
+    let result = blocking_post("/v5/order/create", body)?;
+    result["orderId"].as_str().map(str::to_string).ok_or_else(|| "no order id in response".to_string())
+}
+
+pub fn market_sell(quantity: Decimal) -> Result<(), String> {
+    blocking_post("/v5/order/create", order_body("sell", "Market", quantity, json!({}))).map(|_| ())
+}
+
+pub async fn cancel(client: &reqwest::Client, order_id: &str) -> Result<(), String> {
+    let body = json!({ "category": "linear", "symbol": symbol(), "orderId": order_id });
+    signed_post::<Value>(client, "/v5/order/cancel", body, Duration::from_secs(5)).await.map(|_| ())
+}
@@ -0,0 +1,77 @@
+use std::sync::OnceLock;
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveTime, Utc};
+use serde::{Deserialize, Serialize};
+
+// --- 📅 TRADING CALENDAR ---
+// By default 1m candles close on UTC minute boundaries and the trading day (daily P&L, the daily
+// summary, the daily loss limit) rolls over at UTC midnight. utc_offset sets a local time zone as
+// a fixed offset, day_start the local time the trading day begins (09:15 for the Indian session),
+// and candle_offset_secs shifts the minute boundaries. Read once at startup; the trading loop, the
+// tick recorder and the dashboard all go through these functions so they agree. Candles fetched
+// from the exchange keep the exchange's own boundaries.
+
+const CANDLE_MS: i64 = 60_000;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct CalendarConfig {
+    pub utc_offset: String,      // "+05:30"
+    pub day_start: String,       // Local "HH:MM"
+    pub candle_offset_secs: u32, // 0-59
+}
+
+impl Default for CalendarConfig {
+    fn default() -> Self {
+        CalendarConfig { utc_offset: "+00:00".to_string(), day_start: "00:00".to_string(), candle_offset_secs: 0 }
+    }
+}
+
+struct Calendar {
+    offset: FixedOffset,
+    day_start: chrono::Duration, // Since local midnight
+    candle_offset_ms: i64,
+}
+
+static ACTIVE: OnceLock<Calendar> = OnceLock::new();
+
+fn parse(cfg: &CalendarConfig) -> Result<Calendar, String> {
+    let offset: FixedOffset = cfg.utc_offset.parse().map_err(|_| format!("[calendar] utc_offset {:?} is not like +05:30", cfg.utc_offset))?;
+    let start = NaiveTime::parse_from_str(&cfg.day_start, "%H:%M").map_err(|_| format!("[calendar] day_start {:?} is not like 09:15", cfg.day_start))?;
+    if cfg.candle_offset_secs >= 60 {
+        return Err(format!("[calendar] candle_offset_secs {} must be below 60", cfg.candle_offset_secs));
+    }
+    Ok(Calendar {
+        offset,
+        day_start: start - NaiveTime::MIN,
+        candle_offset_ms: i64::from(cfg.candle_offset_secs) * 1000,
+    })
+}
+
+pub fn validate(cfg: &CalendarConfig) -> Result<(), String> {
+    parse(cfg).map(|_| ())
+}
+
+pub fn configure(cfg: &CalendarConfig) {
+    if let Ok(calendar) = parse(cfg) { let _ = ACTIVE.set(calendar); }
+}
+
+fn active() -> &'static Calendar {
+    ACTIVE.get_or_init(|| parse(&CalendarConfig::default()).unwrap())
+}
+
+/// Start (millis) of the 1m candle that `ts` falls in.
+pub fn candle_start(ts: i64) -> i64 {
+    let shift = active().candle_offset_ms;
+    (ts - shift).div_euclid(CANDLE_MS) * CANDLE_MS + shift
+}
+
+/// The trading day `at` belongs to, named by the local date it starts on.
+pub fn trading_day(at: DateTime<Utc>) -> NaiveDate {
+    let calendar = active();
+    (at.with_timezone(&calendar.offset) - calendar.day_start).date_naive()
+}
+
+/// The local offset, for the dashboard to show times in.
+pub fn utc_offset_mins() -> i32 {
+    active().offset.local_minus_utc() / 60
+}
@@ -0,0 +1,138 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+use ta::indicators::RelativeStrengthIndex;
+use ta::Next;
+
+use crate::calendar;
+use crate::indicators::MIN_HISTORY;
+use crate::{Candle, DbManager};
+
+// --- 🕯️ LIVE CANDLES (in-memory) ---
+// The indicators advance once per closed candle. A tick only updates the candle being built and
+// a preview of the indicators with it as the latest close; nothing touches the database until
+// the candle closes at the minute boundary. The preview runs on every tick, so it allocates nothing:
+// the RSI state is plain data and the bands are computed over a fixed window of closes.
+
+pub const WARMUP: usize = 200; // Stored candles replayed into the indicators at startup
+const CANDLE_MS: i64 = 60_000;
+const BB_PERIOD: usize = 20;
+const BB_WIDTH: f64 = 2.0; // Standard deviations
+
+#[derive(Clone, Copy, Debug, Default, Serialize, Deserialize)]
+pub struct Readings {
+    pub rsi: f64,
+    pub bb_lower: f64,
+    pub bb_upper: f64,
+}
+
+/// A finalized candle with the indicator values it produced.
+pub struct CandleClosed {
+    pub candle: Candle,
+    pub readings: Readings,
+}
+
+// Serializable so a warm restart can pick up exactly here (see snapshot.rs)
+#[derive(Clone, Serialize, Deserialize)]
+pub struct LiveCandles {
+    closes: VecDeque<f64>, // Last BB_PERIOD closes, for the Bollinger Bands
+    rsi: RelativeStrengthIndex,
+    last: Readings, // As of the last closed candle
+    current: Option<Candle>,
+    seen: usize, // Closed candles the indicators have had, up to MIN_HISTORY
+}
+
+impl LiveCandles {
+    /// Warms up from the candles already in the DB (history sync or a previous run).
+    /// A stored candle for the minute at `now_ms` is still open and is continued instead.
+    pub fn load(now_ms: i64) -> Self {
+        let mut live = LiveCandles {
+            closes: VecDeque::with_capacity(BB_PERIOD),
+            rsi: RelativeStrengthIndex::new(14).unwrap(),
+            last: Readings::default(),
+            current: None,
+            seen: 0,
+        };
+        let open_minute = calendar::candle_start(now_ms);
+        for candle in DbManager::get_recent_candles(WARMUP).unwrap_or_default() {
+            if candle.time >= open_minute {
+                live.current = Some(candle);
+            } else {
+                live.advance(candle.close);
+            }
+        }
+        live
+    }
+
+    fn advance(&mut self, close: f64) -> Readings {
+        self.seen = (self.seen + 1).min(MIN_HISTORY);
+        if self.closes.len() == BB_PERIOD { self.closes.pop_front(); }
+        self.closes.push_back(close);
+        let (bb_lower, bb_upper) = bands(self.closes.iter().copied());
+        self.last = Readings { rsi: self.rsi.next(close), bb_lower, bb_upper };
+        self.last
+    }
+
+    fn finalize(&mut self) -> Option<CandleClosed> {
+        let candle = self.current.take()?;
+        let readings = self.advance(candle.close);
+        Some(CandleClosed { candle, readings })
+    }
+
+    /// Applies a trade at `ts` (millis). Closes the previous candle if this one is from a later minute.
+    pub fn update(&mut self, ts: i64, price: f64) -> Option<CandleClosed> {
+        let start = calendar::candle_start(ts);
+        let closed = match &self.current {
+            Some(c) if c.time != start => self.finalize(),
+            _ => None,
+        };
+        match &mut self.current {
+            Some(c) => {
+                c.close = price;
+                if price > c.high { c.high = price; }
+                if price < c.low { c.low = price; }
+            },
+            None => self.current = Some(Candle { open: price, high: price, low: price, close: price, time: start }),
+        }
+        closed
+    }
+
+    /// Closes the candle once its minute is over, even if no trade came after it.
+    pub fn close_due(&mut self, now_ms: i64) -> Option<CandleClosed> {
+        match &self.current {
+            Some(c) if now_ms >= c.time + CANDLE_MS => self.finalize(),
+            _ => None,
+        }
+    }
+
+    /// Time until the candle being built is due to close.
+    pub fn due_in(&self, now_ms: i64) -> Option<Duration> {
+        self.current.as_ref().map(|c| Duration::from_millis((c.time + CANDLE_MS - now_ms).max(0) as u64))
+    }
+
+    /// Closed candles still needed before the indicators are trusted; None once they are.
+    pub fn warming_up(&self) -> Option<usize> {
+        (self.seen < MIN_HISTORY).then(|| MIN_HISTORY - self.seen)
+    }
+
+    pub fn current(&self) -> Option<&Candle> {
+        self.current.as_ref()
+    }
+
+    /// Indicators with the candle being built as the latest close (the indicators themselves don't move).
+    pub fn preview(&self) -> Readings {
+        let Some(c) = &self.current else { return self.last };
+        let skip = usize::from(self.closes.len() == BB_PERIOD); // The oldest close drops out of the window
+        let (bb_lower, bb_upper) = bands(self.closes.iter().skip(skip).copied().chain([c.close]));
+        Readings { rsi: self.rsi.clone().next(c.close), bb_lower, bb_upper }
+    }
+}
+
+/// Lower and upper Bollinger Band (population standard deviation, as in `ta`).
+fn bands(closes: impl Iterator<Item = f64> + Clone) -> (f64, f64) {
+    let (n, sum) = closes.clone().fold((0usize, 0.0), |(n, sum), c| (n + 1, sum + c));
+    if n == 0 { return (0.0, 0.0); }
+    let mean = sum / n as f64;
+    let sd = (closes.map(|c| (c - mean).powi(2)).sum::<f64>() / n as f64).sqrt();
+    (mean - BB_WIDTH * sd, mean + BB_WIDTH * sd)
+}
@@ -0,0 +1,122 @@
+use std::collections::VecDeque;
+use std::time::Duration;
+use parking_lot::Mutex;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::time::sleep;
+
+use crate::{money, simulating, symbols};
+
+// --- 🐒 CHAOS MODE (failure injection, simulation only) ---
+// Randomly breaks the exchange layer so the breaker, outage handling, partial-fill and risk paths
+// get exercised before real money depends on them. Ignored when SIMULATION_MODE is off.
+
+#[derive(Clone, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ChaosConfig {
+    pub enabled: bool,
+    pub seed: u64,
+    pub error_rate: f64,        // 0.0 - 1.0 per exchange call: fails immediately
+    pub timeout_rate: f64,      // Hangs for the call's timeout budget, then fails
+    pub partial_fill_rate: f64, // Orders fill only 10-90% of the quantity
+    pub out_of_order_rate: f64, // Price ticks replaced by one of the last few (stale) prices
+}
+
+impl Default for ChaosConfig {
+    fn default() -> Self {
+        ChaosConfig {
+            enabled: false,
+            seed: 7,
+            error_rate: 0.05,
+            timeout_rate: 0.02,
+            partial_fill_rate: 0.2,
+            out_of_order_rate: 0.1,
+        }
+    }
+}
+
+struct Chaos {
+    cfg: ChaosConfig,
+    rng: StdRng,
+    recent_ticks: VecDeque<f64>,
+}
+
+static CHAOS: Mutex<Option<Chaos>> = Mutex::new(None);
+
+/// Applies the `[chaos]` section (at startup and on every config reload).
+pub fn configure(cfg: &ChaosConfig) {
+    let mut chaos = CHAOS.lock();
+    if chaos.as_ref().map(|c| &c.cfg) == Some(cfg) || (chaos.is_none() && !cfg.enabled) {
+        return;
+    }
+    if cfg.enabled && !simulating() {
+        eprintln!("⚠️ [chaos] is ignored outside simulation");
+        *chaos = None;
+        return;
+    }
+    if cfg.enabled {
+        eprintln!("🐒 CHAOS MODE: errors {:.0}%, timeouts {:.0}%, partial fills {:.0}%, stale ticks {:.0}%",
+            cfg.error_rate * 100.0, cfg.timeout_rate * 100.0, cfg.partial_fill_rate * 100.0, cfg.out_of_order_rate * 100.0);
+    }
+    *chaos = cfg.enabled.then(|| Chaos { cfg: cfg.clone(), rng: StdRng::seed_from_u64(cfg.seed), recent_ticks: VecDeque::new() });
+}
+
+pub fn enabled() -> bool {
+    CHAOS.lock().is_some()
+}
+
+enum Fault {
+    Error,
+    Timeout,
+}
+
+/// Possibly fails an exchange call before it is made.
+pub async fn inject(call: &str, timeout: Duration) -> Result<(), String> {
+    let fault = {
+        let mut guard = CHAOS.lock();
+        let Some(chaos) = guard.as_mut() else { return Ok(()) };
+        let roll: f64 = chaos.rng.gen();
+        if roll < chaos.cfg.error_rate {
+            Some(Fault::Error)
+        } else if roll < chaos.cfg.error_rate + chaos.cfg.timeout_rate {
+            Some(Fault::Timeout)
+        } else {
+            None
+        }
+    };
+    match fault {
+        None => Ok(()),
+        Some(Fault::Error) => Err(format!("chaos: injected HTTP 503 on {}", call)),
+        Some(Fault::Timeout) => {
+            sleep(timeout).await;
+            Err(format!("chaos: injected timeout on {}", call))
+        },
+    }
+}
+
+/// Quantity actually filled for an order of `qty`.
+pub fn fill_quantity(qty: Decimal) -> Decimal {
+    let mut guard = CHAOS.lock();
+    let Some(chaos) = guard.as_mut() else { return qty };
+    if chaos.rng.gen::<f64>() < chaos.cfg.partial_fill_rate {
+        symbols::active().floor_qty(qty * money::dec(chaos.rng.gen_range(0.1..0.9)))
+    } else {
+        qty
+    }
+}
+
+/// Passes a fresh tick through, or replays one of the last few to simulate out-of-order delivery.
+pub fn tick(price: f64) -> f64 {
+    let mut guard = CHAOS.lock();
+    let Some(chaos) = guard.as_mut() else { return price };
+    chaos.recent_ticks.push_back(price);
+    if chaos.recent_ticks.len() > 5 { chaos.recent_ticks.pop_front(); }
+    if chaos.rng.gen::<f64>() < chaos.cfg.out_of_order_rate {
+        let idx = chaos.rng.gen_range(0..chaos.recent_ticks.len());
+        chaos.recent_ticks[idx]
+    } else {
+        price
+    }
+}
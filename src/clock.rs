@@ -0,0 +1,148 @@
+use std::future::Future;
+use std::sync::atomic::{AtomicBool, AtomicI64, Ordering};
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use reqwest::header::DATE;
+use serde::{Deserialize, Serialize};
+use tokio::time::sleep;
+
+use crate::config::SharedConfig;
+use crate::notify::{Event, EventKind, Notifier};
+use crate::{add_log, SharedState, PAIR};
+
+// --- 🕰️ CLOCK DRIFT MONITOR ---
+// HMAC request timestamps and candle bucketing both use the local clock, so a skewed Pi breaks them silently.
+// The exchange has no time endpoint; its HTTP `Date` header (1s resolution) is the reference.
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClockConfig {
+    pub check_interval_secs: u64,
+    pub max_drift_ms: i64, // Orders are paused above this (exchange signature tolerance)
+    pub url: String,
+}
+
+impl Default for ClockConfig {
+    fn default() -> Self {
+        ClockConfig {
+            check_interval_secs: 300,
+            max_drift_ms: 3000,
+            url: "https://api.coindcx.com/exchange/v1/markets".to_string(),
+        }
+    }
+}
+
+static DRIFT_MS: AtomicI64 = AtomicI64::new(0);
+static DRIFT_EXCEEDED: AtomicBool = AtomicBool::new(false);
+
+/// Why live orders are paused, if the last check found the clock out of tolerance.
+pub fn pause_reason() -> Option<String> {
+    DRIFT_EXCEEDED.load(Ordering::Relaxed)
+        .then(|| format!("clock drift {}ms", DRIFT_MS.load(Ordering::Relaxed)))
+}
+
+/// Local minus exchange time in ms (positive = local clock ahead).
+pub async fn measure(client: &reqwest::Client, url: &str) -> Result<i64, String> {
+    let sent = Utc::now();
+    let resp = client.head(url).send().await.map_err(|e| e.to_string())?;
+    let received = Utc::now();
+    let header = resp.headers().get(DATE).and_then(|v| v.to_str().ok()).ok_or("no Date header")?;
+    let server = DateTime::parse_from_rfc2822(header).map_err(|e| e.to_string())?.with_timezone(&Utc);
+
+    // The header is truncated to the second: compare against the middle of that second
+    let local_mid = sent + (received - sent) / 2;
+    Ok((local_mid - server).num_milliseconds() - 500)
+}
+
+pub async fn monitor(state: SharedState, config: SharedConfig) {
+    let client = reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap();
+    let notifier = Notifier::from_env();
+
+    loop {
+        let cfg = config.read().clock.clone();
+        match measure(&client, &cfg.url).await {
+            Ok(drift) => {
+                DRIFT_MS.store(drift, Ordering::Relaxed);
+                state.write().clock_drift_ms = Some(drift);
+                let exceeded = drift.abs() > cfg.max_drift_ms;
+                if exceeded != DRIFT_EXCEEDED.swap(exceeded, Ordering::Relaxed) {
+                    let msg = if exceeded {
+                        format!("⏰ Clock drift {}ms exceeds {}ms, live orders paused (check NTP)", drift, cfg.max_drift_ms)
+                    } else {
+                        format!("⏰ Clock drift back to {}ms, live orders resumed", drift)
+                    };
+                    add_log(&state, msg.clone());
+                    let kind = if exceeded { EventKind::Error } else { EventKind::Alert };
+                    notifier.send(Event::new(kind, PAIR, state.read().price, 0.0, 0.0, &msg));
+                }
+            },
+            Err(e) => eprintln!("Clock Check Error: {}", e),
+        }
+        sleep(Duration::from_secs(cfg.check_interval_secs.max(10))).await;
+    }
+}
+
+// --- ⏩ CLOCK ABSTRACTION ---
+// The trading loop reads time and sleeps only through this, so simulations can run on a virtual clock.
+
+pub trait Clock: Send + Sync + 'static {
+    fn now(&self) -> DateTime<Utc>;
+    fn sleep(&self, d: Duration) -> impl Future<Output = ()> + Send;
+
+    fn elapsed(&self, since: DateTime<Utc>) -> Duration {
+        (self.now() - since).to_std().unwrap_or_default()
+    }
+}
+
+pub struct WallClock;
+
+impl Clock for WallClock {
+    fn now(&self) -> DateTime<Utc> { Utc::now() }
+    fn sleep(&self, d: Duration) -> impl Future<Output = ()> + Send { sleep(d) }
+}
+
+/// Fixed-rate steps on multiples of `period` (since the epoch), however long each pass takes.
+/// A pass that overruns skips the steps it missed instead of running them back to back, so the
+/// schedule never drifts later under load.
+pub struct Ticker {
+    period: chrono::Duration,
+    next: DateTime<Utc>,
+}
+
+impl Ticker {
+    pub fn new(now: DateTime<Utc>, period: Duration) -> Self {
+        let ms = (period.as_millis() as i64).max(1);
+        let next = DateTime::from_timestamp_millis((now.timestamp_millis() / ms + 1) * ms).unwrap_or(now);
+        Ticker { period: chrono::Duration::milliseconds(ms), next }
+    }
+
+    /// Time left until the next step. Waking early (e.g. for a fill) keeps the same step.
+    pub fn until_next(&mut self, now: DateTime<Utc>) -> Duration {
+        while self.next <= now { self.next += self.period; }
+        (self.next - now).to_std().unwrap_or_default()
+    }
+}
+
+/// Starts at a fixed instant and advances by every sleep, `speed` times faster than real time.
+pub struct VirtualClock {
+    now_ms: AtomicI64,
+    speed: f64,
+}
+
+impl VirtualClock {
+    pub fn new(start_ms: i64, speed: f64) -> Self {
+        VirtualClock { now_ms: AtomicI64::new(start_ms), speed: speed.max(1.0) }
+    }
+}
+
+impl Clock for VirtualClock {
+    fn now(&self) -> DateTime<Utc> {
+        DateTime::from_timestamp_millis(self.now_ms.load(Ordering::Relaxed)).unwrap_or_default()
+    }
+
+    fn sleep(&self, d: Duration) -> impl Future<Output = ()> + Send {
+        self.now_ms.fetch_add(d.as_millis() as i64, Ordering::Relaxed);
+        // Still yield for a moment so the dashboard and other tasks keep up
+        sleep(d.div_f64(self.speed))
+    }
+}
@@ -0,0 +1,266 @@
+use std::fs;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use chrono::Utc;
+use parking_lot::Mutex;
+use redis::aio::MultiplexedConnection;
+use redis::{AsyncCommands, Script};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use tokio::time::{sleep, timeout};
+
+use crate::money;
+use crate::notify::{Event, EventKind, Notifier};
+use crate::risk::Holding;
+use crate::symbols;
+use crate::{add_log, simulating, SharedState, PAIR};
+
+// --- 🔒 MULTI-INSTANCE COORDINATION (REDIS) ---
+// Lets two instances (e.g. a hot standby on a second machine) run against the same market without
+// both trading it. Each pair and venue has a lock key in Redis held as a lease: the holder renews
+// it every lease_secs / 3, and only the holder opens positions. If the holder dies or loses Redis
+// the lease runs out and the other instance takes it over; an instance that cannot reach Redis
+// stops opening positions once its own lease would have expired, so the two never overlap. A
+// position already open is still managed (and closed) by the instance that opened it. Each
+// instance also publishes its state as JSON (a key with a TTL plus a pub/sub message), and reads
+// back everyone else's: with one instance per pair, that is how the portfolio limits in [risk]
+// see the other pairs' positions. Read at startup.
+
+const COMMAND_TIMEOUT: Duration = Duration::from_secs(2);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ClusterConfig {
+    pub enabled: bool,
+    pub url: String,
+    pub prefix: String,   // Keys are <prefix>:lock:<venue>:<pair> and <prefix>:state:<instance>
+    pub instance: String, // Empty = <hostname>-<pid>
+    pub lease_secs: u64,
+    pub publish_secs: u64,
+}
+
+impl Default for ClusterConfig {
+    fn default() -> Self {
+        ClusterConfig {
+            enabled: false,
+            url: "redis://127.0.0.1:6379".to_string(),
+            prefix: "scalper".to_string(),
+            instance: String::new(),
+            lease_secs: 15,
+            publish_secs: 2,
+        }
+    }
+}
+
+struct Lease {
+    client: redis::Client,
+    lock_key: String,
+    instance: String,
+    duration: Duration,
+}
+
+// What a peer's published state says it holds
+#[derive(Deserialize)]
+struct PeerState {
+    mode: String,
+    pair: String,
+    #[serde(default)]
+    positions: u32,
+    #[serde(default)]
+    notional: f64,
+}
+
+static LEASE: OnceLock<Lease> = OnceLock::new();
+static HELD_UNTIL: Mutex<Option<Instant>> = parking_lot::const_mutex(None);
+static PEERS: Mutex<Option<(Instant, Vec<Holding>)>> = parking_lot::const_mutex(None);
+
+// Only the holder may extend or drop the lock
+const RENEW: &str = "if redis.call('GET', KEYS[1]) == ARGV[1] then return redis.call('PEXPIRE', KEYS[1], ARGV[2]) else return 0 end";
+const RELEASE: &str = "if redis.call('GET', KEYS[1]) == ARGV[1] then return redis.call('DEL', KEYS[1]) else return 0 end";
+
+fn default_instance() -> String {
+    let host = fs::read_to_string("/etc/hostname").ok()
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "scalper".to_string());
+    format!("{}-{}", host, std::process::id())
+}
+
+/// Why this instance may not open a position: it does not hold the pair's lease.
+pub fn standby_reason() -> Option<String> {
+    LEASE.get()?;
+    let held = HELD_UNTIL.lock().is_some_and(|until| Instant::now() < until);
+    (!held).then(|| "standby, another instance holds the lock".to_string())
+}
+
+/// What the other instances have open, from the states they publish; nothing without a cluster.
+/// Once the last read is older than a lease, the other pairs are unknown and this is an error.
+pub fn peers() -> Result<Vec<Holding>, String> {
+    let Some(lease) = LEASE.get() else { return Ok(Vec::new()) };
+    match &*PEERS.lock() {
+        Some((at, peers)) if at.elapsed() < lease.duration => Ok(peers.clone()),
+        _ => Err("positions of the other instances unknown, Redis unreachable".to_string()),
+    }
+}
+
+/// Connects and starts the lease and publishing loop; a standby never trades until it gets the lock.
+pub fn start(cfg: ClusterConfig, state: SharedState) {
+    if !cfg.enabled { return; }
+    let client = match redis::Client::open(cfg.url.as_str()) {
+        Ok(client) => client,
+        Err(e) => {
+            eprintln!("Cluster Error: bad url {}: {}", cfg.url, e);
+            return;
+        }
+    };
+    let market = symbols::active();
+    let venue = format!("{:?}", market.venue).to_lowercase();
+    let instance = if cfg.instance.is_empty() { default_instance() } else { cfg.instance.clone() };
+    let duration = Duration::from_secs(cfg.lease_secs.max(3));
+    let lease = Lease { client, lock_key: format!("{}:lock:{}:{}", cfg.prefix, venue, PAIR), instance, duration };
+    if LEASE.set(lease).is_err() { return; }
+    tokio::spawn(run(cfg, state));
+}
+
+async fn connect(lease: &Lease) -> Result<MultiplexedConnection, String> {
+    timeout(COMMAND_TIMEOUT, lease.client.get_multiplexed_tokio_connection()).await
+        .map_err(|_| "connect timed out".to_string())?
+        .map_err(|e| e.to_string())
+}
+
+// Takes the lock when it is free, or extends it when it is ours; true while this instance holds it
+async fn hold(conn: &mut MultiplexedConnection, lease: &Lease, lease_ms: u64, holding: bool) -> Result<bool, String> {
+    let work = async {
+        if holding {
+            let renewed: i64 = Script::new(RENEW).key(&lease.lock_key).arg(&lease.instance).arg(lease_ms)
+                .invoke_async(conn).await?;
+            if renewed == 1 { return Ok(true); }
+        }
+        let set: Option<String> = redis::cmd("SET").arg(&lease.lock_key).arg(&lease.instance)
+            .arg("NX").arg("PX").arg(lease_ms).query_async(conn).await?;
+        Ok::<bool, redis::RedisError>(set.is_some())
+    };
+    timeout(COMMAND_TIMEOUT, work).await.map_err(|_| "timed out".to_string())?.map_err(|e| e.to_string())
+}
+
+async fn publish(conn: &mut MultiplexedConnection, cfg: &ClusterConfig, lease: &Lease, state: &SharedState, holding: bool) -> Result<(), String> {
+    let payload = {
+        let d = state.read();
+        let exposure = d.slots.holding(PAIR, money::dec(d.price));
+        json!({
+            "instance": lease.instance,
+            "pair": PAIR,
+            "mode": if simulating() { "simulation" } else { "live" },
+            "lock_holder": holding,
+            "status": d.status,
+            "price": d.price,
+            "position": d.position,
+            "positions": exposure.positions,
+            "notional": exposure.notional,
+            "realized_pl": d.realized_pl,
+            "gross_pl": d.gross_pl,
+            "wallet_usdt": d.wallet_usdt,
+            "at": Utc::now().timestamp_millis(),
+        }).to_string()
+    };
+    let key = format!("{}:state:{}", cfg.prefix, lease.instance);
+    let ttl = (cfg.publish_secs.max(1) * 3).max(cfg.lease_secs);
+    let work = async {
+        let _: () = conn.set_ex(&key, &payload, ttl).await?;
+        let _: () = conn.publish(format!("{}:state", cfg.prefix), &payload).await?;
+        Ok::<(), redis::RedisError>(())
+    };
+    timeout(COMMAND_TIMEOUT, work).await.map_err(|_| "timed out".to_string())?.map_err(|e| e.to_string())
+}
+
+// Every other instance's published state that is still alive (the keys expire with their TTL)
+async fn read_peers(conn: &mut MultiplexedConnection, cfg: &ClusterConfig, lease: &Lease) -> Result<Vec<Holding>, String> {
+    let own = format!("{}:state:{}", cfg.prefix, lease.instance);
+    let pattern = format!("{}:state:*", cfg.prefix);
+    let work = async {
+        let mut keys: Vec<String> = Vec::new();
+        let mut cursor = 0u64;
+        loop {
+            let (next, batch): (u64, Vec<String>) = redis::cmd("SCAN").arg(cursor).arg("MATCH").arg(&pattern).arg("COUNT").arg(100)
+                .query_async(conn).await?;
+            keys.extend(batch.into_iter().filter(|key| *key != own));
+            if next == 0 { break; }
+            cursor = next;
+        }
+        if keys.is_empty() { return Ok(Vec::new()); }
+        redis::cmd("MGET").arg(&keys).query_async::<Vec<Option<String>>>(conn).await
+    };
+    let payloads = timeout(COMMAND_TIMEOUT, work).await.map_err(|_| "timed out".to_string())?.map_err(|e| e.to_string())?;
+    let mode = if simulating() { "simulation" } else { "live" };
+    Ok(payloads.into_iter().flatten()
+        .filter_map(|payload| serde_json::from_str::<PeerState>(&payload).ok())
+        // A paper instance's positions are not exposure
+        .filter(|peer| peer.mode == mode)
+        .map(|peer| Holding { pair: peer.pair, positions: peer.positions, notional: money::dec(peer.notional) })
+        .collect())
+}
+
+async fn run(cfg: ClusterConfig, state: SharedState) {
+    let Some(lease) = LEASE.get() else { return };
+    let notifier = Notifier::from_env();
+    let lease_ms = cfg.lease_secs.max(3) * 1000;
+    let renew_every = Duration::from_millis(lease_ms / 3);
+    let publish_every = Duration::from_secs(cfg.publish_secs.max(1));
+    let mut conn: Option<MultiplexedConnection> = None;
+    let mut holding = false;
+    let mut last_renew: Option<Instant> = None;
+    let mut last_error: Option<String> = None;
+    loop {
+        let result = async {
+            if conn.is_none() { conn = Some(connect(lease).await?); }
+            let c = conn.as_mut().unwrap();
+            if last_renew.is_none_or(|at| at.elapsed() >= renew_every) {
+                // The lease counts from before the request, so it never outlives Redis' copy
+                let asked = Instant::now();
+                let held = hold(c, lease, lease_ms, holding).await?;
+                let first = last_renew.is_none();
+                last_renew = Some(asked);
+                *HELD_UNTIL.lock() = held.then(|| asked + Duration::from_millis(lease_ms));
+                if held != holding || first {
+                    holding = held;
+                    let msg = if held {
+                        format!("🔒 Lock for {} acquired by {}, trading", PAIR, lease.instance)
+                    } else {
+                        format!("🔒 Lock for {} is held by another instance, standing by", PAIR)
+                    };
+                    add_log(&state, msg.clone());
+                    notifier.send(Event::new(EventKind::Alert, PAIR, 0.0, 0.0, 0.0, &msg));
+                }
+            }
+            publish(c, &cfg, lease, &state, holding).await?;
+            let peers = read_peers(c, &cfg, lease).await?;
+            *PEERS.lock() = Some((Instant::now(), peers));
+            Ok::<(), String>(())
+        }.await;
+        match result {
+            Ok(()) => last_error = None,
+            Err(e) => {
+                conn = None;
+                if last_error.as_deref() != Some(e.as_str()) {
+                    eprintln!("Cluster Error: {}", e);
+                    add_log(&state, format!("⚠️ Redis unreachable ({}), no new positions once the lease runs out", e));
+                }
+                last_error = Some(e);
+                // Counted as lost from here on: the local lease runs out on its own
+                holding = holding && standby_reason().is_none();
+            }
+        }
+        sleep(publish_every.min(renew_every)).await;
+    }
+}
+
+/// Drops the lock on shutdown so a standby can take over without waiting out the lease.
+pub async fn release() {
+    let Some(lease) = LEASE.get() else { return };
+    *HELD_UNTIL.lock() = None;
+    let Ok(mut conn) = connect(lease).await else { return };
+    let work = async { Script::new(RELEASE).key(&lease.lock_key).arg(&lease.instance).invoke_async::<i64>(&mut conn).await };
+    if let Ok(Err(e)) = timeout(COMMAND_TIMEOUT, work).await {
+        eprintln!("Cluster Error: releasing the lock: {}", e);
+    }
+}
@@ -0,0 +1,102 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use reqwest::header::{CACHE_CONTROL, PRAGMA};
+use serde::Deserialize;
+
+use crate::orderbook::DepthResponse;
+use crate::symbols::{self, Venue};
+use crate::{ratelimit, Candle, TIMEFRAME};
+
+// --- 🟦 COINDCX (SPOT) ---
+// Public market data of the default venue: candles, the last trade and the order book, for the
+// pair's market data code (B-BTC_USDT). Orders and balances go through the signed API in the
+// executor and the bot's wallet sync.
+
+// Helper to handle "123.45" (string) or 123.45 (number) from API
+fn f64_from_str_or_num<'de, D>(deserializer: D) -> Result<f64, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Val {
+        String(String),
+        Number(f64),
+    }
+
+    match Val::deserialize(deserializer)? {
+        Val::String(s) => s.parse::<f64>().map_err(serde::de::Error::custom),
+        Val::Number(n) => Ok(n),
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct TradeTick {
+    #[serde(alias = "p", deserialize_with = "f64_from_str_or_num")]
+    price: f64,
+}
+
+// Only the traded venue's budget is tracked (the spread monitor polls the others)
+fn observe(resp: &reqwest::Response) {
+    if symbols::venue() == Venue::CoinDcx { ratelimit::observe(resp.status(), resp.headers()); }
+}
+
+/// The traded pair's latest candles (up to 1000), newest first.
+pub async fn candles(client: &reqwest::Client, limit: u32, timeout: Duration) -> Result<Vec<Candle>, String> {
+    let url = "https://public.coindcx.com/market_data/candles";
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis().to_string();
+    let limit = limit.to_string();
+    let params = [("pair", symbols::active().data_code), ("interval", TIMEFRAME), ("limit", &limit), ("_t", &timestamp)];
+
+    let resp = client.get(url)
+        .query(&params)
+        .timeout(timeout)
+        .header(CACHE_CONTROL, "no-cache")
+        .header(PRAGMA, "no-cache")
+        .send()
+        .await
+        .map_err(|e| e.to_string())?;
+    observe(&resp);
+    resp.json::<Vec<Candle>>().await.map_err(|e| e.to_string())
+}
+
+/// A pair's candles (by market data code) opening in [from, to) (Unix millis), oldest first. The
+/// exchange serves 1000 per request, so the range is paged back from `to`.
+pub async fn candles_between(client: &reqwest::Client, pair: &str, from: i64, to: i64, timeout: Duration) -> Result<Vec<Candle>, String> {
+    let url = "https://public.coindcx.com/market_data/candles";
+    let mut candles = Vec::new();
+    let mut end = to - 1;
+    while end >= from {
+        let (start, end_time) = (from.to_string(), end.to_string());
+        let params = [("pair", pair), ("interval", TIMEFRAME), ("startTime", &start), ("endTime", &end_time), ("limit", "1000")];
+        let resp = client.get(url).query(&params).timeout(timeout).header(CACHE_CONTROL, "no-cache").send().await.map_err(|e| e.to_string())?;
+        observe(&resp);
+        let page = resp.json::<Vec<Candle>>().await.map_err(|e| e.to_string())?;
+        let Some(oldest) = page.iter().map(|c| c.time).min() else { break };
+        candles.extend(page.into_iter().filter(|c| c.time >= from && c.time < to));
+        // Nothing older in the range, or the exchange ignored the end time
+        if oldest <= from || oldest > end { break; }
+        end = oldest - 1;
+    }
+    candles.sort_by_key(|c| c.time);
+    candles.dedup_by_key(|c| c.time);
+    Ok(candles)
+}
+
+/// Latest trade of a pair, by its market data code (e.g. B-BTC_USDT).
+pub async fn last_price(client: &reqwest::Client, pair: &str, timeout: Duration) -> Result<Option<f64>, String> {
+    let url = "https://public.coindcx.com/market_data/trade_history";
+    let params = [("pair", pair), ("limit", "1")];
+    let resp = client.get(url).query(&params).timeout(timeout).header(CACHE_CONTROL, "no-cache").send().await
+        .map_err(|e| e.to_string())?;
+    observe(&resp);
+    let resp = resp.json::<Vec<TradeTick>>().await.map_err(|e| e.to_string())?;
+    Ok(resp.first().map(|trade| trade.price))
+}
+
+/// The traded pair's order book snapshot.
+pub async fn depth(client: &reqwest::Client) -> Result<DepthResponse, String> {
+    let url = "https://public.coindcx.com/market_data/orderbook";
+    let resp = client.get(url).query(&[("pair", symbols::active().data_code)]).header(CACHE_CONTROL, "no-cache").send().await.map_err(|e| e.to_string())?;
+    observe(&resp);
+    resp.json::<DepthResponse>().await.map_err(|e| e.to_string())
+}
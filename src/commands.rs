@@ -0,0 +1,280 @@
+use std::fs;
+use std::time::Duration;
+use chrono::{NaiveDate, NaiveTime};
+use serde::Serialize;
+
+use crate::backtest::{self, BacktestRun};
+use crate::config::Config;
+use crate::export::{self, Dataset, Format};
+use crate::optimize::{self, Point};
+use crate::replay::{self, CandleReplay};
+use crate::symbols::{self, Market, Venue};
+use crate::{calendar, coindcx, database, runtime, strategy, ticks, Candle, DB_FILE, PAIR, REPLAYING};
+
+// --- 🧰 COMMANDS ---
+// Headless subcommands for scripting, next to the bot itself: each reads config.toml (or the file
+// given with --config), does one job and exits non-zero with the reason on failure. Flags are
+// `--name value`. Dates are UTC days (YYYY-MM-DD); --to is included. History comes from CoinDCX's
+// public candles, so no keys are needed; export reads the bot's own DB.
+
+const HISTORY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// A backtest as the `backtest` subcommand writes it.
+#[derive(Serialize)]
+struct BacktestReport {
+    pair: &'static str,
+    from: String,
+    to: String,
+    spread_bps: f64,
+    #[serde(flatten)]
+    run: BacktestRun,
+}
+
+/// Runs a subcommand; false if `name` is not one.
+pub fn run(name: &str, args: &[String]) -> bool {
+    let result = match name {
+        "backtest" => backtest_command(args),
+        "optimize" => optimize_command(args),
+        "replay" => replay_command(args),
+        "export" => export_command(args),
+        _ => return false,
+    };
+    if let Err(e) = result {
+        eprintln!("❌ {}: {}", name, e);
+        std::process::exit(1);
+    }
+    true
+}
+
+// The value after --name (or in --name=value)
+fn flag<'a>(args: &'a [String], name: &str) -> Option<&'a str> {
+    let long = format!("--{}", name);
+    args.iter().enumerate().find_map(|(i, arg)| match arg.strip_prefix(&long) {
+        Some("") => args.get(i + 1).map(String::as_str),
+        Some(rest) => rest.strip_prefix('='),
+        None => None,
+    })
+}
+
+fn date(args: &[String], name: &str) -> Result<NaiveDate, String> {
+    let raw = flag(args, name).ok_or_else(|| format!("--{} YYYY-MM-DD is required", name))?;
+    NaiveDate::parse_from_str(raw, "%Y-%m-%d").map_err(|e| format!("--{} {}: {}", name, raw, e))
+}
+
+fn millis(day: NaiveDate) -> i64 {
+    day.and_time(NaiveTime::MIN).and_utc().timestamp_millis()
+}
+
+// --from and --to as [from, to) in Unix millis, --to's whole day included
+fn range(args: &[String]) -> Result<(NaiveDate, NaiveDate, i64, i64), String> {
+    let (from, to) = (date(args, "from")?, date(args, "to")?);
+    if to < from {
+        return Err(format!("--to {} is before --from {}", to, from));
+    }
+    Ok((from, to, millis(from), millis(to) + 86_400_000))
+}
+
+// --pair as a CoinDCX pair or market data code, PAIR by default
+fn market(args: &[String]) -> Result<Market, String> {
+    let name = flag(args, "pair").unwrap_or(PAIR);
+    symbols::find(name, Venue::CoinDcx).ok_or_else(|| format!("{} is not a CoinDCX market in the symbol registry", name))
+}
+
+// --config, or config.toml as the bot loads it. A named file must be valid, it is what was asked for
+fn load_config(args: &[String]) -> Result<Config, String> {
+    match flag(args, "config") {
+        Some(path) => Config::parse(&fs::read_to_string(path).map_err(|e| format!("reading {}: {}", path, e))?).map_err(|e| format!("{}: {}", path, e)),
+        None => Ok(Config::load()),
+    }
+}
+
+// The market's candles in the --from/--to range
+fn history(market: &Market, from: NaiveDate, to: NaiveDate, from_ms: i64, to_ms: i64) -> Result<Vec<Candle>, String> {
+    let client = reqwest::Client::new();
+    let candles = block_on(coindcx::candles_between(&client, market.data_code, from_ms, to_ms, HISTORY_TIMEOUT))?
+        .map_err(|e| format!("history fetch failed: {}", e))?;
+    if candles.is_empty() {
+        return Err(format!("no {} candles between {} and {}", market.pair, from, to));
+    }
+    Ok(candles)
+}
+
+fn block_on<T>(job: impl std::future::Future<Output = T>) -> Result<T, String> {
+    let rt = tokio::runtime::Builder::new_current_thread().enable_all().build().map_err(|e| e.to_string())?;
+    Ok(rt.block_on(job))
+}
+
+fn write_out(args: &[String], json: &str) -> Result<(), String> {
+    match flag(args, "out") {
+        Some(path) => fs::write(path, json).map_err(|e| format!("writing {}: {}", path, e)),
+        None => {
+            println!("{}", json);
+            Ok(())
+        },
+    }
+}
+
+// backtest [--pair B-BTC_USDT] --from 2024-01-01 --to 2024-03-01 [--strategy rsi_bb] [--out report.json]
+fn backtest_command(args: &[String]) -> Result<(), String> {
+    let config = load_config(args)?;
+    let market = market(args)?;
+    let (from, to, from_ms, to_ms) = range(args)?;
+    let name = flag(args, "strategy").unwrap_or(&config.strategy.name).to_string();
+    let params = config.strategy.params;
+    strategy::validate(&name, &params)?;
+    let candles = history(&market, from, to, from_ms, to_ms)?;
+
+    let spread_bps = config.simulation.spread_bps;
+    let mut run = backtest::run(&candles, strategy::build(&name, params).as_ref(), spread_bps);
+    run.created = chrono::Utc::now().to_rfc3339();
+    let m = &run.metrics;
+    eprintln!("{} {} to {}: {} candles, {} trades, win rate {:.1}%, net {:.2}, max drawdown {:.2}",
+        market.pair, from, to, m.candles, m.trades, m.win_rate, m.net_pnl, m.max_drawdown);
+    let report = BacktestReport { pair: market.pair, from: from.to_string(), to: to.to_string(), spread_bps, run };
+    write_out(args, &serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?)
+}
+
+/// The best points as the `optimize` subcommand writes them.
+#[derive(Serialize)]
+struct OptimizeReport {
+    pair: &'static str,
+    from: String,
+    to: String,
+    strategy: String,
+    objective: optimize::Objective,
+    evaluated: usize,
+    skipped: usize, // Outside the strategy's parameter ranges
+    top: Vec<Point>,
+}
+
+// optimize [--pair ..] --from .. --to .. [--strategy ..] [--objective net_pnl|sharpe|profit_factor]
+//          [--top 10] [--out top.json] [--csv points.csv]
+fn optimize_command(args: &[String]) -> Result<(), String> {
+    let config = load_config(args)?;
+    let mut cfg = config.optimize.clone();
+    if let Some(objective) = flag(args, "objective") {
+        cfg.objective = serde_json::from_value(serde_json::Value::String(objective.to_string()))
+            .map_err(|_| format!("--objective {} is not net_pnl, sharpe or profit_factor", objective))?;
+    }
+    if let Some(top) = flag(args, "top") {
+        cfg.top = top.parse().map_err(|_| format!("--top {} is not a number", top))?;
+    }
+    if cfg.ranges.is_empty() {
+        return Err("nothing to search, add [optimize.ranges] to the config".to_string());
+    }
+    let market = market(args)?;
+    let (from, to, from_ms, to_ms) = range(args)?;
+    let name = flag(args, "strategy").unwrap_or(&config.strategy.name).to_string();
+    let points = optimize::grid(config.strategy.params, &cfg.ranges)?;
+    let candles = history(&market, from, to, from_ms, to_ms)?;
+
+    eprintln!("{} {} to {}: {} candles, {} parameter sets", market.pair, from, to, candles.len(), points.len());
+    let (ranked, skipped) = optimize::search(&candles, &name, points, config.simulation.spread_bps, &cfg);
+    if ranked.is_empty() {
+        return Err(format!("all {} parameter sets are outside the {} parameter ranges", skipped, name));
+    }
+
+    let csv_path = flag(args, "csv").unwrap_or("optimize.csv");
+    write_points(csv_path, &cfg.ranges.keys().cloned().collect::<Vec<_>>(), &ranked)?;
+    if let Some(best) = ranked.first() {
+        let score = best.score.map_or("n/a".to_string(), |s| format!("{:.4}", s));
+        eprintln!("Best {:?} {}: {} trades, net {:.2}; all {} points in {}", cfg.objective, score, best.trades, best.net_pnl, ranked.len(), csv_path);
+    }
+    let report = OptimizeReport {
+        pair: market.pair, from: from.to_string(), to: to.to_string(), strategy: name, objective: cfg.objective,
+        evaluated: ranked.len(), skipped, top: ranked.into_iter().take(cfg.top).collect(),
+    };
+    write_out(args, &serde_json::to_string_pretty(&report).map_err(|e| e.to_string())?)
+}
+
+// One row per point, best first: the searched parameters, then the results
+fn write_points(path: &str, keys: &[String], points: &[Point]) -> Result<(), String> {
+    let mut csv = csv::Writer::from_path(path).map_err(|e| format!("writing {}: {}", path, e))?;
+    let results = ["trades", "win_rate", "net_pnl", "sharpe", "profit_factor", "max_drawdown", "score"];
+    let header: Vec<&str> = keys.iter().map(String::as_str).chain(results).collect();
+    csv.write_record(&header).map_err(|e| e.to_string())?;
+    let opt = |v: Option<f64>| v.map(|v| v.to_string()).unwrap_or_default();
+    for p in points {
+        let mut row: Vec<String> = keys.iter().map(|k| p.params.get(k).unwrap_or_default().to_string()).collect();
+        row.extend([p.trades.to_string(), p.win_rate.to_string(), p.net_pnl.to_string(), opt(p.sharpe), opt(p.profit_factor), p.max_drawdown.to_string(), opt(p.score)]);
+        csv.write_record(&row).map_err(|e| e.to_string())?;
+    }
+    csv.flush().map_err(|e| e.to_string())
+}
+
+// replay --from 2024-05-01 --to 2024-05-01 [--speed 60] [--source ticks|exchange] [--dir replay]
+fn replay_command(args: &[String]) -> Result<(), String> {
+    let mut config = load_config(args)?;
+    let (from, to, from_ms, to_ms) = range(args)?;
+    let speed = match flag(args, "speed") {
+        Some(raw) => raw.parse::<f64>().ok().filter(|s| s.is_finite() && *s > 0.0).ok_or_else(|| format!("--speed {} is not a positive number", raw))?,
+        None => 60.0,
+    };
+    // Candles are cut on the configured day boundary, as the live loop cuts them
+    calendar::configure(&config.calendar);
+    let warmup_ms = replay::WARMUP_CANDLES as i64 * 60_000;
+    let recorded = match flag(args, "source").unwrap_or("ticks") {
+        "ticks" => {
+            let recorded = ticks::read(&config.recorder.dir, from_ms - warmup_ms, to_ms - 1)
+                .map_err(|e| format!("reading {}: {}", config.recorder.dir, e))?;
+            CandleReplay::from_ticks(recorded, from_ms)
+                .ok_or_else(|| format!("no ticks recorded in {} between {} and {}", config.recorder.dir, from, to))?
+        },
+        "exchange" => {
+            let market = symbols::find(PAIR, Venue::CoinDcx).ok_or_else(|| format!("{} is not a CoinDCX market in the symbol registry", PAIR))?;
+            CandleReplay::new(history(&market, from, to, from_ms - warmup_ms, to_ms)?)
+                .ok_or_else(|| format!("not enough {} candles between {} and {}", market.pair, from, to))?
+        },
+        source => return Err(format!("--source {} is not ticks or exchange", source)),
+    };
+
+    // Paper trading on its own DB and log, with nothing sent anywhere
+    config.simulation.speed = speed;
+    config.snapshot.enabled = false;
+    config.recorder.enabled = false;
+    config.userstream.enabled = false;
+    (config.notify, config.telegram, config.heartbeat, config.metrics) = Default::default();
+    (config.cluster, config.backup, config.timeseries, config.grpc, config.sheets, config.bus) = Default::default();
+    std::env::remove_var("WEBHOOK_URLS");
+    std::env::remove_var("TELEGRAM_BOT_TOKEN");
+    REPLAYING.store(true, std::sync::atomic::Ordering::Relaxed);
+    let dir = flag(args, "dir").unwrap_or("replay");
+    fs::create_dir_all(dir).map_err(|e| format!("creating {}: {}", dir, e))?;
+    for stale in ["bot_data.db", "bot_data.db-wal", "bot_data.db-shm"] {
+        let _ = fs::remove_file(std::path::Path::new(dir).join(stale));
+    }
+    std::env::set_current_dir(dir).map_err(|e| format!("entering {}: {}", dir, e))?;
+
+    eprintln!("Replaying {} to {} at {}x in {}, dashboard on the usual port (Ctrl-C to stop)", from, to, speed, dir);
+    let rt = runtime::build(&config.runtime).map_err(|e| format!("could not start the async runtime: {}", e))?;
+    rt.block_on(crate::run(config, Some(recorded)));
+    Ok(())
+}
+
+// export trades|candles|performance [--format csv|json|parquet] [--from ..] [--to ..] [--out file]
+fn export_command(args: &[String]) -> Result<(), String> {
+    let name = args.first().map(String::as_str).unwrap_or("");
+    let dataset = Dataset::parse(name).ok_or_else(|| format!("export what? {:?} is not trades, candles or performance", name))?;
+    let raw = flag(args, "format").unwrap_or("csv");
+    let format = Format::parse(raw).ok_or_else(|| format!("--format {} is not csv, json or parquet", raw))?;
+    // Either end may be left open
+    let from_ms = if flag(args, "from").is_some() { millis(date(args, "from")?) } else { i64::MIN };
+    let to_ms = if flag(args, "to").is_some() { millis(date(args, "to")?) + 86_400_000 } else { i64::MAX };
+    if from_ms >= to_ms {
+        return Err("--to is before --from".to_string());
+    }
+    // Opening a missing DB would create an empty one
+    if !std::path::Path::new(DB_FILE).exists() {
+        return Err(format!("no {} here, run this from the bot's directory", DB_FILE));
+    }
+    let config = load_config(args)?;
+    database::configure(&config.database);
+
+    let table = export::table(dataset, from_ms, to_ms).map_err(|e| format!("reading the database: {}", e))?;
+    let out = flag(args, "out");
+    export::write(&table, format, out)?;
+    if let Some(path) = out {
+        eprintln!("{} {} row(s) written to {}", table.rows, name, path);
+    }
+    Ok(())
+}
@@ -0,0 +1,392 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use notify::{RecursiveMode, Watcher};
+use parking_lot::RwLock;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+
+use crate::alerts::AlertRule;
+use crate::auth::{self, SharedSessions, UserConfig};
+use crate::backup::BackupConfig;
+use crate::logfile::{self, LogConfig};
+use crate::breaker::BreakerConfig;
+use crate::bus::BusConfig;
+use crate::bybit::BybitConfig;
+use crate::calendar::{self, CalendarConfig};
+use crate::chaos::{self, ChaosConfig};
+use crate::clock::ClockConfig;
+use crate::cluster::ClusterConfig;
+use crate::currency::{self, ReportingConfig};
+use crate::database::DatabaseConfig;
+use crate::feed::FeedConfig;
+use crate::fix::FixConfig;
+use crate::grpc::GrpcConfig;
+use crate::heartbeat::HeartbeatConfig;
+use crate::metrics::MetricsConfig;
+use crate::network::NetworkConfig;
+use crate::notify::NotifyConfig;
+use crate::okx::OkxConfig;
+use crate::panic_guard::PanicConfig;
+use crate::persist::PersistConfig;
+use crate::reconcile::ReconcileConfig;
+use crate::optimize::{self, OptimizeConfig};
+use crate::replay::SimulationConfig;
+use crate::risk::{self, RiskConfig};
+use crate::runtime::RuntimeConfig;
+use crate::sanity::SanityConfig;
+use crate::sheets::SheetsConfig;
+use crate::sizing::{self, SizingConfig};
+use crate::slots::{self, SlotConfig};
+use crate::snapshot::SnapshotConfig;
+use crate::spread::SpreadConfig;
+use crate::telegram::TelegramConfig;
+use crate::ticks::RecorderConfig;
+use crate::strategy::{StrategyParams, DEFAULT_STRATEGY};
+use crate::timeouts::TimeoutConfig;
+use crate::timeseries::TimeSeriesConfig;
+use crate::userstream::UserStreamConfig;
+use crate::{add_log, strategy, systemd, SharedState, CONFIG_FILE, PAIR, TIMEFRAME};
+
+// --- ⚙️ RUNTIME CONFIG (config.toml) ---
+// Settings that can change while the bot runs; compile-time constants stay in lib.rs.
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StrategyConfig {
+    pub name: String,
+    pub params: StrategyParams,
+}
+
+impl Default for StrategyConfig {
+    fn default() -> Self {
+        StrategyConfig { name: DEFAULT_STRATEGY.to_string(), params: StrategyParams::default() }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct Config {
+    pub strategy: StrategyConfig,
+    pub alerts: Vec<AlertRule>,
+    pub metrics: MetricsConfig,
+    pub users: Vec<UserConfig>,
+    pub breaker: BreakerConfig,
+    pub risk: RiskConfig,
+    pub logging: LogConfig,
+    pub heartbeat: HeartbeatConfig,
+    pub clock: ClockConfig,
+    pub network: NetworkConfig,
+    pub timeouts: TimeoutConfig,
+    pub simulation: SimulationConfig,
+    pub chaos: ChaosConfig,
+    pub panic_guard: PanicConfig,
+    pub feed: FeedConfig,
+    pub persistence: PersistConfig,
+    pub runtime: RuntimeConfig,
+    pub database: DatabaseConfig,
+    pub snapshot: SnapshotConfig,
+    pub recorder: RecorderConfig,
+    pub notify: NotifyConfig,
+    pub timeseries: TimeSeriesConfig,
+    pub sheets: SheetsConfig,
+    pub telegram: TelegramConfig,
+    pub fix: FixConfig,
+    pub grpc: GrpcConfig,
+    pub bus: BusConfig,
+    pub bybit: BybitConfig,
+    pub okx: OkxConfig,
+    pub sanity: SanityConfig,
+    pub spread: SpreadConfig,
+    pub backup: BackupConfig,
+    pub cluster: ClusterConfig,
+    pub reconcile: ReconcileConfig,
+    pub calendar: CalendarConfig,
+    pub sizing: SizingConfig,
+    pub slots: SlotConfig,
+    pub userstream: UserStreamConfig,
+    pub reporting: ReportingConfig,
+    pub optimize: OptimizeConfig, // Only read by the optimize subcommand
+    pub pairs: BTreeMap<String, PairOverrides>,
+    #[serde(skip)]
+    defaults: Option<Box<(StrategyConfig, RiskConfig)>>, // [strategy] and [risk] as written, when PAIR has overrides
+}
+
+pub type SharedConfig = Arc<RwLock<Config>>;
+
+impl Config {
+    /// Loads `config.toml`, falling back to defaults if it is missing or invalid.
+    pub fn load() -> Self {
+        match fs::read_to_string(CONFIG_FILE) {
+            Ok(raw) => Self::parse(&raw).unwrap_or_else(|e| {
+                eprintln!("⚠️ Invalid {} ({}), using defaults", CONFIG_FILE, e);
+                Config::default()
+            }),
+            Err(_) => Config::default(),
+        }
+    }
+
+    pub fn parse(raw: &str) -> Result<Self, String> {
+        let mut config: Config = toml::from_str(raw).map_err(|e| e.to_string())?;
+        config.apply_pair_overrides()?;
+        config.validate()?;
+        Ok(config)
+    }
+
+    pub fn validate(&self) -> Result<(), String> {
+        strategy::validate(&self.strategy.name, &self.strategy.params)?;
+        calendar::validate(&self.calendar)?;
+        sizing::validate(&self.sizing)?;
+        slots::validate(&self.slots)?;
+        risk::validate(&self.risk)?;
+        currency::validate(&self.reporting)?;
+        optimize::validate(&self.optimize)?;
+        if self.bybit.enabled && self.okx.enabled {
+            return Err("[bybit] and [okx] are both enabled, pick one exchange".to_string());
+        }
+        self.alerts.iter().try_for_each(|rule| rule.condition.validate())
+    }
+
+    /// Writes the config back to `config.toml`. The running pair's values that differ from the
+    /// defaults go into its overrides, so an edit made here never changes the other pairs.
+    pub fn save(&mut self) -> Result<(), String> {
+        let file = self.as_written()?;
+        let raw = toml::to_string_pretty(&file).map_err(|e| e.to_string())?;
+        fs::write(CONFIG_FILE, raw).map_err(|e| e.to_string())?;
+        // So the reload the write triggers finds nothing changed
+        self.pairs = file.pairs;
+        Ok(())
+    }
+
+    /// The overrides for the running pair ([pairs."BTC/USDT"], the key matched case-insensitively).
+    pub fn pair_overrides(&self) -> PairOverrides {
+        self.pair_key().and_then(|key| self.pairs.get(&key)).cloned().unwrap_or_default()
+    }
+
+    fn pair_key(&self) -> Option<String> {
+        self.pairs.keys().find(|key| key.eq_ignore_ascii_case(PAIR)).cloned()
+    }
+
+    fn apply_pair_overrides(&mut self) -> Result<(), String> {
+        let Some(key) = self.pair_key() else { return Ok(()) };
+        let overrides = &self.pairs[&key];
+        let strategy = merged(&self.strategy, &overrides.strategy).map_err(|e| format!("[pairs.\"{}\".strategy] {}", key, e))?;
+        let risk = merged(&self.risk, &overrides.risk).map_err(|e| format!("[pairs.\"{}\".risk] {}", key, e))?;
+        let defaults = (std::mem::replace(&mut self.strategy, strategy), std::mem::replace(&mut self.risk, risk));
+        self.defaults = Some(Box::new(defaults));
+        Ok(())
+    }
+
+    /// The config the way the file has it: the defaults in [strategy] and [risk], and whatever the
+    /// running pair has different in its overrides.
+    pub fn as_written(&self) -> Result<Config, String> {
+        let mut file = self.clone();
+        let Some(defaults) = &self.defaults else { return Ok(file) };
+        let (strategy, risk) = (**defaults).clone();
+        let overrides = PairOverrides {
+            strategy: difference(&table(&strategy)?, &table(&self.strategy)?),
+            risk: difference(&table(&risk)?, &table(&self.risk)?),
+        };
+        let key = self.pair_key().unwrap_or_else(|| PAIR.to_string());
+        match overrides == PairOverrides::default() {
+            true => file.pairs.remove(&key),
+            false => file.pairs.insert(key, overrides),
+        };
+        (file.strategy, file.risk, file.defaults) = (strategy, risk, None);
+        Ok(file)
+    }
+
+    /// The config for showing to a dashboard user: no [[users]], and the tokens, webhooks and
+    /// connection URLs (which can carry a password) replaced with "<redacted>" where they are set.
+    pub fn redacted(&self) -> Config {
+        let mut cfg = self.clone();
+        cfg.users.clear();
+        for secret in [
+            &mut cfg.metrics.url, &mut cfg.metrics.token, &mut cfg.timeseries.url, &mut cfg.timeseries.token,
+            &mut cfg.heartbeat.url, &mut cfg.cluster.url, &mut cfg.bus.url,
+            &mut cfg.notify.discord.webhook_url, &mut cfg.notify.slack.webhook_url, &mut cfg.notify.email.username,
+        ] {
+            if !secret.is_empty() { *secret = REDACTED.to_string(); }
+        }
+        cfg
+    }
+}
+
+// --- 🪙 PER-PAIR OVERRIDES ---
+// One config.toml can serve an instance per pair: [pairs."ETH/USDT".strategy] and
+// [pairs."ETH/USDT".risk] set any keys of [strategy] and [risk] for that pair only. The running
+// pair's overrides are merged over the defaults at load, so everything that reads the config sees
+// the pair's values; saving splits them back out.
+
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PairOverrides {
+    #[serde(skip_serializing_if = "toml::Table::is_empty")]
+    pub strategy: toml::Table, // Keys of [strategy], params in a [params] table
+    #[serde(skip_serializing_if = "toml::Table::is_empty")]
+    pub risk: toml::Table,     // Keys of [risk]
+}
+
+fn table<T: Serialize>(value: &T) -> Result<toml::Table, String> {
+    toml::Table::try_from(value).map_err(|e| e.to_string())
+}
+
+/// `base` with every key in `overrides` replaced; nested tables are merged key by key.
+fn merged<T: Serialize + DeserializeOwned>(base: &T, overrides: &toml::Table) -> Result<T, String> {
+    fn merge(base: &mut toml::Table, overrides: &toml::Table) -> Result<(), String> {
+        for (key, value) in overrides {
+            match (base.get_mut(key), value) {
+                (Some(toml::Value::Table(inner)), toml::Value::Table(more)) => merge(inner, more)?,
+                (Some(slot), _) => *slot = value.clone(),
+                // Every key has a default, so one that is missing is a typo
+                (None, _) => return Err(format!("unknown key {}", key)),
+            }
+        }
+        Ok(())
+    }
+    let mut merged = table(base)?;
+    merge(&mut merged, overrides)?;
+    merged.try_into().map_err(|e: toml::de::Error| e.to_string())
+}
+
+/// The keys of `value` that differ from `base`, nested tables compared key by key.
+fn difference(base: &toml::Table, value: &toml::Table) -> toml::Table {
+    value.iter().filter_map(|(key, v)| match (base.get(key), v) {
+        (Some(toml::Value::Table(b)), toml::Value::Table(t)) => {
+            let inner = difference(b, t);
+            (!inner.is_empty()).then(|| (key.clone(), toml::Value::Table(inner)))
+        },
+        (Some(b), _) if b == v => None,
+        _ => Some((key.clone(), v.clone())),
+    }).collect()
+}
+
+// --- 🔄 HOT RELOAD ---
+// Every section above is re-read by its consumer on each cycle, so swapping the shared config is enough.
+// Pair, timeframe and exchange are compiled in; edits to them are only flagged.
+
+const REDACTED: &str = "<redacted>"; // Stands in for a secret in a config shown to a user
+
+const RESTART_KEYS: [(&str, &str); 3] = [("pair", PAIR), ("timeframe", TIMEFRAME), ("exchange", "coindcx")];
+// Sections read once while starting up (they shape threads and queues)
+const STARTUP_SECTIONS: [&str; 14] = ["bus", "bybit", "calendar", "cluster", "database", "fix", "grpc", "okx", "persistence", "recorder", "runtime", "sheets", "timeseries", "userstream"];
+
+/// Top-level keys in the file that ask for something only a rebuild/restart can change.
+fn restart_required(raw: &str) -> Vec<String> {
+    let table: toml::Table = match toml::from_str(raw) {
+        Ok(t) => t,
+        Err(_) => return vec![],
+    };
+    RESTART_KEYS.iter()
+        .filter_map(|(key, current)| match table.get(*key).and_then(|v| v.as_str()) {
+            Some(wanted) if !wanted.eq_ignore_ascii_case(current) => Some(format!("{} = {} (running {})", key, wanted, current)),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Names of the top-level sections that differ between two configs.
+fn changed_sections(old: &Config, new: &Config) -> Vec<String> {
+    let (old, new) = (serde_json::to_value(old).unwrap_or_default(), serde_json::to_value(new).unwrap_or_default());
+    match (old.as_object(), new.as_object()) {
+        (Some(o), Some(n)) => n.iter().filter(|(k, v)| o.get(*k) != Some(*v)).map(|(k, _)| k.clone()).collect(),
+        _ => vec![],
+    }
+}
+
+static RELOAD_GENERATION: AtomicU64 = AtomicU64::new(0);
+
+/// Bumped on every SIGHUP; long-lived tasks compare it to reset their soft state.
+pub fn reload_generation() -> u64 {
+    RELOAD_GENERATION.load(Ordering::Relaxed)
+}
+
+fn reload(config: &SharedConfig, state: &SharedState) {
+    let raw = match fs::read_to_string(CONFIG_FILE) {
+        Ok(raw) => raw,
+        Err(_) => return, // Mid-rename by an editor; the next event picks it up
+    };
+
+    let pending = restart_required(&raw);
+    let notice = (!pending.is_empty()).then(|| format!("Restart required for: {}", pending.join(", ")));
+    if notice.is_some() && state.read().config_notice != notice {
+        add_log(state, format!("⚠️ {}", notice.clone().unwrap_or_default()));
+    }
+    state.write().config_notice = notice;
+
+    match Config::parse(&raw) {
+        Ok(new) => {
+            let changed = changed_sections(&config.read(), &new);
+            // Our own API saves trigger events too; those change nothing
+            if changed.is_empty() { return; }
+            if changed.iter().any(|c| c == "logging") {
+                logfile::init(&new.logging);
+            }
+            chaos::configure(&new.chaos);
+            crate::notify::configure(&new.notify); // `notify` here is the file watcher crate
+            *config.write() = new;
+            add_log(state, format!("Config reloaded ({})", changed.join(", ")));
+            let later: Vec<&str> = STARTUP_SECTIONS.into_iter().filter(|s| changed.iter().any(|c| c == s)).collect();
+            if !later.is_empty() {
+                add_log(state, format!("⚠️ [{}] takes effect after a restart", later.join("], [")));
+            }
+        },
+        Err(e) => add_log(state, format!("⚠️ Config reload rejected, keeping current settings: {}", e)),
+    }
+}
+
+/// Watches the directory (editors often replace the file) and reloads `config.toml` when it changes.
+pub async fn watch(config: SharedConfig, state: SharedState) {
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let watcher = notify::recommended_watcher(move |res: notify::Result<notify::Event>| {
+        if let Ok(event) = res { let _ = tx.send(event); }
+    });
+    let mut watcher = match watcher {
+        Ok(w) => w,
+        Err(e) => return eprintln!("Config Watcher Error: {}", e),
+    };
+    if let Err(e) = watcher.watch(Path::new("."), RecursiveMode::NonRecursive) {
+        return eprintln!("Config Watcher Error: {}", e);
+    }
+
+    // Flags restart-only keys that were already in the file at startup
+    reload(&config, &state);
+
+    while let Some(event) = rx.recv().await {
+        let ours = event.paths.iter().any(|p| p.file_name().is_some_and(|n| n == CONFIG_FILE));
+        if !ours || !(event.kind.is_modify() || event.kind.is_create()) { continue; }
+
+        // Debounce: a single save usually produces several events
+        sleep(Duration::from_millis(300)).await;
+        while rx.try_recv().is_ok() {}
+        reload(&config, &state);
+    }
+}
+
+/// SIGHUP: re-read the config and reinitialize the soft subsystems (log file, alert state, web sessions)
+/// without touching the position, indicators or open orders.
+#[cfg(unix)]
+pub async fn watch_sighup(config: SharedConfig, state: SharedState, sessions: SharedSessions) {
+    use tokio::signal::unix::{signal, SignalKind};
+    let mut hangups = match signal(SignalKind::hangup()) {
+        Ok(s) => s,
+        Err(e) => return eprintln!("SIGHUP Handler Error: {}", e),
+    };
+
+    while hangups.recv().await.is_some() {
+        systemd::reloading();
+        reload(&config, &state);
+        let users = config.read().users.clone();
+        // Reopen even if unchanged, so logrotate's move-and-HUP works
+        logfile::init(&config.read().logging);
+        let dropped = auth::revalidate_sessions(&sessions, &users);
+        RELOAD_GENERATION.fetch_add(1, Ordering::Relaxed);
+        add_log(&state, format!("SIGHUP: config re-read, log file reopened, alerts reset, {} session(s) dropped", dropped));
+        systemd::ready_again();
+    }
+}
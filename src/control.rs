@@ -0,0 +1,79 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use http::StatusCode;
+
+use crate::bus;
+use crate::config::SharedConfig;
+use crate::persist::{self, Write};
+use crate::strategy::{self, StrategyParams};
+use crate::{add_log, BotState, SharedState};
+
+// --- 🎛️ OPERATOR CONTROLS ---
+// Pause, resume, close and strategy changes, shared by the dashboard API, the Telegram commands and
+// gRPC so every channel acts the same way. Pause is a manual risk halt: no new entries (an open
+// position is still managed by the strategy) until resumed, and like any halt it survives a
+// restart. Close hands the trading loop a request to sell the open position on its next tick.
+
+static CLOSE_REQUESTED: AtomicBool = AtomicBool::new(false);
+
+/// Err when trading is already halted (with the reason).
+pub async fn pause(state: &SharedState, by: &str) -> Result<(), String> {
+    let risk = {
+        let mut data = state.write();
+        if let Some(reason) = &data.risk.halted {
+            return Err(format!("already halted: {}", reason));
+        }
+        data.risk.halt(format!("Paused by {}", by));
+        data.risk.clone()
+    };
+    bus::transition("risk", "armed", "halted", &format!("Paused by {}", by));
+    persist::submit(Write::Risk(risk)).await;
+    add_log(state, format!("⏸️ Paused by {}", by));
+    Ok(())
+}
+
+/// Clears a pause or kill-switch halt; returns what it was, if anything.
+pub async fn resume(state: &SharedState) -> Option<String> {
+    let (was_halted, risk) = {
+        let mut data = state.write();
+        let reason = data.risk.halted.clone();
+        data.risk.rearm();
+        (reason, data.risk.clone())
+    };
+    persist::submit(Write::Risk(risk)).await;
+    if let Some(reason) = &was_halted {
+        add_log(state, format!("Risk halt re-armed (was: {})", reason));
+        bus::transition("risk", "halted", "armed", reason);
+    }
+    was_halted
+}
+
+/// Err when there is no open position to close.
+pub fn close(state: &SharedState, by: &str) -> Result<(), String> {
+    if state.read().position == BotState::Idle {
+        return Err("no open position".to_string());
+    }
+    CLOSE_REQUESTED.store(true, Ordering::Relaxed);
+    add_log(state, format!("Close requested by {}", by));
+    Ok(())
+}
+
+/// Taken by the trading loop once per tick.
+pub fn take_close() -> bool {
+    CLOSE_REQUESTED.swap(false, Ordering::Relaxed)
+}
+
+/// Validates and saves a strategy change; the trading loop picks it up on its next tick. `name`
+/// None keeps the active strategy. Switching to another strategy is refused while in a position.
+pub fn set_strategy(state: &SharedState, config: &SharedConfig, name: Option<String>, params: StrategyParams) -> Result<(), (StatusCode, String)> {
+    let name = name.unwrap_or_else(|| config.read().strategy.name.clone());
+    strategy::validate(&name, &params).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let in_position = !state.read().entry_price.is_zero();
+    let mut cfg = config.write();
+    if name != cfg.strategy.name && in_position {
+        return Err((StatusCode::CONFLICT, "Cannot switch strategy while in a position".to_string()));
+    }
+    cfg.strategy.name = name;
+    cfg.strategy.params = params;
+    cfg.save().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Config save failed: {}", e)))
+}
@@ -0,0 +1,121 @@
+use std::time::Duration;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::time::sleep;
+
+use crate::config::SharedConfig;
+use crate::{symbols, SharedState};
+
+// --- 💱 CURRENCIES ---
+// PAIR is BASE/QUOTE, so prices, the wallet and P&L are all in the quote currency: USDT for
+// BTC/USDT, INR for BTC/INR. The balance sync keeps every asset the venue reports, and amounts are
+// written with the quote's sign or code instead of a hardcoded dollar. [reporting] currency is what
+// P&L is also shown and sent in when it is not the quote: converted at the last price of the
+// CoinDCX market between the two (USDTINR, either way round), polled every minute. Empty converts
+// nothing. Read on every poll, so a change applies within a minute.
+
+const TICKER_URL: &str = "https://api.coindcx.com/exchange/ticker";
+const POLL: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReportingConfig {
+    pub currency: String, // Empty = the pair's quote currency
+}
+
+pub fn validate(cfg: &ReportingConfig) -> Result<(), String> {
+    match cfg.currency.chars().all(|c| c.is_ascii_alphanumeric()) {
+        true => Ok(()),
+        false => Err(format!("[reporting] currency {:?} is not a currency code like INR", cfg.currency)),
+    }
+}
+
+/// The pair's currencies and the reporting one, for the dashboard.
+#[derive(Clone, Debug, Serialize)]
+pub struct Currencies {
+    pub base: &'static str,
+    pub quote: &'static str,
+    pub reporting: String,
+    pub rate: Option<f64>, // One unit of the quote in the reporting currency, None until known
+}
+
+impl Currencies {
+    pub fn new() -> Self {
+        let market = symbols::active();
+        Currencies { base: market.base(), quote: market.quote(), reporting: market.quote().to_string(), rate: Some(1.0) }
+    }
+}
+
+// (reporting currency, rate) while it differs from the quote and a rate is known
+static RATE: Mutex<Option<(String, f64)>> = parking_lot::const_mutex(None);
+
+/// `amount` in the quote currency, written the way that currency is: $12.34, ₹1234.00, 12.34 EUR.
+pub fn format(amount: f64, currency: &str) -> String {
+    let (sign, abs) = if amount < 0.0 { ("-", -amount) } else { ("", amount) };
+    match currency {
+        "USD" | "USDT" | "USDC" => format!("{}${:.2}", sign, abs),
+        "INR" => format!("{}₹{:.2}", sign, abs),
+        code => format!("{}{:.2} {}", sign, abs, code),
+    }
+}
+
+/// Like `format`, always with a sign.
+pub fn signed(amount: f64, currency: &str) -> String {
+    match amount < 0.0 {
+        true => format(amount, currency),
+        false => format!("+{}", format(amount, currency)),
+    }
+}
+
+/// A quote amount in the quote currency.
+pub fn quote(amount: f64) -> String {
+    format(amount, symbols::active().quote())
+}
+
+/// A quote amount in the reporting currency, when that is another one and its rate is known.
+pub fn report(amount: f64) -> Option<(f64, String)> {
+    RATE.lock().as_ref().map(|(currency, rate)| (amount * rate, currency.clone()))
+}
+
+// The rate in use, while it is for the same currency
+fn last_rate(reporting: &str) -> Option<f64> {
+    RATE.lock().as_ref().filter(|(currency, _)| currency == reporting).map(|(_, rate)| *rate)
+}
+
+// One `quote` in `reporting`, from whichever of the two markets CoinDCX lists
+async fn fetch(client: &reqwest::Client, quote: &str, reporting: &str) -> Result<f64, String> {
+    let tickers: Vec<Value> = client.get(TICKER_URL).send().await.map_err(|e| e.to_string())?.json().await.map_err(|e| e.to_string())?;
+    let last = |market: String| tickers.iter().find(|t| t["market"] == market.as_str())
+        .and_then(|t| t["last_price"].as_str().and_then(|p| p.parse::<f64>().ok()).or_else(|| t["last_price"].as_f64()))
+        .filter(|p| *p > 0.0);
+    last(format!("{}{}", quote, reporting))
+        .or_else(|| last(format!("{}{}", reporting, quote)).map(|p| 1.0 / p))
+        .ok_or_else(|| format!("no {}{} or {}{} market to convert with", quote, reporting, reporting, quote))
+}
+
+pub async fn monitor(state: SharedState, config: SharedConfig) {
+    let client = reqwest::Client::builder().timeout(Duration::from_secs(5)).build().unwrap();
+    let quote = symbols::active().quote();
+    loop {
+        let wanted = config.read().reporting.currency.to_ascii_uppercase();
+        let reporting = if wanted.is_empty() { quote.to_string() } else { wanted };
+        let rate = match reporting == quote {
+            true => Some(1.0),
+            false => match fetch(&client, quote, &reporting).await {
+                Ok(rate) => Some(rate),
+                Err(e) => {
+                    eprintln!("Currency Rate Error: {}", e);
+                    last_rate(&reporting)
+                },
+            },
+        };
+        *RATE.lock() = rate.filter(|_| reporting != quote).map(|rate| (reporting.clone(), rate));
+        {
+            let mut data = state.write();
+            data.currencies.reporting = reporting;
+            data.currencies.rate = rate;
+        }
+        sleep(POLL).await;
+    }
+}
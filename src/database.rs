@@ -0,0 +1,99 @@
+use std::sync::OnceLock;
+use rusqlite::{Connection, Result as SqlResult};
+use serde::{Deserialize, Serialize};
+
+// --- 🗄️ SQLITE DURABILITY PROFILE ---
+// Applied to every pooled connection when it is opened. `durable` syncs each commit to disk;
+// `fast` (for a Pi on an SD card) only syncs at WAL checkpoints, so a power cut can lose the last
+// few commits but never corrupts the database. Any field set explicitly overrides the profile.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DbProfile {
+    Fast,
+    #[default]
+    Durable,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Synchronous {
+    Off,
+    Normal,
+    Full,
+    Extra,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JournalMode {
+    Wal,
+    Delete,
+    Truncate,
+    Memory,
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DatabaseConfig {
+    pub profile: DbProfile,
+    pub synchronous: Option<Synchronous>,
+    pub journal_mode: Option<JournalMode>,
+    pub cache_size_kb: Option<u64>, // Page cache per connection
+    pub mmap_size_mb: Option<u64>,  // Memory-mapped reads, 0 = off
+}
+
+/// The settings in effect once the profile and overrides are combined.
+#[derive(Clone, Copy, Debug)]
+pub struct Settings {
+    pub synchronous: Synchronous,
+    pub journal_mode: JournalMode,
+    pub cache_size_kb: u64,
+    pub mmap_size_mb: u64,
+}
+
+impl DatabaseConfig {
+    pub fn settings(&self) -> Settings {
+        let base = match self.profile {
+            DbProfile::Fast => Settings { synchronous: Synchronous::Normal, journal_mode: JournalMode::Wal, cache_size_kb: 8192, mmap_size_mb: 64 },
+            DbProfile::Durable => Settings { synchronous: Synchronous::Full, journal_mode: JournalMode::Wal, cache_size_kb: 2000, mmap_size_mb: 0 },
+        };
+        Settings {
+            synchronous: self.synchronous.unwrap_or(base.synchronous),
+            journal_mode: self.journal_mode.unwrap_or(base.journal_mode),
+            cache_size_kb: self.cache_size_kb.unwrap_or(base.cache_size_kb),
+            mmap_size_mb: self.mmap_size_mb.unwrap_or(base.mmap_size_mb),
+        }
+    }
+}
+
+static SETTINGS: OnceLock<Settings> = OnceLock::new();
+
+/// Must run before the first query (connections are opened once and kept).
+pub fn configure(cfg: &DatabaseConfig) {
+    let _ = SETTINGS.set(cfg.settings());
+}
+
+pub fn settings() -> Settings {
+    *SETTINGS.get_or_init(|| DatabaseConfig::default().settings())
+}
+
+pub fn apply(conn: &Connection) -> SqlResult<()> {
+    let s = settings();
+    conn.pragma_update(None, "journal_mode", match s.journal_mode {
+        JournalMode::Wal => "WAL",
+        JournalMode::Delete => "DELETE",
+        JournalMode::Truncate => "TRUNCATE",
+        JournalMode::Memory => "MEMORY",
+    })?;
+    conn.pragma_update(None, "synchronous", match s.synchronous {
+        Synchronous::Off => "OFF",
+        Synchronous::Normal => "NORMAL",
+        Synchronous::Full => "FULL",
+        Synchronous::Extra => "EXTRA",
+    })?;
+    // A negative cache_size is in KiB rather than pages
+    conn.pragma_update(None, "cache_size", -(s.cache_size_kb as i64))?;
+    conn.pragma_update(None, "mmap_size", s.mmap_size_mb * 1024 * 1024)?;
+    Ok(())
+}
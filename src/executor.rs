@@ -0,0 +1,194 @@
+use std::fmt::Write;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use sha2::Sha256;
+use tokio::sync::mpsc;
+use tokio::time::{interval, Instant, MissedTickBehavior};
+use tracing::{info_span, Instrument};
+
+use crate::fills::SimFills;
+use crate::symbols::{self, Market};
+use crate::{bybit, chaos, execute_trade, fix, get_api_credentials, okx, ratelimit, settle_order, simulating, timeouts, userstream};
+
+// --- 🏦 EXECUTOR STAGE ---
+// Orders run on their own task with a dedicated connection pool (plus the seeded fill model in
+// simulation). The trading loop hands over a request, keeps evaluating ticks while it is in
+// flight, and applies the fill when the report comes back.
+
+const QUEUE_SIZE: usize = 8;
+// Same host as order placement, so the ping keeps that connection alive; HEAD skips the body
+const WARM_URL: &str = "https://api.coindcx.com/exchange/ticker";
+const WARM_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Clone, Copy, Debug)]
+pub struct Fill {
+    pub price: Decimal,
+    pub qty: Decimal, // Zero: a simulated limit order that expired unfilled
+}
+
+#[derive(Clone, Copy, Debug)]
+pub enum OrderKind {
+    Entry { reason: &'static str },
+    Exit { reason: &'static str },    // Strategy exit or risk halt
+    Flatten { reason: &'static str }, // Outside the strategy: breaker trip, feed outage
+}
+
+/// What the loop saw when it decided on an order, stored with the trade.
+#[derive(Clone, Copy, Debug, Default, Serialize)]
+pub struct SignalContext {
+    pub signal_price: Decimal, // The tick decided on, before rounding, the spread and slippage
+    pub rsi: f64,
+    pub bb_lower: f64,
+    pub bb_upper: f64,
+    pub stop_price: Option<f64>, // Trailing stop level
+    pub spread_bps: Option<f64>, // Of the order book, when it was fresh
+    pub mode: &'static str,      // live | simulation | replay
+    pub slot: Option<usize>,     // Position slot the order is for; None for all of them (a flatten)
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct OrderRequest {
+    pub kind: OrderKind,
+    pub side: &'static str,
+    pub price: Decimal,
+    pub qty: Decimal,
+    pub timeout: Duration,
+    pub context: SignalContext,
+}
+
+pub struct OrderReport {
+    pub request: OrderRequest,
+    pub result: Result<Fill, String>, // Err: nothing was filled, the loop leaves the position as it was
+}
+
+/// Everything about an order that doesn't change between orders, prepared once: the JSON up to
+/// the price, the auth headers and the keyed HMAC. At signal time only price, quantity and
+/// timestamp are written in and signed.
+pub struct OrderTemplate {
+    buy_prefix: String,
+    sell_prefix: String,
+    market: Market,
+    headers: HeaderMap,
+    mac: Hmac<Sha256>,
+}
+
+impl OrderTemplate {
+    pub fn new() -> Self {
+        let (api_key, api_secret) = get_api_credentials();
+        let market = symbols::active();
+        let prefix = |side: &str| format!(r#"{{"side":"{}","order_type":"limit_order","market":"{}","price_per_unit":"#, side, market.order_code);
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        headers.insert("X-AUTH-APIKEY", HeaderValue::from_str(&api_key).unwrap());
+        OrderTemplate {
+            buy_prefix: prefix("buy"),
+            sell_prefix: prefix("sell"),
+            market,
+            headers,
+            mac: Hmac::new_from_slice(api_secret.as_bytes()).expect("Invalid Key"),
+        }
+    }
+
+    /// The signed limit order body and its headers.
+    pub fn render(&self, side: &str, price: Decimal, qty: Decimal, client_id: &str) -> (String, HeaderMap) {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+        let mut body = String::with_capacity(self.buy_prefix.len() + 96);
+        body.push_str(if side == "buy" { &self.buy_prefix } else { &self.sell_prefix });
+        let _ = write!(body, r#"{},"total_quantity":{},"client_order_id":"{}","timestamp":{}}}"#, self.market.price(price), self.market.qty(qty), client_id, timestamp);
+
+        let mut mac = self.mac.clone();
+        mac.update(body.as_bytes());
+        let mut headers = self.headers.clone();
+        headers.insert("X-AUTH-SIGNATURE", HeaderValue::from_str(&hex::encode(mac.finalize().into_bytes())).unwrap());
+        (body, headers)
+    }
+}
+
+pub struct Executor {
+    client: reqwest::Client,
+    template: OrderTemplate,
+    fills: SimFills,
+    keep_warm: Option<Duration>,
+}
+
+impl Executor {
+    pub fn new(fills: SimFills, keep_warm: Option<Duration>) -> Self {
+        Executor { client: timeouts::order_client(), template: OrderTemplate::new(), fills, keep_warm }
+    }
+
+    /// Places the order and returns the fill (the reported fill over FIX, from Bybit or OKX, or from
+    /// the user stream; the polled order status live without one; matched against the live book, or across
+    /// the spread with simulated slippage, and partial fills otherwise).
+    pub async fn place(&mut self, side: &'static str, price: Decimal, qty: Decimal, timeout: Duration) -> Result<Fill, String> {
+        let deadline = Instant::now() + timeout;
+        // Every route gets the order on the venue's grid, or nothing is sent
+        let (price, qty) = symbols::active().round_order(side, price, qty).map_err(|e| format!("order not placed: {}", e))?;
+        if !simulating() && fix::enabled() {
+            chaos::inject("order", timeout).await?;
+            return fix::place(side, price, qty, timeout).instrument(info_span!("order")).await;
+        }
+        if !simulating() && bybit::enabled() {
+            chaos::inject("order", timeout).await?;
+            return bybit::place(&self.client, side, price, qty, timeout).instrument(info_span!("order")).await;
+        }
+        if !simulating() && okx::enabled() {
+            chaos::inject("order", timeout).await?;
+            return okx::place(&self.client, side, price, qty, timeout).instrument(info_span!("order")).await;
+        }
+        let client_id = client_order_id();
+        let watch = userstream::watch(&client_id);
+        execute_trade(&self.client, &self.template, side, price, qty, &client_id, timeout).instrument(info_span!("order")).await?;
+        if simulating() {
+            let fill = self.fills.execute(side, price, qty, deadline).await;
+            return Ok(match fill.qty.is_zero() {
+                true => fill,
+                false => Fill { qty: chaos::fill_quantity(fill.qty), ..fill },
+            });
+        }
+        // Without a report from the stream the order is polled, and canceled if it is still working
+        match userstream::report(watch, deadline).await {
+            Some(outcome) => outcome,
+            None => settle_order(&self.client, &client_id, deadline).await,
+        }
+    }
+}
+
+/// A fresh id for a CoinDCX order; the user stream reports the order under it.
+pub fn client_order_id() -> String {
+    format!("scalper-{}", SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis())
+}
+
+/// Starts the executor task. It ends when the request sender is dropped (e.g. the trading task restarted).
+pub fn spawn(mut executor: Executor) -> (mpsc::Sender<OrderRequest>, mpsc::Receiver<OrderReport>) {
+    let (requests, mut incoming) = mpsc::channel::<OrderRequest>(QUEUE_SIZE);
+    let (outgoing, reports) = mpsc::channel(QUEUE_SIZE);
+    // Simulated orders never reach the exchange, so there is nothing to keep warm
+    if let (Some(every), false) = (executor.keep_warm, simulating()) {
+        tokio::spawn(keep_warm(executor.client.clone(), every, requests.downgrade()));
+    }
+    tokio::spawn(async move {
+        while let Some(request) = incoming.recv().await {
+            let result = executor.place(request.side, request.price, request.qty, request.timeout).await;
+            if outgoing.send(OrderReport { request, result }).await.is_err() { break; }
+        }
+    });
+    (requests, reports)
+}
+
+/// Connects right away, then pings on an interval so an order after a quiet spell doesn't pay for
+/// DNS + TCP + TLS. Runs beside the executor (a ping never delays an order) and stops with it.
+async fn keep_warm(client: reqwest::Client, every: Duration, executor: mpsc::WeakSender<OrderRequest>) {
+    let mut ticks = interval(every);
+    ticks.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        ticks.tick().await;
+        if executor.upgrade().is_none() { return; }
+        let url = if bybit::enabled() { bybit::warm_url() } else if okx::enabled() { okx::WARM_URL } else { WARM_URL };
+        if let Ok(r) = client.head(url).timeout(WARM_TIMEOUT).send().await {
+            ratelimit::observe(r.status(), r.headers());
+        }
+    }
+}
@@ -0,0 +1,272 @@
+use std::fs::File;
+use std::io::Write;
+use std::sync::Arc;
+use parquet::basic::{Compression, GzipLevel};
+use parquet::data_type::{ByteArray, ByteArrayType, DoubleType, Int64Type};
+use parquet::file::properties::WriterProperties;
+use parquet::file::writer::SerializedFileWriter;
+use parquet::schema::parser::parse_message_type;
+use serde::ser::{Serialize, SerializeMap, SerializeSeq, Serializer};
+
+use crate::stats::{self, Performance};
+use crate::{money, Candle, DbManager, TradeRecord};
+
+// --- 📤 EXPORT ---
+// The bot's own data out of bot_data.db for the `export` subcommand: the trades, the candles with
+// the indicators saved alongside, or the performance report of the trades in a range. Each comes
+// out as one table, written as CSV, JSON (an array of rows) or Parquet (one gzipped row group, no
+// Arrow needed to read it back). Amounts are written as numbers, like the JSON API does. The DB is
+// read as it is, so an export can run next to the bot.
+
+type SavedCandle = (Candle, [Option<f64>; 3]); // With its rsi, bb_lower and bb_upper
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Dataset {
+    Trades,
+    Candles,
+    Performance,
+}
+
+impl Dataset {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "trades" => Some(Dataset::Trades),
+            "candles" => Some(Dataset::Candles),
+            "performance" => Some(Dataset::Performance),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Format {
+    Csv,
+    Json,
+    Parquet,
+}
+
+impl Format {
+    pub fn parse(name: &str) -> Option<Self> {
+        match name {
+            "csv" => Some(Format::Csv),
+            "json" => Some(Format::Json),
+            "parquet" => Some(Format::Parquet),
+            _ => None,
+        }
+    }
+}
+
+/// A column's values, one per row; None is an empty cell, null or a Parquet null.
+pub enum Values {
+    Int(Vec<Option<i64>>),
+    Float(Vec<Option<f64>>),
+    Text(Vec<Option<String>>),
+}
+
+impl Values {
+    fn cell(&self, row: usize) -> String {
+        match self {
+            Values::Int(v) => v[row].map(|x| x.to_string()).unwrap_or_default(),
+            Values::Float(v) => v[row].map(|x| x.to_string()).unwrap_or_default(),
+            Values::Text(v) => v[row].clone().unwrap_or_default(),
+        }
+    }
+
+    // The column's line in a Parquet schema
+    fn parquet_field(&self, name: &str) -> String {
+        match self {
+            Values::Int(_) => format!("OPTIONAL INT64 {};", name),
+            Values::Float(_) => format!("OPTIONAL DOUBLE {};", name),
+            Values::Text(_) => format!("OPTIONAL BYTE_ARRAY {} (UTF8);", name),
+        }
+    }
+}
+
+pub struct Table {
+    pub name: &'static str,
+    pub rows: usize,
+    pub columns: Vec<(&'static str, Values)>,
+}
+
+// One row as a JSON object, columns in table order
+struct Row<'a>(&'a Table, usize);
+
+impl Serialize for Row<'_> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let Row(table, row) = self;
+        let mut map = serializer.serialize_map(Some(table.columns.len()))?;
+        for (name, values) in &table.columns {
+            match values {
+                Values::Int(v) => map.serialize_entry(name, &v[*row])?,
+                Values::Float(v) => map.serialize_entry(name, &v[*row])?,
+                Values::Text(v) => map.serialize_entry(name, &v[*row])?,
+            }
+        }
+        map.end()
+    }
+}
+
+impl Serialize for Table {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let mut seq = serializer.serialize_seq(Some(self.rows))?;
+        for row in 0..self.rows {
+            seq.serialize_element(&Row(self, row))?;
+        }
+        seq.end()
+    }
+}
+
+fn millis(timestamp: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(timestamp).ok().map(|t| t.timestamp_millis())
+}
+
+// Trades in [from, to), oldest first
+fn trades_between(from: i64, to: i64) -> Result<Vec<TradeRecord>, String> {
+    let trades = DbManager::get_all_trades().map_err(|e| e.to_string())?;
+    Ok(trades.into_iter().filter(|t| millis(&t.timestamp).is_some_and(|at| at >= from && at < to)).collect())
+}
+
+/// The dataset over [from, to) in Unix millis.
+pub fn table(dataset: Dataset, from: i64, to: i64) -> Result<Table, String> {
+    match dataset {
+        Dataset::Trades => Ok(trades_table(&trades_between(from, to)?)),
+        Dataset::Candles => {
+            let rows = DbManager::get_candles_between(from, to).map_err(|e| e.to_string())?;
+            let float = |f: fn(&SavedCandle) -> Option<f64>| Values::Float(rows.iter().map(f).collect());
+            Ok(Table {
+                name: "candles",
+                rows: rows.len(),
+                columns: vec![
+                    ("time", Values::Int(rows.iter().map(|(c, _)| Some(c.time)).collect())),
+                    ("open", float(|(c, _)| Some(c.open))),
+                    ("high", float(|(c, _)| Some(c.high))),
+                    ("low", float(|(c, _)| Some(c.low))),
+                    ("close", float(|(c, _)| Some(c.close))),
+                    ("rsi", float(|(_, i)| i[0])),
+                    ("bb_lower", float(|(_, i)| i[1])),
+                    ("bb_upper", float(|(_, i)| i[2])),
+                ],
+            })
+        },
+        Dataset::Performance => {
+            let equity: Vec<(i64, f64)> = DbManager::get_equity_curve().map_err(|e| e.to_string())?
+                .into_iter().filter(|(time, _)| *time >= from && *time < to).collect();
+            Ok(performance_table(&stats::compute(&trades_between(from, to)?, &equity)))
+        },
+    }
+}
+
+fn trades_table(trades: &[TradeRecord]) -> Table {
+    let amount = |f: fn(&TradeRecord) -> rust_decimal::Decimal| Values::Float(trades.iter().map(|t| Some(money::float(f(t)))).collect());
+    let float = |f: fn(&TradeRecord) -> Option<f64>| Values::Float(trades.iter().map(f).collect());
+    let text = |f: fn(&TradeRecord) -> &str| Values::Text(trades.iter().map(|t| Some(f(t).to_string())).collect());
+    Table {
+        name: "trades",
+        rows: trades.len(),
+        columns: vec![
+            ("id", Values::Int(trades.iter().map(|t| Some(t.id)).collect())),
+            ("timestamp", text(|t| &t.timestamp)),
+            ("action", text(|t| &t.action)),
+            ("price", amount(|t| t.price)),
+            ("quantity", amount(|t| t.quantity)),
+            ("profit", amount(|t| t.profit)),
+            ("gross_profit", amount(|t| t.gross_profit)),
+            ("reason", text(|t| &t.reason)),
+            ("signal_price", amount(|t| t.signal_price)),
+            ("rsi", float(|t| Some(t.rsi))),
+            ("bb_lower", float(|t| Some(t.bb_lower))),
+            ("bb_upper", float(|t| Some(t.bb_upper))),
+            ("stop_price", float(|t| t.stop_price)),
+            ("spread_bps", float(|t| t.spread_bps)),
+            ("mode", text(|t| &t.mode)),
+            ("slot", Values::Int(trades.iter().map(|t| t.slot.map(|s| s as i64)).collect())),
+        ],
+    }
+}
+
+// The report as a single row
+fn performance_table(p: &Performance) -> Table {
+    let int = |v: usize| Values::Int(vec![Some(v as i64)]);
+    let float = |v: f64| Values::Float(vec![Some(v)]);
+    Table {
+        name: "performance",
+        rows: 1,
+        columns: vec![
+            ("closed_trades", int(p.closed_trades)),
+            ("wins", int(p.wins)),
+            ("losses", int(p.losses)),
+            ("win_rate", float(p.win_rate)),
+            ("net_pnl", float(p.net_pnl)),
+            ("gross_profit", float(p.gross_profit)),
+            ("gross_loss", float(p.gross_loss)),
+            ("profit_factor", Values::Float(vec![p.profit_factor])),
+            ("expectancy", float(p.expectancy)),
+            ("avg_win", float(p.avg_win)),
+            ("avg_loss", float(p.avg_loss)),
+            ("sharpe", Values::Float(vec![p.sharpe])),
+            ("sortino", Values::Float(vec![p.sortino])),
+            ("max_drawdown", float(p.max_drawdown)),
+            ("avg_holding_secs", float(p.avg_holding_secs)),
+            ("rolling_win_rate", float(p.rolling_win_rate)),
+            ("rolling_expectancy", float(p.rolling_expectancy)),
+            ("streak", Values::Int(vec![Some(p.streak)])),
+        ],
+    }
+}
+
+/// The table in `format` to the file at `path`, or stdout without one (not for Parquet).
+pub fn write(table: &Table, format: Format, path: Option<&str>) -> Result<(), String> {
+    let mut out: Box<dyn Write> = match (format, path) {
+        (Format::Parquet, Some(path)) => return write_parquet(table, path),
+        (Format::Parquet, None) => return Err("Parquet is binary, give a file with --out".to_string()),
+        (_, Some(path)) => Box::new(File::create(path).map_err(|e| format!("writing {}: {}", path, e))?),
+        (_, None) => Box::new(std::io::stdout().lock()),
+    };
+    match format {
+        Format::Csv => {
+            let mut csv = csv::Writer::from_writer(out);
+            csv.write_record(table.columns.iter().map(|(name, _)| name)).map_err(|e| e.to_string())?;
+            for row in 0..table.rows {
+                csv.write_record(table.columns.iter().map(|(_, values)| values.cell(row))).map_err(|e| e.to_string())?;
+            }
+            csv.flush().map_err(|e| e.to_string())
+        },
+        _ => {
+            serde_json::to_writer_pretty(&mut out, table).map_err(|e| e.to_string())?;
+            writeln!(out).map_err(|e| e.to_string())
+        },
+    }
+}
+
+// Every column optional, one row group
+fn write_parquet(table: &Table, path: &str) -> Result<(), String> {
+    let fields: Vec<String> = table.columns.iter().map(|(name, values)| values.parquet_field(name)).collect();
+    let schema = parse_message_type(&format!("message {} {{ {} }}", table.name, fields.join(" "))).map_err(|e| e.to_string())?;
+    let props = WriterProperties::builder().set_compression(Compression::GZIP(GzipLevel::default())).build();
+    let file = File::create(path).map_err(|e| format!("writing {}: {}", path, e))?;
+    let mut writer = SerializedFileWriter::new(file, Arc::new(schema), Arc::new(props)).map_err(|e| e.to_string())?;
+    let mut group = writer.next_row_group().map_err(|e| e.to_string())?;
+    for (_, values) in &table.columns {
+        let mut column = group.next_column().map_err(|e| e.to_string())?.ok_or("schema has fewer columns than the table")?;
+        // A definition level of 1 is a value, 0 a null; only the values are written
+        let written = match values {
+            Values::Int(v) => {
+                let levels: Vec<i16> = v.iter().map(|x| x.is_some() as i16).collect();
+                column.typed::<Int64Type>().write_batch(&v.iter().flatten().copied().collect::<Vec<_>>(), Some(&levels), None)
+            },
+            Values::Float(v) => {
+                let levels: Vec<i16> = v.iter().map(|x| x.is_some() as i16).collect();
+                column.typed::<DoubleType>().write_batch(&v.iter().flatten().copied().collect::<Vec<_>>(), Some(&levels), None)
+            },
+            Values::Text(v) => {
+                let levels: Vec<i16> = v.iter().map(|x| x.is_some() as i16).collect();
+                let strings: Vec<ByteArray> = v.iter().flatten().map(|s| ByteArray::from(s.as_str())).collect();
+                column.typed::<ByteArrayType>().write_batch(&strings, Some(&levels), None)
+            },
+        };
+        written.map_err(|e| e.to_string())?;
+        column.close().map_err(|e| e.to_string())?;
+    }
+    group.close().map_err(|e| e.to_string())?;
+    writer.close().map(|_| ()).map_err(|e| e.to_string())
+}
@@ -0,0 +1,167 @@
+use std::time::Duration;
+use chrono::Utc;
+use futures_util::{SinkExt, StreamExt};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::time::{sleep, timeout_at, Instant};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{info_span, Span};
+
+use crate::{bybit, chaos, fills, okx, symbols, ticks};
+
+// --- 📶 TRADE STREAM (WebSocket) ---
+// Every public trade is pushed to the trading loop as it happens, instead of polling the latest
+// trade over REST every 5s. CoinDCX streams over socket.io (engine.io v3 framing on a WebSocket);
+// the order book's depth stream (orderbook.rs) uses the same session code.
+
+const STREAM_URL: &str = "wss://stream.coindcx.com/socket.io/?EIO=3&transport=websocket";
+const CHANNEL_SIZE: usize = 1024;
+pub const MAX_BACKOFF_SECS: u64 = 30;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum FeedSource {
+    #[default]
+    Websocket, // Trade stream, each trade processed as it arrives
+    Rest,      // Latest trade polled every 5s
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FeedConfig {
+    pub source: FeedSource,
+    pub book: FeedSource, // Order book: depth deltas kept in a local book, or a snapshot polled every 2s
+}
+
+/// A trade price with its latency span (closed when the loop picks it up), or why the stream dropped.
+/// The stream reconnects on its own.
+pub type StreamTick = Result<(f64, Span), String>;
+
+/// Starts the stream task. It stops once the receiver is dropped (e.g. the trading task restarted).
+pub fn spawn() -> mpsc::Receiver<StreamTick> {
+    let (tx, rx) = mpsc::channel(CHANNEL_SIZE);
+    tokio::spawn(run(tx));
+    rx
+}
+
+async fn run(tx: mpsc::Sender<StreamTick>) {
+    let mut backoff = 1;
+    loop {
+        let connected_at = Instant::now();
+        let streamed = if bybit::enabled() {
+            bybit::stream_trades(&tx).await
+        } else if okx::enabled() {
+            okx::stream_trades(&tx).await
+        } else {
+            stream_trades(&tx).await
+        };
+        let err = match streamed {
+            Ok(()) => return, // Receiver gone
+            Err(e) => e,
+        };
+        if tx.send(Err(format!("trade stream: {}", err))).await.is_err() { return; }
+        // A connection that lived for a while resets the backoff
+        if connected_at.elapsed() > Duration::from_secs(60) { backoff = 1; }
+        sleep(Duration::from_secs(backoff)).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF_SECS);
+    }
+}
+
+async fn stream_trades(tx: &mpsc::Sender<StreamTick>) -> Result<(), String> {
+    subscribe(&format!("{}@trades", symbols::active().data_code), |event| {
+        let Some(price) = trade_price(event) else { return Flow::Continue };
+        deliver(tx, price)
+    }).await
+}
+
+/// Records a trade and hands its price to the trading loop.
+pub fn deliver(tx: &mpsc::Sender<StreamTick>, price: f64) -> Flow {
+    ticks::record(Utc::now().timestamp_millis(), price);
+    fills::print(price);
+    match tx.try_send(Ok((chaos::tick(price), info_span!("feed")))) {
+        Err(mpsc::error::TrySendError::Closed(_)) => Flow::Stop,
+        // The loop is behind; this trade is dropped, the next one carries the price
+        Err(mpsc::error::TrySendError::Full(_)) | Ok(()) => Flow::Continue,
+    }
+}
+
+/// What a channel handler wants after an event.
+pub enum Flow {
+    Continue,
+    Resync, // Leave and rejoin the channel, so the server starts over with a full snapshot
+    Stop,   // Nobody is listening any more
+}
+
+/// One socket.io session joined to `channel`: every event (JSON, `["name", {..}]`) goes to
+/// `on_event`. Returns Ok once the handler stops, Err when the connection drops.
+pub async fn subscribe(channel: &str, on_event: impl FnMut(&str) -> Flow) -> Result<(), String> {
+    subscribe_with(serde_json::json!({ "channelName": channel }), on_event).await
+}
+
+/// Like `subscribe`, with the whole join payload (a private channel adds the key and signature).
+pub async fn subscribe_with(join: serde_json::Value, mut on_event: impl FnMut(&str) -> Flow) -> Result<(), String> {
+    let (ws, _) = connect_async(STREAM_URL).await.map_err(|e| e.to_string())?;
+    let (mut write, mut read) = ws.split();
+    let mut ping_every = Duration::from_secs(25);
+    let mut next_ping = Instant::now() + ping_every;
+
+    loop {
+        let msg = match timeout_at(next_ping, read.next()).await {
+            Err(_) => {
+                write.send(Message::Text("2".to_string())).await.map_err(|e| e.to_string())?;
+                next_ping = Instant::now() + ping_every;
+                continue;
+            },
+            Ok(None) => return Err("connection closed".to_string()),
+            Ok(Some(msg)) => msg.map_err(|e| e.to_string())?,
+        };
+        let text = match msg {
+            Message::Text(text) => text,
+            Message::Close(_) => return Err("closed by server".to_string()),
+            _ => continue,
+        };
+
+        match text.as_bytes().first() {
+            // Open: {"sid":..,"pingInterval":25000,..}
+            Some(b'0') => {
+                let open: serde_json::Value = serde_json::from_str(&text[1..]).unwrap_or_default();
+                if let Some(ms) = open["pingInterval"].as_u64() {
+                    ping_every = Duration::from_millis(ms);
+                    next_ping = Instant::now() + ping_every;
+                }
+            },
+            Some(b'4') if text == "40" => {
+                let msg = serde_json::json!(["join", join]);
+                write.send(Message::Text(format!("42{}", msg))).await.map_err(|e| e.to_string())?;
+            },
+            Some(b'4') if text == "41" => return Err("disconnected by server".to_string()),
+            Some(b'4') if text.starts_with("42") => match on_event(&text[2..]) {
+                Flow::Continue => {},
+                Flow::Resync => {
+                    for action in ["leave", "join"] {
+                        let msg = serde_json::json!([action, join]);
+                        write.send(Message::Text(format!("42{}", msg))).await.map_err(|e| e.to_string())?;
+                    }
+                },
+                Flow::Stop => return Ok(()),
+            },
+            _ => {}, // Pongs and anything else
+        }
+    }
+}
+
+// ["new-trade", {"data": "{\"p\":\"64000.5\",\"q\":\"0.01\",\"T\":...}"}]
+fn trade_price(event: &str) -> Option<f64> {
+    let event: serde_json::Value = serde_json::from_str(event).ok()?;
+    if event[0] != "new-trade" { return None; }
+    let data = &event[1]["data"];
+    let trade: serde_json::Value = match data.as_str() {
+        Some(raw) => serde_json::from_str(raw).ok()?,
+        None => data.clone(),
+    };
+    match &trade["p"] {
+        serde_json::Value::String(p) => p.parse().ok(),
+        p => p.as_f64(),
+    }
+}
@@ -0,0 +1,107 @@
+use std::sync::OnceLock;
+use chrono::Utc;
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio::time::{timeout_at, Instant};
+
+use crate::executor::Fill;
+use crate::money;
+use crate::orderbook::SharedBook;
+
+// --- 🎲 SIMULATED FILLS ---
+// Seeded, and drawn only from the trading loop in order, so two simulation runs over the same
+// data with the same seed produce identical trades. A fill crosses the spread like a real order
+// (buys pay half of it above the signal price, sells get half below), taken from the live book
+// when it is fresh and from the configured spread otherwise (replays, no book), with the random
+// slippage on top.
+//
+// With matching = "book" a simulated order on live data is a resting limit order instead: it fills
+// right away only if the fresh book's other side is already at or through its price (at that
+// touch), and otherwise at its own price once a streamed trade prints through it (below a buy,
+// above a sell). A trade at exactly the price doesn't count, since the order would be behind the
+// queue there. An order still resting when its time is up (the order timeout) expires unfilled,
+// so limit strategies see the fill rate they would get. Replays have no book or prints and always
+// fill the instant way.
+
+const PRINTS_SIZE: usize = 1024;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Matching {
+    #[default]
+    Book,    // Rests until the live market trades through it
+    Instant, // Fills at the signal price across the spread
+}
+
+// Every streamed trade price, for resting orders to match against
+static PRINTS: OnceLock<broadcast::Sender<f64>> = OnceLock::new();
+
+/// A trade off the stream; only kept while a simulated order is resting.
+pub fn print(price: f64) {
+    if let Some(prints) = PRINTS.get() {
+        let _ = prints.send(price);
+    }
+}
+
+pub struct SimFills {
+    rng: StdRng,
+    slippage_bps: f64,
+    spread_bps: f64,
+    book: Option<SharedBook>,
+    matching: Matching,
+}
+
+impl SimFills {
+    pub fn new(seed: u64, slippage_bps: f64, spread_bps: f64, book: Option<SharedBook>, matching: Matching) -> Self {
+        SimFills { rng: StdRng::seed_from_u64(seed), slippage_bps: slippage_bps.max(0.0), spread_bps: spread_bps.max(0.0), book, matching }
+    }
+
+    // Half the spread as a fraction of the price: the book's own if it is usable
+    fn half_spread(&self) -> f64 {
+        let live = self.book.as_ref().and_then(|book| book.read().fresh_spread_bps(Utc::now().timestamp_millis()));
+        live.unwrap_or(self.spread_bps) / 20_000.0
+    }
+
+    /// The spread, then adverse slippage uniform up to `slippage_bps`: buys fill above the signal
+    /// price, sells below.
+    pub fn fill(&mut self, side: &str, price: Decimal) -> Decimal {
+        let mut cost = self.half_spread();
+        if self.slippage_bps > 0.0 {
+            cost += self.rng.gen::<f64>() * self.slippage_bps / 10_000.0;
+        }
+        if cost == 0.0 { return price; }
+        let factor = if side == "buy" { 1.0 + cost } else { 1.0 - cost };
+        (price * money::dec(factor)).round_dp(8)
+    }
+
+    /// The simulated order's fill: instant across the spread, or matched against the live book and
+    /// trades until `deadline`. A zero quantity means it expired unfilled.
+    pub async fn execute(&mut self, side: &str, price: Decimal, qty: Decimal, deadline: Instant) -> Fill {
+        let Some(book) = self.book.clone().filter(|_| self.matching == Matching::Book) else {
+            return Fill { price: self.fill(side, price), qty };
+        };
+        // Subscribed before the book is looked at, so no trade falls in between
+        let mut prints = PRINTS.get_or_init(|| broadcast::channel(PRINTS_SIZE).0).subscribe();
+        let limit = money::float(price);
+        loop {
+            let touch = book.read().fresh_touch(Utc::now().timestamp_millis());
+            let crossed = touch.and_then(|(bid, ask)| match side {
+                "buy" => (ask <= limit).then_some(ask),
+                _ => (bid >= limit).then_some(bid),
+            });
+            if let Some(at) = crossed {
+                return Fill { price: money::dec(at), qty };
+            }
+            match timeout_at(deadline, prints.recv()).await {
+                Err(_) => return Fill { price, qty: Decimal::ZERO },
+                Ok(Ok(print)) if (side == "buy" && print < limit) || (side != "buy" && print > limit) => return Fill { price, qty },
+                // Trades missed while lagging only change the book, which is checked again
+                Ok(Ok(_)) | Ok(Err(broadcast::error::RecvError::Lagged(_))) => {},
+                Ok(Err(broadcast::error::RecvError::Closed)) => return Fill { price, qty: Decimal::ZERO },
+            }
+        }
+    }
+}
@@ -0,0 +1,307 @@
+use std::collections::HashMap;
+use std::env;
+use std::fmt::Write as _;
+use std::sync::OnceLock;
+use std::time::Duration;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::{interval_at, sleep, timeout, Instant};
+
+use crate::executor::Fill;
+use crate::feed::MAX_BACKOFF_SECS;
+use crate::{money, simulating, symbols};
+
+// --- 🔌 FIX GATEWAY ---
+// Optional FIX 4.4 order route for venues and brokers that offer one. A single session is kept
+// for the life of the process (logon, heartbeats, test requests, reconnect with backoff); when it
+// is enabled the executor sends live orders as NewOrderSingle here instead of over REST and takes
+// the fill from the ExecutionReport. Orders are limit IOC, so every order ends in one final report
+// (filled, partly filled then canceled, or rejected) like a REST order that fills or fails.
+// Sequence numbers are reset at each logon and resends are not served, so the acceptor has to
+// accept ResetSeqNumFlag. Credentials, if the venue wants them, come from FIX_USERNAME / FIX_PASSWORD.
+
+const SOH: char = '\x01';
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+const QUEUE_SIZE: usize = 8;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct FixConfig {
+    pub enabled: bool,
+    pub host: String,
+    pub port: u16,
+    pub sender_comp_id: String,
+    pub target_comp_id: String,
+    pub symbol: String, // Empty = the registry's order code
+    pub heartbeat_secs: u64,
+}
+
+impl Default for FixConfig {
+    fn default() -> Self {
+        FixConfig {
+            enabled: false,
+            host: String::new(),
+            port: 9878,
+            sender_comp_id: String::new(),
+            target_comp_id: String::new(),
+            symbol: String::new(),
+            heartbeat_secs: 30,
+        }
+    }
+}
+
+struct FixOrder {
+    side: &'static str,
+    price: Decimal,
+    qty: Decimal,
+    deadline: Instant, // Not sent after this; the executor has given up on it
+    reply: oneshot::Sender<Result<Fill, String>>,
+}
+
+static ORDERS: OnceLock<mpsc::Sender<FixOrder>> = OnceLock::new();
+
+/// Starts the session (live mode only).
+pub fn start(cfg: FixConfig) {
+    if !cfg.enabled || simulating() { return; }
+    let (tx, rx) = mpsc::channel(QUEUE_SIZE);
+    if ORDERS.set(tx).is_err() { return; }
+    tokio::spawn(session_loop(rx, cfg));
+}
+
+pub fn enabled() -> bool {
+    ORDERS.get().is_some()
+}
+
+/// Sends a limit IOC order and waits for its final execution report.
+pub async fn place(side: &'static str, price: Decimal, qty: Decimal, wait: Duration) -> Result<Fill, String> {
+    let orders = ORDERS.get().ok_or("FIX session not started")?;
+    let (reply, report) = oneshot::channel();
+    let order = FixOrder { side, price, qty, deadline: Instant::now() + wait, reply };
+    orders.send(order).await.map_err(|_| "FIX session stopped".to_string())?;
+    match timeout(wait, report).await {
+        Ok(Ok(result)) => result,
+        Ok(Err(_)) => Err("FIX session lost before the execution report".to_string()),
+        Err(_) => Err(format!("no execution report within {:?}", wait)),
+    }
+}
+
+struct Session<'a> {
+    cfg: &'a FixConfig,
+    stream: TcpStream,
+    seq: u64,
+}
+
+impl Session<'_> {
+    async fn send(&mut self, msg_type: &str, fields: &[(u32, String)]) -> Result<u64, String> {
+        let seq = self.seq;
+        let sent_at = Utc::now().format("%Y%m%d-%H:%M:%S%.3f").to_string();
+        let msg = encode(self.cfg, msg_type, seq, &sent_at, fields);
+        self.stream.write_all(msg.as_bytes()).await.map_err(|e| e.to_string())?;
+        self.seq += 1;
+        Ok(seq)
+    }
+}
+
+/// A message as it goes on the wire: header, body, BodyLength and CheckSum.
+fn encode(cfg: &FixConfig, msg_type: &str, seq: u64, sent_at: &str, fields: &[(u32, String)]) -> String {
+    let mut body = String::with_capacity(256);
+    let _ = write!(body, "35={}{s}49={}{s}56={}{s}34={}{s}52={}{s}", msg_type, cfg.sender_comp_id, cfg.target_comp_id, seq, sent_at, s = SOH);
+    for (tag, value) in fields {
+        let _ = write!(body, "{}={}{}", tag, value, SOH);
+    }
+    let mut msg = format!("8=FIX.4.4{s}9={}{s}{}", body.len(), body, s = SOH);
+    let checksum = msg.bytes().map(u32::from).sum::<u32>() % 256;
+    let _ = write!(msg, "10={:03}{}", checksum, SOH);
+    msg
+}
+
+/// Length of the first complete message in `buf` (from 8= through the checksum), if it is all there.
+fn frame(buf: &[u8]) -> Result<Option<usize>, String> {
+    let Some(first) = buf.iter().position(|b| *b == SOH as u8) else { return Ok(None) };
+    let rest = &buf[first + 1..];
+    let Some(second) = rest.iter().position(|b| *b == SOH as u8) else { return Ok(None) };
+    let body_len = std::str::from_utf8(&rest[..second]).ok()
+        .and_then(|f| f.strip_prefix("9="))
+        .and_then(|n| n.parse::<usize>().ok())
+        .ok_or("malformed message (no BodyLength)")?;
+    let total = first + 1 + second + 1 + body_len + "10=000\x01".len();
+    Ok((buf.len() >= total).then_some(total))
+}
+
+fn fields(msg: &[u8]) -> HashMap<u32, String> {
+    String::from_utf8_lossy(msg).split(SOH)
+        .filter_map(|f| f.split_once('='))
+        .filter_map(|(tag, value)| Some((tag.parse().ok()?, value.to_string())))
+        .collect()
+}
+
+/// The order's outcome from an execution report, or None while it is still working.
+fn outcome(report: &HashMap<u32, String>) -> Option<Result<Fill, String>> {
+    let num = |tag| report.get(&tag).and_then(|v| money::parse(v)).unwrap_or_default();
+    let (filled, avg_px) = (num(14), num(6));
+    let text = report.get(&58).cloned().unwrap_or_default();
+    match report.get(&39).map(String::as_str) {
+        Some("2") => Some(Ok(Fill { price: avg_px, qty: filled })),
+        // IOC remainder canceled (or expired): whatever filled is the fill
+        Some("4") | Some("C") if filled > Decimal::ZERO => Some(Ok(Fill { price: avg_px, qty: filled })),
+        Some("4") | Some("C") => Some(Err(format!("not filled (IOC canceled) {}", text).trim_end().to_string())),
+        Some("8") => Some(Err(format!("order rejected: {}", text))),
+        _ => None,
+    }
+}
+
+async fn run_session(orders: &mut mpsc::Receiver<FixOrder>, cfg: &FixConfig) -> Result<(), String> {
+    let stream = timeout(CONNECT_TIMEOUT, TcpStream::connect((cfg.host.as_str(), cfg.port))).await
+        .map_err(|_| "connect timed out".to_string())?
+        .map_err(|e| e.to_string())?;
+    let _ = stream.set_nodelay(true);
+    let mut session = Session { cfg, stream, seq: 1 };
+    let heartbeat = Duration::from_secs(cfg.heartbeat_secs.max(1));
+    let mut logon = vec![(98, "0".to_string()), (108, heartbeat.as_secs().to_string()), (141, "Y".to_string())];
+    if let (Ok(user), Ok(password)) = (env::var("FIX_USERNAME"), env::var("FIX_PASSWORD")) {
+        logon.extend([(553, user), (554, password)]);
+    }
+    session.send("A", &logon).await?;
+
+    let market = symbols::active();
+    let symbol = if cfg.symbol.is_empty() { market.order_code.to_string() } else { cfg.symbol.clone() };
+    let mut logged_on = false;
+    let mut pending: HashMap<String, (u64, FixOrder)> = HashMap::new(); // ClOrdID -> (MsgSeqNum, order)
+    let mut buf: Vec<u8> = Vec::with_capacity(4096);
+    let mut chunk = [0u8; 4096];
+    let mut ticks = interval_at(Instant::now() + heartbeat, heartbeat);
+    let mut last_received = Instant::now();
+
+    loop {
+        tokio::select! {
+            read = session.stream.read(&mut chunk) => {
+                let n = read.map_err(|e| e.to_string())?;
+                if n == 0 { return Err("connection closed by the acceptor".to_string()); }
+                buf.extend_from_slice(&chunk[..n]);
+                last_received = Instant::now();
+                while let Some(len) = frame(&buf)? {
+                    let msg = fields(&buf[..len]);
+                    buf.drain(..len);
+                    let text = msg.get(&58).cloned().unwrap_or_default();
+                    match msg.get(&35).map(String::as_str) {
+                        Some("A") => {
+                            logged_on = true;
+                            println!("🔌 FIX session logged on ({}:{})", cfg.host, cfg.port);
+                        },
+                        Some("1") => {
+                            let id = msg.get(&112).cloned().unwrap_or_default();
+                            session.send("0", &[(112, id)]).await?;
+                        },
+                        Some("5") => return Err(format!("logged out by the acceptor: {}", text)),
+                        Some("8") => {
+                            let Some(id) = msg.get(&11) else { continue };
+                            if let Some(result) = outcome(&msg) {
+                                if let Some((_, order)) = pending.remove(id) { let _ = order.reply.send(result); }
+                            }
+                        },
+                        // Session or business level reject of one of our messages
+                        Some("3") | Some("j") => {
+                            let ref_seq = msg.get(&45).and_then(|s| s.parse::<u64>().ok());
+                            let id = pending.iter().find(|(_, (seq, _))| Some(*seq) == ref_seq).map(|(id, _)| id.clone());
+                            match id.and_then(|id| pending.remove(&id)) {
+                                Some((_, order)) => { let _ = order.reply.send(Err(format!("order rejected: {}", text))); },
+                                None => eprintln!("FIX Reject: {}", text),
+                            }
+                        },
+                        _ => {},
+                    }
+                }
+            },
+            order = orders.recv(), if logged_on => {
+                let Some(order) = order else { return Ok(()) };
+                if Instant::now() >= order.deadline {
+                    let _ = order.reply.send(Err("expired while the FIX session was down".to_string()));
+                    continue;
+                }
+                let id = format!("{}-{}", Utc::now().timestamp_millis(), session.seq);
+                let seq = session.send("D", &[
+                    (11, id.clone()),
+                    (55, symbol.clone()),
+                    (54, if order.side == "buy" { "1" } else { "2" }.to_string()),
+                    (60, Utc::now().format("%Y%m%d-%H:%M:%S%.3f").to_string()),
+                    (38, market.qty(order.qty)),
+                    (40, "2".to_string()), // Limit
+                    (44, market.price(order.price)),
+                    (59, "3".to_string()), // Immediate or cancel
+                ]).await?;
+                pending.insert(id, (seq, order));
+            },
+            _ = ticks.tick() => {
+                if !logged_on { return Err("no logon response".to_string()); }
+                if last_received.elapsed() > heartbeat * 2 {
+                    return Err(format!("nothing from the acceptor for {}s", last_received.elapsed().as_secs()));
+                }
+                session.send("0", &[]).await?;
+            },
+        }
+    }
+}
+
+async fn session_loop(mut orders: mpsc::Receiver<FixOrder>, cfg: FixConfig) {
+    let mut backoff = 1;
+    loop {
+        let started = Instant::now();
+        let result = run_session(&mut orders, &cfg).await;
+        if started.elapsed() > Duration::from_secs(60) { backoff = 1; }
+        match result {
+            Ok(()) => return,
+            Err(e) => eprintln!("FIX Error: {} (reconnecting in {}s)", e, backoff),
+        }
+        sleep(Duration::from_secs(backoff)).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF_SECS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // A fully filled buy as a broker would report it, | for SOH
+    const FILLED: &str = "8=FIX.4.4|9=135|35=8|49=BROKER|56=SCALPER|34=7|52=20240301-12:00:00.000|37=O1|11=C1|17=E1|150=F|39=2|55=BTCUSDT|54=1|38=0.002|14=0.002|6=60000.5|151=0|10=166|";
+
+    fn wire(text: &str) -> String {
+        text.replace('|', &SOH.to_string())
+    }
+
+    #[test]
+    fn encodes_length_and_checksum_like_the_wire() {
+        let cfg = FixConfig { sender_comp_id: "BROKER".to_string(), target_comp_id: "SCALPER".to_string(), ..FixConfig::default() };
+        let fields: Vec<(u32, String)> = [(37, "O1"), (11, "C1"), (17, "E1"), (150, "F"), (39, "2"), (55, "BTCUSDT"), (54, "1"), (38, "0.002"), (14, "0.002"), (6, "60000.5"), (151, "0")]
+            .into_iter().map(|(tag, value)| (tag, value.to_string())).collect();
+        assert_eq!(encode(&cfg, "8", 7, "20240301-12:00:00.000", &fields), wire(FILLED));
+    }
+
+    #[test]
+    fn frames_one_message_at_a_time() {
+        let msg = wire(FILLED);
+        // Two messages back to back, the second cut short
+        let buf = format!("{}{}", msg, &msg[..40]);
+        assert_eq!(frame(buf.as_bytes()), Ok(Some(msg.len())));
+        assert_eq!(frame(&buf.as_bytes()[msg.len()..]), Ok(None));
+        assert_eq!(frame(&msg.as_bytes()[..msg.len() - 1]), Ok(None));
+        assert!(frame(wire("8=FIX.4.4|9=x|35=0|").as_bytes()).is_err());
+    }
+
+    #[test]
+    fn reads_the_fill_from_an_execution_report() {
+        let report = fields(wire(FILLED).as_bytes());
+        assert_eq!(report.get(&35).map(String::as_str), Some("8"));
+        let fill = outcome(&report).unwrap().unwrap();
+        assert_eq!((fill.price, fill.qty), (Decimal::new(600005, 1), Decimal::new(2, 3)));
+
+        let partial = fields(wire("35=8|39=4|14=0.001|6=60000|").as_bytes());
+        assert_eq!(outcome(&partial).map(|r| r.map(|f| f.qty)), Some(Ok(Decimal::new(1, 3))));
+        let rejected = fields(wire("35=8|39=8|58=Unknown symbol|").as_bytes());
+        assert_eq!(outcome(&rejected).map(|r| r.map(|f| f.qty)), Some(Err("order rejected: Unknown symbol".to_string())));
+        assert!(outcome(&fields(wire("35=8|39=0|").as_bytes())).is_none());
+    }
+}
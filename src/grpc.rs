@@ -0,0 +1,268 @@
+use std::env;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use std::sync::{Arc, OnceLock};
+use std::time::Duration;
+use http::StatusCode;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::sync::broadcast;
+use tokio::time::{interval, MissedTickBehavior};
+use tokio_stream::wrappers::{BroadcastStream, IntervalStream};
+use tokio_stream::{Stream, StreamExt};
+use tonic::service::Interceptor;
+use tonic::{Request, Response};
+
+use crate::config::SharedConfig;
+use crate::strategy::StrategyParams;
+use crate::{control, money, runtime, simulating, BotState, DashboardData, DbManager, SharedState, PAIR};
+
+mod pb {
+    tonic::include_proto!("scalper");
+}
+
+use pb::scalper_server::{Scalper, ScalperServer};
+
+// --- 🛰️ gRPC API ---
+// The dashboard's control surface (status, pause, resume, flatten, config and strategy) as a typed
+// gRPC service, plus server-streaming feeds of ticks, trades and state for external risk systems
+// and desktop clients; proto/scalper.proto is the contract. Controls go through the same functions
+// as the dashboard and are recorded in the activity log as "grpc". Feeds are broadcast: a
+// subscriber that falls behind skips what it missed rather than slowing the trading loop.
+// GRPC_TOKEN, when set, must be sent as a bearer token on every call.
+
+const FEED_SIZE: usize = 1024;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct GrpcConfig {
+    pub enabled: bool,
+    pub bind: String, // Anything but loopback needs GRPC_TOKEN
+    pub port: u16,
+}
+
+impl Default for GrpcConfig {
+    fn default() -> Self {
+        GrpcConfig { enabled: false, bind: "127.0.0.1".to_string(), port: 50051 }
+    }
+}
+
+static TICKS: OnceLock<broadcast::Sender<pb::Tick>> = OnceLock::new();
+static TRADES: OnceLock<broadcast::Sender<pb::Trade>> = OnceLock::new();
+
+/// Called by the trading loop for every price it acts on; never waits.
+pub fn tick(time: i64, price: f64) {
+    if let Some(feed) = TICKS.get() {
+        let _ = feed.send(pb::Tick { time, price });
+    }
+}
+
+/// Called for every fill; never waits.
+pub fn trade(at: DateTime<Utc>, side: &str, price: Decimal, qty: Decimal, profit: Decimal, gross: Decimal, reason: &str) {
+    if let Some(feed) = TRADES.get() {
+        let _ = feed.send(pb::Trade {
+            time: at.timestamp_millis(), side: side.to_string(), price: money::float(price), qty: money::float(qty),
+            profit: money::float(profit), reason: reason.to_string(), gross_profit: money::float(gross),
+        });
+    }
+}
+
+fn status_of(data: &DashboardData) -> pb::Status {
+    let (in_position, quantity) = match data.position {
+        BotState::InPosition { quantity, .. } => (true, money::float(quantity)),
+        BotState::Idle => (false, 0.0),
+    };
+    let s = &data.session;
+    pb::Status {
+        pair: PAIR.to_string(),
+        status: data.status.clone(),
+        strategy: data.strategy.clone(),
+        price: data.price,
+        rsi: data.rsi,
+        bb_lower: data.bb_lower,
+        bb_upper: data.bb_upper,
+        in_position,
+        entry_price: money::float(data.entry_price),
+        quantity,
+        unrealized_pl_pct: data.unrealized_pl,
+        realized_pl: money::float(data.realized_pl),
+        gross_pl: money::float(data.gross_pl),
+        day_pnl: data.risk.day_pnl,
+        halted: data.risk.halted.clone().unwrap_or_default(),
+        breaker: data.breaker.clone(),
+        last_tick_at: data.last_tick_at,
+        trades: s.trades,
+        wins: s.wins,
+        losses: s.losses,
+        simulation: simulating(),
+    }
+}
+
+impl From<StrategyParams> for pb::StrategyParams {
+    fn from(p: StrategyParams) -> Self {
+        pb::StrategyParams {
+            rsi_buy: p.rsi_buy, rsi_sell: p.rsi_sell, rsi_crash: p.rsi_crash,
+            trailing_stop_pct: p.trailing_stop_pct, trade_capital: p.trade_capital, hard_stop_pct: p.hard_stop_pct, break_even_pct: p.break_even_pct, max_hold_mins: p.max_hold_mins,
+            reentry_pct: p.reentry_pct, reentry_rsi: p.reentry_rsi,
+        }
+    }
+}
+
+impl From<pb::StrategyParams> for StrategyParams {
+    fn from(p: pb::StrategyParams) -> Self {
+        StrategyParams {
+            rsi_buy: p.rsi_buy, rsi_sell: p.rsi_sell, rsi_crash: p.rsi_crash,
+            trailing_stop_pct: p.trailing_stop_pct, trade_capital: p.trade_capital, hard_stop_pct: p.hard_stop_pct, break_even_pct: p.break_even_pct, max_hold_mins: p.max_hold_mins,
+            reentry_pct: p.reentry_pct, reentry_rsi: p.reentry_rsi,
+        }
+    }
+}
+
+fn to_status((code, message): (StatusCode, String)) -> tonic::Status {
+    match code {
+        StatusCode::BAD_REQUEST => tonic::Status::invalid_argument(message),
+        StatusCode::CONFLICT => tonic::Status::failed_precondition(message),
+        _ => tonic::Status::internal(message),
+    }
+}
+
+async fn log_action(action: String) {
+    let _ = runtime::db(move || DbManager::log_activity("grpc", &action)).await;
+}
+
+type Feed<T> = Pin<Box<dyn Stream<Item = Result<T, tonic::Status>> + Send>>;
+
+fn subscribe<T: Clone + Send + 'static>(feed: &OnceLock<broadcast::Sender<T>>) -> Option<Feed<T>> {
+    let rx = feed.get()?.subscribe();
+    // Lagged: the messages it missed are gone, carry on from the newest
+    Some(Box::pin(BroadcastStream::new(rx).filter_map(|item| item.ok().map(Ok))))
+}
+
+/// Rejects calls without the GRPC_TOKEN bearer token (when one is set).
+#[derive(Clone)]
+struct TokenCheck {
+    expected: Option<String>, // "Bearer <token>"
+}
+
+impl Interceptor for TokenCheck {
+    fn call(&mut self, request: Request<()>) -> Result<Request<()>, tonic::Status> {
+        let Some(expected) = &self.expected else { return Ok(request) };
+        match request.metadata().get("authorization") {
+            Some(sent) if sent.to_str().ok() == Some(expected.as_str()) => Ok(request),
+            _ => Err(tonic::Status::unauthenticated("missing or wrong bearer token")),
+        }
+    }
+}
+
+struct Service {
+    state: SharedState,
+    config: SharedConfig,
+}
+
+#[tonic::async_trait]
+impl Scalper for Service {
+    async fn get_status(&self, _: Request<pb::Empty>) -> Result<Response<pb::Status>, tonic::Status> {
+        Ok(Response::new(status_of(&self.state.read())))
+    }
+
+    async fn pause(&self, _: Request<pb::Empty>) -> Result<Response<pb::ControlReply>, tonic::Status> {
+        let result = control::pause(&self.state, "grpc").await;
+        log_action(format!("Pause -> {}", result.as_ref().map_or_else(|e| e.as_str(), |()| "ok"))).await;
+        result.map_err(tonic::Status::failed_precondition)?;
+        Ok(Response::new(pb::ControlReply { message: "Paused, no new entries until resumed".to_string() }))
+    }
+
+    async fn resume(&self, _: Request<pb::Empty>) -> Result<Response<pb::ControlReply>, tonic::Status> {
+        let message = match control::resume(&self.state).await {
+            Some(reason) => format!("Resumed (was: {})", reason),
+            None => "Not halted, nothing to resume".to_string(),
+        };
+        log_action(format!("Resume -> {}", message)).await;
+        Ok(Response::new(pb::ControlReply { message }))
+    }
+
+    async fn flatten(&self, _: Request<pb::Empty>) -> Result<Response<pb::ControlReply>, tonic::Status> {
+        let result = control::close(&self.state, "grpc");
+        log_action(format!("Flatten -> {}", result.as_ref().map_or_else(|e| e.as_str(), |()| "ok"))).await;
+        result.map_err(tonic::Status::failed_precondition)?;
+        Ok(Response::new(pb::ControlReply { message: "Closing the position on the next tick".to_string() }))
+    }
+
+    async fn get_config(&self, _: Request<pb::Empty>) -> Result<Response<pb::ConfigReply>, tonic::Status> {
+        let cfg = self.config.read().clone();
+        let file = cfg.as_written().map_err(tonic::Status::internal)?.redacted();
+        let toml = toml::to_string_pretty(&file).map_err(|e| tonic::Status::internal(e.to_string()))?;
+        Ok(Response::new(pb::ConfigReply { strategy: cfg.strategy.name, params: Some(cfg.strategy.params.into()), toml }))
+    }
+
+    async fn set_strategy(&self, request: Request<pb::StrategyUpdate>) -> Result<Response<pb::ConfigReply>, tonic::Status> {
+        let update = request.into_inner();
+        let params = update.params.ok_or_else(|| tonic::Status::invalid_argument("params are required"))?;
+        let name = (!update.name.is_empty()).then_some(update.name);
+        let result = control::set_strategy(&self.state, &self.config, name.clone(), params.into());
+        let outcome = result.as_ref().map_or_else(|(_, e)| e.clone(), |()| "ok".to_string());
+        log_action(format!("SetStrategy {} -> {}", name.as_deref().unwrap_or("(active)"), outcome)).await;
+        result.map_err(to_status)?;
+        self.get_config(Request::new(pb::Empty {})).await
+    }
+
+    type StreamTicksStream = Feed<pb::Tick>;
+
+    async fn stream_ticks(&self, _: Request<pb::Empty>) -> Result<Response<Self::StreamTicksStream>, tonic::Status> {
+        subscribe(&TICKS).map(Response::new).ok_or_else(|| tonic::Status::unavailable("feed not started"))
+    }
+
+    type StreamTradesStream = Feed<pb::Trade>;
+
+    async fn stream_trades(&self, _: Request<pb::Empty>) -> Result<Response<Self::StreamTradesStream>, tonic::Status> {
+        subscribe(&TRADES).map(Response::new).ok_or_else(|| tonic::Status::unavailable("feed not started"))
+    }
+
+    type StreamStateStream = Feed<pb::Status>;
+
+    async fn stream_state(&self, request: Request<pb::StateRequest>) -> Result<Response<Self::StreamStateStream>, tonic::Status> {
+        let every = match request.into_inner().interval_ms {
+            0 => Duration::from_secs(1),
+            ms => Duration::from_millis(u64::from(ms).max(100)),
+        };
+        let mut ticker = interval(every);
+        ticker.set_missed_tick_behavior(MissedTickBehavior::Skip);
+        let state = self.state.clone();
+        let mut last: Option<Arc<DashboardData>> = None;
+        // A new value is published on every tick; unchanged samples are not sent again
+        let feed = IntervalStream::new(ticker).filter_map(move |_| {
+            let current = Arc::clone(&state.read());
+            if last.as_ref().is_some_and(|seen| Arc::ptr_eq(seen, &current)) { return None; }
+            let status = status_of(&current);
+            last = Some(current);
+            Some(Ok(status))
+        });
+        Ok(Response::new(Box::pin(feed)))
+    }
+}
+
+/// Starts the server (when enabled) and the feeds it streams from.
+pub fn start(cfg: GrpcConfig, state: SharedState, config: SharedConfig) {
+    if !cfg.enabled { return; }
+    let token = env::var("GRPC_TOKEN").ok().filter(|t| !t.is_empty());
+    let addr: SocketAddr = match format!("{}:{}", cfg.bind, cfg.port).parse() {
+        Ok(addr) => addr,
+        Err(e) => return eprintln!("❌ gRPC API disabled: bad bind address {}:{}: {}", cfg.bind, cfg.port, e),
+    };
+    if token.is_none() && !addr.ip().is_loopback() {
+        return eprintln!("❌ gRPC API disabled: set GRPC_TOKEN to listen on {}", addr);
+    }
+    let (ticks, _) = broadcast::channel(FEED_SIZE);
+    let (trades, _) = broadcast::channel(FEED_SIZE);
+    if TICKS.set(ticks).is_err() || TRADES.set(trades).is_err() { return; }
+
+    let check = TokenCheck { expected: token.map(|t| format!("Bearer {}", t)) };
+    let service = ScalperServer::with_interceptor(Service { state, config }, check);
+    tokio::spawn(async move {
+        println!("🛰️ gRPC API: {}", addr);
+        if let Err(e) = tonic::transport::Server::builder().add_service(service).serve(addr).await {
+            eprintln!("gRPC Error: {}", e);
+        }
+    });
+}
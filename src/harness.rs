@@ -0,0 +1,267 @@
+use crate::backtest::{Held, Step, Stepper};
+use crate::indicators::MIN_HISTORY;
+use crate::strategy::{self, Strategy, StrategyParams};
+use crate::Candle;
+
+// --- 🧪 STRATEGY TEST HARNESS ---
+// Scripted price scenarios (one close per 1m candle) driven through any strategy instance by the
+// backtest's own Stepper, so they exercise what backtest::run trades on: the indicator pipeline and
+// its warm-up, the re-entry band, and the exit chain. Fills are at the closes (no spread). Tests
+// assert on the signals and the position left at the end, so strategies can be refactored safely.
+
+const CANDLE_MS: i64 = 60_000;
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum Signal {
+    Entry { step: usize, price: f64, reason: &'static str },
+    Exit { step: usize, price: f64, reason: &'static str },
+}
+
+#[derive(Debug)]
+pub struct HarnessRun {
+    pub signals: Vec<Signal>,
+    pub position: Option<Held>,
+}
+
+impl HarnessRun {
+    pub fn entries(&self) -> Vec<(usize, &'static str)> {
+        self.signals.iter().filter_map(|s| match s {
+            Signal::Entry { step, reason, .. } => Some((*step, *reason)),
+            _ => None,
+        }).collect()
+    }
+
+    pub fn exits(&self) -> Vec<(usize, &'static str)> {
+        self.signals.iter().filter_map(|s| match s {
+            Signal::Exit { step, reason, .. } => Some((*step, *reason)),
+            _ => None,
+        }).collect()
+    }
+
+    /// Realized P&L per unit of quantity over all closed round trips.
+    pub fn realized_per_unit(&self) -> f64 {
+        let mut entry = 0.0;
+        let mut pnl = 0.0;
+        for s in &self.signals {
+            match s {
+                Signal::Entry { price, .. } => entry = *price,
+                Signal::Exit { price, .. } => pnl += price - entry,
+            }
+        }
+        pnl
+    }
+}
+
+pub fn drive(strategy: &dyn Strategy, closes: &[f64]) -> HarnessRun {
+    let mut stepper = Stepper::new(strategy, 0.0);
+    let mut signals = Vec::new();
+
+    for (step, &price) in closes.iter().enumerate() {
+        let candle = Candle { open: price, high: price, low: price, close: price, time: step as i64 * CANDLE_MS };
+        match stepper.step(&candle) {
+            Some(Step::Entered { reason }) => signals.push(Signal::Entry { step, price, reason }),
+            Some(Step::Exited { reason, .. }) => signals.push(Signal::Exit { step, price, reason }),
+            None => {},
+        }
+    }
+    HarnessRun { signals, position: stepper.position }
+}
+
+// --- Scenario fixtures ---
+
+pub const WARMUP: usize = MIN_HISTORY;
+
+/// Sideways chop (±0.05%) as long as the pipeline's warm-up, to settle RSI and the bands.
+pub fn warmup(price: f64) -> Vec<f64> {
+    (0..WARMUP).map(|i| if i % 2 == 0 { price } else { price * 1.0005 }).collect()
+}
+
+/// Appends a straight move from the last close to `target` over `steps` candles.
+pub fn ramp(mut closes: Vec<f64>, target: f64, steps: usize) -> Vec<f64> {
+    let start = *closes.last().unwrap();
+    closes.extend((1..=steps).map(|i| start + (target - start) * i as f64 / steps as f64));
+    closes
+}
+
+/// Steady 4% dump over 10 candles, then a full recovery over 15.
+pub fn v_shaped_dump() -> Vec<f64> {
+    let dumped = ramp(warmup(100.0), 96.0, 10);
+    ramp(dumped, 100.5, 15)
+}
+
+/// Slow grind up: +0.05% per candle for two hours.
+pub fn slow_grind_up() -> Vec<f64> {
+    let mut closes = warmup(100.0);
+    for _ in 0..120 {
+        let last = *closes.last().unwrap();
+        closes.push(last * 1.0005);
+    }
+    closes
+}
+
+/// One candle prints 6% lower, the next one is back, then it goes sideways.
+pub fn flash_crash() -> Vec<f64> {
+    let mut closes = warmup(100.0);
+    closes.push(94.0);
+    closes.push(100.0);
+    closes.extend(warmup(100.0).into_iter().take(10));
+    closes
+}
+
+/// The flash crash's low (a CRASH CATCH entry at step WARMUP), then `after`.
+pub fn after_crash(after: &[f64]) -> Vec<f64> {
+    let mut closes = warmup(100.0);
+    closes.push(94.0);
+    closes.extend_from_slice(after);
+    closes
+}
+
+fn default_strategy(name: &str) -> Box<dyn Strategy> {
+    strategy::build(name, StrategyParams::default())
+}
+
+// A named rsi_bb run and every entry and exit it must produce, as (step, reason)
+struct Scenario {
+    name: &'static str,
+    params: StrategyParams,
+    closes: Vec<f64>,
+    entries: &'static [(usize, &'static str)],
+    exits: &'static [(usize, &'static str)],
+}
+
+fn exit_scenarios() -> Vec<Scenario> {
+    let defaults = StrategyParams::default();
+    // Wide enough that the trailing stop never decides these
+    let wide_trail = StrategyParams { trailing_stop_pct: 0.05, ..defaults };
+    vec![
+        // 92 is 2.1% under the entry: the hard stop takes it on the gap candle, while the trail
+        // would allow down to 89.3. RSI is still crashed, so it is caught again a candle later.
+        Scenario {
+            name: "gap down hits the hard stop",
+            params: wide_trail,
+            closes: after_crash(&[92.0; 5]),
+            entries: &[(WARMUP, "CRASH CATCH"), (WARMUP + 2, "CRASH CATCH")],
+            exits: &[(WARMUP + 1, "HARD STOP")],
+        },
+        // The bounce to 94.5 is past break_even_pct, so the stop moves to the entry plus both fees
+        // (94.188) and the fade to 94.15 sells still in profit; the trail would sit at 89.8
+        Scenario {
+            name: "winner is stopped at break-even",
+            params: wide_trail,
+            closes: after_crash(&[94.5, 94.15, 94.15, 94.15, 94.15, 94.15, 94.15]),
+            entries: &[(WARMUP, "CRASH CATCH"), (WARMUP + 3, "CRASH CATCH")],
+            exits: &[(WARMUP + 2, "BREAK EVEN")],
+        },
+        // Flat at 94 hits no stop and RSI never nears rsi_sell: only max_hold_mins closes it
+        Scenario {
+            name: "stale position leaves on the time exit",
+            params: StrategyParams { max_hold_mins: 5.0, ..defaults },
+            closes: after_crash(&[94.0; 10]),
+            entries: &[(WARMUP, "CRASH CATCH"), (WARMUP + 6, "CRASH CATCH")],
+            exits: &[(WARMUP + 5, "TIME EXIT")],
+        },
+        Scenario {
+            name: "without a hold limit it stays open",
+            params: defaults,
+            closes: after_crash(&[94.0; 10]),
+            entries: &[(WARMUP, "CRASH CATCH")],
+            exits: &[],
+        },
+        // Each stop-out in the dump is bought again on the next candle, 0.4% lower...
+        Scenario {
+            name: "without a re-entry band the dump is re-bought at once",
+            params: defaults,
+            closes: v_shaped_dump(),
+            entries: &[(WARMUP, "RSI+BB"), (WARMUP + 3, "CRASH CATCH"), (WARMUP + 6, "CRASH CATCH"), (WARMUP + 9, "CRASH CATCH")],
+            exits: &[(WARMUP + 2, "STOP LOSS"), (WARMUP + 5, "STOP LOSS"), (WARMUP + 8, "STOP LOSS"), (WARMUP + 18, "PROFIT TAKE")],
+        },
+        // ...while with a 1% band the same signal waits until price is 1.2% under the exit
+        Scenario {
+            name: "re-entry waits for the band",
+            params: StrategyParams { reentry_pct: 0.01, ..defaults },
+            closes: v_shaped_dump(),
+            entries: &[(WARMUP, "RSI+BB"), (WARMUP + 5, "CRASH CATCH")],
+            exits: &[(WARMUP + 2, "STOP LOSS"), (WARMUP + 7, "STOP LOSS")],
+        },
+    ]
+}
+
+#[test]
+fn exit_scenarios_trade_as_scripted() {
+    for scenario in exit_scenarios() {
+        let run = drive(strategy::build("rsi_bb", scenario.params).as_ref(), &scenario.closes);
+        assert_eq!(run.entries(), scenario.entries, "{}: {:?}", scenario.name, run.signals);
+        assert_eq!(run.exits(), scenario.exits, "{}: {:?}", scenario.name, run.signals);
+    }
+}
+
+#[test]
+fn warmup_alone_never_trades() {
+    for name in ["rsi_bb", "rsi_only"] {
+        let run = drive(default_strategy(name).as_ref(), &warmup(100.0));
+        assert!(run.signals.is_empty(), "{} traded during warm-up: {:?}", name, run.signals);
+    }
+}
+
+#[test]
+fn v_shaped_dump_buys_the_dump_and_ends_flat() {
+    let closes = v_shaped_dump();
+    let run = drive(default_strategy("rsi_bb").as_ref(), &closes);
+
+    let entries = run.entries();
+    assert!(!entries.is_empty(), "no entry during the dump");
+    // The first entry happens while price is falling, not during warm-up or the recovery
+    let (first_step, _) = entries[0];
+    assert!((WARMUP..WARMUP + 10).contains(&first_step), "entered at step {}", first_step);
+    // Entries and exits alternate, starting with an entry
+    for pair in run.signals.chunks(2) {
+        assert!(matches!(pair[0], Signal::Entry { .. }), "{:?}", run.signals);
+        if let Some(exit) = pair.get(1) { assert!(matches!(exit, Signal::Exit { .. })); }
+    }
+    // A falling knife is cut by the trailing stop rather than held through the whole dump
+    assert!(run.exits().iter().any(|(_, r)| *r == "STOP LOSS"), "{:?}", run.signals);
+    // The last buy rides the recovery and takes profit before the scenario ends
+    assert_eq!(run.position, None);
+    let (last_exit, reason) = *run.exits().last().unwrap();
+    assert!(last_exit >= WARMUP + 10 && reason == "PROFIT TAKE", "{:?}", run.signals);
+    let Some(Signal::Entry { price: last_entry, .. }) = run.signals.iter().rev().nth(1) else { panic!("{:?}", run.signals) };
+    let Some(Signal::Exit { price: last_exit_price, .. }) = run.signals.last() else { panic!("{:?}", run.signals) };
+    assert!(last_exit_price > last_entry);
+}
+
+#[test]
+fn slow_grind_up_never_enters() {
+    for name in ["rsi_bb", "rsi_only"] {
+        let run = drive(default_strategy(name).as_ref(), &slow_grind_up());
+        assert!(run.signals.is_empty(), "{} traded in an uptrend: {:?}", name, run.signals);
+        assert_eq!(run.position, None);
+    }
+}
+
+#[test]
+fn flash_crash_is_caught_at_the_low() {
+    let run = drive(default_strategy("rsi_bb").as_ref(), &flash_crash());
+    let crash_step = WARMUP;
+
+    assert_eq!(run.entries().first(), Some(&(crash_step, "CRASH CATCH")), "{:?}", run.signals);
+    // The rebound candle makes the round trip profitable, whether it already exited or not
+    match run.position {
+        Some(open) => {
+            assert_eq!(open.entry_price, 94.0);
+            assert!(open.highest_price >= 100.0);
+        },
+        None => assert!(run.realized_per_unit() > 0.0, "{:?}", run.signals),
+    }
+}
+
+#[test]
+fn tighter_stop_exits_earlier() {
+    let closes = v_shaped_dump();
+    let loose = StrategyParams { trailing_stop_pct: 0.02, ..StrategyParams::default() };
+    let tight = StrategyParams { trailing_stop_pct: 0.002, ..StrategyParams::default() };
+
+    let loose_run = drive(strategy::build("rsi_bb", loose).as_ref(), &closes);
+    let tight_run = drive(strategy::build("rsi_bb", tight).as_ref(), &closes);
+    let first_exit = |run: &HarnessRun| run.exits().first().map(|(s, _)| *s).unwrap_or(usize::MAX);
+    assert!(first_exit(&tight_run) < first_exit(&loose_run));
+}
@@ -0,0 +1,63 @@
+use std::time::Duration;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::time::sleep;
+
+use crate::config::SharedConfig;
+use crate::SharedState;
+
+// --- 💓 EXTERNAL HEARTBEAT (healthchecks.io style dead-man switch) ---
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct HeartbeatConfig {
+    pub url: String,            // Empty = disabled
+    pub interval_secs: u64,
+    pub max_tick_age_secs: u64, // Feed is considered stale after this long without a price
+}
+
+impl Default for HeartbeatConfig {
+    fn default() -> Self {
+        HeartbeatConfig { url: String::new(), interval_secs: 60, max_tick_age_secs: 30 }
+    }
+}
+
+/// Why the bot is not healthy right now, if it isn't.
+fn unhealthy_reason(state: &SharedState, cfg: &HeartbeatConfig) -> Option<String> {
+    let data = state.read();
+    let tick_age = (Utc::now().timestamp_millis() - data.last_tick_at) / 1000;
+    if data.last_tick_at == 0 || tick_age > cfg.max_tick_age_secs as i64 {
+        return Some(format!("price feed stale ({}s)", tick_age));
+    }
+    if data.breaker != "closed" {
+        return Some(format!("circuit breaker {}", data.breaker));
+    }
+    data.risk.halted.as_ref().map(|r| format!("halted: {}", r))
+}
+
+// Pings are simply skipped while unhealthy, so the external service raises the alarm
+pub async fn run(state: SharedState, config: SharedConfig) {
+    let client = reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap();
+    let mut last_skip: Option<String> = None;
+
+    loop {
+        let cfg = config.read().heartbeat.clone();
+        if !cfg.url.is_empty() {
+            match unhealthy_reason(&state, &cfg) {
+                None => {
+                    last_skip = None;
+                    if let Err(e) = client.get(&cfg.url).send().await {
+                        eprintln!("Heartbeat Error: {}", e);
+                    }
+                },
+                Some(reason) => {
+                    if last_skip.as_ref() != Some(&reason) {
+                        eprintln!("Heartbeat skipped: {}", reason);
+                        last_skip = Some(reason);
+                    }
+                },
+            }
+        }
+        sleep(Duration::from_secs(cfg.interval_secs.max(5))).await;
+    }
+}
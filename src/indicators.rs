@@ -0,0 +1,51 @@
+use ta::indicators::{BollingerBands, RelativeStrengthIndex};
+use ta::Next;
+
+use crate::strategy::Indicators;
+
+// --- 📈 INDICATOR PIPELINE ---
+// RSI(14) and Bollinger Bands(20, 2σ) over closes, fed one at a time, producing the Indicators a
+// strategy decides on. The backtester runs every candle through one; an embedding program can
+// drive it with its own prices and hand the result to any Strategy.
+
+const RSI_PERIOD: usize = 14;
+const BB_PERIOD: usize = 20;
+const BB_WIDTH: f64 = 2.0; // Standard deviations
+/// Closes before the readings can be trusted: the bands need BB_PERIOD of them, and the RSI's
+/// smoothing takes a few periods to forget where it started.
+pub const MIN_HISTORY: usize = 3 * RSI_PERIOD;
+
+pub struct IndicatorPipeline {
+    rsi: RelativeStrengthIndex,
+    bb: BollingerBands,
+    seen: usize,
+}
+
+impl Default for IndicatorPipeline {
+    fn default() -> Self {
+        IndicatorPipeline {
+            rsi: RelativeStrengthIndex::new(RSI_PERIOD).unwrap(),
+            bb: BollingerBands::new(BB_PERIOD, BB_WIDTH).unwrap(),
+            seen: 0,
+        }
+    }
+}
+
+impl IndicatorPipeline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds the next close and returns the readings including it. Until `ready` they are still
+    /// warming up and shouldn't be traded on.
+    pub fn next(&mut self, close: f64) -> Indicators {
+        self.seen += 1;
+        let bands = self.bb.next(close);
+        Indicators { price: close, rsi: self.rsi.next(close), bb_lower: bands.lower, venue_premium_pct: None, last_exit: None }
+    }
+
+    /// Whether MIN_HISTORY closes have gone in.
+    pub fn ready(&self) -> bool {
+        self.seen >= MIN_HISTORY
+    }
+}
@@ -0,0 +1,142 @@
+use std::env;
+use std::fs;
+use std::io::{self, Write};
+use std::path::Path;
+use std::sync::OnceLock;
+use age::secrecy::SecretString;
+use serde::{Deserialize, Serialize};
+
+use crate::KEYSTORE_FILE;
+
+// --- 🔑 ENCRYPTED KEYSTORE ---
+// API credentials encrypted with age (scrypt passphrase key derivation).
+// The passphrase comes from the systemd credential below, or is prompted for on the terminal.
+// One set of keys is stored, for whichever exchange the bot runs on; OKX keys also carry the API
+// passphrase given when the key was created.
+
+const PASSPHRASE_CREDENTIAL: &str = "scalper-passphrase"; // LoadCredentialEncrypted=scalper-passphrase:...
+// Any of these set means credentials come from the environment and the keystore stays locked
+const ENV_KEYS: [&str; 5] = ["COINDCX_API_KEY", "BYBIT_API_KEY", "BYBIT_TESTNET_API_KEY", "OKX_API_KEY", "OKX_TESTNET_API_KEY"];
+
+#[derive(Serialize, Deserialize)]
+struct Keys {
+    api_key: String,
+    secret_key: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    api_passphrase: Option<String>,
+}
+
+/// An exchange API key: key and secret, plus a passphrase for exchanges that sign with one (OKX).
+#[derive(Clone)]
+pub struct Credentials {
+    pub key: String,
+    pub secret: String,
+    pub passphrase: Option<String>,
+}
+
+impl Credentials {
+    /// Reads the named env vars; missing ones are empty.
+    pub fn from_env(key: &str, secret: &str, passphrase: Option<&str>) -> Self {
+        Credentials {
+            key: env::var(key).unwrap_or_default(),
+            secret: env::var(secret).unwrap_or_default(),
+            passphrase: passphrase.and_then(|var| env::var(var).ok()),
+        }
+    }
+}
+
+static UNLOCKED: OnceLock<Credentials> = OnceLock::new();
+
+/// Credentials decrypted at startup, if a keystore was unlocked.
+pub fn credentials() -> Option<Credentials> {
+    UNLOCKED.get().cloned()
+}
+
+fn read_passphrase(prompt: &str) -> Result<String, String> {
+    // systemd exposes credentials as files under $CREDENTIALS_DIRECTORY
+    if let Ok(dir) = env::var("CREDENTIALS_DIRECTORY") {
+        if let Ok(raw) = fs::read_to_string(Path::new(&dir).join(PASSPHRASE_CREDENTIAL)) {
+            return Ok(raw.trim_end_matches(['\r', '\n']).to_string());
+        }
+    }
+    rpassword::prompt_password(prompt).map_err(|e| format!("cannot read passphrase: {}", e))
+}
+
+fn decrypt(passphrase: String) -> Result<Keys, String> {
+    let ciphertext = fs::read(KEYSTORE_FILE).map_err(|e| e.to_string())?;
+    let identity = age::scrypt::Identity::new(SecretString::from(passphrase));
+    let plaintext = age::decrypt(&identity, &ciphertext).map_err(|e| e.to_string())?;
+    serde_json::from_slice(&plaintext).map_err(|e| e.to_string())
+}
+
+/// Unlocks the keystore if it exists. Env vars still take precedence, so nothing is asked for when they are set.
+pub fn unlock() -> Result<(), String> {
+    if !Path::new(KEYSTORE_FILE).exists() || ENV_KEYS.iter().any(|var| env::var(var).is_ok()) {
+        return Ok(());
+    }
+    let keys = decrypt(read_passphrase("🔑 Keystore passphrase: ")?)?;
+    let _ = UNLOCKED.set(Credentials { key: keys.api_key, secret: keys.secret_key, passphrase: keys.api_passphrase });
+    println!("🔑 API credentials loaded from {}", KEYSTORE_FILE);
+    Ok(())
+}
+
+fn prompt_line(prompt: &str) -> Result<String, String> {
+    print!("{}", prompt);
+    io::stdout().flush().map_err(|e| e.to_string())?;
+    let mut line = String::new();
+    io::stdin().read_line(&mut line).map_err(|e| e.to_string())?;
+    Ok(line.trim().to_string())
+}
+
+fn set() -> Result<(), String> {
+    let api_key = prompt_line("API key: ")?;
+    let secret_key = rpassword::prompt_password("API secret: ").map_err(|e| e.to_string())?;
+    if api_key.is_empty() || secret_key.is_empty() {
+        return Err("API key and secret must not be empty".to_string());
+    }
+    let api_passphrase = rpassword::prompt_password("API passphrase (OKX only, empty for none): ").map_err(|e| e.to_string())?;
+    let api_passphrase = (!api_passphrase.is_empty()).then_some(api_passphrase);
+    let passphrase = rpassword::prompt_password("New passphrase: ").map_err(|e| e.to_string())?;
+    if passphrase.len() < 8 {
+        return Err("passphrase must be at least 8 characters".to_string());
+    }
+    if rpassword::prompt_password("Repeat passphrase: ").map_err(|e| e.to_string())? != passphrase {
+        return Err("passphrases do not match".to_string());
+    }
+
+    let plaintext = serde_json::to_vec(&Keys { api_key, secret_key, api_passphrase }).map_err(|e| e.to_string())?;
+    let recipient = age::scrypt::Recipient::new(SecretString::from(passphrase));
+    let ciphertext = age::encrypt(&recipient, &plaintext).map_err(|e| e.to_string())?;
+    fs::write(KEYSTORE_FILE, ciphertext).map_err(|e| e.to_string())?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(KEYSTORE_FILE, fs::Permissions::from_mode(0o600)).map_err(|e| e.to_string())?;
+    }
+    println!("🔑 Saved encrypted credentials to {}", KEYSTORE_FILE);
+    Ok(())
+}
+
+fn check() -> Result<(), String> {
+    if !Path::new(KEYSTORE_FILE).exists() {
+        return Err(format!("no keystore at {}, create one with `keys set`", KEYSTORE_FILE));
+    }
+    let keys = decrypt(read_passphrase("Passphrase: ")?)?;
+    let shown: String = keys.api_key.chars().take(4).collect();
+    let extra = if keys.api_passphrase.is_some() { ", with API passphrase" } else { "" };
+    println!("🔑 Keystore OK (API key {}…{})", shown, extra);
+    Ok(())
+}
+
+/// `coindcx_scalper keys <set|check>`
+pub fn run_cli(args: &[String]) {
+    let result = match args.first().map(String::as_str) {
+        Some("set") => set(),
+        Some("check") => check(),
+        _ => Err("Usage: coindcx_scalper keys <set|check>".to_string()),
+    };
+    if let Err(e) = result {
+        eprintln!("Keystore Error: {}", e);
+        std::process::exit(1);
+    }
+}
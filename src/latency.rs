@@ -0,0 +1,109 @@
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+use parking_lot::Mutex;
+use tracing::span::{Attributes, Id};
+use tracing::Subscriber;
+use tracing_subscriber::layer::{Context, SubscriberExt};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::Layer;
+
+use crate::PAIR;
+
+// --- ⏱️ HOT PATH LATENCY ---
+// Each stage of a tick is a tracing span; the layer below times spans from creation to close and
+// keeps the recent durations per stage, exported as p50/p95/p99 on /metrics.
+//   feed:     trade received (stream) or requested (REST) -> picked up by the trading loop
+//   evaluate: picked up -> entry/exit decision made
+//   order:    order sent -> exchange acknowledged (or simulated fill)
+
+const STAGES: [&str; 3] = ["feed", "evaluate", "order"];
+const WINDOW: usize = 2048; // Recent samples per stage used for the quantiles
+
+struct Stage {
+    recent: VecDeque<Duration>,
+    count: u64,
+    sum: Duration,
+}
+
+impl Stage {
+    const fn new() -> Self {
+        Stage { recent: VecDeque::new(), count: 0, sum: Duration::ZERO }
+    }
+}
+
+static HISTOGRAMS: Mutex<[Stage; 3]> = Mutex::new([Stage::new(), Stage::new(), Stage::new()]);
+
+fn record(stage: &str, elapsed: Duration) {
+    let Some(idx) = STAGES.iter().position(|s| *s == stage) else { return };
+    let mut hists = HISTOGRAMS.lock();
+    let h = &mut hists[idx];
+    h.recent.push_back(elapsed);
+    if h.recent.len() > WINDOW { h.recent.pop_front(); }
+    h.count += 1;
+    h.sum += elapsed;
+}
+
+struct Started(Instant);
+
+struct LatencyLayer;
+
+impl<S: Subscriber + for<'a> LookupSpan<'a>> Layer<S> for LatencyLayer {
+    fn on_new_span(&self, _attrs: &Attributes<'_>, id: &Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(id) {
+            span.extensions_mut().insert(Started(Instant::now()));
+        }
+    }
+
+    fn on_close(&self, id: Id, ctx: Context<'_, S>) {
+        if let Some(span) = ctx.span(&id) {
+            if let Some(Started(at)) = span.extensions().get::<Started>() {
+                record(span.name(), at.elapsed());
+            }
+        }
+    }
+}
+
+pub fn install() {
+    let subscriber = tracing_subscriber::registry().with(LatencyLayer);
+    if let Err(e) = tracing::subscriber::set_global_default(subscriber) {
+        eprintln!("Latency tracing disabled: {}", e);
+    }
+}
+
+const QUANTILES: [f64; 3] = [0.5, 0.95, 0.99];
+
+pub struct StageSummary {
+    pub stage: &'static str,
+    pub quantiles: [f64; 3], // p50, p95, p99 in seconds (0 with no samples)
+    pub sum: f64,
+    pub count: u64,
+}
+
+/// The current summary of every stage.
+pub fn summaries() -> Vec<StageSummary> {
+    let hists = HISTOGRAMS.lock();
+    STAGES.iter().zip(hists.iter()).map(|(stage, h)| {
+        let mut sorted: Vec<Duration> = h.recent.iter().copied().collect();
+        sorted.sort_unstable();
+        let quantiles = QUANTILES.map(|q| match sorted.len() {
+            0 => 0.0,
+            n => sorted[((n as f64 * q).ceil() as usize).clamp(1, n) - 1].as_secs_f64(),
+        });
+        StageSummary { stage, quantiles, sum: h.sum.as_secs_f64(), count: h.count }
+    }).collect()
+}
+
+/// Prometheus summaries, in seconds.
+pub fn render_prometheus(prefix: &str) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "# TYPE {}_stage_latency_seconds summary", prefix);
+    for s in summaries() {
+        for (q, value) in QUANTILES.iter().zip(s.quantiles) {
+            let _ = writeln!(out, "{}_stage_latency_seconds{{pair=\"{}\",stage=\"{}\",quantile=\"{}\"}} {}", prefix, PAIR, s.stage, q, value);
+        }
+        let _ = writeln!(out, "{}_stage_latency_seconds_sum{{pair=\"{}\",stage=\"{}\"}} {}", prefix, PAIR, s.stage, s.sum);
+        let _ = writeln!(out, "{}_stage_latency_seconds_count{{pair=\"{}\",stage=\"{}\"}} {}", prefix, PAIR, s.stage, s.count);
+    }
+    out
+}
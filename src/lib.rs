@@ -0,0 +1,2200 @@
+//! The scalper's engine as a library. The `coindcx_scalper` binary is a thin wrapper around
+//! [`cli`]; everything it runs lives here, so another program can embed the parts it needs
+//! instead of forking the bot.
+//!
+//! The stable surface:
+//! * **Exchange connectors:** [`coindcx`], [`bybit`] and [`okx`] (candles, prices, depth, the
+//!   trade stream and, for Bybit and OKX, orders and balances), addressed through the symbol
+//!   registry in [`symbols`].
+//! * **Indicators:** [`indicators::IndicatorPipeline`] turns closes into the
+//!   [`strategy::Indicators`] a strategy sees.
+//! * **Strategies:** the [`strategy::Strategy`] trait and the built-in ones ([`strategy::build`],
+//!   [`strategy::available`]).
+//! * **Backtester:** [`backtest::run`] replays [`Candle`]s through any strategy, net of the spread
+//!   and fees.
+//!
+//! Everything else (the trading loop, persistence, notifications) is internal and may change.
+//! The web dashboard is behind the `web` feature, on by default; without it the bot runs
+//! headless.
+//!
+//! ```no_run
+//! use coindcx_scalper::{backtest, strategy, Candle};
+//!
+//! let candles: Vec<Candle> = Vec::new(); // Oldest first
+//! let rsi_bb = strategy::build(strategy::DEFAULT_STRATEGY, strategy::StrategyParams::default());
+//! let run = backtest::run(&candles, rsi_bb.as_ref(), 2.0); // 2 bps spread
+//! println!("{} trades, net {:.2}, gross {:.2}", run.metrics.trades, run.metrics.net_pnl, run.metrics.gross_pnl);
+//! ```
+
+// Without the dashboard, some of its DB queries and helpers have no caller
+#![cfg_attr(not(feature = "web"), allow(dead_code))]
+
+use std::cell::Cell;
+use std::collections::{BTreeMap, VecDeque};
+use std::env;
+use std::fmt::{self, Write as _};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant, SystemTime, UNIX_EPOCH};
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use hmac::{Hmac, Mac};
+use ta::indicators::{BollingerBands, RelativeStrengthIndex};
+use ta::Next;
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+use tracing::{info_span, Instrument};
+use chrono::{DateTime, Utc}; 
+use parking_lot::{ReentrantMutex, ReentrantMutexGuard, RwLock};
+use rusqlite::{params, Connection, Result as SqlResult};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+mod alerts;
+#[cfg(feature = "web")]
+mod assets;
+mod auth;
+pub mod backtest;
+mod backup;
+mod breaker;
+mod bus;
+mod calendar;
+pub mod bybit;
+mod candles;
+mod chaos;
+mod clock;
+mod cluster;
+mod commands;
+pub mod coindcx;
+mod config;
+mod control;
+mod currency;
+mod database;
+mod executor;
+mod export;
+mod feed;
+mod fills;
+mod fix;
+mod grpc;
+#[cfg(test)]
+mod harness;
+mod heartbeat;
+pub mod indicators;
+mod keystore;
+mod latency;
+mod logfile;
+mod metrics;
+mod money;
+mod network;
+mod notify;
+mod optimize;
+pub mod okx;
+mod orderbook;
+mod panic_guard;
+mod persist;
+mod preflight;
+mod published;
+#[cfg(feature = "python")]
+mod python;
+mod ratelimit;
+mod reconcile;
+mod replay;
+mod resources;
+mod risk;
+mod runtime;
+mod sanity;
+mod sheets;
+mod sizing;
+mod slots;
+mod snapshot;
+mod spread;
+mod stats;
+pub mod strategy;
+pub mod symbols;
+mod systemd;
+mod telegram;
+mod ticks;
+mod timeouts;
+mod timeseries;
+mod userstream;
+#[cfg(feature = "web")]
+mod web;
+// Types the public modules hand out
+pub use executor::Fill;
+pub use feed::StreamTick;
+pub use orderbook::{DepthResponse, Level};
+use alerts::{AlertEngine, AlertInputs};
+use auth::SharedSessions;
+use backtest::BacktestRun;
+use breaker::{CircuitBreaker, TripAction};
+use bus::BusEvent;
+use candles::{CandleClosed, LiveCandles};
+use clock::{Clock, Ticker, VirtualClock, WallClock};
+use config::{Config, SharedConfig};
+use currency::Currencies;
+use feed::FeedSource;
+use fills::SimFills;
+use executor::{Executor, OrderKind, OrderReport, OrderRequest, OrderTemplate, SignalContext};
+use notify::{Event, EventKind, Notifier};
+use persist::Write;
+use orderbook::{OrderBook, SharedBook};
+use network::{Link, LinkState, NetworkStatus, OutageAction};
+use published::Published;
+use reconcile::Reconciler;
+use replay::CandleReplay;
+use resources::ResourceUsage;
+use risk::{RiskState, WalletBalance};
+use slots::Slots;
+use strategy::{Indicators, ReentryBand, Stop, Strategy, StrategyParams};
+use symbols::Environment;
+
+// --- 🛠️ CONFIGURATION ---
+const SIMULATION_MODE: bool = true; 
+const PAIR: &str = "BTC/USDT"; // Canonical name; each exchange's codes come from the symbol registry
+const TIMEFRAME: &str = "1m"; // 1 Minute candles
+const TRADE_CAPITAL: f64 = 10000.0; // Trade size in USDT
+const TRAILING_STOP_PCT: f64 = 0.005; // 0.5%
+const HARD_STOP_PCT: f64 = 0.012; // 1.2% under the entry, whatever the trailing stop says
+const BREAK_EVEN_PCT: f64 = 0.004; // Peak 0.4% above the entry moves the stop up to break-even
+const MAX_HOLD_MINS: f64 = 0.0; // Minutes a position may stay open, 0 = no limit
+const REENTRY_PCT: f64 = 0.0; // Price move away from the last exit before entries re-arm, 0 = off
+const REENTRY_RSI: f64 = 0.0; // The same in RSI points, 0 = off
+const FEE_RATE: Decimal = dec!(0.001); // 0.1% exchange fee per side (estimate)
+const RSI_BUY: f64 = 30.0;
+const RSI_SELL: f64 = 70.0;
+const RSI_CRASH: f64 = 20.0; // Aggressive "crash catch" entry
+const STREAM_WAIT: Duration = Duration::from_secs(1); // Longest wait for a streamed trade before the loop runs anyway
+const POLL_EVERY: Duration = Duration::from_secs(5); // Loop step when polling REST (and in a replay)
+const BOOK_DEPTH: usize = 10; // Levels per side shown on the dashboard
+const LOG_LINES: usize = 30; // Dashboard log tail
+const ORDER_POLL: Duration = Duration::from_secs(1); // Order status polls while the user stream is silent
+const ORDER_CALL_TIMEOUT: Duration = Duration::from_secs(5); // Per order status or cancel call
+const ORDER_FAILURES_CRITICAL: u32 = 3; // Failed orders in a row before it is treated as critical (emailed)
+const BACKTEST_CANDLES: u32 = 1000; // Max history the exchange serves per request
+const DB_FILE: &str = "bot_data.db";
+const DB_POOL_SIZE: usize = 4; // One writer at a time in SQLite anyway; the rest serve dashboard reads
+const CONFIG_FILE: &str = "config.toml";
+const KEYSTORE_FILE: &str = "keys.age"; // Encrypted API credentials, see `keys set`
+const PORT: u16 = 3000; 
+const PUBLIC_PORT: Option<u16> = None; // e.g. Some(3001) to serve a read-only public dashboard
+
+// Set by the replay subcommand: recorded data is traded on paper, whatever SIMULATION_MODE says
+static REPLAYING: AtomicBool = AtomicBool::new(false);
+
+/// Whether orders are simulated: SIMULATION_MODE, or a replay of recorded data.
+fn simulating() -> bool {
+    SIMULATION_MODE || REPLAYING.load(Ordering::Relaxed)
+}
+
+// --- 📊 SHARED APP STATE ---
+#[derive(Clone, Serialize)]
+struct DashboardData {
+    price: f64,
+    rsi: f64,
+    bb_lower: f64,
+    bb_upper: f64, 
+    status: String,
+    strategy: String,
+    entry_price: Decimal,
+    unrealized_pl: f64,     // %
+    realized_pl: Decimal,   // Net: at the fills, after fees
+    gross_pl: Decimal,      // The same trades at the signal prices, before the spread, slippage and fees
+    wallet_usdt: Decimal,   // The pair's quote currency
+    wallet_btc: Decimal,    // The pair's base currency
+    balances: Arc<BTreeMap<String, Decimal>>, // Every asset the last balance sync reported
+    wallets: Arc<Vec<WalletBalance>>, // The quote in each of the account's wallets
+    currencies: Currencies,
+    logs: Arc<VecDeque<String>>, // Newest first, at most LOG_LINES; shared, so publishing a tick doesn't copy it
+    session: SessionStats,
+    position: BotState,     // All slots combined
+    slots: Slots,
+    stop: Option<Stop>,     // The active stop of all slots combined, None when flat
+    exit_at: Option<i64>,   // Unix millis when the oldest slot reaches max_hold_mins, None without a limit
+    breaker: String,        // Circuit breaker state: closed / open / half_open
+    risk: RiskState,
+    config_notice: Option<String>, // Config edits that need a restart to take effect
+    last_tick_at: i64,      // Unix millis of the last successful price tick, 0 = none yet
+    clock_drift_ms: Option<i64>, // Local minus exchange time, None until the first check
+    resources: ResourceUsage,
+    network: NetworkStatus,
+    utc_offset_mins: i32,   // [calendar], for the dashboard's times
+    warmup: Option<usize>,  // Candles until the indicators are trusted, None once they are
+}
+
+#[derive(Clone, Default, Serialize)]
+struct SessionStats {
+    started_at: i64, // Unix millis
+    signals: u32,
+    trades: u32,     // Executed orders (buys + sells)
+    unfilled: u32,   // Simulated limit orders that expired without a fill
+    wins: u32,
+    losses: u32,
+    fees_paid: Decimal,
+    restarts: u32,   // Trading task restarts by the supervisor
+    breaker_trips: u32,
+    last_error: Option<String>,
+}
+
+type SharedState = Arc<Published<DashboardData>>;
+// The risk fields whose change is written to the DB: halt, peak equity, realized total, trading day, re-arm offset
+type SavedRisk = (Option<String>, f64, f64, Option<String>, f64);
+
+// --- DATA STRUCTURES ---
+/// One OHLC bar; `time` is its open in Unix millis.
+#[derive(Debug, Deserialize, Serialize, Clone)]
+pub struct Candle {
+    pub open: f64,
+    pub high: f64,
+    pub low: f64,
+    pub close: f64,
+    pub time: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct Balance {
+    currency: String,
+    balance: String,
+    #[serde(default)]
+    locked_balance: String, // In open orders
+}
+
+#[derive(Debug, Serialize)]
+struct TradeRecord {
+    id: i64,
+    action: String,
+    price: Decimal,
+    quantity: Decimal,
+    profit: Decimal,       // Net of both sides' fees
+    gross_profit: Decimal, // At the signal prices
+    reason: String,
+    timestamp: String,
+    // What the bot saw when it decided (see SignalContext)
+    signal_price: Decimal,
+    rsi: f64,
+    bb_lower: f64,
+    bb_upper: f64,
+    stop_price: Option<f64>,
+    spread_bps: Option<f64>,
+    mode: String,
+    slot: Option<usize>,
+}
+
+#[derive(Debug, Serialize)]
+struct ActivityRecord {
+    id: i64,
+    timestamp: String,
+    username: String,
+    action: String,
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "state", rename_all = "snake_case")]
+enum BotState {
+    #[default]
+    Idle,
+    // The peak is for the trailing stop only; entry_signal is the price the entry was signalled at,
+    // before the spread and slippage (gross P&L); entered_at is the fill time in Unix millis on the
+    // loop's clock, 0 for a position saved before it was kept
+    InPosition { entry_price: Decimal, highest_price: f64, quantity: Decimal, #[serde(default)] entry_signal: Decimal, #[serde(default)] entered_at: i64 },
+}
+
+// --- 🗄️ DATABASE MANAGER ---
+struct DbManager;
+
+// Long-lived connections shared by every caller; opening one per call costs tens of ms on an SD card
+static DB_POOL: OnceLock<Vec<ReentrantMutex<Connection>>> = OnceLock::new();
+static DB_NEXT: AtomicUsize = AtomicUsize::new(0);
+
+thread_local! {
+    // Pool slot held by a batch on this thread, so every write inside it joins the open transaction
+    static PINNED: Cell<Option<usize>> = const { Cell::new(None) };
+}
+
+impl DbManager {
+    fn open_connection() -> SqlResult<Connection> {
+        let conn = Connection::open(DB_FILE)?;
+        conn.busy_timeout(Duration::from_secs(5))?; // Wait out another connection's write instead of failing
+        conn.set_prepared_statement_cache_capacity(32);
+        database::apply(&conn)?; // Durability profile from [database]
+        Ok(conn)
+    }
+
+    /// A pooled connection: a free one if there is one, otherwise the next in turn once it is released.
+    fn connect() -> SqlResult<ReentrantMutexGuard<'static, Connection>> {
+        Self::checkout().map(|(_, conn)| conn)
+    }
+
+    fn checkout() -> SqlResult<(usize, ReentrantMutexGuard<'static, Connection>)> {
+        let pool = match DB_POOL.get() {
+            Some(pool) => pool,
+            None => {
+                let conns = (0..DB_POOL_SIZE).map(|_| Self::open_connection()).collect::<SqlResult<Vec<_>>>()?;
+                let _ = DB_POOL.set(conns.into_iter().map(ReentrantMutex::new).collect()); // Lost race: keep the winner's
+                DB_POOL.get().unwrap()
+            },
+        };
+        if let Some(idx) = PINNED.get() {
+            return Ok((idx, pool[idx].lock()));
+        }
+        if let Some(found) = pool.iter().enumerate().find_map(|(idx, c)| c.try_lock().map(|conn| (idx, conn))) {
+            return Ok(found);
+        }
+        let idx = DB_NEXT.fetch_add(1, Ordering::Relaxed) % pool.len();
+        Ok((idx, pool[idx].lock()))
+    }
+
+    /// Runs `writes` as one transaction: one fsync for the whole batch instead of one per row.
+    fn batch(writes: impl FnOnce()) -> SqlResult<()> {
+        let (idx, conn) = Self::checkout()?;
+        conn.execute_batch("BEGIN IMMEDIATE")?;
+        PINNED.set(Some(idx));
+        writes();
+        PINNED.set(None);
+        conn.execute_batch("COMMIT").inspect_err(|_| { let _ = conn.execute_batch("ROLLBACK"); })
+    }
+
+    fn init() -> SqlResult<()> {
+        let conn = Self::connect()?;
+        
+        // 🛑 FIX: Drop old tables to ensure schema matches code (Handles bb_upper addition)
+        conn.execute("DROP TABLE IF EXISTS candles", [])?;
+        conn.execute("DROP TABLE IF EXISTS trades", [])?;
+        conn.execute("DROP TABLE IF EXISTS equity", [])?;
+        
+        // Candles Table
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS candles (
+                time INTEGER PRIMARY KEY,
+                open REAL, high REAL, low REAL, close REAL,
+                rsi REAL, bb_lower REAL, bb_upper REAL
+            )",
+            [],
+        )?;
+
+        // Trades Table (amounts as decimal text, exactly as traded)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS trades (
+                id INTEGER PRIMARY KEY,
+                action TEXT, price TEXT, quantity TEXT, profit TEXT, gross_profit TEXT, reason TEXT, timestamp TEXT,
+                signal_price TEXT, rsi REAL, bb_lower REAL, bb_upper REAL, stop_price REAL, spread_bps REAL, mode TEXT, slot INTEGER
+            )",
+            [],
+        )?;
+        // Equity Table (realized + unrealized P&L, sampled once per candle)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS equity (
+                time INTEGER PRIMARY KEY,
+                equity REAL
+            )",
+            [],
+        )?;
+
+        // Dashboard activity log (who pressed which control), kept across restarts
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS activity (
+                id INTEGER PRIMARY KEY,
+                timestamp TEXT, username TEXT, action TEXT
+            )",
+            [],
+        )?;
+
+        // Small persistent key/value store for state that must survive restarts (JSON values)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS bot_state (
+                key TEXT PRIMARY KEY,
+                value TEXT
+            )",
+            [],
+        )?;
+
+        // Each venue's price and spread against the traded venue, from the spread monitor
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS spreads (
+                time INTEGER, venue TEXT, price REAL, spread_pct REAL
+            )",
+            [],
+        )?;
+        conn.execute("CREATE INDEX IF NOT EXISTS spreads_time ON spreads (time)", [])?;
+
+        // Backtest runs survive restarts (not part of the schema reset above)
+        conn.execute(
+            "CREATE TABLE IF NOT EXISTS backtests (
+                id INTEGER PRIMARY KEY,
+                created TEXT, strategy TEXT, params TEXT, metrics TEXT, equity TEXT, trades TEXT
+            )",
+            [],
+        )?;
+        let db = database::settings();
+        println!("🗄️ Database Initialized & Schema Reset ({:?} journal, {:?} sync)", db.journal_mode, db.synchronous);
+        Ok(())
+    }
+
+    fn save_candle(candle: &Candle, rsi: f64, bb_lower: f64, bb_upper: f64) -> SqlResult<()> {
+        let conn = Self::connect()?;
+        conn.prepare_cached(
+            "INSERT OR REPLACE INTO candles (time, open, high, low, close, rsi, bb_lower, bb_upper)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+        )?.execute(params![candle.time, candle.open, candle.high, candle.low, candle.close, rsi, bb_lower, bb_upper])?;
+        Ok(())
+    }
+
+    fn get_recent_candles(limit: usize) -> SqlResult<Vec<Candle>> {
+        let conn = Self::connect()?;
+        let mut stmt = conn.prepare_cached("SELECT time, open, high, low, close FROM candles ORDER BY time DESC LIMIT ?1")?;
+        let candle_iter = stmt.query_map(params![limit], |row| {
+            Ok(Candle {
+                time: row.get(0)?,
+                open: row.get(1)?,
+                high: row.get(2)?,
+                low: row.get(3)?,
+                close: row.get(4)?,
+            })
+        })?;
+
+        let mut candles = Vec::new();
+        for candle in candle_iter { candles.push(candle?); }
+        candles.reverse();
+        Ok(candles)
+    }
+
+    // Oldest first, with the indicators saved alongside (rsi, bb_lower, bb_upper), for the export subcommand
+    fn get_candles_between(from: i64, to: i64) -> SqlResult<Vec<(Candle, [Option<f64>; 3])>> {
+        let conn = Self::connect()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT time, open, high, low, close, rsi, bb_lower, bb_upper FROM candles WHERE time >= ?1 AND time < ?2 ORDER BY time ASC"
+        )?;
+        let rows = stmt.query_map(params![from, to], |row| {
+            let candle = Candle { time: row.get(0)?, open: row.get(1)?, high: row.get(2)?, low: row.get(3)?, close: row.get(4)? };
+            Ok((candle, [row.get(5)?, row.get(6)?, row.get(7)?]))
+        })?;
+        rows.collect()
+    }
+
+    fn log_trade(action: &str, fill: Fill, profit: Decimal, gross: Decimal, reason: &str, context: &SignalContext, at: DateTime<Utc>) -> SqlResult<()> {
+        let conn = Self::connect()?;
+        let time_str = at.to_rfc3339();
+        conn.prepare_cached(
+            "INSERT INTO trades (action, price, quantity, profit, gross_profit, reason, timestamp,
+                                 signal_price, rsi, bb_lower, bb_upper, stop_price, spread_bps, mode, slot)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15)",
+        )?.execute(params![
+            action, fill.price.to_string(), fill.qty.to_string(), profit.to_string(), gross.to_string(), reason, time_str,
+            context.signal_price.to_string(), context.rsi, context.bb_lower, context.bb_upper, context.stop_price, context.spread_bps, context.mode, context.slot,
+        ])?;
+        Ok(())
+    }
+
+    fn get_recent_trades(limit: usize) -> SqlResult<Vec<TradeRecord>> {
+        Self::query_trades(limit as i64)
+    }
+
+    // Oldest first, for analytics
+    fn get_all_trades() -> SqlResult<Vec<TradeRecord>> {
+        let mut trades = Self::query_trades(-1)?;
+        trades.reverse();
+        Ok(trades)
+    }
+
+    // Newest first; a negative limit means no limit in SQLite
+    fn query_trades(limit: i64) -> SqlResult<Vec<TradeRecord>> {
+        let conn = Self::connect()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, action, price, quantity, profit, gross_profit, reason, timestamp,
+                    signal_price, rsi, bb_lower, bb_upper, stop_price, spread_bps, mode, slot
+             FROM trades ORDER BY id DESC LIMIT ?1"
+        )?;
+        let amount = |row: &rusqlite::Row, idx| row.get::<_, String>(idx).map(|v| money::parse(&v).unwrap_or_default());
+        let trade_iter = stmt.query_map(params![limit], |row| {
+            Ok(TradeRecord {
+                id: row.get(0)?,
+                action: row.get(1)?,
+                price: amount(row, 2)?,
+                quantity: amount(row, 3)?,
+                profit: amount(row, 4)?,
+                gross_profit: amount(row, 5)?,
+                reason: row.get::<_, Option<String>>(6)?.unwrap_or_default(),
+                timestamp: row.get(7)?,
+                signal_price: amount(row, 8)?,
+                rsi: row.get::<_, Option<f64>>(9)?.unwrap_or_default(),
+                bb_lower: row.get::<_, Option<f64>>(10)?.unwrap_or_default(),
+                bb_upper: row.get::<_, Option<f64>>(11)?.unwrap_or_default(),
+                stop_price: row.get(12)?,
+                spread_bps: row.get(13)?,
+                mode: row.get::<_, Option<String>>(14)?.unwrap_or_default(),
+                slot: row.get(15)?,
+            })
+        })?;
+
+        let mut trades = Vec::new();
+        for trade in trade_iter { trades.push(trade?); }
+        Ok(trades)
+    }
+
+    fn set_state(key: &str, value: &str) -> SqlResult<()> {
+        let conn = Self::connect()?;
+        conn.prepare_cached("INSERT OR REPLACE INTO bot_state (key, value) VALUES (?1, ?2)")?.execute(params![key, value])?;
+        Ok(())
+    }
+
+    fn get_state(key: &str) -> SqlResult<Option<String>> {
+        let conn = Self::connect()?;
+        let mut stmt = conn.prepare_cached("SELECT value FROM bot_state WHERE key = ?1")?;
+        let mut rows = stmt.query_map(params![key], |row| row.get(0))?;
+        rows.next().transpose()
+    }
+
+    fn save_risk_state(risk: &RiskState) -> SqlResult<()> {
+        Self::set_state("risk", &serde_json::to_string(risk).unwrap_or_default())
+    }
+
+    fn load_risk_state() -> Option<RiskState> {
+        let raw = Self::get_state("risk").ok()??;
+        serde_json::from_str(&raw).ok()
+    }
+
+    // The open position with its trailing-stop peak, so a crash doesn't reset the stop to the current price
+    // "position" is the slots combined, for anyone reading the record by hand
+    fn save_position(slots: &Slots, stop_price: Option<f64>) -> SqlResult<()> {
+        let record = serde_json::json!({ "position": slots.combined(), "slots": slots, "stop_price": stop_price, "updated_at": Utc::now().to_rfc3339() });
+        Self::set_state("position", &record.to_string())
+    }
+
+    fn load_position() -> Option<(Slots, Option<f64>)> {
+        let raw = Self::get_state("position").ok()??;
+        let record: serde_json::Value = serde_json::from_str(&raw).ok()?;
+        let mut slots = match record.get("slots") {
+            Some(slots) => serde_json::from_value(slots.clone()).ok()?,
+            // Saved before there were slots
+            None => Slots::single(serde_json::from_value(record.get("position")?.clone()).ok()?),
+        };
+        // Saved before signal prices were kept: the fill stands in for it
+        for position in &mut slots.0 {
+            if let BotState::InPosition { entry_price, entry_signal, .. } = position {
+                if entry_signal.is_zero() { *entry_signal = *entry_price; }
+            }
+        }
+        Some((slots, record.get("stop_price").and_then(|v| v.as_f64())))
+    }
+
+    fn log_activity(username: &str, action: &str) -> SqlResult<()> {
+        let conn = Self::connect()?;
+        conn.prepare_cached("INSERT INTO activity (timestamp, username, action) VALUES (?1, ?2, ?3)")?
+            .execute(params![Utc::now().to_rfc3339(), username, action])?;
+        Ok(())
+    }
+
+    fn get_activity(limit: usize) -> SqlResult<Vec<ActivityRecord>> {
+        let conn = Self::connect()?;
+        let mut stmt = conn.prepare_cached("SELECT id, timestamp, username, action FROM activity ORDER BY id DESC LIMIT ?1")?;
+        let rows = stmt.query_map(params![limit], |row| {
+            Ok(ActivityRecord { id: row.get(0)?, timestamp: row.get(1)?, username: row.get(2)?, action: row.get(3)? })
+        })?;
+        rows.collect()
+    }
+
+    fn save_equity(time: i64, equity: f64) -> SqlResult<()> {
+        let conn = Self::connect()?;
+        conn.prepare_cached("INSERT OR REPLACE INTO equity (time, equity) VALUES (?1, ?2)")?.execute(params![time, equity])?;
+        Ok(())
+    }
+
+    fn get_equity_curve() -> SqlResult<Vec<(i64, f64)>> {
+        let conn = Self::connect()?;
+        let mut stmt = conn.prepare_cached("SELECT time, equity FROM equity ORDER BY time ASC")?;
+        let rows = stmt.query_map([], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        rows.collect()
+    }
+
+    fn save_backtest(run: &BacktestRun) -> SqlResult<i64> {
+        let conn = Self::connect()?;
+        conn.execute(
+            "INSERT INTO backtests (created, strategy, params, metrics, equity, trades) VALUES (?1, ?2, ?3, ?4, ?5, ?6)",
+            params![
+                run.created,
+                run.strategy,
+                serde_json::to_string(&run.params).unwrap_or_default(),
+                serde_json::to_string(&run.metrics).unwrap_or_default(),
+                serde_json::to_string(&run.equity).unwrap_or_default(),
+                serde_json::to_string(&run.trades).unwrap_or_default(),
+            ],
+        )?;
+        Ok(conn.last_insert_rowid())
+    }
+
+    // `with_trades` is false for listings, where the trade lists would bloat the payload
+    fn get_backtests(id: Option<i64>, with_trades: bool) -> SqlResult<Vec<BacktestRun>> {
+        let conn = Self::connect()?;
+        let mut stmt = conn.prepare_cached(
+            "SELECT id, created, strategy, params, metrics, equity, trades FROM backtests
+             WHERE ?1 IS NULL OR id = ?1 ORDER BY id DESC LIMIT 50"
+        )?;
+        let run_iter = stmt.query_map(params![id], |row| {
+            let params: String = row.get(3)?;
+            let metrics: String = row.get(4)?;
+            let equity: String = row.get(5)?;
+            let trades: String = if with_trades { row.get(6)? } else { "[]".to_string() };
+            Ok(BacktestRun {
+                id: row.get(0)?,
+                created: row.get(1)?,
+                strategy: row.get::<_, Option<String>>(2)?.unwrap_or_default(),
+                params: serde_json::from_str(&params).unwrap_or_default(),
+                metrics: serde_json::from_str(&metrics).unwrap_or_default(),
+                equity: serde_json::from_str(&equity).unwrap_or_default(),
+                trades: serde_json::from_str(&trades).unwrap_or_default(),
+            })
+        })?;
+
+        let mut runs = Vec::new();
+        for run in run_iter { runs.push(run?); }
+        Ok(runs)
+    }
+
+    /// Trade count and SHA-256 over the trade log, to compare simulation runs.
+    fn trades_digest() -> SqlResult<(usize, String)> {
+        let conn = Self::connect()?;
+        let mut stmt = conn.prepare_cached("SELECT action, price, quantity, profit, reason, timestamp FROM trades ORDER BY id")?;
+        let rows = stmt.query_map([], |row| {
+            Ok(format!("{}|{}|{}|{}|{}|{}\n",
+                row.get::<_, String>(0)?, row.get::<_, String>(1)?, row.get::<_, String>(2)?,
+                row.get::<_, String>(3)?, row.get::<_, String>(4)?, row.get::<_, String>(5)?))
+        })?;
+        let mut hasher = Sha256::new();
+        let mut count = 0;
+        for row in rows {
+            hasher.update(row?.as_bytes());
+            count += 1;
+        }
+        Ok((count, hex::encode(hasher.finalize())))
+    }
+
+    // One poll's rows, and rows older than the retention dropped
+    fn save_spreads(time: i64, rows: &[(String, f64, f64)], retention_days: u32) -> SqlResult<()> {
+        let conn = Self::connect()?;
+        let mut insert = conn.prepare_cached("INSERT INTO spreads (time, venue, price, spread_pct) VALUES (?1, ?2, ?3, ?4)")?;
+        for (venue, price, spread_pct) in rows {
+            insert.execute(params![time, venue, price, spread_pct])?;
+        }
+        if retention_days > 0 {
+            let threshold = time - i64::from(retention_days) * 24 * 60 * 60 * 1000;
+            conn.prepare_cached("DELETE FROM spreads WHERE time < ?1")?.execute(params![threshold])?;
+        }
+        Ok(())
+    }
+
+    // A consistent copy of the whole database, taken while the bot keeps writing
+    fn snapshot_to(path: &str) -> SqlResult<()> {
+        Self::connect()?.execute("VACUUM INTO ?1", params![path])?;
+        Ok(())
+    }
+
+    fn prune_old_data(now_ms: i64) -> SqlResult<()> {
+        let conn = Self::connect()?;
+        let threshold = now_ms - (60 * 60 * 1000); 
+        conn.prepare_cached("DELETE FROM candles WHERE time < ?1")?.execute(params![threshold])?;
+        Ok(())
+    }
+}
+
+// --- 🌐 API HELPERS ---
+fn get_api_credentials() -> (String, String) {
+    if let Some(keys) = keystore::credentials() { return (keys.key, keys.secret); }
+    (env::var("COINDCX_API_KEY").unwrap_or("dummy".into()), env::var("COINDCX_SECRET_KEY").unwrap_or("dummy".into()))
+}
+
+fn sign_payload(payload: &str, secret: &str) -> String {
+    type HmacSha256 = Hmac<Sha256>;
+    let mut mac = HmacSha256::new_from_slice(secret.as_bytes()).expect("Invalid Key");
+    mac.update(payload.as_bytes());
+    hex::encode(mac.finalize().into_bytes())
+}
+
+// Auth headers for private endpoints: the JSON body is signed with the API secret
+fn signed_headers(body: &str) -> HeaderMap {
+    let (api_key, api_secret) = get_api_credentials();
+    let signature = sign_payload(body, &api_secret);
+    let mut headers = HeaderMap::new();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    headers.insert("X-AUTH-APIKEY", HeaderValue::from_str(&api_key).unwrap());
+    headers.insert("X-AUTH-SIGNATURE", HeaderValue::from_str(&signature).unwrap());
+    headers
+}
+
+fn record_error(state: &SharedState, msg: String) {
+    let time_str = Utc::now().format("%H:%M:%S").to_string();
+    logfile::write("ERROR", &msg);
+    state.write().session.last_error = Some(format!("{} | {}", time_str, msg));
+}
+
+// Feeds an exchange call result into the circuit breaker and announces trips/recoveries
+fn track_api_result<T, E: std::fmt::Display>(
+    state: &SharedState, notifier: &Notifier, breaker: &mut CircuitBreaker, what: &str, result: &Result<T, E>,
+) -> bool {
+    match result {
+        Ok(_) => {
+            if breaker.record_success() {
+                add_log(state, "Circuit breaker closed, trading resumed".to_string());
+                bus::transition("breaker", "open", "closed", "probe succeeded");
+                notifier.send(Event::new(EventKind::Alert, PAIR, state.read().price, 0.0, 0.0, "Circuit breaker closed, trading resumed"));
+            }
+            false
+        },
+        Err(e) => {
+            record_error(state, format!("{}: {}", what, e));
+            let tripped = breaker.record_failure();
+            if tripped {
+                let msg = format!("Circuit breaker tripped ({}: {}), orders paused", what, e);
+                add_log(state, msg.clone());
+                bus::transition("breaker", "closed", "open", &msg);
+                state.write().session.breaker_trips += 1;
+                notifier.send(Event::new(EventKind::Error, PAIR, state.read().price, 0.0, 0.0, &msg));
+            }
+            tripped
+        },
+    }
+}
+
+fn add_log(state: &SharedState, msg: String) {
+    let line = format!("{} | {}", Utc::now().format("%H:%M:%S"), msg);
+    println!("{}", line);
+    logfile::write("INFO", &msg);
+    let mut data = state.write();
+    let logs = Arc::make_mut(&mut data.logs);
+    if logs.len() >= LOG_LINES { logs.pop_back(); }
+    logs.push_front(line);
+}
+
+async fn fetch_historical_candles(client: &reqwest::Client, limit: u32, timeout: Duration) -> Result<Vec<Candle>, String> {
+    if bybit::enabled() {
+        return bybit::candles(client, limit, timeout).await;
+    }
+    if okx::enabled() {
+        return okx::candles(client, limit, timeout).await;
+    }
+    coindcx::candles(client, limit, timeout).await
+}
+
+async fn get_latest_price(client: &reqwest::Client, timeout: Duration) -> Result<Option<f64>, String> {
+    chaos::inject("ticker", timeout).await?;
+    if bybit::enabled() {
+        return Ok(bybit::latest_price(client, timeout).await?.map(chaos::tick));
+    }
+    if okx::enabled() {
+        return Ok(okx::latest_price(client, timeout).await?.map(chaos::tick));
+    }
+    Ok(coindcx::last_price(client, symbols::active().data_code, timeout).await?.map(chaos::tick))
+}
+
+async fn fetch_orderbook(client: &reqwest::Client) -> Result<DepthResponse, String> {
+    if bybit::enabled() {
+        return bybit::depth(client).await;
+    }
+    if okx::enabled() {
+        return okx::depth(client).await;
+    }
+    coindcx::depth(client).await
+}
+
+// Keeps the order book current independently of the trading loop: from the depth stream, or by polling snapshots
+async fn orderbook_feed(book: SharedBook, source: FeedSource) {
+    // Bybit's and OKX's books are always polled
+    if source == FeedSource::Websocket && !bybit::enabled() && !okx::enabled() {
+        return orderbook::stream(book).await;
+    }
+    let client = reqwest::Client::builder().timeout(Duration::from_secs(5)).build().unwrap();
+    loop {
+        // Depth is the first thing to give up when the rate-limit budget is tight
+        if ratelimit::market_data_paused().is_none() {
+            match fetch_orderbook(&client).await {
+                Ok(depth) => *book.write() = OrderBook::from_snapshot(&depth, Utc::now().timestamp_millis()),
+                Err(e) => eprintln!("Order Book Error: {}", e),
+            }
+        }
+        sleep(Duration::from_secs(2)).await;
+    }
+}
+
+async fn fetch_wallet_balance(client: &reqwest::Client, state: &SharedState, timeout: Duration) -> Result<(), String> {
+    chaos::inject("balances", timeout).await?;
+    let market = symbols::active();
+    let (quote, base) = (market.quote(), market.base());
+    if simulating() {
+        let balances = BTreeMap::from([(quote.to_string(), dec!(10500)), (base.to_string(), dec!(0.05))]);
+        set_balances(state, balances, vec![WalletBalance::new("spot", dec!(10500), Decimal::ZERO)]);
+        return Ok(());
+    }
+    if bybit::enabled() {
+        // The position size stands in for the base balance
+        let (unified, position) = bybit::wallet(client, timeout).await?;
+        let balances = BTreeMap::from([(quote.to_string(), unified.available + unified.locked), (base.to_string(), position)]);
+        set_balances(state, balances, vec![unified, bybit::funding(client, timeout).await]);
+        return Ok(());
+    }
+    if okx::enabled() {
+        let (trading, base_balance) = okx::wallet(client, timeout).await?;
+        let balances = BTreeMap::from([(quote.to_string(), trading.available), (base.to_string(), base_balance)]);
+        set_balances(state, balances, vec![trading, okx::funding(client, timeout).await]);
+        return Ok(());
+    }
+
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let body_str = serde_json::json!({ "timestamp": timestamp }).to_string();
+    let headers = signed_headers(&body_str);
+
+    let res = client.post("https://api.coindcx.com/exchange/v1/users/balances").headers(headers).body(body_str).timeout(timeout).send().await
+        .map_err(|e| e.to_string())?;
+    ratelimit::observe(res.status(), res.headers());
+    let balances = res.json::<Vec<Balance>>().await.map_err(|e| e.to_string())?;
+    let mut held = BTreeMap::from([(quote.to_string(), Decimal::ZERO), (base.to_string(), Decimal::ZERO)]);
+    let mut spot = WalletBalance::new("spot", Decimal::ZERO, Decimal::ZERO);
+    for b in balances {
+        let amount = money::parse(&b.balance).unwrap_or_default();
+        if b.currency == quote {
+            spot = WalletBalance::new("spot", amount, money::parse(&b.locked_balance).unwrap_or_default());
+        }
+        if amount > Decimal::ZERO || b.currency == quote || b.currency == base { held.insert(b.currency, amount); }
+    }
+    set_balances(state, held, vec![spot, coindcx_futures_wallet(client, timeout).await]);
+    Ok(())
+}
+
+// The quote in the CoinDCX futures wallet. What is locked there is the locked balance plus the
+// margin held for cross orders and positions.
+async fn coindcx_futures_wallet(client: &reqwest::Client, timeout: Duration) -> WalletBalance {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let body_str = serde_json::json!({ "timestamp": timestamp }).to_string();
+    let headers = signed_headers(&body_str);
+    let res = client.get("https://api.coindcx.com/exchange/v1/derivatives/futures/wallets").headers(headers).body(body_str).timeout(timeout).send().await;
+    let wallets = match res {
+        Ok(r) if r.status().is_success() => r.json::<Vec<serde_json::Value>>().await.map_err(|e| e.to_string()),
+        Ok(r) => Err(format!("HTTP {}", r.status())),
+        Err(e) => Err(e.to_string()),
+    };
+    match wallets {
+        Ok(wallets) => {
+            let quote = symbols::active().quote();
+            let Some(w) = wallets.iter().find(|w| w["currency_short_name"] == quote) else {
+                return WalletBalance::new("futures", Decimal::ZERO, Decimal::ZERO);
+            };
+            let locked = money::from_value(&w["locked_balance"]) + money::from_value(&w["cross_order_margin"]) + money::from_value(&w["cross_user_margin"]);
+            WalletBalance::new("futures", (money::from_value(&w["balance"]) - locked).max(Decimal::ZERO), locked)
+        },
+        Err(e) => WalletBalance::failed("futures", e),
+    }
+}
+
+// Every asset's balance, with the pair's quote and base in the wallet fields, and the quote per wallet
+fn set_balances(state: &SharedState, balances: BTreeMap<String, Decimal>, wallets: Vec<WalletBalance>) {
+    let market = symbols::active();
+    let mut data = state.write();
+    data.wallet_usdt = balances.get(market.quote()).copied().unwrap_or_default();
+    data.wallet_btc = balances.get(market.base()).copied().unwrap_or_default();
+    data.balances = Arc::new(balances);
+    data.wallets = Arc::new(wallets);
+}
+
+async fn execute_trade(client: &reqwest::Client, template: &OrderTemplate, side: &str, price: Decimal, qty: Decimal, client_id: &str, timeout: Duration) -> Result<(), String> {
+    chaos::inject("order", timeout).await?;
+    if simulating() { 
+        println!("(SIMULATION) {} {} {} @ {}", side, qty, symbols::active().base(), currency::quote(money::float(price)));
+        return Ok(()); 
+    }
+    ratelimit::order_permit().await?;
+    let (body_str, headers) = template.render(side, price, qty, client_id);
+
+    // 🛑 FIX: Real execution enabled (when SIMULATION_MODE is false)
+    let res = client.post("https://api.coindcx.com/exchange/v1/orders/create").headers(headers).body(body_str).timeout(timeout).send().await;
+    if let Ok(r) = &res {
+        ratelimit::observe(r.status(), r.headers());
+    }
+    match res {
+        Ok(r) if r.status().is_success() => {
+            println!("(REAL) API Response: {:?}", r.status());
+            Ok(())
+        },
+        Ok(r) => Err(format!("order rejected: HTTP {}", r.status())),
+        Err(e) => {
+            eprintln!("(REAL) API Error: {}", e);
+            Err(e.to_string())
+        },
+    }
+}
+
+// A signed CoinDCX call about one order, by its client order id
+async fn order_call(client: &reqwest::Client, url: &str, client_id: &str, timeout: Duration) -> Result<serde_json::Value, String> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let body = serde_json::json!({ "client_order_id": client_id, "timestamp": timestamp }).to_string();
+    let r = client.post(url).headers(signed_headers(&body)).body(body).timeout(timeout).send().await.map_err(|e| e.to_string())?;
+    ratelimit::observe(r.status(), r.headers());
+    if !r.status().is_success() { return Err(format!("HTTP {}", r.status())); }
+    r.json().await.map_err(|e| e.to_string())
+}
+
+/// The final state of a CoinDCX order the user stream did not report: polled until `deadline`,
+/// then canceled if it is still working and read back. Err if it did not fill, or if its state
+/// can't be known (the balance reconciliation then finds any fill).
+async fn settle_order(client: &reqwest::Client, client_id: &str, deadline: tokio::time::Instant) -> Result<Fill, String> {
+    let status = |timeout| order_call(client, "https://api.coindcx.com/exchange/v1/orders/status", client_id, timeout);
+    loop {
+        if let Some(outcome) = status(ORDER_CALL_TIMEOUT).await.ok().as_ref().and_then(userstream::order_outcome) {
+            return outcome;
+        }
+        if tokio::time::Instant::now() + ORDER_POLL >= deadline { break; }
+        sleep(ORDER_POLL).await;
+    }
+    // A filled or already canceled order refuses the cancel; its status says which
+    let _ = order_call(client, "https://api.coindcx.com/exchange/v1/orders/cancel", client_id, ORDER_CALL_TIMEOUT).await;
+    match status(ORDER_CALL_TIMEOUT).await {
+        Ok(order) => userstream::order_outcome(&order).unwrap_or_else(|| Err(format!("order {} still open after cancel", client_id))),
+        Err(e) => Err(format!("order {} status unknown: {}", client_id, e)),
+    }
+}
+
+// --- 🛡️ SUPERVISOR ---
+// Restarts the trading task if it panics or exits, so the dashboard never serves stale data silently.
+async fn supervise_bot<C: Clock>(state: SharedState, config: SharedConfig, book: SharedBook, clock: Arc<C>, replay: Option<Arc<CandleReplay>>) {
+    let notifier = Notifier::from_env();
+    let mut consecutive_failures: u32 = 0;
+
+    loop {
+        let started = Instant::now();
+        let result = tokio::spawn(panic_guard::guarded(bot_logic(state.clone(), config.clone(), book.clone(), clock.clone(), replay.clone()))).await;
+        let reason = match result {
+            Err(e) if e.is_panic() => {
+                let payload = e.into_panic();
+                let msg = payload.downcast_ref::<&str>().map(|s| s.to_string())
+                    .or_else(|| payload.downcast_ref::<String>().cloned())
+                    .unwrap_or_else(|| "unknown panic".to_string());
+                format!("Trading task panicked: {}", msg)
+            },
+            Err(e) => format!("Trading task aborted: {}", e),
+            Ok(()) => "Trading task exited unexpectedly".to_string(),
+        };
+
+        // A task that ran for a while resets the backoff
+        if started.elapsed() > Duration::from_secs(300) { consecutive_failures = 0; }
+        consecutive_failures += 1;
+        let backoff = Duration::from_secs((5u64 << consecutive_failures.min(4)).min(60));
+
+        add_log(&state, format!("{} (restart in {}s)", reason, backoff.as_secs()));
+        record_error(&state, reason.clone());
+        state.write().session.restarts += 1;
+        notifier.send(Event::new(EventKind::Error, PAIR, state.read().price, 0.0, 0.0, &reason));
+
+        // Keep the systemd watchdog fed while waiting; the process itself is healthy
+        let resume_at = Instant::now() + backoff;
+        while Instant::now() < resume_at {
+            systemd::watchdog("Restarting trading task");
+            sleep(resume_at.saturating_duration_since(Instant::now()).min(Duration::from_secs(5))).await;
+        }
+    }
+}
+
+// --- 🧠 CORE LOGIC ---
+// Stores candles (oldest first) with their indicator values, warming up the strategy
+fn seed_candles<'a>(candles: impl Iterator<Item = &'a Candle>) {
+    let mut bb = BollingerBands::new(20, 2.0).unwrap();
+    let mut rsi = RelativeStrengthIndex::new(14).unwrap();
+    // Written directly (not queued): the live candles are loaded from these rows right after
+    let _ = DbManager::batch(|| {
+        for candle in candles {
+            let bb_out = bb.next(candle.close);
+            let rsi_val = rsi.next(candle.close);
+            let _ = DbManager::save_candle(candle, rsi_val, bb_out.lower, bb_out.upper);
+        }
+    });
+}
+
+// Candle-closed event: the finalized candle with its indicators and the equity sample are stored
+async fn on_candle_closed(closed: CandleClosed, position: BotState, realized_pl: Decimal) {
+    let unrealized = match position {
+        BotState::InPosition { entry_price, quantity, .. } => (money::dec(closed.candle.close) - entry_price) * quantity,
+        BotState::Idle => Decimal::ZERO,
+    };
+    let time = closed.candle.time;
+    let equity = money::float(realized_pl + unrealized);
+    timeseries::candle_closed(&closed, equity);
+    persist::submit(Write::Candle { candle: closed.candle, readings: closed.readings }).await;
+    persist::submit(Write::Equity { time, equity }).await;
+}
+
+// Hands a sell of the whole open position at the last known price to the executor, outside the
+// strategy (breaker trip, feed outage). None when flat or the executor is gone.
+async fn request_flatten(
+    orders: &mpsc::Sender<OrderRequest>, state: &SharedState, bot_state: BotState, reason: &'static str, context: SignalContext, timeout: Duration,
+) -> Option<OrderRequest> {
+    let BotState::InPosition { quantity, .. } = bot_state else { return None };
+    let price = context.signal_price;
+    let (order_price, qty) = match symbols::active().round_order("sell", price, quantity) {
+        Ok(order) => order,
+        Err(e) => {
+            add_log(state, format!("{} not sent: {}", reason, e));
+            return None;
+        },
+    };
+    add_log(state, format!("{} @ ${:.2}", reason, price));
+    let request = OrderRequest { kind: OrderKind::Flatten { reason }, side: "sell", price: order_price, qty, timeout, context };
+    send_order(orders, request).await
+}
+
+// Stats over the trades table, including writes still queued
+async fn trade_performance() -> stats::Performance {
+    persist::flush().await;
+    runtime::db(DbManager::get_all_trades).await.map(|t| stats::compute(&t, &[])).unwrap_or_default()
+}
+
+// The active stop of an open position
+fn active_stop(position: BotState, params: &StrategyParams) -> Option<Stop> {
+    match position {
+        BotState::InPosition { entry_price, highest_price, .. } => Some(strategy::stop_level(params, money::float(entry_price), highest_price)),
+        BotState::Idle => None,
+    }
+}
+
+// What the loop sees at `price` right now, to store with the trade. The indicators in the shared
+// state are the ones the decision was made on; the spread only when the book is fresh.
+fn signal_context(state: &SharedState, book: Option<&SharedBook>, price: f64, stop_price: Option<f64>, mode: &'static str) -> SignalContext {
+    let (rsi, bb_lower, bb_upper) = {
+        let d = state.read();
+        (d.rsi, d.bb_lower, d.bb_upper)
+    };
+    SignalContext {
+        signal_price: money::dec(price),
+        rsi,
+        bb_lower,
+        bb_upper,
+        stop_price,
+        spread_bps: book.and_then(|book| book.read().fresh_spread_bps(Utc::now().timestamp_millis())),
+        mode,
+        slot: None,
+    }
+}
+
+// The request, kept while it is in flight; None if the executor is gone
+async fn send_order(orders: &mpsc::Sender<OrderRequest>, request: OrderRequest) -> Option<OrderRequest> {
+    bus::order_sent(&request);
+    orders.send(request).await.ok().map(|()| request)
+}
+
+// A round trip's profit after both sides' fees
+fn net_profit(entry: Decimal, exit: Decimal, qty: Decimal) -> Decimal {
+    (exit - entry) * qty - (entry + exit) * qty * FEE_RATE
+}
+
+// Books a sell fill against one slot's position and returns the realized profit, net and gross.
+// Gross is the same trade at the prices the entry and exit were signalled at.
+// A partial fill leaves the rest open; the exit is re-evaluated on the next tick.
+async fn apply_sell_fill(
+    state: &SharedState, notifier: &Notifier, position: &mut BotState, fill: Fill, context: SignalContext, reason: &str, at: DateTime<Utc>,
+) -> (Decimal, Decimal) {
+    let BotState::InPosition { entry_price, highest_price, quantity, entry_signal, entered_at } = *position else { return (Decimal::ZERO, Decimal::ZERO) };
+    let profit_amt = net_profit(entry_price, fill.price, fill.qty);
+    let gross = (context.signal_price - entry_signal) * fill.qty;
+    let kind = if matches!(reason, "STOP LOSS" | "HARD STOP" | "BREAK EVEN") { EventKind::StopOut } else { EventKind::Trade };
+    persist::submit(Write::Trade { action: "sell", fill, profit: profit_amt, gross, reason: reason.to_string(), context, at }).await;
+    grpc::trade(at, "sell", fill.price, fill.qty, profit_amt, gross, reason);
+    notifier.send(Event::new(kind, PAIR, money::float(fill.price), money::float(fill.qty), money::float(profit_amt), reason).side("sell"));
+    sheets::trade_closed(at, entry_price, fill, profit_amt, gross, reason);
+    let mut remaining = quantity - fill.qty;
+    // Less than the venue's minimum order can never be sold on its own
+    if remaining > Decimal::ZERO && remaining < symbols::active().min_qty {
+        add_log(state, format!("{} {} left after the fill is below the minimum order, written off as dust", remaining, symbols::active().base()));
+        remaining = Decimal::ZERO;
+    }
+    {
+        let mut data = state.write();
+        data.realized_pl += profit_amt;
+        data.gross_pl += gross;
+        data.session.trades += 1;
+        data.session.fees_paid += fill.price * fill.qty * FEE_RATE;
+        if profit_amt > Decimal::ZERO { data.session.wins += 1; } else { data.session.losses += 1; }
+    }
+    if remaining > Decimal::ZERO {
+        add_log(state, format!("Partial fill: {:.6} of {:.6}, {:.6} still open", fill.qty, quantity, remaining));
+        *position = BotState::InPosition { entry_price, highest_price, quantity: remaining, entry_signal, entered_at };
+    } else {
+        *position = BotState::Idle;
+    }
+    (profit_amt, gross)
+}
+
+// Books a sell against the slot it was for, or against the open slots in order when it was for all
+// of them (a flatten, an external sell). Returns the realized profit, net and gross.
+async fn book_sell(
+    state: &SharedState, notifier: &Notifier, slots: &mut Slots, fill: Fill, context: SignalContext, reason: &str, at: DateTime<Utc>,
+) -> (Decimal, Decimal) {
+    let (mut net, mut gross) = (Decimal::ZERO, Decimal::ZERO);
+    for (slot, qty) in slots.allocate(fill.qty, context.slot) {
+        let context = SignalContext { slot: Some(slot), ..context };
+        let (n, g) = apply_sell_fill(state, notifier, &mut slots.0[slot], Fill { price: fill.price, qty }, context, reason, at).await;
+        net += n;
+        gross += g;
+    }
+    (net, gross)
+}
+
+/// Opens the entry's slot with its fill and returns the fill; a failed order opens nothing.
+fn open_slot(slots: &mut Slots, request: &OrderRequest, result: &Result<Fill, String>, now_ms: i64) -> Option<Fill> {
+    let fill = *result.as_ref().ok()?;
+    let signal = request.context.signal_price;
+    slots.0[request.context.slot.unwrap_or(0)] = BotState::InPosition {
+        entry_price: fill.price, highest_price: money::float(signal), quantity: fill.qty, entry_signal: signal, entered_at: now_ms,
+    };
+    Some(fill)
+}
+
+fn sell_status(slots: &Slots, sold_from: Option<usize>) -> &'static str {
+    match sold_from.map(|slot| slots.0[slot]) {
+        Some(BotState::InPosition { .. }) => "HOLDING (partial exit)",
+        _ if slots.open() > 0 => "HOLDING",
+        _ => "IDLE",
+    }
+}
+
+// The sells that close every open slot at `price`: slot, fill, net and gross profit
+fn close_all(slots: &Slots, price: Decimal) -> Vec<(usize, Fill, Decimal, Decimal)> {
+    slots.0.iter().enumerate().filter_map(|(slot, position)| match *position {
+        BotState::InPosition { entry_price, quantity, entry_signal, .. } => {
+            Some((slot, Fill { price, qty: quantity }, net_profit(entry_price, price, quantity), (price - entry_signal) * quantity))
+        },
+        BotState::Idle => None,
+    }).collect()
+}
+
+// Publishes the slots with what is derived from them, when they changed
+fn publish_slots(state: &SharedState, slots: Slots, params: &StrategyParams) {
+    let combined = slots.combined();
+    let stop = active_stop(combined, params);
+    let exit_at = match combined {
+        BotState::InPosition { entered_at, .. } if entered_at > 0 && params.max_hold_mins > 0.0 => Some(entered_at + (params.max_hold_mins * 60_000.0) as i64),
+        _ => None,
+    };
+    if state.read().slots == slots && state.read().stop == stop && state.read().exit_at == exit_at { return; }
+    let mut data = state.write();
+    data.slots = slots;
+    data.stop = stop;
+    data.exit_at = exit_at;
+    data.position = combined;
+    data.entry_price = match combined {
+        BotState::InPosition { entry_price, .. } => entry_price,
+        BotState::Idle => Decimal::ZERO,
+    };
+}
+
+// The loop's status text lives in a reused buffer and is only published when it changes
+fn set_status(buf: &mut String, text: fmt::Arguments) {
+    buf.clear();
+    let _ = buf.write_fmt(text);
+}
+
+// Pipeline: feed task -> this loop (strategy) -> executor task -> persistence thread.
+// The loop only evaluates ticks and applies fills; orders and DB writes never block it.
+async fn bot_logic<C: Clock>(state: SharedState, config: SharedConfig, book: SharedBook, clock: Arc<C>, replay: Option<Arc<CandleReplay>>) {
+    // Market data and balances share one pool; orders get their own in the executor
+    let client = reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap();
+    let sim_cfg = config.read().simulation.clone();
+    let keep_warm = config.read().timeouts.keep_warm();
+    // Today's book says nothing about a replayed day's spread
+    let live_book = replay.is_none().then_some(book);
+    let fills = SimFills::new(sim_cfg.seed, sim_cfg.slippage_bps, sim_cfg.spread_bps, live_book.clone(), sim_cfg.matching);
+    let mode = match (&replay, simulating()) {
+        (Some(_), _) => "replay",
+        (None, true) => "simulation",
+        (None, false) => "live",
+    };
+    let (orders, mut reports) = executor::spawn(Executor::new(fills, keep_warm));
+    let notifier = Notifier::from_env();
+    
+    // A snapshot restored at startup already holds the indicators, so history isn't synced again
+    let warm = if replay.is_none() { snapshot::take() } else { None };
+
+    // 1. Sync History (DB is initialized once in main so restarts keep session data; a replay is seeded by main)
+    let candles_timeout = config.read().timeouts.candles();
+    let history = if replay.is_none() && warm.is_none() { Some(fetch_historical_candles(&client, 500, candles_timeout).await) } else { None };
+    match history {
+        None => {},
+        Some(Ok(candles)) => {
+            seed_candles(candles.iter().rev());
+            add_log(&state, format!("Synced {} candles to DB", candles.len()));
+        },
+        Some(Err(e)) => {
+            eprintln!("History Sync Failed: {}", e);
+            record_error(&state, format!("History sync failed: {}", e));
+            notifier.send(Event::new(EventKind::Error, PAIR, 0.0, 0.0, 0.0, &format!("History sync failed: {}", e)));
+        },
+    }
+
+    let mut strategy: Box<dyn Strategy> = {
+        let cfg = config.read().strategy.clone();
+        strategy::build(&cfg.name, cfg.params)
+    };
+    state.write().strategy = strategy.name().to_string();
+    if chaos::enabled() {
+        add_log(&state, "🐒 CHAOS MODE: exchange errors, timeouts, partial fills and stale ticks are being injected".to_string());
+    }
+    // Resume from the last published positions (Idle on first start, restored after a supervisor restart)
+    let mut slots = state.read().slots;
+    match (slots.open(), slots.combined()) {
+        (1, BotState::InPosition { entry_price, .. }) => add_log(&state, format!("Resuming open position from ${:.2}", entry_price)),
+        (open, BotState::InPosition { entry_price, .. }) => add_log(&state, format!("Resuming {} open slots, average entry ${:.2}", open, entry_price)),
+        (_, BotState::Idle) => {},
+    }
+    let mut last_prune = clock.now();
+    let mut last_wallet = clock.now();
+
+    // Candles and indicators are kept in memory; live trades are streamed unless REST polling is configured
+    let mut candles = match warm {
+        Some(snapshot) => {
+            let age = clock.now().timestamp_millis() - snapshot.saved_at;
+            add_log(&state, format!("⚡ Warm restart: indicators restored from a {:.1}s old snapshot", age as f64 / 1000.0));
+            snapshot.candles
+        },
+        None => LiveCandles::load(clock.now().timestamp_millis()),
+    };
+    let mut snapshot_requests = snapshot::listen();
+    let mut last_snapshot = clock.now();
+    let mut last_candle_saved = clock.now();
+    let mut stream = (replay.is_none() && config.read().feed.source == FeedSource::Websocket).then(feed::spawn);
+    if stream.is_some() {
+        add_log(&state, "📶 Streaming trades over WebSocket".to_string());
+    }
+
+    // Daily summary tracking (trading day, see calendar.rs)
+    let mut summary_day = calendar::trading_day(clock.now());
+    let mut day_trades: u32 = 0;
+    // Realized P&L of the day so far, kept across restarts on the same trading day
+    let mut day_pnl = {
+        let mut data = state.write();
+        if !data.risk.is_day(summary_day) {
+            data.risk.new_day(summary_day);
+        }
+        money::dec(data.risk.day_realized)
+    };
+    let mut day_gross = Decimal::ZERO;
+
+    let mut alert_engine = AlertEngine::default();
+    let mut reload_generation = config::reload_generation();
+    let mut last_tick_at = clock.now();
+    let mut poll_steps = Ticker::new(clock.now(), POLL_EVERY);
+    let mut breaker = CircuitBreaker::new(config.read().breaker.clone());
+    let mut last_risk_saved: Option<SavedRisk> = None;
+    let mut last_entry_block: Option<String> = None;
+    let mut last_exit_block: Option<String> = None;
+    let mut price_rejected: Option<String> = None;
+    let mut last_saved_slots = slots;
+    let mut last_position_saved = clock.now();
+    let mut last_watchdog = clock.now();
+    let mut feed_link = Link::new("Market feed");
+    let mut private_link = Link::new("Private API");
+    let mut was_throttled = false;
+    let mut stop_checked = false;
+    // One order at a time: while it is in flight ticks are still processed, but no new order is sent
+    let mut in_flight: Option<OrderRequest> = None;
+    let mut order_failures: u32 = 0; // In a row
+    let mut next_report: Option<OrderReport> = None;
+    let mut reconciler = Reconciler::default();
+    let mut record = trade_performance().await; // For sizing, refreshed when a position closes
+    let mut reentry = ReentryBand::default();
+    let mut force_exit = false; // Risk halt flatten, kept until the exit order can go out
+    let mut manual_close = false; // Close asked for by an operator, kept the same way
+    // Steady-state ticks format nothing: status texts are written into these and compared
+    let mut status = state.read().status.clone();
+    let mut shown = String::with_capacity(64);
+    let mut breaker_text = String::with_capacity(32);
+
+    loop {
+        let today = calendar::trading_day(clock.now());
+        if today != summary_day {
+            let last_price = state.read().price;
+            let perf = trade_performance().await;
+            let reason = format!(
+                "{}: {} trades closed, gross {} | win rate {:.0}% (last {}: {:.0}%), streak {:+}, avg win {} / loss {}, expectancy {} (last {}: {})",
+                summary_day, day_trades, currency::quote(money::float(day_gross)), perf.win_rate, stats::ROLLING_TRADES, perf.rolling_win_rate, perf.streak,
+                currency::quote(perf.avg_win), currency::quote(perf.avg_loss), currency::quote(perf.expectancy), stats::ROLLING_TRADES, currency::quote(perf.rolling_expectancy),
+            );
+            notifier.send(Event::new(EventKind::DailySummary, PAIR, last_price, 0.0, money::float(day_pnl), &reason));
+            sheets::daily_summary(summary_day, day_trades, money::float(day_pnl));
+            summary_day = today;
+            day_trades = 0;
+            day_pnl = Decimal::ZERO;
+            day_gross = Decimal::ZERO;
+            state.write().risk.new_day(today);
+        }
+
+        if clock.elapsed(last_prune) > Duration::from_secs(300) {
+            persist::submit(Write::Prune { now_ms: clock.now().timestamp_millis() }).await;
+            add_log(&state, "Pruned old DB data".to_string());
+            last_prune = clock.now();
+        }
+
+        // Shutdown asks for the state as it is now
+        while let Ok(reply) = snapshot_requests.try_recv() {
+            let _ = reply.send(snapshot::Snapshot::new(clock.now().timestamp_millis(), &candles, slots.combined(), in_flight.as_ref()));
+        }
+
+        // Minute boundary: the candle is final even if no trade has arrived since
+        if let Some(closed) = candles.close_due(clock.now().timestamp_millis()) {
+            on_candle_closed(closed, slots.combined(), state.read().realized_pl).await;
+        }
+        // The chart reads candles from the DB, so the one being built is stored every few seconds
+        if clock.elapsed(last_candle_saved) >= Duration::from_secs(5) {
+            if let Some(candle) = candles.current() {
+                persist::submit(Write::Candle { candle: candle.clone(), readings: candles.preview() }).await;
+            }
+            last_candle_saved = clock.now();
+        }
+
+        let net_cfg = config.read().network.clone();
+        let budgets = config.read().timeouts.clone();
+
+        // Executor reports: fills are applied here, in order with the ticks
+        let report = match next_report.take() {
+            Some(report) => Some(report),
+            // A replay waits for its fill so the run stays deterministic
+            None if in_flight.is_some() && replay.is_some() => reports.recv().await,
+            None => reports.try_recv().ok(),
+        };
+        if let Some(OrderReport { request, result }) = report {
+            in_flight = None;
+            reconciler.reset();
+            private_link.record(&result);
+            match &result {
+                Ok(_) => order_failures = 0,
+                Err(e) => {
+                    order_failures += 1;
+                    if order_failures == ORDER_FAILURES_CRITICAL {
+                        let msg = format!("{} orders in a row failed, last: {}", order_failures, e);
+                        notifier.send(Event::new(EventKind::Error, PAIR, money::float(request.price), money::float(request.qty), 0.0, &msg).critical());
+                    }
+                },
+            }
+            let OrderRequest { price, qty, .. } = request;
+            let (before, before_open) = (slots.combined(), slots.open());
+            match request.kind {
+                // A simulated limit order nothing traded through; the signal may send it again
+                OrderKind::Entry { reason } | OrderKind::Exit { reason } | OrderKind::Flatten { reason } if matches!(result, Ok(fill) if fill.qty.is_zero()) => {
+                    add_log(&state, format!("{} ({}) not filled: nothing traded through {} in time", request.side.to_uppercase(), reason, currency::quote(money::float(price))));
+                    state.write().session.unfilled += 1;
+                    set_status(&mut status, format_args!("{}", if slots.open() > 0 { "HOLDING" } else { "IDLE" }));
+                },
+                OrderKind::Entry { reason } => {
+                    track_api_result(&state, &notifier, &mut breaker, "Order error", &result);
+                    match open_slot(&mut slots, &request, &result, clock.now().timestamp_millis()) {
+                        Some(fill) => {
+                            if fill.qty < qty {
+                                add_log(&state, format!("Partial fill: {:.6} of {:.6}", fill.qty, qty));
+                            }
+                            persist::submit(Write::Trade { action: "buy", fill, profit: Decimal::ZERO, gross: Decimal::ZERO, reason: reason.to_string(), context: request.context, at: clock.now() }).await;
+                            grpc::trade(clock.now(), "buy", fill.price, fill.qty, Decimal::ZERO, Decimal::ZERO, reason);
+                            notifier.send(Event::new(EventKind::Trade, PAIR, money::float(fill.price), money::float(fill.qty), 0.0, reason).side("buy"));
+                            set_status(&mut status, format_args!("IN POSITION"));
+                            let mut data = state.write();
+                            data.session.trades += 1;
+                            data.session.fees_paid += fill.price * fill.qty * FEE_RATE;
+                        },
+                        // Nothing was bought: the error is logged and counted, the slot stays free
+                        None => {
+                            add_log(&state, format!("BUY ({}) failed, no position opened: {}", reason, result.as_ref().err().map_or("", String::as_str)));
+                            set_status(&mut status, format_args!("{}", if slots.open() > 0 { "HOLDING" } else { "IDLE" }));
+                        },
+                    }
+                },
+                OrderKind::Exit { reason } => {
+                    track_api_result(&state, &notifier, &mut breaker, "Order error", &result);
+                    match &result {
+                        Ok(fill) => {
+                            let (profit_amt, gross) = book_sell(&state, &notifier, &mut slots, *fill, request.context, reason, clock.now()).await;
+                            set_status(&mut status, format_args!("{}", sell_status(&slots, request.context.slot)));
+                            day_trades += 1;
+                            day_pnl += profit_amt;
+                            day_gross += gross;
+                        },
+                        // Nothing was sold: the position stays open and the strategy or risk check tries again
+                        Err(e) => {
+                            add_log(&state, format!("SELL ({}) failed, position still open: {}", reason, e));
+                            set_status(&mut status, format_args!("HOLDING"));
+                        },
+                    }
+                },
+                OrderKind::Flatten { reason } => match &result {
+                    Ok(fill) => {
+                        let (profit_amt, gross) = book_sell(&state, &notifier, &mut slots, *fill, request.context, reason, clock.now()).await;
+                        set_status(&mut status, format_args!("{}", sell_status(&slots, request.context.slot)));
+                        day_trades += 1;
+                        day_pnl += profit_amt;
+                        day_gross += gross;
+                    },
+                    Err(e) => add_log(&state, format!("{} failed: {}", reason, e)),
+                },
+            }
+            let after = slots.combined();
+            bus::order_done(&request, &result, before, after);
+            // A closed position changes the track record the sizer works from, and starts a re-entry band
+            if slots.open() < before_open {
+                record = trade_performance().await;
+                reentry.exited(money::float(request.context.signal_price), request.context.rsi);
+            }
+            publish_slots(&state, slots, strategy.params());
+        }
+
+        // Balances double as the private API probe, polled faster while that link is failing
+        let wallet_every = if private_link.state(&net_cfg) == LinkState::Healthy { 60 } else { 10 };
+        let throttled = ratelimit::market_data_paused();
+        if throttled.is_some() != was_throttled {
+            add_log(&state, match &throttled {
+                Some(reason) => format!("🚥 Exchange {}, polling paused (orders still allowed)", reason),
+                None => "🚥 Rate limit cleared, polling resumed".to_string(),
+            });
+            was_throttled = throttled.is_some();
+        }
+
+        // A balance change pushed by the user stream is synced (and reconciled) right away
+        if throttled.is_none() && (userstream::take_balance_change() || clock.elapsed(last_wallet) > Duration::from_secs(wallet_every)) {
+            let wallet = fetch_wallet_balance(&client, &state, budgets.balances()).await;
+            private_link.record(&wallet);
+            last_wallet = clock.now();
+            let reconcile_cfg = config.read().reconcile.clone();
+            if wallet.is_ok() && !simulating() && reconcile_cfg.enabled {
+                if in_flight.is_some() {
+                    reconciler.reset();
+                } else {
+                    let held = match slots.combined() { BotState::InPosition { quantity, .. } => quantity, BotState::Idle => Decimal::ZERO };
+                    let base = state.read().wallet_btc;
+                    if let Some(change) = reconciler.check(&client, &reconcile_cfg, base, held, budgets.balances()).await {
+                        let last_price = state.read().price;
+                        if change.sold > Decimal::ZERO {
+                            let price = change.price.unwrap_or(money::dec(last_price));
+                            let fill = Fill { price, qty: change.sold };
+                            let context = signal_context(&state, live_book.as_ref(), money::float(price), active_stop(slots.combined(), strategy.params()).map(|stop| stop.price), mode);
+                            let (profit_amt, gross) = book_sell(&state, &notifier, &mut slots, fill, context, "EXTERNAL SELL", clock.now()).await;
+                            publish_slots(&state, slots, strategy.params());
+                            set_status(&mut status, format_args!("{}", sell_status(&slots, None)));
+                            day_trades += 1;
+                            day_pnl += profit_amt;
+                            day_gross += gross;
+                            let msg = format!("🔍 {} {} sold outside the bot (balance {}), position reconciled", change.sold, symbols::active().base(), base);
+                            add_log(&state, msg.clone());
+                            notifier.send(Event::new(EventKind::Alert, PAIR, money::float(price), money::float(change.sold), money::float(profit_amt), &msg).critical());
+                        } else {
+                            let msg = format!("🔍 {:+} {} changed outside the bot ({} fills), position unchanged", change.delta, symbols::active().base(), change.fills);
+                            add_log(&state, msg.clone());
+                            notifier.send(Event::new(EventKind::Alert, PAIR, last_price, money::float(change.delta), 0.0, &msg));
+                        }
+                    }
+                }
+            }
+        }
+
+        breaker.cfg = config.read().breaker.clone();
+
+        // While the breaker is open (or the rate limit is tight) the API is left alone until the next probe is due
+        let mut tick_price = None;
+        // The stream is pushed rather than polled, so only REST ticks wait out a rate limit
+        let polling_paused = throttled.is_some() && stream.is_none();
+        // Wake up when the candle is due, so it closes on the minute even if no trade arrives
+        let stream_wait = candles.due_in(clock.now().timestamp_millis()).map_or(STREAM_WAIT, |due| due.min(STREAM_WAIT));
+        let tick = if !polling_paused && breaker.allow_request() {
+            match (&replay, &mut stream) {
+                (Some(replay), _) => match replay.price_at(clock.now().timestamp_millis()) {
+                    Some(price) => Some(Ok(Some(price))),
+                    None => break,
+                },
+                (None, Some(rx)) => tokio::select! {
+                    biased;
+                    Some(report) = reports.recv(), if in_flight.is_some() => {
+                        next_report = Some(report);
+                        None
+                    },
+                    tick = tokio::time::timeout(stream_wait, rx.recv()) => match tick {
+                        Ok(Some(tick)) => Some(tick.map(|(price, _feed_span)| Some(price))),
+                        Ok(None) => Some(Err("trade stream stopped".to_string())),
+                        Err(_) => None, // No trades for a moment: run the rest of the loop anyway
+                    },
+                },
+                (None, None) => Some(get_latest_price(&client, budgets.tick()).instrument(info_span!("feed")).await),
+            }
+        } else {
+            if let Some(rx) = &mut stream {
+                // Trades that arrive while the breaker is open would be stale once it closes
+                while rx.try_recv().is_ok() {}
+                sleep(STREAM_WAIT).await;
+            }
+            None
+        };
+        if let Some(result) = &tick {
+            feed_link.record(result);
+            let tripped = track_api_result(&state, &notifier, &mut breaker, "Tick error", result);
+            // Last known price; the order itself may fail too if the exchange is down
+            if tripped && breaker.cfg.on_trip == TripAction::Flatten && in_flight.is_none() {
+                let position = slots.combined();
+                let context = signal_context(&state, live_book.as_ref(), state.read().price, active_stop(position, strategy.params()).map(|stop| stop.price), mode);
+                in_flight = request_flatten(&orders, &state, position, "BREAKER FLATTEN", context, budgets.orders()).await;
+            }
+        }
+
+        match tick {
+            Some(Ok(Some(price))) => {
+                systemd::ready(); // DB is initialized in main before the trading task starts
+                tick_price = Some(price);
+                last_tick_at = clock.now();
+                grpc::tick(last_tick_at.timestamp_millis(), price);
+
+                // The loop is managing the position again: drop any stop left by the panic guard
+                if !stop_checked {
+                    stop_checked = true;
+                    if let Some(msg) = panic_guard::release(&timeouts::order_client()).await {
+                        add_log(&state, msg);
+                    }
+                }
+                // Closed once the entry/exit decision is made (before any order goes out)
+                let mut evaluate_span = Some(info_span!("evaluate"));
+
+                // Pick up strategy changes from the API; switching strategies only happens while flat
+                let switch_to = {
+                    let cfg = &config.read().strategy;
+                    let changed = strategy.name() != cfg.name || *strategy.params() != cfg.params;
+                    (changed && (slots.open() == 0 || strategy.name() == cfg.name)).then(|| cfg.clone())
+                };
+                if let Some(cfg) = switch_to {
+                    strategy = strategy::build(&cfg.name, cfg.params);
+                    state.write().strategy = strategy.name().to_string();
+                    add_log(&state, format!("Strategy updated: {}", strategy.name()));
+                }
+
+                // Hot path: no DB or HTTP until a signal fires, except once per closed candle
+                if let Some(closed) = candles.update(clock.now().timestamp_millis(), price) {
+                    on_candle_closed(closed, slots.combined(), state.read().realized_pl).await;
+                }
+                let readings = candles.preview();
+                let premium = spread::premium(&config.read().spread);
+                let mut ind = Indicators { price, rsi: readings.rsi, bb_lower: readings.bb_lower, venue_premium_pct: premium, last_exit: None };
+                reentry.apply(strategy.params(), &mut ind);
+
+                // Kill switches: block new entries (and optionally flatten) until re-armed via the API
+                let combined = slots.combined();
+                let unrealized_amt = match combined {
+                    BotState::InPosition { entry_price, quantity, .. } => (price - money::float(entry_price)) * money::float(quantity),
+                    BotState::Idle => 0.0,
+                };
+                let risk_cfg = config.read().risk.clone();
+                let capital = strategy.params().trade_capital;
+                // One published update per tick
+                let newly_halted = {
+                    let mut data = state.write();
+                    data.price = price;
+                    data.last_tick_at = Utc::now().timestamp_millis(); // Wall time: feed health, not candle time
+                    data.rsi = readings.rsi;
+                    data.bb_lower = readings.bb_lower;
+                    data.bb_upper = readings.bb_upper;
+                    data.warmup = candles.warming_up();
+                    if let BotState::InPosition { entry_price, .. } = combined {
+                        let entry_price = money::float(entry_price);
+                        let diff = price - entry_price;
+                        data.unrealized_pl = (diff / entry_price) * 100.0;
+                    } else {
+                        data.unrealized_pl = 0.0;
+                    }
+                    let realized = money::float(data.realized_pl);
+                    let daily = data.risk.check_daily_loss(&risk_cfg, money::float(day_pnl), unrealized_amt, capital);
+                    let drawdown = data.risk.check_drawdown(&risk_cfg, realized, unrealized_amt, capital);
+                    daily.or(drawdown)
+                };
+                if let Some(reason) = &newly_halted {
+                    add_log(&state, format!("🛑 HALT: {}", reason));
+                    bus::transition("risk", "armed", "halted", reason);
+                    notifier.send(Event::new(EventKind::Error, PAIR, price, 0.0, money::float(day_pnl) + unrealized_amt, reason).critical());
+                }
+                let halted = {
+                    let risk = state.read().risk.clone();
+                    let persisted = (risk.halted.clone(), risk.peak_equity, risk.realized_total, risk.day.clone(), risk.rearm_offset);
+                    if last_risk_saved.as_ref() != Some(&persisted) {
+                        persist::submit(Write::Risk(risk.clone())).await;
+                        last_risk_saved = Some(persisted);
+                    }
+                    risk.halted
+                };
+                // Live entries also wait while the clock is out of tolerance; exits are still attempted
+                let halted = halted.or_else(|| if simulating() { None } else { clock::pause_reason() });
+                // Without a working private API an entry could not be confirmed (or exited), so none are opened
+                let halted = halted.or_else(|| match private_link.state(&net_cfg) {
+                    LinkState::Healthy => None,
+                    link_state => Some(format!("private API {}", link_state)),
+                });
+                // Only the instance holding the pair's lock opens positions
+                let halted = halted.or_else(cluster::standby_reason);
+                force_exit |= newly_halted.is_some() && risk_cfg.flatten_on_halt;
+                manual_close |= control::take_close();
+                // A price the reference source disagrees with is not traded on (a manual close still is)
+                let rejected = sanity::reject_reason(&config.read().sanity, price);
+                if rejected.is_some() != price_rejected.is_some() {
+                    let msg = match &rejected {
+                        Some(why) => format!("🧭 Price check failed, not trading: {}", why),
+                        None => "🧭 Price check passing again".to_string(),
+                    };
+                    add_log(&state, msg.clone());
+                    let kind = if rejected.is_some() { EventKind::Error } else { EventKind::Alert };
+                    notifier.send(Event::new(kind, PAIR, price, 0.0, 0.0, &msg));
+                }
+                price_rejected = rejected;
+
+                let slot_cfg = config.read().slots.clone();
+                let open = slots.open();
+                if open == 0 {
+                    // A manual close or risk halt is kept until every slot is flat
+                    force_exit = false;
+                    manual_close = false;
+                }
+                // Every open slot trails its own peak
+                for position in &mut slots.0 {
+                    if let BotState::InPosition { highest_price, .. } = position {
+                        *highest_price = highest_price.max(price);
+                    }
+                }
+                // The first slot that wants out; the others follow on the next ticks, one order at a time
+                let now_ms = clock.now().timestamp_millis();
+                let exit = slots.0.iter().enumerate().find_map(|(slot, position)| match *position {
+                    BotState::InPosition { quantity, highest_price, entry_price, entered_at, .. } => manual_close.then_some("MANUAL CLOSE")
+                        .or_else(|| strategy::protective_exit(strategy.params(), price, money::float(entry_price), highest_price))
+                        // A position from before entry times were kept has no clock to run out
+                        .or_else(|| strategy::time_exit(strategy.params(), now_ms - entered_at).filter(|_| entered_at > 0))
+                        .or_else(|| strategy.exit_reason(&ind, highest_price))
+                        .or(force_exit.then_some("RISK HALT"))
+                        .map(|reason| (slot, reason, quantity)),
+                    BotState::Idle => None,
+                });
+                // Another slot only opens on a deeper dip than the lowest open entry
+                let free_slot = slots.free(slot_cfg.count).filter(|_| match slots.lowest_entry() {
+                    Some(lowest) => price <= money::float(lowest) * (1.0 - slot_cfg.add_below_pct / 100.0),
+                    None => true,
+                });
+                if let Some(request) = &in_flight {
+                    match request.kind {
+                        OrderKind::Entry { .. } => set_status(&mut status, format_args!("ENTRY PENDING")),
+                        OrderKind::Exit { .. } | OrderKind::Flatten { .. } => set_status(&mut status, format_args!("EXIT PENDING")),
+                    }
+                } else if let Some(why) = price_rejected.as_ref().filter(|_| exit.is_some() && !manual_close) {
+                    set_status(&mut status, format_args!("EXIT HELD: {}", why));
+                } else if let Some((slot, reason, quantity)) = exit {
+                    match symbols::active().round_order("sell", money::dec(price), quantity) {
+                        Ok((order_price, qty)) => {
+                            last_exit_block = None;
+                            add_log(&state, format!("{} @ ${:.2}", reason, price));
+                            bus::publish(BusEvent::Signal { side: "sell", price, rsi: readings.rsi, reason });
+                            evaluate_span.take();
+                            let stop = active_stop(slots.0[slot], strategy.params()).map(|stop| stop.price);
+                            let context = SignalContext { slot: Some(slot), ..signal_context(&state, live_book.as_ref(), price, stop, mode) };
+                            let request = OrderRequest { kind: OrderKind::Exit { reason }, side: "sell", price: order_price, qty, timeout: budgets.orders(), context };
+                            in_flight = send_order(&orders, request).await;
+                            set_status(&mut status, format_args!("EXIT PENDING"));
+                            state.write().session.signals += 1;
+                        },
+                        Err(why) => {
+                            if last_exit_block.as_deref() != Some(why.as_str()) {
+                                add_log(&state, format!("EXIT BLOCKED ({}): {}", reason, why));
+                                last_exit_block = Some(why.clone());
+                            }
+                            set_status(&mut status, format_args!("EXIT BLOCKED: {}", why));
+                        },
+                    }
+                } else if open > 0 && (free_slot.is_none() || halted.is_some() || price_rejected.is_some() || candles.warming_up().is_some()) {
+                    set_status(&mut status, format_args!("HOLDING"));
+                } else if let Some(reason) = &halted {
+                    set_status(&mut status, format_args!("HALTED: {}", reason));
+                } else if let Some(why) = &price_rejected {
+                    set_status(&mut status, format_args!("IDLE (Blocked: {})", why));
+                } else if let Some(left) = candles.warming_up() {
+                    // Readings from a short history are noise, whatever they say
+                    set_status(&mut status, format_args!("IDLE (Warming up, {} candles to go)", left));
+                } else if let Some(exit) = ind.last_exit.filter(|_| free_slot.is_some() && strategy.entry_reason(&ind).is_some()) {
+                    set_status(&mut status, format_args!("IDLE (Re-entry held, last exit ${:.2} at RSI {:.1})", exit.price, exit.rsi));
+                } else if let Some((slot, reason)) = free_slot.zip(strategy.entry_signal(&ind)) {
+                    let market = symbols::active();
+                    let order_price = market.order_price("buy", money::dec(price));
+                    let sizing_cfg = config.read().sizing.clone();
+                    let sizer = sizing::build(&sizing_cfg);
+                    let params = strategy.params();
+                    let (wallet_usdt, wallet_btc, realized, wallets) = { let d = state.read(); (d.wallet_usdt, d.wallet_btc, d.risk.realized_total, d.wallets.clone()) };
+                    // On Bybit the base "balance" is the position on margin, already in the USDT wallet
+                    let held = if bybit::enabled() { Decimal::ZERO } else { wallet_btc * order_price };
+                    let account = sizing::Account {
+                        capital: params.trade_capital,
+                        equity: sizing::equity(&sizing_cfg, params.trade_capital, realized, money::float(wallet_usdt + held)),
+                        stop_distance: params.trailing_stop_pct,
+                        record: &record,
+                    };
+                    let mut wanted = Decimal::ZERO;
+                    let sized = sizer.notional(&account).map_err(|why| format!("{} sizing: {}", sizer.name(), why)).and_then(|notional| {
+                        wanted = market.floor_qty(money::dec(notional).checked_div(order_price).unwrap_or_default());
+                        // This pair's open slots plus whatever the other instances hold
+                        let mut portfolio = cluster::peers()?;
+                        portfolio.push(slots.holding(PAIR, order_price));
+                        risk::check_entry(&risk_cfg, PAIR, order_price, wanted, wallet_usdt, &portfolio, &wallets)
+                    });
+                    match sized.and_then(|qty| market.order_qty(order_price, qty)) {
+                        Ok(qty) => {
+                            last_entry_block = None;
+                            if qty < wanted {
+                                add_log(&state, format!("Size capped by risk limits: ${:.2} -> ${:.2}", wanted * order_price, qty * order_price));
+                            }
+                            match ind.venue_premium_pct {
+                                Some(premium) => add_log(&state, format!("BUY SIGNAL @ ${:.2} (venue premium {:+.2}%)", price, premium)),
+                                None => add_log(&state, format!("BUY SIGNAL @ ${:.2}", price)),
+                            }
+                            bus::publish(BusEvent::Signal { side: "buy", price, rsi: readings.rsi, reason });
+                            evaluate_span.take();
+                            // The stop starts from the signal price, the first high
+                            let stop = strategy::stop_level(strategy.params(), price, price).price;
+                            let context = SignalContext { slot: Some(slot), ..signal_context(&state, live_book.as_ref(), price, Some(stop), mode) };
+                            let request = OrderRequest { kind: OrderKind::Entry { reason }, side: "buy", price: order_price, qty, timeout: budgets.orders(), context };
+                            in_flight = send_order(&orders, request).await;
+                            set_status(&mut status, format_args!("ENTRY PENDING"));
+                            state.write().session.signals += 1;
+                        },
+                        Err(why) => {
+                            // Log once per distinct reason, the signal may persist for many ticks
+                            if last_entry_block.as_deref() != Some(why.as_str()) {
+                                add_log(&state, format!("ENTRY BLOCKED ({}): {}", reason, why));
+                                last_entry_block = Some(why.clone());
+                            }
+                            match open {
+                                0 => set_status(&mut status, format_args!("IDLE (Blocked: {})", why)),
+                                _ => set_status(&mut status, format_args!("HOLDING")),
+                            }
+                        },
+                    }
+                } else if open > 0 {
+                    set_status(&mut status, format_args!("HOLDING"));
+                } else {
+                    set_status(&mut status, format_args!("IDLE (Scanning)"));
+                }
+                publish_slots(&state, slots, strategy.params());
+            },
+            Some(Ok(None)) => eprintln!("No trades found in recent history"),
+            Some(Err(e)) => eprintln!("Tick Error: {}", e), // Alerting is driven by the feed link state below
+            None => {},
+        }
+
+        // Outage state machine: announce transitions, then apply the per-state position policy
+        for link in [&mut feed_link, &mut private_link] {
+            if let Some((from, to)) = link.transition(&net_cfg) {
+                let msg = match to {
+                    LinkState::Healthy => format!("📡 {} recovered", link.name),
+                    _ => format!("📡 {} {} ({})", link.name, to, link.last_error),
+                };
+                add_log(&state, msg.clone());
+                // Degraded is often a blip: only full disconnects and the recovery from them are pushed
+                let last_price = state.read().price;
+                if to == LinkState::Disconnected {
+                    notifier.send(Event::new(EventKind::Error, PAIR, last_price, 0.0, 0.0, &msg));
+                } else if from == LinkState::Disconnected {
+                    notifier.send(Event::new(EventKind::Alert, PAIR, last_price, 0.0, 0.0, &msg));
+                }
+            }
+        }
+        let network = NetworkStatus { feed: feed_link.state(&net_cfg), private_api: private_link.state(&net_cfg) };
+        // Degraded feed: hold and keep retrying. Disconnected: apply `on_feed_outage` if orders can still go out.
+        if network.feed == LinkState::Disconnected
+            && network.private_api != LinkState::Disconnected
+            && net_cfg.on_feed_outage == OutageAction::Flatten
+            && in_flight.is_none()
+        {
+            let position = slots.combined();
+            let context = signal_context(&state, live_book.as_ref(), state.read().price, active_stop(position, strategy.params()).map(|stop| stop.price), mode);
+            in_flight = request_flatten(&orders, &state, position, "FEED OUTAGE FLATTEN", context, budgets.orders()).await;
+        }
+
+        // Persist entries and exits right away; a new trailing-stop high at most every few seconds
+        let only_new_high = slots.0.iter().zip(&last_saved_slots.0).all(|pair| match pair {
+            (BotState::InPosition { entry_price: a, quantity: q, .. }, BotState::InPosition { entry_price: b, quantity: r, .. }) => a == b && q == r,
+            (a, b) => a == b,
+        });
+        if slots != last_saved_slots && (!only_new_high || clock.elapsed(last_position_saved) >= Duration::from_secs(5)) {
+            let stop_price = active_stop(slots.combined(), strategy.params()).map(|stop| stop.price);
+            persist::submit(Write::Position { slots, stop_price }).await;
+            last_saved_slots = slots;
+            last_position_saved = clock.now();
+        }
+        // Warm-restart snapshot (not in a replay, its clock is virtual); the file is written off the loop
+        let snapshot_cfg = config.read().snapshot.clone();
+        if replay.is_none() && snapshot_cfg.enabled && clock.elapsed(last_snapshot) >= Duration::from_secs(snapshot_cfg.interval_secs) {
+            let taken = snapshot::Snapshot::new(clock.now().timestamp_millis(), &candles, slots.combined(), in_flight.as_ref());
+            tokio::task::spawn_blocking(move || {
+                if let Err(e) = snapshot::save(&taken) { eprintln!("Snapshot Error: {}", e); }
+            });
+            last_snapshot = clock.now();
+        }
+
+        // What the dashboard shows: the most urgent condition, otherwise the strategy status
+        if let Some(reason) = &throttled {
+            set_status(&mut shown, format_args!("THROTTLED: {}", reason));
+        } else if network.feed == LinkState::Disconnected {
+            set_status(&mut shown, format_args!("OFFLINE: market feed disconnected"));
+        } else if network.feed == LinkState::Degraded {
+            set_status(&mut shown, format_args!("DEGRADED: market feed retrying"));
+        } else if !breaker.trading_allowed() {
+            set_status(&mut shown, format_args!("HALTED: breaker {}", breaker.status()));
+        } else {
+            set_status(&mut shown, format_args!("{}", status));
+        }
+        set_status(&mut breaker_text, format_args!("{}", breaker.status()));
+        let changed = {
+            let data = state.read();
+            data.status != shown || data.breaker != breaker_text || data.network != network
+        };
+        if changed {
+            let mut data = state.write();
+            data.status.clone_from(&shown);
+            data.breaker.clone_from(&breaker_text);
+            data.network = network;
+        }
+
+        if config::reload_generation() != reload_generation {
+            alert_engine = AlertEngine::default();
+            reload_generation = config::reload_generation();
+        }
+        let inputs = AlertInputs {
+            price: tick_price,
+            unrealized_pct: state.read().unrealized_pl,
+            secs_since_tick: clock.elapsed(last_tick_at).as_secs(),
+        };
+        let fired = alert_engine.evaluate(&config.read().alerts, &inputs);
+        for msg in fired {
+            add_log(&state, format!("ALERT: {}", msg));
+            notifier.send(Event::new(EventKind::Alert, PAIR, tick_price.unwrap_or(0.0), 0.0, inputs.unrealized_pct, &msg));
+        }
+        // A loop stuck on a blocking call stops these pings and systemd restarts the service
+        if clock.elapsed(last_watchdog) >= Duration::from_secs(1) {
+            systemd::watchdog(&state.read().status);
+            last_watchdog = clock.now();
+        }
+        // Streamed trades pace the loop themselves; polling (and a replay) steps on a fixed 5s grid
+        if stream.is_none() {
+            let mut wait = poll_steps.until_next(clock.now());
+            // A replay of recorded trades wakes for each one
+            let now_ms = clock.now().timestamp_millis();
+            if let Some(next) = replay.as_ref().and_then(|r| r.next_tick(now_ms)) {
+                wait = wait.min(Duration::from_millis((next - now_ms) as u64));
+            }
+            if in_flight.is_some() && replay.is_none() {
+                // Wake up for the fill instead of sleeping through it
+                tokio::select! {
+                    Some(report) = reports.recv() => next_report = Some(report),
+                    _ = clock.sleep(wait) => {},
+                }
+            } else {
+                clock.sleep(wait).await;
+            }
+        }
+    }
+
+    // Only a replay (accelerated or recorded) leaves the loop: park here instead of being restarted
+    add_log(&state, format!("⏩ Replay finished at {}", clock.now().format("%Y-%m-%d %H:%M")));
+    persist::flush().await;
+    if let Ok((count, digest)) = DbManager::trades_digest() {
+        // Same data + same seed must give the same digest; a change means the strategy behaves differently
+        add_log(&state, format!("⏩ {} trades, realized ${:.2}, trade log sha256 {}", count, state.read().realized_pl, digest));
+    }
+    state.write().status = "IDLE (Replay finished)".to_string();
+    loop {
+        systemd::watchdog("Replay finished");
+        sleep(Duration::from_secs(5)).await;
+    }
+}
+
+/// Entry point of the `coindcx_scalper` binary: the subcommands, or else the bot itself.
+pub fn cli() {
+    // Helper for filling `password_hash` of [[users]] in config.toml
+    let args: Vec<String> = env::args().collect();
+    if args.get(1).map(String::as_str) == Some("hash-password") {
+        match args.get(2).map(|pw| auth::hash_password(pw)) {
+            Some(Ok(hash)) => println!("{}", hash),
+            Some(Err(e)) => eprintln!("Hashing failed: {}", e),
+            None => eprintln!("Usage: coindcx_scalper hash-password <password>"),
+        }
+        return;
+    }
+    if args.get(1).map(String::as_str) == Some("keys") {
+        keystore::run_cli(&args[2..]);
+        return;
+    }
+    // Subcommands (backtest, optimize, replay), which need no keys
+    if let Some(name) = args.get(1) {
+        if commands::run(name, &args[2..]) { return; }
+    }
+
+    // Prompts for the passphrase (or reads the systemd credential) before anything starts
+    if let Err(e) = keystore::unlock() {
+        eprintln!("❌ Could not unlock {}: {}", KEYSTORE_FILE, e);
+        std::process::exit(1);
+    }
+
+    // The runtime shape comes from config, so it is read before the runtime exists
+    let config = Config::load();
+    let rt = runtime::build(&config.runtime).unwrap_or_else(|e| {
+        eprintln!("❌ Could not start the async runtime: {}", e);
+        std::process::exit(1);
+    });
+    rt.block_on(run(config, None));
+}
+
+// Warm restart: a recent snapshot is handed to the trading task. The DB position (written on every
+// change) wins over the snapshot's; an order in flight at shutdown can only be flagged.
+fn restore_snapshot(state: &SharedState, config: &Config) {
+    // An accelerated simulation starts from history on a virtual clock
+    if !config.snapshot.enabled || (simulating() && config.simulation.speed > 1.0) { return; }
+    let snapshot = match snapshot::load(Utc::now().timestamp_millis(), Duration::from_secs(config.snapshot.max_age_secs)) {
+        Ok(Some(snapshot)) => snapshot,
+        Ok(None) => return,
+        Err(why) => return add_log(state, format!("Snapshot not used ({}), syncing history", why)),
+    };
+    let notifier = Notifier::from_env();
+    let position = state.read().position;
+    if snapshot.position() != position {
+        let msg = "Snapshot position differs from the DB record, keeping the DB's";
+        add_log(state, format!("⚠️ {}", msg));
+        notifier.send(Event::new(EventKind::Error, PAIR, 0.0, 0.0, 0.0, msg).critical());
+    }
+    if let Some(order) = &snapshot.pending {
+        let msg = format!("{} order ({:.6} @ ${:.2}, {}) was in flight when the last run stopped, check the exchange for its fill", order.side, order.qty, order.price, order.reason);
+        add_log(state, format!("⚠️ {}", msg));
+        notifier.send(Event::new(EventKind::Error, PAIR, money::float(order.price), money::float(order.qty), 0.0, &msg).critical());
+        record_error(state, msg);
+    }
+    snapshot::restore(snapshot);
+}
+
+// `recorded` is a replay of recorded data (the replay subcommand) and its warm-up candles
+async fn run(config: Config, recorded: Option<(CandleReplay, Vec<Candle>)>) {
+    let shared_config: SharedConfig = Arc::new(RwLock::new(config));
+    logfile::init(&shared_config.read().logging);
+    database::configure(&shared_config.read().database);
+    latency::install();
+    chaos::configure(&shared_config.read().chaos);
+    notify::configure(&shared_config.read().notify);
+    calendar::configure(&shared_config.read().calendar);
+    bybit::configure(shared_config.read().bybit.clone());
+    okx::configure(shared_config.read().okx.clone());
+    if let Err(e) = symbols::configure() {
+        eprintln!("❌ {}", e);
+        std::process::exit(1);
+    }
+    let shared_state = Arc::new(Published::new(DashboardData {
+        price: 0.0, rsi: 0.0, bb_lower: 0.0, bb_upper: 0.0, status: "Starting...".to_string(),
+        strategy: String::new(),
+        entry_price: Decimal::ZERO, unrealized_pl: 0.0, realized_pl: Decimal::ZERO, gross_pl: Decimal::ZERO,
+        wallet_usdt: Decimal::ZERO, wallet_btc: Decimal::ZERO, logs: Arc::new(VecDeque::with_capacity(LOG_LINES)),
+        balances: Arc::default(),
+        wallets: Arc::default(),
+        currencies: Currencies::new(),
+        session: SessionStats { started_at: Utc::now().timestamp_millis(), ..Default::default() },
+        position: BotState::Idle,
+        breaker: "closed".to_string(),
+        risk: RiskState::default(),
+        config_notice: None,
+        last_tick_at: 0,
+        clock_drift_ms: None,
+        resources: ResourceUsage::default(),
+        network: NetworkStatus::default(),
+        utc_offset_mins: calendar::utc_offset_mins(),
+        warmup: None,
+        slots: Slots::default(),
+        stop: None,
+        exit_at: None,
+    }));
+    panic_guard::install(shared_state.clone(), shared_config.clone());
+
+    let state_shutdown = shared_state.clone();
+    let config_shutdown = shared_config.clone();
+    // Prepared now, the emergency sell only signs it; it goes out on the same route as every order
+    let mut shutdown_executor = {
+        let sim = shared_config.read().simulation.clone();
+        Executor::new(SimFills::new(sim.seed, sim.slippage_bps, sim.spread_bps, None, sim.matching), None)
+    };
+    tokio::spawn(async move {
+        tokio::signal::ctrl_c().await.unwrap();
+        println!("\n🚨 SHUTDOWN: Checking open positions...");
+        systemd::stopping();
+        let (slots, price) = {
+            let d = state_shutdown.read();
+            (d.slots, d.price)
+        };
+        if let BotState::InPosition { quantity, .. } = slots.combined() {
+            println!("💥 EMERGENCY SELL: Closing at {}", price);
+            let (order_timeout, params) = { let c = config_shutdown.read(); (c.timeouts.orders(), c.strategy.params) };
+            let mode = if simulating() { "simulation" } else { "live" };
+            let context = signal_context(&state_shutdown, None, price, active_stop(slots.combined(), &params).map(|stop| stop.price), mode);
+            // Rounded to the venue's steps and refused below its minimums, like every order. Only the
+            // venue's fill is booked; whatever it didn't sell stays persisted for the next start.
+            match shutdown_executor.place("sell", money::dec(price), quantity, order_timeout).await {
+                Ok(fill) if !fill.qty.is_zero() => {
+                    let mut slots = slots;
+                    book_sell(&state_shutdown, &Notifier::from_env(), &mut slots, fill, context, "SHUTDOWN", Utc::now()).await;
+                    persist::submit(Write::Position { slots, stop_price: active_stop(slots.combined(), &params).map(|stop| stop.price) }).await;
+                    if slots.open() > 0 {
+                        eprintln!("Emergency sell filled {} of {}, the rest is kept for the next start", fill.qty, quantity);
+                    }
+                },
+                Ok(_) => eprintln!("Emergency sell not filled, the position is kept for the next start"),
+                Err(e) => eprintln!("Emergency sell failed, the position is kept for the next start: {}", e),
+            }
+        }
+        cluster::release().await;
+        // Writes still queued by the trading loop land before exiting
+        persist::flush().await;
+        bus::flush(Duration::from_secs(5)).await;
+        if config_shutdown.read().snapshot.enabled {
+            match snapshot::capture().await.map(|taken| snapshot::save(&taken)) {
+                Some(Ok(())) => println!("⚡ State snapshot saved for a warm restart"),
+                Some(Err(e)) => eprintln!("Snapshot Error: {}", e),
+                None => eprintln!("Snapshot Error: trading loop did not answer, keeping the last one"),
+            }
+        }
+        std::process::exit(0);
+    });
+
+    let shared_book: SharedBook = Arc::new(RwLock::new(OrderBook::default()));
+    tokio::spawn(orderbook_feed(shared_book.clone(), shared_config.read().feed.book));
+
+    tokio::spawn(metrics::push_loop(shared_state.clone(), shared_config.clone()));
+    tokio::spawn(telegram::poll_loop(shared_state.clone(), shared_config.clone()));
+    tokio::spawn(heartbeat::run(shared_state.clone(), shared_config.clone()));
+    tokio::spawn(clock::monitor(shared_state.clone(), shared_config.clone()));
+    tokio::spawn(resources::monitor(shared_state.clone()));
+    tokio::spawn(currency::monitor(shared_state.clone(), shared_config.clone()));
+
+    // Init DB once (drops old session tables to fix schema); restarts of the trading task keep the data
+    let _ = DbManager::init();
+    tokio::spawn(config::watch(shared_config.clone(), shared_state.clone()));
+    let shared_sessions = SharedSessions::default();
+    #[cfg(unix)]
+    tokio::spawn(config::watch_sighup(shared_config.clone(), shared_state.clone(), shared_sessions.clone()));
+    if let Some(saved) = DbManager::load_risk_state() {
+        let risk = RiskState::restore(saved, calendar::trading_day(Utc::now()));
+        if let Some(reason) = &risk.halted {
+            add_log(&shared_state, format!("🛑 Still halted from a previous run: {} (re-arm to resume)", reason));
+        }
+        shared_state.write().risk = risk;
+    }
+    if let Some((slots, stop_price)) = DbManager::load_position() {
+        if let BotState::InPosition { entry_price, highest_price, quantity, .. } = slots.combined() {
+            add_log(&shared_state, format!(
+                "♻️ Recovered position: {:.5} {} from {}, peak {}, stop {} ({} slot(s))",
+                quantity, symbols::active().base(), currency::quote(money::float(entry_price)), currency::quote(highest_price),
+                currency::quote(stop_price.unwrap_or(0.0)), slots.open()
+            ));
+            publish_slots(&shared_state, slots, &shared_config.read().strategy.params);
+            shared_state.write().status = "HOLDING (recovered)".to_string();
+        }
+    }
+    restore_snapshot(&shared_state, &shared_config.read());
+    let client = reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap();
+    let clock_cfg = shared_config.read().clock.clone();
+    let checks = preflight::run(&client, &clock_cfg).await;
+    preflight::print_report(&checks);
+    let failed: Vec<&str> = checks.iter().filter(|c| !c.passed).map(|c| c.name).collect();
+    if failed.is_empty() {
+        add_log(&shared_state, format!("Preflight passed ({} checks)", checks.len()));
+    } else if simulating() {
+        add_log(&shared_state, format!("⚠️ Preflight failed: {} (continuing in simulation)", failed.join(", ")));
+    } else {
+        eprintln!("❌ Refusing to start live trading, preflight failed: {}", failed.join(", "));
+        std::process::exit(1);
+    }
+    if !simulating() {
+        let msg = match symbols::environment() {
+            Environment::Testnet => "Live trading started (testnet)",
+            Environment::Mainnet => "Live trading started",
+        };
+        Notifier::from_env().send(Event::new(EventKind::Alert, PAIR, 0.0, 0.0, 0.0, msg).critical());
+    }
+
+    // Dashboard pages are compressed off the async workers while the rest starts up
+    #[cfg(feature = "web")]
+    tokio::task::spawn_blocking(assets::init);
+
+    // DB writes from the trading loop go through the persistence thread from here on
+    persist::start(shared_config.read().persistence.clone());
+    ticks::start(shared_config.read().recorder.clone());
+    timeseries::start(shared_config.read().timeseries.clone());
+    fix::start(shared_config.read().fix.clone());
+    userstream::start(shared_config.read().userstream.clone(), shared_state.clone());
+    grpc::start(shared_config.read().grpc.clone(), shared_state.clone(), shared_config.clone());
+    tokio::spawn(backup::monitor(shared_state.clone(), shared_config.clone()));
+    cluster::start(shared_config.read().cluster.clone(), shared_state.clone());
+
+    // Accelerated simulation: trade through recent history on a virtual clock instead of live ticks
+    let sim_cfg = shared_config.read().simulation.clone();
+    let replay = match recorded {
+        Some((replay, warmup)) => {
+            seed_candles(warmup.iter());
+            add_log(&shared_state, format!("⏪ Replay of recorded data: {} candles from {} UTC at {}x", replay.candle_count(),
+                DateTime::from_timestamp_millis(replay.start_ms()).unwrap_or_default().format("%Y-%m-%d %H:%M"), sim_cfg.speed));
+            Some(replay)
+        },
+        None if SIMULATION_MODE && sim_cfg.speed > 1.0 => {
+            let candles = fetch_historical_candles(&client, sim_cfg.history_candles, Duration::from_secs(30)).await
+                .unwrap_or_else(|e| {
+                    eprintln!("❌ Could not fetch history for the accelerated simulation: {}", e);
+                    std::process::exit(1);
+                });
+            let Some((replay, warmup)) = CandleReplay::new(candles) else {
+                eprintln!("❌ Not enough history for the accelerated simulation (need more than {} candles)", replay::WARMUP_CANDLES);
+                std::process::exit(1);
+            };
+            seed_candles(warmup.iter());
+            add_log(&shared_state, format!("⏩ Accelerated simulation: {} candles at {}x", replay.candle_count(), sim_cfg.speed));
+            Some(replay)
+        },
+        None => None,
+    };
+    // A replay's trades are history, not the account's, so they stay out of the spreadsheet and off the
+    // bus, and its prices are not compared with today's reference
+    if replay.is_none() {
+        sheets::start(shared_config.read().sheets.clone());
+        bus::start(shared_config.read().bus.clone());
+        tokio::spawn(sanity::monitor(shared_config.clone()));
+        tokio::spawn(spread::monitor(shared_state.clone(), shared_config.clone()));
+    }
+
+    let runtime_cfg = shared_config.read().runtime.clone();
+    match replay {
+        Some(replay) => {
+            let clock = Arc::new(VirtualClock::new(replay.start_ms(), sim_cfg.speed));
+            runtime::spawn_trading(&runtime_cfg, supervise_bot(shared_state.clone(), shared_config.clone(), shared_book.clone(), clock, Some(Arc::new(replay))));
+        },
+        None => runtime::spawn_trading(&runtime_cfg, supervise_bot(shared_state.clone(), shared_config.clone(), shared_book.clone(), Arc::new(WallClock), None)),
+    }
+
+    #[cfg(feature = "web")]
+    web::run(shared_state, shared_config, shared_book, shared_sessions).await;
+    // Headless: the trading task and services run until Ctrl-C
+    #[cfg(not(feature = "web"))]
+    {
+        let _ = (shared_book, shared_sessions);
+        std::future::pending::<()>().await;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chaos::ChaosConfig;
+    use fills::Matching;
+
+    #[tokio::test]
+    async fn injected_order_error_leaves_the_bot_flat() {
+        chaos::configure(&ChaosConfig { enabled: true, error_rate: 1.0, timeout_rate: 0.0, partial_fill_rate: 0.0, out_of_order_rate: 0.0, ..ChaosConfig::default() });
+        let (requests, mut reports) = executor::spawn(Executor::new(SimFills::new(1, 0.0, 2.0, None, Matching::Instant), None));
+        let context = SignalContext { signal_price: dec!(60000), slot: Some(0), ..SignalContext::default() };
+        let request = OrderRequest { kind: OrderKind::Entry { reason: "TEST" }, side: "buy", price: dec!(60000), qty: dec!(0.001), timeout: Duration::from_secs(1), context };
+        requests.send(request).await.unwrap();
+        let report = reports.recv().await.unwrap();
+        chaos::configure(&ChaosConfig::default());
+
+        assert!(matches!(&report.result, Err(e) if e.starts_with("chaos:")), "{:?}", report.result);
+        let mut slots = Slots::default();
+        assert!(open_slot(&mut slots, &report.request, &report.result, 0).is_none());
+        assert_eq!(slots.open(), 0);
+        assert_eq!(slots.combined(), BotState::Idle);
+    }
+}
@@ -0,0 +1,143 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use chrono::{NaiveDate, Utc};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+// --- 🗂️ ROTATING FILE LOG ---
+// Optional copy of the bot log on disk, rotated by day or size with a fixed number of old files kept.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Rotation {
+    #[default]
+    Daily,
+    Size,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct LogConfig {
+    pub file: String,       // e.g. "logs/scalper.log", empty = disabled
+    pub rotation: Rotation,
+    pub max_size_mb: u64,   // Size rotation threshold
+    pub keep_files: usize,  // Rotated files to keep
+    pub json: bool,         // JSON lines instead of plain text
+}
+
+impl Default for LogConfig {
+    fn default() -> Self {
+        LogConfig { file: String::new(), rotation: Rotation::Daily, max_size_mb: 10, keep_files: 14, json: false }
+    }
+}
+
+struct RollingFile {
+    cfg: LogConfig,
+    path: PathBuf,
+    file: File,
+    size: u64,
+    day: NaiveDate,
+}
+
+static LOG: Mutex<Option<RollingFile>> = Mutex::new(None);
+
+fn open(path: &Path) -> std::io::Result<(File, u64)> {
+    if let Some(dir) = path.parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)?;
+    }
+    let file = OpenOptions::new().create(true).append(true).open(path)?;
+    let size = file.metadata()?.len();
+    Ok((file, size))
+}
+
+impl RollingFile {
+    fn needs_rotation(&self, incoming: u64) -> bool {
+        match self.cfg.rotation {
+            Rotation::Daily => Utc::now().date_naive() != self.day,
+            Rotation::Size => self.size > 0 && self.size + incoming > self.cfg.max_size_mb.max(1) * 1024 * 1024,
+        }
+    }
+
+    fn rotate(&mut self) -> std::io::Result<()> {
+        let stamp = match self.cfg.rotation {
+            Rotation::Daily => self.day.format("%Y-%m-%d").to_string(),
+            Rotation::Size => Utc::now().format("%Y-%m-%d_%H%M%S").to_string(),
+        };
+        let mut rotated = self.path.clone().into_os_string();
+        rotated.push(format!(".{}", stamp));
+        fs::rename(&self.path, &rotated)?;
+
+        let (file, size) = open(&self.path)?;
+        self.file = file;
+        self.size = size;
+        self.day = Utc::now().date_naive();
+        self.prune();
+        Ok(())
+    }
+
+    // Rotated names sort chronologically, so the oldest come first
+    fn prune(&self) {
+        let (Some(dir), Some(name)) = (self.path.parent(), self.path.file_name()) else { return };
+        let dir = if dir.as_os_str().is_empty() { Path::new(".") } else { dir };
+        let prefix = format!("{}.", name.to_string_lossy());
+        let Ok(entries) = fs::read_dir(dir) else { return };
+        let mut rotated: Vec<PathBuf> = entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.file_name().to_string_lossy().starts_with(&prefix))
+            .map(|e| e.path())
+            .collect();
+        rotated.sort();
+        let excess = rotated.len().saturating_sub(self.cfg.keep_files);
+        for old in rotated.into_iter().take(excess) {
+            let _ = fs::remove_file(old);
+        }
+    }
+
+    fn write(&mut self, level: &str, msg: &str) -> std::io::Result<()> {
+        let ts = Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        let line = if self.cfg.json {
+            serde_json::json!({ "ts": ts, "level": level, "msg": msg }).to_string()
+        } else {
+            format!("{} {:<5} {}", ts, level, msg)
+        };
+        if self.needs_rotation(line.len() as u64 + 1) {
+            self.rotate()?;
+        }
+        writeln!(self.file, "{}", line)?;
+        self.size += line.len() as u64 + 1;
+        Ok(())
+    }
+}
+
+/// (Re)configures the file log; called at startup and when the `[logging]` section is reloaded.
+pub fn init(cfg: &LogConfig) {
+    let mut log = LOG.lock();
+    if cfg.file.is_empty() {
+        *log = None;
+        return;
+    }
+    let path = PathBuf::from(&cfg.file);
+    match open(&path) {
+        Ok((file, size)) => {
+            // Resume the current file's day so a restart after midnight still rotates yesterday's lines
+            let day = file.metadata().ok()
+                .and_then(|m| m.modified().ok())
+                .map(|t| chrono::DateTime::<Utc>::from(t).date_naive())
+                .unwrap_or_else(|| Utc::now().date_naive());
+            *log = Some(RollingFile { cfg: cfg.clone(), path, file, size, day });
+        },
+        Err(e) => {
+            eprintln!("Log File Error ({}): {}", cfg.file, e);
+            *log = None;
+        },
+    }
+}
+
+pub fn write(level: &str, msg: &str) {
+    if let Some(log) = LOG.lock().as_mut() {
+        if let Err(e) = log.write(level, msg) {
+            eprintln!("Log File Error: {}", e);
+        }
+    }
+}
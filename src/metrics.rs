@@ -0,0 +1,155 @@
+use std::fmt::Write;
+use std::time::Duration;
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use tokio::net::UdpSocket;
+use tokio::time::sleep;
+
+use crate::config::SharedConfig;
+use crate::{money, BotState, DashboardData, SharedState, PAIR};
+
+// --- 📡 METRICS (Prometheus pull + StatsD / Influx push) ---
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MetricsSink {
+    #[default]
+    None,
+    Statsd,
+    InfluxUdp,
+    InfluxHttp,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct MetricsConfig {
+    pub sink: MetricsSink,
+    pub address: String, // UDP target for statsd / influx_udp
+    pub url: String,     // Influx HTTP write endpoint, e.g. http://host:8086/api/v2/write?org=o&bucket=b&precision=ns
+    pub token: String,   // Influx API token (optional)
+    pub prefix: String,
+    pub interval_secs: u64,
+}
+
+impl Default for MetricsConfig {
+    fn default() -> Self {
+        MetricsConfig {
+            sink: MetricsSink::None,
+            address: "127.0.0.1:8125".to_string(),
+            url: String::new(),
+            token: String::new(),
+            prefix: "scalper".to_string(),
+            interval_secs: 10,
+        }
+    }
+}
+
+/// Gauge samples shared by every exporter.
+pub fn collect(data: &DashboardData) -> Vec<(&'static str, f64)> {
+    let s = &data.session;
+    let db = crate::persist::stats();
+    vec![
+        ("price", data.price),
+        ("rsi", data.rsi),
+        ("bb_lower", data.bb_lower),
+        ("bb_upper", data.bb_upper),
+        ("unrealized_pl_pct", data.unrealized_pl),
+        ("realized_pl", money::float(data.realized_pl)),
+        ("gross_pl", money::float(data.gross_pl)),
+        ("wallet_usdt", money::float(data.wallet_usdt)),
+        ("wallet_btc", money::float(data.wallet_btc)),
+        ("in_position", if matches!(data.position, BotState::InPosition { .. }) { 1.0 } else { 0.0 }),
+        ("signals_total", s.signals as f64),
+        ("trades_total", s.trades as f64),
+        ("wins_total", s.wins as f64),
+        ("losses_total", s.losses as f64),
+        ("fees_paid", money::float(s.fees_paid)),
+        ("restarts_total", s.restarts as f64),
+        ("breaker_open", if data.breaker == "closed" { 0.0 } else { 1.0 }),
+        ("breaker_trips_total", s.breaker_trips as f64),
+        ("risk_halted", if data.risk.halted.is_some() { 1.0 } else { 0.0 }),
+        ("day_pnl", data.risk.day_pnl),
+        ("drawdown", data.risk.peak_equity - data.risk.equity),
+        ("clock_drift_ms", data.clock_drift_ms.unwrap_or(0) as f64),
+        ("feed_link_state", data.network.feed as u8 as f64),
+        ("private_api_link_state", data.network.private_api as u8 as f64),
+        ("rate_limit_hits_total", crate::ratelimit::hits() as f64),
+        ("process_cpu_pct", data.resources.cpu_pct),
+        ("process_rss_mb", data.resources.rss_mb),
+        ("process_open_fds", data.resources.open_fds as f64),
+        ("db_size_mb", data.resources.db_size_mb),
+        ("soc_temp_c", data.resources.soc_temp_c.unwrap_or(0.0)),
+        ("throttled", if data.resources.throttled { 1.0 } else { 0.0 }),
+        ("db_queue_depth", db.queue_depth as f64),
+        ("db_queue_full_total", db.full_waits as f64),
+        ("db_queue_wait_seconds_total", db.wait_secs),
+        ("db_batches_total", db.batches as f64),
+        ("db_rows_total", db.rows as f64),
+        ("db_last_commit_seconds", db.commit_secs),
+        ("uptime_seconds", ((Utc::now().timestamp_millis() - s.started_at) / 1000) as f64),
+    ]
+}
+
+pub fn render_prometheus(prefix: &str, samples: &[(&'static str, f64)]) -> String {
+    let mut out = String::new();
+    for (name, value) in samples {
+        let _ = writeln!(out, "# TYPE {}_{} gauge", prefix, name);
+        let _ = writeln!(out, "{}_{}{{pair=\"{}\"}} {}", prefix, name, PAIR, value);
+    }
+    out
+}
+
+fn render_statsd(prefix: &str, samples: &[(&'static str, f64)]) -> String {
+    samples.iter().map(|(name, value)| format!("{}.{}:{}|g", prefix, name, value)).collect::<Vec<_>>().join("\n")
+}
+
+fn render_influx(prefix: &str, samples: &[(&'static str, f64)]) -> String {
+    let fields = samples.iter().map(|(name, value)| format!("{}={}", name, value)).collect::<Vec<_>>().join(",");
+    let ts = Utc::now().timestamp_nanos_opt().unwrap_or_default();
+    format!("{},pair={} {} {}", prefix, PAIR, fields, ts)
+}
+
+// Config is re-read every cycle so sink changes apply without a restart
+pub async fn push_loop(state: SharedState, config: SharedConfig) {
+    let client = reqwest::Client::builder().timeout(Duration::from_secs(5)).build().unwrap();
+    let mut socket: Option<UdpSocket> = None;
+
+    loop {
+        let cfg = config.read().metrics.clone();
+        if cfg.sink != MetricsSink::None {
+            let samples = collect(&state.read());
+            let result = match cfg.sink {
+                MetricsSink::Statsd | MetricsSink::InfluxUdp => {
+                    let payload = if cfg.sink == MetricsSink::Statsd {
+                        render_statsd(&cfg.prefix, &samples)
+                    } else {
+                        render_influx(&cfg.prefix, &samples)
+                    };
+                    if socket.is_none() {
+                        socket = UdpSocket::bind("0.0.0.0:0").await.ok();
+                    }
+                    match &socket {
+                        Some(sock) => sock.send_to(payload.as_bytes(), &cfg.address).await.map(|_| ()).map_err(|e| e.to_string()),
+                        None => Err("could not bind UDP socket".to_string()),
+                    }
+                },
+                MetricsSink::InfluxHttp => {
+                    let mut req = client.post(&cfg.url).body(render_influx(&cfg.prefix, &samples));
+                    if !cfg.token.is_empty() {
+                        req = req.header("Authorization", format!("Token {}", cfg.token));
+                    }
+                    match req.send().await {
+                        Ok(r) if r.status().is_success() => Ok(()),
+                        Ok(r) => Err(format!("HTTP {}", r.status())),
+                        Err(e) => Err(e.to_string()),
+                    }
+                },
+                MetricsSink::None => Ok(()),
+            };
+            if let Err(e) = result {
+                eprintln!("Metrics Push Error ({:?}): {}", cfg.sink, e);
+            }
+        }
+        sleep(Duration::from_secs(cfg.interval_secs.max(1))).await;
+    }
+}
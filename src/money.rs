@@ -0,0 +1,59 @@
+use std::str::FromStr;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal::Decimal;
+
+// --- 💵 MONEY ---
+// Prices, quantities, balances, fees and P&L are Decimal from the order path down to the trades
+// table, so realized P&L doesn't drift over thousands of fills and order sizes land exactly on the
+// venue's steps. Indicators, the trailing stop, risk metrics and reporting (charts, metrics,
+// notifications) stay f64; these are the crossings between the two.
+
+/// A price or amount coming from the f64 side (a tick, an indicator, a config value). NaN and
+/// infinity become 0.
+pub fn dec(value: f64) -> Decimal {
+    Decimal::from_f64(value).unwrap_or_default()
+}
+
+/// A Decimal going out to the f64 side.
+pub fn float(value: Decimal) -> f64 {
+    value.to_f64().unwrap_or(0.0)
+}
+
+/// An amount as exchanges send it: "123.45", "1e-8", or a JSON number's text.
+pub fn parse(text: &str) -> Option<Decimal> {
+    let text = text.trim();
+    Decimal::from_str(text).ok().or_else(|| Decimal::from_scientific(text).ok())
+}
+
+/// An amount in an exchange's JSON, sent as a string or a number; 0 when missing.
+pub fn from_value(value: &serde_json::Value) -> Decimal {
+    match value {
+        serde_json::Value::String(text) => parse(text),
+        serde_json::Value::Number(n) => parse(&n.to_string()),
+        _ => None,
+    }.unwrap_or_default()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn parses_plain_and_scientific_text() {
+        assert_eq!(parse("123.45"), Some(Decimal::new(12345, 2)));
+        assert_eq!(parse(" 0.00012 "), Some(Decimal::new(12, 5)));
+        assert_eq!(parse("1e-8"), Some(Decimal::new(1, 8)));
+        assert_eq!(parse("2.5E-3"), Some(Decimal::new(25, 4)));
+        assert_eq!(parse("n/a"), None);
+    }
+
+    #[test]
+    fn reads_json_strings_and_numbers_alike() {
+        assert_eq!(from_value(&json!("0.1")), Decimal::new(1, 1));
+        assert_eq!(from_value(&json!(0.1)), Decimal::new(1, 1));
+        assert_eq!(from_value(&json!(42)), Decimal::from(42));
+        assert_eq!(from_value(&json!(1e-8)), Decimal::new(1, 8));
+        assert_eq!(from_value(&json!(null)), Decimal::ZERO);
+    }
+}
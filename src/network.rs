@@ -0,0 +1,92 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+use serde::{Deserialize, Serialize};
+
+// --- 📡 NETWORK LINK STATES (market feed + private API, tracked separately) ---
+// healthy: the last call succeeded
+// degraded: calls are failing and being retried, data is stale
+// disconnected: failing for longer than `disconnected_after_secs`
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LinkState {
+    #[default]
+    Healthy,
+    Degraded,
+    Disconnected,
+}
+
+impl fmt::Display for LinkState {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(match self {
+            LinkState::Healthy => "healthy",
+            LinkState::Degraded => "degraded",
+            LinkState::Disconnected => "disconnected",
+        })
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutageAction {
+    #[default]
+    Hold,    // Keep the position and resume exit checks when prices come back
+    Flatten, // Sell at the last known price while the private API still works
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NetworkConfig {
+    pub disconnected_after_secs: u64,
+    pub on_feed_outage: OutageAction, // Applied to an open position once the feed is disconnected
+}
+
+impl Default for NetworkConfig {
+    fn default() -> Self {
+        NetworkConfig { disconnected_after_secs: 60, on_feed_outage: OutageAction::Hold }
+    }
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize)]
+pub struct NetworkStatus {
+    pub feed: LinkState,
+    pub private_api: LinkState,
+}
+
+pub struct Link {
+    pub name: &'static str,
+    failing_since: Option<Instant>,
+    pub last_error: String,
+    reported: LinkState,
+}
+
+impl Link {
+    pub fn new(name: &'static str) -> Self {
+        Link { name, failing_since: None, last_error: String::new(), reported: LinkState::Healthy }
+    }
+
+    pub fn record<T, E: fmt::Display>(&mut self, result: &Result<T, E>) {
+        match result {
+            Ok(_) => self.failing_since = None,
+            Err(e) => {
+                self.failing_since.get_or_insert_with(Instant::now);
+                self.last_error = e.to_string();
+            },
+        }
+    }
+
+    // Time-based, so a link keeps degrading even while no calls are being made (e.g. breaker open)
+    pub fn state(&self, cfg: &NetworkConfig) -> LinkState {
+        match self.failing_since {
+            None => LinkState::Healthy,
+            Some(since) if since.elapsed() >= Duration::from_secs(cfg.disconnected_after_secs) => LinkState::Disconnected,
+            Some(_) => LinkState::Degraded,
+        }
+    }
+
+    /// (from, to) if the state changed since the last call.
+    pub fn transition(&mut self, cfg: &NetworkConfig) -> Option<(LinkState, LinkState)> {
+        let now = self.state(cfg);
+        (now != self.reported).then(|| (std::mem::replace(&mut self.reported, now), now))
+    }
+}
@@ -0,0 +1,318 @@
+use std::env;
+use std::time::Duration;
+use chrono::Utc;
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+
+use crate::{currency, symbols};
+
+// --- 🔔 OUTBOUND NOTIFICATIONS ---
+// Every event goes to the generic webhooks (WEBHOOK_URLS, raw JSON) and to each chat channel
+// configured in config.toml, formatted for that service. Channels are re-read on config reload.
+// Critical events (live start, kill switch, reconciliation mismatch, repeated order rejections)
+// are also emailed, since chat apps get muted.
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EventKind {
+    Trade,
+    StopOut,
+    Error,
+    Alert,
+    DailySummary,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Severity {
+    Info,
+    Warning,
+    Critical,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct Event {
+    pub event: EventKind,
+    pub pair: String,
+    pub price: f64,
+    pub qty: f64,
+    pub pnl: f64,
+    pub reason: String,
+    pub timestamp: String,
+    pub severity: Severity,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub side: Option<&'static str>, // Trades and stop-outs: "buy" or "sell"
+}
+
+impl Event {
+    pub fn new(event: EventKind, pair: &str, price: f64, qty: f64, pnl: f64, reason: &str) -> Self {
+        Event {
+            event,
+            pair: pair.to_string(),
+            price,
+            qty,
+            pnl,
+            reason: reason.to_string(),
+            timestamp: Utc::now().to_rfc3339(),
+            severity: match event {
+                EventKind::Trade | EventKind::DailySummary => Severity::Info,
+                EventKind::StopOut | EventKind::Error | EventKind::Alert => Severity::Warning,
+            },
+            side: None,
+        }
+    }
+
+    pub fn side(mut self, side: &'static str) -> Self {
+        self.side = Some(side);
+        self
+    }
+
+    /// Also sent by email.
+    pub fn critical(mut self) -> Self {
+        self.severity = Severity::Critical;
+        self
+    }
+}
+
+const ALL_EVENTS: [EventKind; 5] = [EventKind::Trade, EventKind::StopOut, EventKind::Error, EventKind::Alert, EventKind::DailySummary];
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct DiscordConfig {
+    pub webhook_url: String,    // Empty = off
+    pub events: Vec<EventKind>, // Which events are posted
+}
+
+impl Default for DiscordConfig {
+    fn default() -> Self {
+        DiscordConfig { webhook_url: String::new(), events: ALL_EVENTS.to_vec() }
+    }
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct EmailConfig {
+    pub smtp_host: String, // Empty = off
+    pub smtp_port: u16,    // 465 = implicit TLS, otherwise STARTTLS
+    pub username: String,  // Password from the SMTP_PASSWORD env var
+    pub from: String,
+    pub to: Vec<String>,
+}
+
+const ALL_SEVERITIES: [Severity; 3] = [Severity::Info, Severity::Warning, Severity::Critical];
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlackConfig {
+    pub webhook_url: String,       // Incoming webhook, empty = off
+    pub severities: Vec<Severity>, // Which severities are posted
+}
+
+impl Default for SlackConfig {
+    fn default() -> Self {
+        SlackConfig { webhook_url: String::new(), severities: ALL_SEVERITIES.to_vec() }
+    }
+}
+
+impl Default for EmailConfig {
+    fn default() -> Self {
+        EmailConfig { smtp_host: String::new(), smtp_port: 587, username: String::new(), from: String::new(), to: vec![] }
+    }
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct NotifyConfig {
+    pub discord: DiscordConfig,
+    pub slack: SlackConfig,
+    pub email: EmailConfig,
+}
+
+static CHANNELS: RwLock<Option<NotifyConfig>> = RwLock::new(None);
+
+pub fn configure(cfg: &NotifyConfig) {
+    *CHANNELS.write() = Some(cfg.clone());
+}
+
+const BLUE: u32 = 0x2196F3;
+const GREEN: u32 = 0x4CAF50;
+const ORANGE: u32 = 0xFF9800;
+const RED: u32 = 0xF44336;
+
+// In the quote currency, with the reporting currency alongside when it is another one
+fn signed_pnl(v: f64) -> String {
+    let quote = currency::signed(v, symbols::active().quote());
+    match currency::report(v) {
+        Some((converted, code)) => format!("{} ({})", quote, currency::signed(converted, &code)),
+        None => quote,
+    }
+}
+
+/// Title and color shared by the chat channels: buys in blue, closed trades and summaries green or red by P&L.
+fn headline(event: &Event) -> (String, u32) {
+    match (event.event, event.side) {
+        (EventKind::Trade, Some("buy")) => (format!("🟢 BUY {}", event.pair), BLUE),
+        (EventKind::Trade, _) => (format!("🔴 SELL {}", event.pair), if event.pnl >= 0.0 { GREEN } else { RED }),
+        (EventKind::StopOut, _) => (format!("🛑 STOP OUT {}", event.pair), RED),
+        (EventKind::Error, _) => (format!("❌ Error ({})", event.pair), RED),
+        (EventKind::Alert, _) => (format!("🔔 Alert ({})", event.pair), ORANGE),
+        (EventKind::DailySummary, _) => (format!("📅 Daily Summary {}", event.pair), if event.pnl >= 0.0 { GREEN } else { RED }),
+    }
+}
+
+/// (name, value) pairs worth showing; P&L only where a trade was closed or a day summed up.
+fn facts(event: &Event) -> Vec<(&'static str, String)> {
+    let mut facts = Vec::new();
+    if event.price > 0.0 { facts.push(("Price", currency::quote(event.price))); }
+    if event.qty > 0.0 { facts.push(("Qty", format!("{:.6}", event.qty))); }
+    if event.side == Some("sell") || matches!(event.event, EventKind::StopOut | EventKind::DailySummary) {
+        facts.push(("P&L", signed_pnl(event.pnl)));
+    }
+    facts
+}
+
+fn discord_payload(event: &Event) -> serde_json::Value {
+    let (title, color) = headline(event);
+    let fields: Vec<_> = facts(event).into_iter()
+        .map(|(name, value)| serde_json::json!({ "name": name, "value": value, "inline": true }))
+        .collect();
+    serde_json::json!({ "embeds": [{
+        "title": title,
+        "description": event.reason,
+        "color": color,
+        "fields": fields,
+        "timestamp": event.timestamp,
+    }] })
+}
+
+/// Block Kit message inside a colored attachment; `text` is what notifications and old clients show.
+fn slack_payload(event: &Event) -> serde_json::Value {
+    let (title, color) = headline(event);
+    let mut blocks = vec![serde_json::json!({
+        "type": "section",
+        "text": { "type": "mrkdwn", "text": format!("*{}*\n{}", title, event.reason) },
+    })];
+    let fields: Vec<_> = facts(event).into_iter()
+        .map(|(name, value)| serde_json::json!({ "type": "mrkdwn", "text": format!("*{}*\n{}", name, value) }))
+        .collect();
+    if !fields.is_empty() {
+        blocks.push(serde_json::json!({ "type": "section", "fields": fields }));
+    }
+    serde_json::json!({
+        "text": format!("{}: {}", title, event.reason),
+        "attachments": [{ "color": format!("#{:06X}", color), "blocks": blocks }],
+    })
+}
+
+/// (url, body) for every configured channel that wants this event.
+fn channel_posts(event: &Event) -> Vec<(String, serde_json::Value)> {
+    let channels = CHANNELS.read();
+    let Some(cfg) = channels.as_ref() else { return vec![] };
+    let mut posts = Vec::new();
+    if !cfg.discord.webhook_url.is_empty() && cfg.discord.events.contains(&event.event) {
+        posts.push((cfg.discord.webhook_url.clone(), discord_payload(event)));
+    }
+    if !cfg.slack.webhook_url.is_empty() && cfg.slack.severities.contains(&event.severity) {
+        posts.push((cfg.slack.webhook_url.clone(), slack_payload(event)));
+    }
+    posts
+}
+
+fn email_config() -> Option<EmailConfig> {
+    let channels = CHANNELS.read();
+    let email = &channels.as_ref()?.email;
+    (!email.smtp_host.is_empty() && !email.to.is_empty()).then(|| email.clone())
+}
+
+async fn send_email(cfg: EmailConfig, event: Event) -> Result<(), String> {
+    let address = |a: &str| a.parse::<Mailbox>().map_err(|e| format!("{}: {}", a, e));
+    let mut message = Message::builder()
+        .from(address(&cfg.from)?)
+        .subject(format!("🚨 {}: {}", event.pair, event.reason));
+    for to in &cfg.to {
+        message = message.to(address(to)?);
+    }
+    let body = format!("{}\n\nPair: {}\nPrice: {}\nP&L: {}\nTime: {}\n", event.reason, event.pair, currency::quote(event.price), signed_pnl(event.pnl), event.timestamp);
+    let message = message.body(body).map_err(|e| e.to_string())?;
+
+    let transport = if cfg.smtp_port == 465 {
+        AsyncSmtpTransport::<Tokio1Executor>::relay(&cfg.smtp_host)
+    } else {
+        AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(&cfg.smtp_host)
+    };
+    let mut transport = transport.map_err(|e| e.to_string())?.port(cfg.smtp_port).timeout(Some(Duration::from_secs(30)));
+    if let (false, Ok(password)) = (cfg.username.is_empty(), env::var("SMTP_PASSWORD")) {
+        transport = transport.credentials(Credentials::new(cfg.username, password));
+    }
+    transport.build().send(message).await.map(|_| ()).map_err(|e| e.to_string())
+}
+
+/// Fire-and-forget webhook sink. URLs come from `WEBHOOK_URLS` (comma separated).
+#[derive(Clone)]
+pub struct Notifier {
+    client: reqwest::Client,
+    urls: Vec<String>,
+}
+
+impl Notifier {
+    pub fn from_env() -> Self {
+        let urls = env::var("WEBHOOK_URLS")
+            .unwrap_or_default()
+            .split(',')
+            .map(|u| u.trim().to_string())
+            .filter(|u| !u.is_empty())
+            .collect();
+        let client = reqwest::Client::builder().timeout(Duration::from_secs(5)).build().unwrap();
+        Notifier { client, urls }
+    }
+
+    /// Synchronous delivery for code without a runtime (the panic hook). Must not be called on a runtime thread.
+    pub fn send_blocking(&self, event: &Event) {
+        let Ok(client) = reqwest::blocking::Client::builder().timeout(Duration::from_secs(5)).build() else { return };
+        for url in &self.urls {
+            if let Err(e) = client.post(url).json(event).send() {
+                eprintln!("Webhook Error ({}): {}", url, e);
+            }
+        }
+        for (url, body) in channel_posts(event) {
+            if let Err(e) = client.post(&url).json(&body).send() {
+                eprintln!("Notification Error: {}", e.without_url());
+            }
+        }
+    }
+
+    // Each POST runs on its own task so a slow endpoint never blocks the trading loop
+    pub fn send(&self, event: Event) {
+        for url in &self.urls {
+            let client = self.client.clone();
+            let url = url.clone();
+            let event = event.clone();
+            tokio::spawn(async move {
+                if let Err(e) = client.post(&url).json(&event).send().await {
+                    eprintln!("Webhook Error ({}): {}", url, e);
+                }
+            });
+        }
+        // Chat webhook URLs carry their token, so they stay out of the logs
+        for (url, body) in channel_posts(&event) {
+            let client = self.client.clone();
+            tokio::spawn(async move {
+                match client.post(&url).json(&body).send().await {
+                    Ok(r) if !r.status().is_success() => eprintln!("Notification Error: HTTP {}", r.status()),
+                    Ok(_) => {},
+                    Err(e) => eprintln!("Notification Error: {}", e.without_url()),
+                }
+            });
+        }
+        if let (Severity::Critical, Some(cfg)) = (event.severity, email_config()) {
+            tokio::spawn(async move {
+                if let Err(e) = send_email(cfg, event).await {
+                    eprintln!("Email Error: {}", e);
+                }
+            });
+        }
+    }
+}
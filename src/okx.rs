@@ -0,0 +1,419 @@
+use std::sync::OnceLock;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use base64::engine::general_purpose::STANDARD;
+use base64::Engine;
+use chrono::{SecondsFormat, Utc};
+use futures_util::{SinkExt, StreamExt};
+use hmac::{Hmac, Mac};
+use reqwest::header::{HeaderMap, HeaderValue, CONTENT_TYPE};
+use reqwest::Method;
+use rust_decimal::Decimal;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use tokio::sync::mpsc;
+use tokio::time::{sleep, timeout_at, Instant};
+use tokio_tungstenite::connect_async;
+use tokio_tungstenite::tungstenite::Message;
+
+use crate::executor::Fill;
+use crate::feed::{self, Flow, StreamTick};
+use crate::keystore::{self, Credentials};
+use crate::orderbook::DepthResponse;
+use crate::reconcile::Execution;
+use crate::risk::WalletBalance;
+use crate::symbols::{self, Environment};
+use crate::userstream::{self, AccountEvent};
+use crate::{money, ratelimit, Candle};
+
+// --- ⚫ OKX (SPOT) ---
+// Runs the bot on an OKX spot pair instead of CoinDCX: candles, instrument info and the order book
+// over the v5 REST API, trades over the public WebSocket, and balances and orders through the
+// signed API. OKX signs with three parts: the key, the secret (base64 HMAC of timestamp + method +
+// path + body) and the passphrase chosen when the key was created, sent on every private call.
+// Orders are IOC limits in cash mode and their fill is read back from the order. Credentials come
+// from OKX_API_KEY / OKX_API_SECRET / OKX_API_PASSPHRASE, or the keystore. With environment =
+// "testnet" it trades in OKX's demo trading instead: the same REST host with the simulated-trading
+// header, the demo WebSocket, and demo keys from OKX_TESTNET_API_KEY / _SECRET / _PASSPHRASE.
+// Read at startup.
+
+const REST_URL: &str = "https://www.okx.com";
+const STREAM_URL: &str = "wss://ws.okx.com:8443/ws/v5/public";
+pub const WARM_URL: &str = "https://www.okx.com/api/v5/public/time";
+const DEMO_STREAM_URL: &str = "wss://wspap.okx.com:8443/ws/v5/public";
+const PRIVATE_URL: &str = "wss://ws.okx.com:8443/ws/v5/private";
+const DEMO_PRIVATE_URL: &str = "wss://wspap.okx.com:8443/ws/v5/private";
+const PING_EVERY: Duration = Duration::from_secs(20); // The server drops a connection silent for 30s
+const ORDER_POLL: Duration = Duration::from_millis(200);
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OkxConfig {
+    pub enabled: bool,
+    pub environment: Environment, // mainnet, or testnet (demo trading, demo keys)
+}
+
+static CONFIG: OnceLock<OkxConfig> = OnceLock::new();
+
+/// Selects OKX for the rest of the process when enabled; called before anything talks to an exchange.
+pub fn configure(cfg: OkxConfig) {
+    if !cfg.enabled { return; }
+    if cfg.environment == Environment::Testnet { println!("🧪 OKX demo trading"); }
+    let _ = CONFIG.set(cfg);
+}
+
+pub fn enabled() -> bool {
+    CONFIG.get().is_some()
+}
+
+pub fn environment() -> Environment {
+    CONFIG.get().map_or(Environment::Mainnet, |cfg| cfg.environment)
+}
+
+fn demo() -> bool {
+    environment() == Environment::Testnet
+}
+
+// Every request in demo trading carries this header, public ones included (demo has its own book)
+fn demo_headers() -> HeaderMap {
+    let mut headers = HeaderMap::new();
+    if demo() { headers.insert("x-simulated-trading", HeaderValue::from_static("1")); }
+    headers
+}
+
+fn inst_id() -> &'static str {
+    symbols::active().order_code
+}
+
+fn credentials() -> Credentials {
+    keystore::credentials().unwrap_or_else(|| match demo() {
+        true => Credentials::from_env("OKX_TESTNET_API_KEY", "OKX_TESTNET_API_SECRET", Some("OKX_TESTNET_API_PASSPHRASE")),
+        false => Credentials::from_env("OKX_API_KEY", "OKX_API_SECRET", Some("OKX_API_PASSPHRASE")),
+    })
+}
+
+// `path` includes the query string; the signature covers it exactly as sent
+fn signed_headers(method: &Method, path: &str, body: &str) -> HeaderMap {
+    let creds = credentials();
+    let timestamp = Utc::now().to_rfc3339_opts(SecondsFormat::Millis, true);
+    let mut mac = Hmac::<Sha256>::new_from_slice(creds.secret.as_bytes()).expect("Invalid Key");
+    mac.update(format!("{}{}{}{}", timestamp, method, path, body).as_bytes());
+    let header = |v: &str| HeaderValue::from_str(v).unwrap_or(HeaderValue::from_static(""));
+    let mut headers = demo_headers();
+    headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+    headers.insert("OK-ACCESS-KEY", header(&creds.key));
+    headers.insert("OK-ACCESS-SIGN", header(&STANDARD.encode(mac.finalize().into_bytes())));
+    headers.insert("OK-ACCESS-TIMESTAMP", header(&timestamp));
+    headers.insert("OK-ACCESS-PASSPHRASE", header(creds.passphrase.as_deref().unwrap_or("")));
+    headers
+}
+
+// Every v5 response: code "0" or an error with msg, even on HTTP 200. Order calls put the
+// per-order error (sCode / sMsg) in data.
+#[derive(Deserialize)]
+struct Reply<T> {
+    code: String,
+    #[serde(default)]
+    msg: String,
+    data: Option<T>,
+}
+
+fn unwrap_reply(reply: Reply<Value>) -> Result<Value, String> {
+    let data = reply.data.unwrap_or_default();
+    let item = &data[0];
+    if reply.code != "0" || item["sCode"].as_str().is_some_and(|c| c != "0") {
+        let (code, msg) = match item["sCode"].as_str() {
+            Some(code) if code != "0" => (code.to_string(), item["sMsg"].as_str().unwrap_or("").to_string()),
+            _ => (reply.code, reply.msg),
+        };
+        return Err(format!("OKX {}: {}", code, msg));
+    }
+    Ok(data)
+}
+
+async fn decode<T: DeserializeOwned>(res: reqwest::Response) -> Result<T, String> {
+    // Only the traded venue's budget is tracked (the spread monitor polls the others)
+    if enabled() { ratelimit::observe(res.status(), res.headers()); }
+    let status = res.status();
+    let reply: Reply<Value> = res.json().await.map_err(|e| format!("HTTP {}: {}", status, e))?;
+    serde_json::from_value(unwrap_reply(reply)?).map_err(|e| e.to_string())
+}
+
+async fn public<T: DeserializeOwned>(client: &reqwest::Client, path: &str, query: &[(&str, &str)], timeout: Duration) -> Result<T, String> {
+    let res = client.get(format!("{}{}", REST_URL, path)).query(query).headers(demo_headers()).timeout(timeout).send().await.map_err(|e| e.to_string())?;
+    decode(res).await
+}
+
+async fn signed_get<T: DeserializeOwned>(client: &reqwest::Client, path: &str, query: &[(&str, &str)], timeout: Duration) -> Result<T, String> {
+    let path = if query.is_empty() { path.to_string() } else { format!("{}?{}", path, query_string(query)) };
+    let res = client.get(format!("{}{}", REST_URL, path)).headers(signed_headers(&Method::GET, &path, "")).timeout(timeout).send().await
+        .map_err(|e| e.to_string())?;
+    decode(res).await
+}
+
+async fn signed_post<T: DeserializeOwned>(client: &reqwest::Client, path: &str, body: Value, timeout: Duration) -> Result<T, String> {
+    let body = body.to_string();
+    let res = client.post(format!("{}{}", REST_URL, path)).headers(signed_headers(&Method::POST, path, &body)).body(body).timeout(timeout).send().await
+        .map_err(|e| e.to_string())?;
+    decode(res).await
+}
+
+fn query_string(query: &[(&str, &str)]) -> String {
+    let mut url = reqwest::Url::parse(REST_URL).unwrap();
+    url.query_pairs_mut().extend_pairs(query);
+    url.query().unwrap_or("").to_string()
+}
+
+fn num(v: &Value) -> f64 {
+    v.as_str().and_then(|s| s.parse().ok()).or_else(|| v.as_f64()).unwrap_or(0.0)
+}
+
+/// 1m candles, newest first like CoinDCX's (OKX returns at most 300 per call).
+pub async fn candles(client: &reqwest::Client, limit: u32, timeout: Duration) -> Result<Vec<Candle>, String> {
+    let limit = limit.min(300).to_string();
+    let rows: Vec<Value> = public(client, "/api/v5/market/candles", &[("instId", inst_id()), ("bar", "1m"), ("limit", &limit)], timeout).await?;
+    // [ts, open, high, low, close, vol, volCcy, volCcyQuote, confirm]
+    Ok(rows.iter().map(|k| Candle { time: num(&k[0]) as i64, open: num(&k[1]), high: num(&k[2]), low: num(&k[3]), close: num(&k[4]) }).collect())
+}
+
+pub async fn latest_price(client: &reqwest::Client, timeout: Duration) -> Result<Option<f64>, String> {
+    last_price(client, inst_id(), timeout).await
+}
+
+/// Last traded price of any instrument.
+pub async fn last_price(client: &reqwest::Client, inst_id: &str, timeout: Duration) -> Result<Option<f64>, String> {
+    let tickers: Vec<Value> = public(client, "/api/v5/market/ticker", &[("instId", inst_id)], timeout).await?;
+    Ok(tickers.first().map(|t| num(&t["last"])).filter(|p| *p > 0.0))
+}
+
+/// Order book snapshot in CoinDCX's shape, so the local book is built the same way.
+pub async fn depth(client: &reqwest::Client) -> Result<DepthResponse, String> {
+    let books: Vec<Value> = public(client, "/api/v5/market/books", &[("instId", inst_id()), ("sz", "50")], Duration::from_secs(5)).await?;
+    let book = books.first().ok_or("empty order book")?;
+    // [price, size, deprecated, order count]
+    let levels = |side: &Value| side.as_array().into_iter().flatten()
+        .filter_map(|l| Some((l[0].as_str()?.to_string(), l[1].as_str()?.to_string())))
+        .collect();
+    Ok(DepthResponse { bids: levels(&book["bids"]), asks: levels(&book["asks"]), vs: None })
+}
+
+/// Public trades into the trading loop, like the CoinDCX stream. Returns Ok once the receiver is gone.
+pub async fn stream_trades(tx: &mpsc::Sender<StreamTick>) -> Result<(), String> {
+    let (ws, _) = connect_async(if demo() { DEMO_STREAM_URL } else { STREAM_URL }).await.map_err(|e| e.to_string())?;
+    let (mut write, mut read) = ws.split();
+    let inst_id = inst_id();
+    let subscribe = json!({ "op": "subscribe", "args": [{ "channel": "trades", "instId": inst_id }] });
+    write.send(Message::Text(subscribe.to_string())).await.map_err(|e| e.to_string())?;
+    let mut next_ping = Instant::now() + PING_EVERY;
+    loop {
+        let msg = match timeout_at(next_ping, read.next()).await {
+            Err(_) => {
+                write.send(Message::Text("ping".to_string())).await.map_err(|e| e.to_string())?;
+                next_ping = Instant::now() + PING_EVERY;
+                continue;
+            },
+            Ok(None) => return Err("connection closed".to_string()),
+            Ok(Some(msg)) => msg.map_err(|e| e.to_string())?,
+        };
+        let text = match msg {
+            Message::Text(text) => text,
+            Message::Close(_) => return Err("closed by server".to_string()),
+            _ => continue,
+        };
+        if text == "pong" { continue; }
+        let event: Value = serde_json::from_str(&text).unwrap_or_default();
+        if event["event"] == "error" {
+            return Err(format!("subscribe to trades {} refused: {} {}", inst_id, event["code"], event["msg"]));
+        }
+        if event["arg"]["channel"] != "trades" { continue; }
+        for trade in event["data"].as_array().into_iter().flatten() {
+            let price = num(&trade["px"]);
+            if price <= 0.0 { continue; }
+            if let Flow::Stop = feed::deliver(tx, price) { return Ok(()); }
+        }
+    }
+}
+
+/// The account's private stream: logs in (the REST signature of GET /users/self/verify at a
+/// timestamp in seconds), then the pair's order updates and the account balances go to
+/// `on_event`. Returns Err when it drops.
+pub async fn stream_account(on_event: &mut impl FnMut(AccountEvent)) -> Result<(), String> {
+    let (ws, _) = connect_async(if demo() { DEMO_PRIVATE_URL } else { PRIVATE_URL }).await.map_err(|e| e.to_string())?;
+    let (mut write, mut read) = ws.split();
+    let creds = credentials();
+    let timestamp = Utc::now().timestamp().to_string();
+    let mut mac = Hmac::<Sha256>::new_from_slice(creds.secret.as_bytes()).expect("Invalid Key");
+    mac.update(format!("{}GET/users/self/verify", timestamp).as_bytes());
+    let login = json!({ "op": "login", "args": [{
+        "apiKey": creds.key, "passphrase": creds.passphrase.as_deref().unwrap_or(""), "timestamp": timestamp,
+        "sign": STANDARD.encode(mac.finalize().into_bytes()),
+    }] });
+    write.send(Message::Text(login.to_string())).await.map_err(|e| e.to_string())?;
+    let market = symbols::active();
+    let mut next_ping = Instant::now() + PING_EVERY;
+    loop {
+        let msg = match timeout_at(next_ping, read.next()).await {
+            Err(_) => {
+                write.send(Message::Text("ping".to_string())).await.map_err(|e| e.to_string())?;
+                next_ping = Instant::now() + PING_EVERY;
+                continue;
+            },
+            Ok(None) => return Err("connection closed".to_string()),
+            Ok(Some(msg)) => msg.map_err(|e| e.to_string())?,
+        };
+        let text = match msg {
+            Message::Text(text) => text,
+            Message::Close(_) => return Err("closed by server".to_string()),
+            _ => continue,
+        };
+        if text == "pong" { continue; }
+        let event: Value = serde_json::from_str(&text).unwrap_or_default();
+        match event["event"].as_str() {
+            Some("error") => return Err(format!("login or subscribe refused: {} {}", event["code"], event["msg"])),
+            Some("login") => {
+                let subscribe = json!({ "op": "subscribe", "args": [
+                    { "channel": "orders", "instType": "SPOT", "instId": market.order_code },
+                    { "channel": "account" },
+                ] });
+                write.send(Message::Text(subscribe.to_string())).await.map_err(|e| e.to_string())?;
+            },
+            Some("subscribe") if event["arg"]["channel"] == "orders" => on_event(AccountEvent::Ready),
+            Some(_) => {},
+            None => match event["arg"]["channel"].as_str() {
+                Some("orders") => for order in event["data"].as_array().into_iter().flatten() {
+                    let (Some(cl_ord_id), Some(outcome)) = (order["clOrdId"].as_str(), outcome(order)) else { continue };
+                    on_event(AccountEvent::Order { client_id: cl_ord_id.to_string(), outcome });
+                },
+                Some("account") => {
+                    let details: Vec<&Value> = event["data"].as_array().into_iter().flatten()
+                        .flat_map(|a| a["details"].as_array().into_iter().flatten()).collect();
+                    let balance = |ccy: &str| details.iter().find(|d| d["ccy"] == ccy).map(|d| money::from_value(&d["availBal"]));
+                    let (quote, base) = (balance(market.quote()), balance(market.base()));
+                    if quote.is_some() || base.is_some() { on_event(AccountEvent::Balances { quote, base }); }
+                },
+                _ => {},
+            },
+        }
+    }
+}
+
+/// Checks the pair is live and hands its lot size, tick and minimum to the registry.
+pub async fn instrument(client: &reqwest::Client) -> Result<String, String> {
+    let inst_id = inst_id();
+    let info: Vec<Value> = public(client, "/api/v5/public/instruments", &[("instType", "SPOT"), ("instId", inst_id)], Duration::from_secs(10)).await?;
+    let item = info.first().ok_or_else(|| format!("{} not listed", inst_id))?;
+    if item["state"] != "live" {
+        return Err(format!("{} state is {}", inst_id, item["state"]));
+    }
+    let (lot, tick, min) = (money::from_value(&item["lotSz"]), money::from_value(&item["tickSz"]), money::from_value(&item["minSz"]));
+    symbols::refine(lot, tick, min, Decimal::ZERO);
+    Ok(format!("{} live (lot {}, tick {}, min {})", inst_id, lot, tick, min))
+}
+
+/// The trading account's quote (available and frozen in orders) and available base balance (USDT
+/// and BTC for the default pair).
+pub async fn wallet(client: &reqwest::Client, timeout: Duration) -> Result<(WalletBalance, Decimal), String> {
+    let market = symbols::active();
+    let (base, quote) = (market.base(), market.quote());
+    let accounts: Vec<Value> = signed_get(client, "/api/v5/account/balance", &[("ccy", &format!("{},{}", base, quote))], timeout).await?;
+    let details = accounts.first().and_then(|a| a["details"].as_array().cloned()).unwrap_or_default();
+    let field = |ccy: &str, key: &str| details.iter().find(|d| d["ccy"] == ccy).map(|d| money::from_value(&d[key])).unwrap_or_default();
+    Ok((WalletBalance::new("trading", field(quote, "availBal"), field(quote, "frozenBal")), field(base, "availBal")))
+}
+
+/// The quote in the funding account, which has to be transferred before it can trade.
+pub async fn funding(client: &reqwest::Client, timeout: Duration) -> WalletBalance {
+    let result: Result<Vec<Value>, String> = signed_get(client, "/api/v5/asset/balances", &[("ccy", symbols::active().quote())], timeout).await;
+    match result {
+        Ok(balances) => {
+            let field = |key: &str| balances.first().map(|b| money::from_value(&b[key])).unwrap_or_default();
+            WalletBalance::new("funding", field("availBal"), field("frozenBal"))
+        },
+        Err(e) => WalletBalance::failed("funding", e),
+    }
+}
+
+/// Fills on the instrument since `since_ms` that did not come from the bot's orders.
+pub async fn fills(client: &reqwest::Client, since_ms: i64, timeout: Duration) -> Result<Vec<Execution>, String> {
+    let since = since_ms.to_string();
+    let fills: Vec<Value> = signed_get(client, "/api/v5/trade/fills", &[("instType", "SPOT"), ("instId", symbols::active().order_code), ("begin", &since)], timeout).await?;
+    Ok(fills.iter()
+        .filter(|f| !f["clOrdId"].as_str().unwrap_or("").starts_with("scalper"))
+        .map(|f| Execution { sell: f["side"] == "sell", fill: Fill { price: money::from_value(&f["fillPx"]), qty: money::from_value(&f["fillSz"]) } })
+        .collect())
+}
+
+/// The key's permissions; trading needs "trade".
+pub async fn permissions(client: &reqwest::Client) -> Result<String, String> {
+    let config: Vec<Value> = signed_get(client, "/api/v5/account/config", &[], Duration::from_secs(10)).await?;
+    let perm = config.first().and_then(|c| c["perm"].as_str()).unwrap_or("").to_string();
+    if perm.split(',').any(|p| p == "trade") { Ok(perm) } else { Err(format!("key lacks trade permission ({})", perm)) }
+}
+
+/// IOC limit order; waits for its final state and returns what filled.
+pub async fn place(client: &reqwest::Client, side: &str, price: Decimal, qty: Decimal, wait: Duration) -> Result<Fill, String> {
+    let deadline = Instant::now() + wait;
+    ratelimit::order_permit().await?;
+    // Client order ids are alphanumeric only
+    let cl_ord_id = format!("scalper{}", SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis());
+    let market = symbols::active();
+    let inst_id = market.order_code;
+    let body = json!({
+        "instId": inst_id, "tdMode": "cash", "side": side, "ordType": "ioc", "clOrdId": cl_ord_id,
+        "px": market.price(price), "sz": market.qty(qty),
+    });
+    let watch = userstream::watch(&cl_ord_id);
+    signed_post::<Value>(client, "/api/v5/trade/order", body, wait).await.map_err(|e| format!("order rejected: {}", e))?;
+    if let Some(outcome) = userstream::report(watch, deadline).await { return outcome; }
+    loop {
+        let left = deadline.saturating_duration_since(Instant::now());
+        if left.is_zero() { return Err(format!("order {} status unknown after {:?}", cl_ord_id, wait)); }
+        let orders: Vec<Value> = signed_get(client, "/api/v5/trade/order", &[("instId", inst_id), ("clOrdId", &cl_ord_id)], left).await?;
+        if let Some(outcome) = orders.first().and_then(outcome) { return outcome; }
+        sleep(ORDER_POLL).await;
+    }
+}
+
+// An order's outcome once it is final, the same from the REST order and the orders channel
+fn outcome(order: &Value) -> Option<Result<Fill, String>> {
+    let filled = money::from_value(&order["accFillSz"]);
+    let avg_price = money::from_value(&order["avgPx"]);
+    match order["state"].as_str().unwrap_or("") {
+        "filled" => Some(Ok(Fill { price: avg_price, qty: filled })),
+        "canceled" | "mmp_canceled" if filled > Decimal::ZERO => Some(Ok(Fill { price: avg_price, qty: filled })),
+        "canceled" | "mmp_canceled" => Some(Err("not filled (IOC canceled)".to_string())),
+        _ => None,
+    }
+}
+
+// Blocking version for the panic hook, which runs outside the async runtime
+fn blocking_post(path: &str, body: Value) -> Result<Value, String> {
+    let body = body.to_string();
+    let client = reqwest::blocking::Client::builder().timeout(Duration::from_secs(5)).build().map_err(|e| e.to_string())?;
+    let reply: Reply<Value> = client.post(format!("{}{}", REST_URL, path)).headers(signed_headers(&Method::POST, path, &body)).body(body).send()
+        .map_err(|e| e.to_string())?.json().map_err(|e| e.to_string())?;
+    unwrap_reply(reply)
+}
+
+/// Stop-market sell (conditional algo order) triggered when the last price falls to `stop_price`; returns its algo id.
+pub fn place_stop(stop_price: Decimal, quantity: Decimal) -> Result<String, String> {
+    let market = symbols::active();
+    let body = json!({
+        "instId": market.order_code, "tdMode": "cash", "side": "sell", "ordType": "conditional",
+        "sz": market.qty(quantity), "slTriggerPx": market.price(stop_price), "slOrdPx": "-1",
+    });
+    let data = blocking_post("/api/v5/trade/order-algo", body)?;
+    data[0]["algoId"].as_str().map(str::to_string).ok_or_else(|| "no algo id in response".to_string())
+}
+
+pub fn market_sell(quantity: Decimal) -> Result<(), String> {
+    let market = symbols::active();
+    let body = json!({ "instId": market.order_code, "tdMode": "cash", "side": "sell", "ordType": "market", "sz": market.qty(quantity) });
+    blocking_post("/api/v5/trade/order", body).map(|_| ())
+}
+
+pub async fn cancel_stop(client: &reqwest::Client, algo_id: &str) -> Result<(), String> {
+    let body = json!([{ "algoId": algo_id, "instId": inst_id() }]);
+    signed_post::<Value>(client, "/api/v5/trade/cancel-algos", body, Duration::from_secs(5)).await.map(|_| ())
+}
@@ -0,0 +1,183 @@
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+
+use crate::backtest;
+use crate::stats;
+use crate::strategy::{self, StrategyParams};
+use crate::Candle;
+
+// --- 🔎 OPTIMIZER ---
+// A grid search over strategy parameters for the `optimize` subcommand. [optimize.ranges] maps a
+// parameter to [from, to, step]; every combination is backtested over the same candles, on worker
+// threads pulling points off a shared counter, and ranked by the objective. Parameters without a
+// range keep their [strategy] value. Points the strategy's schema rejects are skipped, and points
+// with fewer than min_trades trades or an undefined objective rank last.
+
+pub const MAX_POINTS: usize = 100_000; // A grid bigger than this is a typo, not a search
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Objective {
+    #[default]
+    NetPnl,
+    Sharpe,       // Per trade, as on the performance page
+    ProfitFactor, // Undefined without a losing trade
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct OptimizeConfig {
+    pub objective: Objective,
+    pub top: usize,        // Parameter sets reported
+    pub threads: usize,    // 0 = one per core
+    pub min_trades: usize, // Fewer trades rank last, whatever they score
+    pub ranges: BTreeMap<String, [f64; 3]>, // Parameter -> [from, to, step]
+}
+
+impl Default for OptimizeConfig {
+    fn default() -> Self {
+        OptimizeConfig { objective: Objective::NetPnl, top: 10, threads: 0, min_trades: 5, ranges: BTreeMap::new() }
+    }
+}
+
+pub fn validate(cfg: &OptimizeConfig) -> Result<(), String> {
+    for (key, [from, to, step]) in &cfg.ranges {
+        if StrategyParams::default().get(key).is_none() {
+            return Err(format!("[optimize.ranges] {} is not a strategy parameter", key));
+        }
+        if !(from.is_finite() && to.is_finite() && *step > 0.0 && from <= to) {
+            return Err(format!("[optimize.ranges] {} must be [from, to, step] with from <= to and step > 0", key));
+        }
+    }
+    Ok(())
+}
+
+/// One evaluated parameter set.
+#[derive(Clone, Debug, Serialize)]
+pub struct Point {
+    pub params: StrategyParams,
+    pub trades: usize,
+    pub win_rate: f64,
+    pub net_pnl: f64,
+    pub sharpe: Option<f64>,
+    pub profit_factor: Option<f64>,
+    pub max_drawdown: f64,
+    pub score: Option<f64>, // The objective; None ranks last
+}
+
+/// Every combination of the ranges over `base`, or Err if there are more than MAX_POINTS.
+pub fn grid(base: StrategyParams, ranges: &BTreeMap<String, [f64; 3]>) -> Result<Vec<StrategyParams>, String> {
+    let mut points = vec![base];
+    for (key, [from, to, step]) in ranges {
+        // Counted, not accumulated, so the last value isn't lost to rounding
+        let steps = ((to - from) / step + 1e-9).floor() as usize + 1;
+        if points.len().saturating_mul(steps) > MAX_POINTS {
+            return Err(format!("more than {} parameter sets, narrow the ranges or widen the steps", MAX_POINTS));
+        }
+        points = points.into_iter()
+            .flat_map(|p| (0..steps).map(move |i| {
+                let mut p = p;
+                // Rounded so 0.001 steps stay 0.003 and not 0.0030000000000000005
+                p.set(key, ((from + step * i as f64) * 1e9).round() / 1e9);
+                p
+            }))
+            .collect();
+    }
+    Ok(points)
+}
+
+fn evaluate(candles: &[Candle], name: &str, params: StrategyParams, spread_bps: f64, cfg: &OptimizeConfig) -> Point {
+    let run = backtest::run(candles, strategy::build(name, params).as_ref(), spread_bps);
+    let profits: Vec<f64> = run.trades.iter().map(|t| t.profit).collect();
+    let returns: Vec<f64> = run.trades.iter()
+        .map(|t| if t.entry_price * t.quantity > 0.0 { t.profit / (t.entry_price * t.quantity) * 100.0 } else { 0.0 })
+        .collect();
+    let (sharpe, profit_factor) = (stats::sharpe(&returns), stats::profit_factor(&profits));
+    let m = run.metrics;
+    let score = match cfg.objective {
+        Objective::NetPnl => Some(m.net_pnl),
+        Objective::Sharpe => sharpe,
+        Objective::ProfitFactor => profit_factor,
+    };
+    Point {
+        params, trades: m.trades, win_rate: m.win_rate, net_pnl: m.net_pnl, sharpe, profit_factor, max_drawdown: m.max_drawdown,
+        score: score.filter(|_| m.trades >= cfg.min_trades),
+    }
+}
+
+/// Backtests every valid point of the grid in parallel; best first. Also returns how many points
+/// the strategy's schema rejected.
+pub fn search(candles: &[Candle], name: &str, points: Vec<StrategyParams>, spread_bps: f64, cfg: &OptimizeConfig) -> (Vec<Point>, usize) {
+    let total = points.len();
+    let points: Vec<StrategyParams> = points.into_iter().filter(|p| strategy::validate(name, p).is_ok()).collect();
+    let skipped = total - points.len();
+    let threads = match cfg.threads {
+        0 => thread::available_parallelism().map_or(1, |n| n.get()),
+        n => n,
+    };
+    let next = AtomicUsize::new(0);
+    let results = Mutex::new(Vec::with_capacity(points.len()));
+    thread::scope(|scope| {
+        for _ in 0..threads.min(points.len()) {
+            scope.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                let Some(params) = points.get(i) else { break };
+                let point = evaluate(candles, name, *params, spread_bps, cfg);
+                results.lock().push((i, point));
+            });
+        }
+    });
+    let mut results = results.into_inner();
+    // Ties keep grid order, so a rerun ranks the same way
+    results.sort_by(|(i, a), (j, b)| match (a.score, b.score) {
+        (Some(x), Some(y)) => y.total_cmp(&x),
+        (Some(_), None) => std::cmp::Ordering::Less,
+        (None, Some(_)) => std::cmp::Ordering::Greater,
+        (None, None) => b.net_pnl.total_cmp(&a.net_pnl),
+    }.then(i.cmp(j)));
+    (results.into_iter().map(|(_, point)| point).collect(), skipped)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn ranges(list: &[(&str, [f64; 3])]) -> BTreeMap<String, [f64; 3]> {
+        list.iter().map(|(key, range)| (key.to_string(), *range)).collect()
+    }
+
+    fn values(points: &[StrategyParams], key: &str) -> Vec<f64> {
+        points.iter().map(|p| p.get(key).unwrap()).collect()
+    }
+
+    #[test]
+    fn inclusive_ranges_keep_their_last_step() {
+        // (0.3 - 0.1) / 0.1 is 1.9999999999999996 in f64; the end is still in
+        let points = grid(StrategyParams::default(), &ranges(&[("trailing_stop_pct", [0.1, 0.3, 0.1])])).unwrap();
+        assert_eq!(values(&points, "trailing_stop_pct"), [0.1, 0.2, 0.3]);
+        let points = grid(StrategyParams::default(), &ranges(&[("hard_stop_pct", [0.001, 0.005, 0.001])])).unwrap();
+        assert_eq!(values(&points, "hard_stop_pct"), [0.001, 0.002, 0.003, 0.004, 0.005]);
+        // An end between steps is left out
+        let points = grid(StrategyParams::default(), &ranges(&[("rsi_buy", [20.0, 35.0, 10.0])])).unwrap();
+        assert_eq!(values(&points, "rsi_buy"), [20.0, 30.0]);
+    }
+
+    #[test]
+    fn ranges_multiply_and_leave_the_rest_alone() {
+        let base = StrategyParams::default();
+        let points = grid(base, &ranges(&[("rsi_buy", [20.0, 30.0, 5.0]), ("rsi_sell", [60.0, 80.0, 10.0])])).unwrap();
+        assert_eq!(points.len(), 9);
+        assert!(points.iter().all(|p| p.trailing_stop_pct == base.trailing_stop_pct));
+    }
+
+    #[test]
+    fn grids_over_the_cap_are_refused() {
+        let at_cap = grid(StrategyParams::default(), &ranges(&[("rsi_buy", [1.0, MAX_POINTS as f64, 1.0])])).unwrap();
+        assert_eq!(at_cap.len(), MAX_POINTS);
+        let over = ranges(&[("rsi_buy", [0.0, 999.0, 1.0]), ("rsi_sell", [0.0, 100.0, 1.0])]);
+        assert!(grid(StrategyParams::default(), &over).unwrap_err().contains("more than 100000 parameter sets"));
+    }
+}
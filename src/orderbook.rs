@@ -0,0 +1,263 @@
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::Arc;
+use std::time::Duration;
+use chrono::Utc;
+use parking_lot::RwLock;
+use serde::{Deserialize, Serialize};
+use tokio::time::{sleep, Instant};
+
+use crate::feed::{self, Flow, MAX_BACKOFF_SECS};
+use crate::symbols;
+
+// --- 📚 ORDER BOOK ---
+// Kept locally from the depth stream: a full snapshot when the channel is joined, then only the
+// levels that changed. Each update carries a version; a missing one means the book can no longer
+// be trusted, so it is flagged unsynced and the channel rejoined for a fresh snapshot.
+
+const BOOK_LEVELS: usize = 50;
+const MAX_AGE_MS: i64 = 10_000; // An older book says nothing about the market now
+
+/// CoinDCX depth (REST snapshot or stream event): price -> quantity, both as strings.
+#[derive(Debug, Deserialize)]
+pub struct DepthResponse {
+    #[serde(default)]
+    pub bids: HashMap<String, String>,
+    #[serde(default)]
+    pub asks: HashMap<String, String>,
+    #[serde(default)]
+    pub vs: Option<u64>, // Version, only on the stream
+}
+
+#[derive(Clone, Copy, Debug, Serialize)]
+pub struct Level {
+    pub price: f64,
+    pub qty: f64,
+}
+
+// Prices as map keys: always finite and positive, so total order is plain numeric order
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct Px(f64);
+
+impl Eq for Px {}
+
+impl PartialOrd for Px {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> { Some(self.cmp(other)) }
+}
+
+impl Ord for Px {
+    fn cmp(&self, other: &Self) -> Ordering { self.0.total_cmp(&other.0) }
+}
+
+#[derive(Clone, Debug, Default)]
+pub struct OrderBook {
+    bids: BTreeMap<Px, f64>, // Price -> quantity, best (highest) last
+    asks: BTreeMap<Px, f64>, // Best (lowest) first
+    sequence: Option<u64>,   // Last version applied from the stream
+    pub synced: bool,        // False until a snapshot arrives, and again after a gap
+    pub updated_at: i64,     // Unix millis
+}
+
+#[derive(Serialize)]
+pub struct DepthView {
+    pub bids: Vec<Level>,
+    pub asks: Vec<Level>,
+    pub spread: Option<f64>,
+    pub spread_bps: Option<f64>,
+    pub mid: Option<f64>,
+    pub imbalance: Option<f64>,
+    pub synced: bool,
+    pub updated_at: i64,
+}
+
+pub type SharedBook = Arc<RwLock<OrderBook>>;
+
+/// Sets each level's quantity; zero (or unparseable) removes it.
+fn apply_levels(side: &mut BTreeMap<Px, f64>, raw: &HashMap<String, String>) {
+    for (p, q) in raw {
+        let Ok(price) = p.parse::<f64>() else { continue };
+        match q.parse::<f64>() {
+            Ok(qty) if qty > 0.0 => { side.insert(Px(price), qty); },
+            _ => { side.remove(&Px(price)); },
+        }
+    }
+}
+
+impl OrderBook {
+    pub fn from_snapshot(depth: &DepthResponse, updated_at: i64) -> Self {
+        let mut book = OrderBook { sequence: depth.vs, synced: true, updated_at, ..Default::default() };
+        apply_levels(&mut book.bids, &depth.bids);
+        apply_levels(&mut book.asks, &depth.asks);
+        book
+    }
+
+    /// Applies a stream update. Err on a version gap: the book is then unsynced until the next snapshot.
+    pub fn apply_update(&mut self, delta: &DepthResponse, updated_at: i64) -> Result<(), String> {
+        if let (Some(last), Some(vs)) = (self.sequence, delta.vs) {
+            if vs <= last { return Ok(()); } // Already in the snapshot
+            if vs != last + 1 {
+                self.synced = false;
+                return Err(format!("version {} after {}", vs, last));
+            }
+        }
+        self.sequence = delta.vs.or(self.sequence);
+        apply_levels(&mut self.bids, &delta.bids);
+        apply_levels(&mut self.asks, &delta.asks);
+        // Levels far from the touch are never shown; updates for them would only grow the maps
+        while self.bids.len() > BOOK_LEVELS { self.bids.pop_first(); }
+        while self.asks.len() > BOOK_LEVELS { self.asks.pop_last(); }
+        self.updated_at = updated_at;
+        Ok(())
+    }
+
+    pub fn best_bid(&self) -> Option<f64> { self.bids.keys().next_back().map(|p| p.0) }
+    pub fn best_ask(&self) -> Option<f64> { self.asks.keys().next().map(|p| p.0) }
+
+    pub fn mid(&self) -> Option<f64> {
+        Some((self.best_bid()? + self.best_ask()?) / 2.0)
+    }
+
+    pub fn spread(&self) -> Option<f64> {
+        Some(self.best_ask()? - self.best_bid()?)
+    }
+
+    /// The spread in bps of the mid, if the book is in sync and no older than MAX_AGE_MS at `now_ms`.
+    pub fn fresh_spread_bps(&self, now_ms: i64) -> Option<f64> {
+        if !self.synced || now_ms - self.updated_at > MAX_AGE_MS { return None; }
+        Some(self.spread()? / self.mid()? * 10_000.0).filter(|bps| bps.is_finite() && *bps >= 0.0)
+    }
+
+    /// Best bid and ask, if the book is in sync and no older than MAX_AGE_MS at `now_ms`.
+    pub fn fresh_touch(&self, now_ms: i64) -> Option<(f64, f64)> {
+        if !self.synced || now_ms - self.updated_at > MAX_AGE_MS { return None; }
+        Some((self.best_bid()?, self.best_ask()?))
+    }
+
+    /// (bid qty - ask qty) / total over the top `depth` levels: +1 all bids, -1 all asks.
+    pub fn imbalance(&self, depth: usize) -> Option<f64> {
+        let bid: f64 = self.bids.values().rev().take(depth).sum();
+        let ask: f64 = self.asks.values().take(depth).sum();
+        (bid + ask > 0.0).then(|| (bid - ask) / (bid + ask))
+    }
+
+    /// Top `depth` levels per side plus spread, for the dashboard widget.
+    pub fn view(&self, depth: usize) -> DepthView {
+        let spread = self.spread();
+        let mid = self.mid();
+        DepthView {
+            bids: self.bids.iter().rev().take(depth).map(|(p, q)| Level { price: p.0, qty: *q }).collect(),
+            asks: self.asks.iter().take(depth).map(|(p, q)| Level { price: p.0, qty: *q }).collect(),
+            spread,
+            spread_bps: match (spread, mid) {
+                (Some(s), Some(m)) if m > 0.0 => Some(s / m * 10_000.0),
+                _ => None,
+            },
+            mid,
+            imbalance: self.imbalance(depth),
+            synced: self.synced,
+            updated_at: self.updated_at,
+        }
+    }
+}
+
+// ["depth-snapshot" | "depth-update", {"data": "{\"vs\":..,\"bids\":{..},\"asks\":{..}}"}]
+fn depth_event(event: &str) -> Option<(bool, DepthResponse)> {
+    let event: serde_json::Value = serde_json::from_str(event).ok()?;
+    let snapshot = match event[0].as_str()? {
+        "depth-snapshot" => true,
+        "depth-update" => false,
+        _ => return None,
+    };
+    let data = &event[1]["data"];
+    let depth = match data.as_str() {
+        Some(raw) => serde_json::from_str(raw).ok()?,
+        None => serde_json::from_value(data.clone()).ok()?,
+    };
+    Some((snapshot, depth))
+}
+
+/// Keeps `book` up to date from the depth stream, reconnecting with backoff.
+pub async fn stream(book: SharedBook) {
+    let channel = format!("{}@orderbook@{}", symbols::active().data_code, BOOK_LEVELS);
+    let mut backoff = 1;
+    loop {
+        let connected_at = Instant::now();
+        let result = feed::subscribe(&channel, |event| {
+            let Some((snapshot, depth)) = depth_event(event) else { return Flow::Continue };
+            let now = Utc::now().timestamp_millis();
+            let mut book = book.write();
+            if snapshot {
+                *book = OrderBook::from_snapshot(&depth, now);
+                return Flow::Continue;
+            }
+            // Waiting for the snapshot after a resync
+            if !book.synced { return Flow::Continue; }
+            match book.apply_update(&depth, now) {
+                Ok(()) => Flow::Continue,
+                Err(gap) => {
+                    eprintln!("Order Book: missed an update ({}), resyncing", gap);
+                    Flow::Resync
+                },
+            }
+        }).await;
+        book.write().synced = false;
+        if let Err(e) = result { eprintln!("Order Book Error: depth stream: {}", e); }
+        if connected_at.elapsed() > Duration::from_secs(60) { backoff = 1; }
+        sleep(Duration::from_secs(backoff)).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF_SECS);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn depth(bids: &[(&str, &str)], asks: &[(&str, &str)], vs: u64) -> DepthResponse {
+        let side = |levels: &[(&str, &str)]| levels.iter().map(|(p, q)| (p.to_string(), q.to_string())).collect();
+        DepthResponse { bids: side(bids), asks: side(asks), vs: Some(vs) }
+    }
+
+    fn snapshot() -> OrderBook {
+        OrderBook::from_snapshot(&depth(&[("100.0", "1"), ("99.5", "2")], &[("100.5", "1"), ("101.0", "3")], 10), 0)
+    }
+
+    #[test]
+    fn applies_updates_in_order() {
+        let mut book = snapshot();
+        book.apply_update(&depth(&[("100.2", "0.5")], &[], 11), 1).unwrap();
+        book.apply_update(&depth(&[], &[("100.5", "4")], 12), 2).unwrap();
+        assert_eq!(book.best_bid(), Some(100.2));
+        assert_eq!(book.asks.get(&Px(100.5)), Some(&4.0));
+        assert_eq!((book.sequence, book.synced, book.updated_at), (Some(12), true, 2));
+    }
+
+    #[test]
+    fn skips_a_version_it_already_has() {
+        let mut book = snapshot();
+        book.apply_update(&depth(&[("100.2", "0.5")], &[], 11), 1).unwrap();
+        // The same version again (or one already in the snapshot) changes nothing
+        book.apply_update(&depth(&[("100.3", "9")], &[], 11), 2).unwrap();
+        book.apply_update(&depth(&[("100.4", "9")], &[], 9), 3).unwrap();
+        assert_eq!(book.best_bid(), Some(100.2));
+        assert_eq!((book.sequence, book.synced, book.updated_at), (Some(11), true, 1));
+    }
+
+    #[test]
+    fn a_gap_unsyncs_the_book() {
+        let mut book = snapshot();
+        let gap = book.apply_update(&depth(&[("100.2", "0.5")], &[], 12), 1);
+        assert_eq!(gap, Err("version 12 after 10".to_string()));
+        assert!(!book.synced);
+        assert_eq!(book.best_bid(), Some(100.0));
+        assert_eq!(book.fresh_touch(1), None);
+    }
+
+    #[test]
+    fn zero_quantity_removes_the_level() {
+        let mut book = snapshot();
+        book.apply_update(&depth(&[("100.0", "0")], &[("100.5", "0.00000000")], 11), 1).unwrap();
+        assert_eq!(book.best_bid(), Some(99.5));
+        assert_eq!(book.best_ask(), Some(101.0));
+        assert_eq!((book.bids.len(), book.asks.len()), (1, 1));
+    }
+}
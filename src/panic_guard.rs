@@ -0,0 +1,198 @@
+use std::future::Future;
+use std::panic;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::config::SharedConfig;
+use crate::executor::SignalContext;
+use crate::slots::Slots;
+use crate::strategy::StrategyParams;
+use crate::notify::{Event, EventKind, Notifier};
+use crate::{bybit, currency, logfile, money, okx, signed_headers, simulating, symbols, BotState, DbManager, SharedState, PAIR};
+
+// --- 🧯 PANIC GUARD ---
+// A panic in the trading task leaves an open position unmanaged until the supervisor restarts it
+// (or forever, if it keeps panicking). The hook protects the position and alerts synchronously,
+// on its own OS thread, before anything else happens.
+
+const LOCK_WAIT: Duration = Duration::from_millis(500); // The panicking code may hold a state draft or the config lock
+const STOP_KEY: &str = "protective_stop";
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PanicAction {
+    #[default]
+    Stop,    // Exchange-side stop-limit at the trailing-stop level, cancelled when the loop is back
+    Flatten, // Sell immediately
+    Hold,    // Only alert
+}
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PanicConfig {
+    pub on_panic: PanicAction,
+}
+
+#[derive(Serialize, Deserialize)]
+struct ProtectiveStop {
+    id: String,
+    stop_price: Decimal,
+    quantity: Decimal,
+}
+
+tokio::task_local! {
+    static TRADING_TASK: ();
+}
+
+/// Marks a future as the trading task, so only its panics touch the position.
+pub async fn guarded<F: Future>(fut: F) -> F::Output {
+    TRADING_TASK.scope((), fut).await
+}
+
+pub fn install(state: SharedState, config: SharedConfig) {
+    let default_hook = panic::take_hook();
+    panic::set_hook(Box::new(move |info| {
+        default_hook(info); // Usual message (and backtrace with RUST_BACKTRACE)
+        let msg = format!("PANIC {}", info).replace('\n', " ");
+        logfile::write("ERROR", &msg);
+        let in_trading_task = TRADING_TASK.try_with(|_| ()).is_ok();
+        let (state, config) = (state.clone(), config.clone());
+        // Blocking HTTP must not run on a runtime thread
+        let _ = thread::spawn(move || protect(&state, &config, &msg, in_trading_task)).join();
+    }));
+}
+
+fn protect(state: &SharedState, config: &SharedConfig, msg: &str, in_trading_task: bool) {
+    let (position, slots, price) = { let data = state.read(); (data.position, data.slots, data.price) };
+    let (action, params) = config.try_read_for(LOCK_WAIT)
+        .map(|c| (c.panic_guard.on_panic, c.strategy.params))
+        .unwrap_or((PanicAction::Stop, StrategyParams::default()));
+
+    let outcome = match position {
+        BotState::Idle => "no open position".to_string(),
+        _ if !in_trading_task => "outside the trading task, position untouched".to_string(),
+        BotState::InPosition { quantity, .. } => match action {
+            PanicAction::Hold => "position held, waiting for the supervisor restart".to_string(),
+            PanicAction::Stop => {
+                let level = crate::active_stop(position, &params).map(|stop| stop.price).unwrap_or_default();
+                let stop_price = symbols::active().floor_price(money::dec(level));
+                match place_stop(stop_price, quantity) {
+                    Ok(id) => {
+                        let record = ProtectiveStop { id, stop_price, quantity };
+                        let _ = DbManager::set_state(STOP_KEY, &serde_json::to_string(&record).unwrap_or_default());
+                        format!("protective stop placed @ ${:.2}", stop_price)
+                    },
+                    Err(e) => format!("protective stop FAILED: {}", e),
+                }
+            },
+            PanicAction::Flatten => match market_sell(quantity) {
+                Ok(()) => {
+                    let mode = if simulating() { "simulation" } else { "live" };
+                    let (mut profit, mut gross) = (Decimal::ZERO, Decimal::ZERO);
+                    // One sell for the whole position, booked per slot
+                    for (slot, fill, net, slot_gross) in crate::close_all(&slots, money::dec(price)) {
+                        let context = SignalContext { slot: Some(slot), ..crate::signal_context(state, None, price, crate::active_stop(slots.0[slot], &params).map(|stop| stop.price), mode) };
+                        let _ = DbManager::log_trade("sell", fill, net, slot_gross, "PANIC FLATTEN", &context, chrono::Utc::now());
+                        profit += net;
+                        gross += slot_gross;
+                    }
+                    let _ = DbManager::save_position(&Slots::default(), None);
+                    if let Some(mut data) = state.try_write_for(LOCK_WAIT) {
+                        data.position = BotState::Idle;
+                        data.slots = Slots::default();
+                        data.entry_price = Decimal::ZERO;
+                        data.realized_pl += profit;
+                        data.gross_pl += gross;
+                    }
+                    format!("position flattened @ ~${:.2}", price)
+                },
+                Err(e) => format!("flatten FAILED: {}", e),
+            },
+        },
+    };
+
+    let text = format!("{} ({})", msg, outcome);
+    eprintln!("🧯 {}", text);
+    logfile::write("ERROR", &text);
+    Notifier::from_env().send_blocking(&Event::new(EventKind::Error, PAIR, price, 0.0, 0.0, &text));
+}
+
+fn signed_post(url: &str, mut body: serde_json::Value) -> Result<serde_json::Value, String> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    body["timestamp"] = serde_json::json!(timestamp);
+    let body = body.to_string();
+    let client = reqwest::blocking::Client::builder().timeout(Duration::from_secs(5)).build().map_err(|e| e.to_string())?;
+    let resp = client.post(url).headers(signed_headers(&body)).body(body).send().map_err(|e| e.to_string())?;
+    if !resp.status().is_success() {
+        return Err(format!("HTTP {}", resp.status()));
+    }
+    resp.json().map_err(|e| e.to_string())
+}
+
+fn place_stop(stop_price: Decimal, quantity: Decimal) -> Result<String, String> {
+    if simulating() {
+        println!("(SIMULATION) protective stop {} {} @ {}", quantity, symbols::active().base(), currency::quote(money::float(stop_price)));
+        return Ok("simulated".to_string());
+    }
+    if bybit::enabled() {
+        return bybit::place_stop(stop_price, quantity);
+    }
+    if okx::enabled() {
+        return okx::place_stop(stop_price, quantity);
+    }
+    // Limit slightly below the trigger so a fast move still fills
+    let market = symbols::active();
+    let resp = signed_post("https://api.coindcx.com/exchange/v1/orders/create", serde_json::json!({
+        "side": "sell", "order_type": "stop_limit", "market": market.order_code, "stop_price": market.floor_price(stop_price),
+        "price_per_unit": market.floor_price(stop_price * dec!(0.995)), "total_quantity": market.floor_qty(quantity),
+    }))?;
+    resp["orders"][0]["id"].as_str().map(str::to_string).ok_or_else(|| "no order id in response".to_string())
+}
+
+fn market_sell(quantity: Decimal) -> Result<(), String> {
+    if simulating() {
+        println!("(SIMULATION) panic flatten {} {}", quantity, symbols::active().base());
+        return Ok(());
+    }
+    if bybit::enabled() {
+        return bybit::market_sell(quantity);
+    }
+    if okx::enabled() {
+        return okx::market_sell(quantity);
+    }
+    let market = symbols::active();
+    signed_post("https://api.coindcx.com/exchange/v1/orders/create", serde_json::json!({
+        "side": "sell", "order_type": "market_order", "market": market.order_code, "total_quantity": market.floor_qty(quantity),
+    })).map(|_| ())
+}
+
+/// Cancels a protective stop left by a previous panic, once the loop manages the position again.
+/// If it already triggered, the exchange position is smaller than the bot thinks.
+pub async fn release(client: &reqwest::Client) -> Option<String> {
+    let raw = DbManager::get_state(STOP_KEY).ok().flatten().filter(|s| !s.is_empty())?;
+    let stop: ProtectiveStop = serde_json::from_str(&raw).ok()?;
+    if !simulating() && bybit::enabled() {
+        if let Err(e) = bybit::cancel(client, &stop.id).await {
+            return Some(format!("🧯 Could not cancel protective stop {}: {} (it may have filled)", stop.id, e));
+        }
+    } else if !simulating() && okx::enabled() {
+        if let Err(e) = okx::cancel_stop(client, &stop.id).await {
+            return Some(format!("🧯 Could not cancel protective stop {}: {} (it may have filled)", stop.id, e));
+        }
+    } else if !simulating() {
+        let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+        let body = serde_json::json!({ "id": stop.id, "timestamp": timestamp }).to_string();
+        let resp = client.post("https://api.coindcx.com/exchange/v1/orders/cancel")
+            .headers(signed_headers(&body)).body(body).send().await;
+        match resp {
+            Ok(r) if r.status().is_success() => {},
+            Ok(r) => return Some(format!("🧯 Could not cancel protective stop {}: HTTP {} (it may have filled)", stop.id, r.status())),
+            Err(e) => return Some(format!("🧯 Could not cancel protective stop {}: {}", stop.id, e)),
+        }
+    }
+    let _ = DbManager::set_state(STOP_KEY, "");
+    Some(format!("🧯 Protective stop @ ${:.2} for {:.6} cancelled, loop manages the position again", stop.stop_price, stop.quantity))
+}
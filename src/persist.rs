@@ -0,0 +1,157 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::timeout_at;
+
+use crate::candles::Readings;
+use crate::executor::{Fill, SignalContext};
+use crate::slots::Slots;
+use crate::risk::RiskState;
+use crate::runtime;
+use crate::{Candle, DbManager};
+
+// --- 💾 PERSISTENCE STAGE (write-behind) ---
+// The trading loop hands its DB writes to a background task over a bounded queue, so a slow SD
+// card never delays signal evaluation. Writes are committed in batches (one transaction each) on
+// the DB threads, in the order they were submitted.
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PersistConfig {
+    pub batch_ms: u64,     // Longest a write waits for its batch to be committed
+    pub batch_rows: usize, // Commit early once this many writes are collected
+    pub queue_size: usize, // Writes buffered before the trading loop has to wait
+}
+
+impl Default for PersistConfig {
+    fn default() -> Self {
+        PersistConfig { batch_ms: 250, batch_rows: 200, queue_size: 4096 }
+    }
+}
+
+pub enum Write {
+    Candle { candle: Candle, readings: Readings },
+    Equity { time: i64, equity: f64 },
+    Trade { action: &'static str, fill: Fill, profit: Decimal, gross: Decimal, reason: String, context: SignalContext, at: DateTime<Utc> },
+    Position { slots: Slots, stop_price: Option<f64> },
+    Risk(RiskState),
+    Prune { now_ms: i64 },
+    Flush(oneshot::Sender<()>), // Answered once everything queued before it is committed
+}
+
+static QUEUE: OnceLock<mpsc::Sender<Write>> = OnceLock::new();
+
+// Backpressure counters for /metrics
+static FULL_WAITS: AtomicU64 = AtomicU64::new(0);
+static WAIT_MICROS: AtomicU64 = AtomicU64::new(0);
+static BATCHES: AtomicU64 = AtomicU64::new(0);
+static ROWS: AtomicU64 = AtomicU64::new(0);
+static COMMIT_MICROS: AtomicU64 = AtomicU64::new(0);
+
+pub struct Stats {
+    pub queue_depth: usize,
+    pub full_waits: u64,  // Submits that found the queue full
+    pub wait_secs: f64,   // Total time the trading loop spent waiting on a full queue
+    pub batches: u64,
+    pub rows: u64,
+    pub commit_secs: f64, // Duration of the last commit
+}
+
+pub fn stats() -> Stats {
+    let queue_depth = QUEUE.get().map_or(0, |q| q.max_capacity() - q.capacity());
+    Stats {
+        queue_depth,
+        full_waits: FULL_WAITS.load(Ordering::Relaxed),
+        wait_secs: WAIT_MICROS.load(Ordering::Relaxed) as f64 / 1e6,
+        batches: BATCHES.load(Ordering::Relaxed),
+        rows: ROWS.load(Ordering::Relaxed),
+        commit_secs: COMMIT_MICROS.load(Ordering::Relaxed) as f64 / 1e6,
+    }
+}
+
+pub fn start(cfg: PersistConfig) {
+    let (tx, rx) = mpsc::channel(cfg.queue_size.max(1));
+    if QUEUE.set(tx).is_err() { return; }
+    tokio::spawn(drain(rx, Duration::from_millis(cfg.batch_ms), cfg.batch_rows.max(1)));
+}
+
+async fn drain(mut rx: mpsc::Receiver<Write>, batch_wait: Duration, batch_rows: usize) {
+    while let Some(first) = rx.recv().await {
+        let deadline = tokio::time::Instant::now() + batch_wait;
+        let mut batch = vec![first];
+        // A flush is waited on, so it ends the batch early
+        while batch.len() < batch_rows && !matches!(batch.last(), Some(Write::Flush(_))) {
+            match timeout_at(deadline, rx.recv()).await {
+                Ok(Some(write)) => batch.push(write),
+                Ok(None) | Err(_) => break,
+            }
+        }
+        runtime::db(move || commit(batch)).await;
+    }
+}
+
+fn commit(batch: Vec<Write>) {
+    let started = Instant::now();
+    let rows = batch.len() as u64;
+    let mut flushed = Vec::new();
+    let result = DbManager::batch(|| {
+        for write in batch {
+            match write {
+                Write::Flush(done) => flushed.push(done),
+                write => apply(write),
+            }
+        }
+    });
+    if let Err(e) = result {
+        eprintln!("DB batch of {} writes failed: {}", rows, e);
+    }
+    BATCHES.fetch_add(1, Ordering::Relaxed);
+    ROWS.fetch_add(rows, Ordering::Relaxed);
+    COMMIT_MICROS.store(started.elapsed().as_micros() as u64, Ordering::Relaxed);
+    for done in flushed {
+        let _ = done.send(());
+    }
+}
+
+fn apply(write: Write) {
+    let _ = match write {
+        Write::Candle { candle, readings: r } => DbManager::save_candle(&candle, r.rsi, r.bb_lower, r.bb_upper),
+        Write::Equity { time, equity } => DbManager::save_equity(time, equity),
+        Write::Trade { action, fill, profit, gross, reason, context, at } => DbManager::log_trade(action, fill, profit, gross, &reason, &context, at),
+        Write::Position { slots, stop_price } => DbManager::save_position(&slots, stop_price),
+        Write::Risk(risk) => DbManager::save_risk_state(&risk),
+        Write::Prune { now_ms } => DbManager::prune_old_data(now_ms),
+        Write::Flush(done) => {
+            let _ = done.send(());
+            Ok(())
+        },
+    };
+}
+
+/// Queues a write. Only waits when the writer is a full queue behind, so order is always kept.
+pub async fn submit(write: Write) {
+    let Some(queue) = QUEUE.get() else { return apply(write) };
+    let write = match queue.try_send(write) {
+        Ok(()) => return,
+        Err(mpsc::error::TrySendError::Full(write)) => write,
+        Err(mpsc::error::TrySendError::Closed(write)) => return apply(write),
+    };
+    FULL_WAITS.fetch_add(1, Ordering::Relaxed);
+    let started = Instant::now();
+    let sent = queue.send(write).await;
+    WAIT_MICROS.fetch_add(started.elapsed().as_micros() as u64, Ordering::Relaxed);
+    if let Err(mpsc::error::SendError(write)) = sent {
+        apply(write);
+    }
+}
+
+/// Waits until every write submitted so far is in the DB.
+pub async fn flush() {
+    let (done, wait) = oneshot::channel();
+    submit(Write::Flush(done)).await;
+    let _ = wait.await;
+}
@@ -0,0 +1,154 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+
+use crate::clock::{self, ClockConfig};
+use crate::{bybit, okx, signed_headers, simulating, symbols, DbManager};
+
+// --- ✈️ STARTUP PREFLIGHT ---
+// Self-test before the trading loop starts. Live trading refuses to start unless every check passes.
+
+pub struct CheckResult {
+    pub name: &'static str,
+    pub passed: bool,
+    pub detail: String,
+}
+
+#[derive(Deserialize)]
+struct MarketDetails {
+    pair: String,
+    #[serde(default)]
+    status: String,
+    // Order size and price rules, for the symbol registry
+    target_currency_precision: Option<i32>,
+    base_currency_precision: Option<i32>,
+    #[serde(default)]
+    min_quantity: Decimal,
+    #[serde(default)]
+    min_notional: Decimal,
+}
+
+fn check(name: &'static str, result: Result<String, String>) -> CheckResult {
+    match result {
+        Ok(detail) => CheckResult { name, passed: true, detail },
+        Err(detail) => CheckResult { name, passed: false, detail },
+    }
+}
+
+async fn exchange_reachable(client: &reqwest::Client) -> Result<String, String> {
+    let resp = client.get("https://api.coindcx.com/exchange/ticker").send().await.map_err(|e| e.to_string())?;
+    if resp.status().is_success() { Ok("ticker endpoint OK".into()) } else { Err(format!("HTTP {}", resp.status())) }
+}
+
+async fn market_active(client: &reqwest::Client) -> Result<String, String> {
+    let markets = client.get("https://api.coindcx.com/exchange/v1/markets_details").send().await
+        .map_err(|e| e.to_string())?
+        .json::<Vec<MarketDetails>>().await
+        .map_err(|e| e.to_string())?;
+    let code = symbols::active().data_code;
+    match markets.iter().find(|m| m.pair == code) {
+        Some(m) if m.status == "active" => {
+            let step = |decimals: Option<i32>| decimals.map_or(Decimal::ZERO, |d| Decimal::new(1, d.clamp(0, 28) as u32));
+            symbols::refine(step(m.target_currency_precision), step(m.base_currency_precision), m.min_quantity, m.min_notional);
+            Ok(format!("{} active", code))
+        },
+        Some(m) => Err(format!("{} status is '{}'", code, m.status)),
+        None => Err(format!("{} not listed", code)),
+    }
+}
+
+// Signed POST; 401/403 mean a bad key or a key without that permission
+async fn signed_probe(client: &reqwest::Client, url: &str, mut body: serde_json::Value) -> Result<String, String> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    body["timestamp"] = serde_json::json!(timestamp);
+    let body = body.to_string();
+    let resp = client.post(url).headers(signed_headers(&body)).body(body).send().await.map_err(|e| e.to_string())?;
+    match resp.status().as_u16() {
+        200..=299 => Ok("accepted".into()),
+        401 | 403 => Err(format!("rejected (HTTP {}): {}", resp.status(), resp.text().await.unwrap_or_default())),
+        _ => Err(format!("HTTP {}", resp.status())),
+    }
+}
+
+fn db_writable() -> Result<String, String> {
+    let stamp = chrono::Utc::now().to_rfc3339();
+    DbManager::set_state("preflight", &stamp).map(|_| "write OK".into()).map_err(|e| e.to_string())
+}
+
+async fn clock_skew(client: &reqwest::Client, cfg: &ClockConfig) -> Result<String, String> {
+    let drift = clock::measure(client, &cfg.url).await?;
+    if drift.abs() <= cfg.max_drift_ms {
+        Ok(format!("{}ms", drift))
+    } else {
+        Err(format!("{}ms exceeds {}ms", drift, cfg.max_drift_ms))
+    }
+}
+
+pub async fn run(client: &reqwest::Client, clock_cfg: &ClockConfig) -> Vec<CheckResult> {
+    if bybit::enabled() {
+        return run_bybit(client, clock_cfg).await;
+    }
+    if okx::enabled() {
+        return run_okx(client, clock_cfg).await;
+    }
+    let mut results = vec![
+        check("Exchange reachable", exchange_reachable(client).await),
+        check("Market listed & active", market_active(client).await),
+        check("Database writable", db_writable()),
+        check("Clock skew", clock_skew(client, clock_cfg).await),
+    ];
+
+    if simulating() {
+        results.push(check("API key (read)", Ok("skipped in simulation".into())));
+        results.push(check("API key (trade)", Ok("skipped in simulation".into())));
+    } else {
+        let balances = "https://api.coindcx.com/exchange/v1/users/balances";
+        results.push(check("API key (read)", signed_probe(client, balances, serde_json::json!({})).await));
+        let orders = "https://api.coindcx.com/exchange/v1/orders/active_orders";
+        results.push(check("API key (trade)", signed_probe(client, orders, serde_json::json!({ "market": symbols::active().order_code })).await));
+    }
+    results
+}
+
+// Same checks against Bybit; setting the leverage is the trade-permission probe
+async fn run_bybit(client: &reqwest::Client, clock_cfg: &ClockConfig) -> Vec<CheckResult> {
+    let mut results = vec![
+        check("Market listed & active", bybit::instrument(client).await),
+        check("Database writable", db_writable()),
+        check("Clock skew", clock_skew(client, clock_cfg).await),
+    ];
+    if simulating() {
+        results.push(check("API key (read)", Ok("skipped in simulation".into())));
+        results.push(check("API key (trade)", Ok("skipped in simulation".into())));
+    } else {
+        let wallet = bybit::wallet(client, Duration::from_secs(10)).await;
+        results.push(check("API key (read)", wallet.map(|(usdt, position)| format!("{:.2} {}, position {}", usdt.available + usdt.locked, symbols::active().quote(), position))));
+        results.push(check("API key (trade)", bybit::set_leverage(client).await));
+    }
+    results
+}
+
+// Same checks against OKX; the key's permission list is the trade probe
+async fn run_okx(client: &reqwest::Client, clock_cfg: &ClockConfig) -> Vec<CheckResult> {
+    let mut results = vec![
+        check("Market listed & active", okx::instrument(client).await),
+        check("Database writable", db_writable()),
+        check("Clock skew", clock_skew(client, clock_cfg).await),
+    ];
+    if simulating() {
+        results.push(check("API key (read)", Ok("skipped in simulation".into())));
+        results.push(check("API key (trade)", Ok("skipped in simulation".into())));
+    } else {
+        let wallet = okx::wallet(client, Duration::from_secs(10)).await;
+        results.push(check("API key (read)", wallet.map(|(quote, base)| format!("{:.2} quote, {} base", quote.available, base))));
+        results.push(check("API key (trade)", okx::permissions(client).await));
+    }
+    results
+}
+
+pub fn print_report(results: &[CheckResult]) {
+    println!("✈️ Preflight checks:");
+    for r in results {
+        println!("   {} {:<24} {}", if r.passed { "✅" } else { "❌" }, r.name, r.detail);
+    }
+}
@@ -0,0 +1,100 @@
+use std::collections::hash_map::DefaultHasher;
+use std::hash::{Hash, Hasher};
+use std::ops::{Deref, DerefMut};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+use arc_swap::{ArcSwap, Guard};
+use bytes::Bytes;
+use parking_lot::{Mutex, MutexGuard};
+use serde::Serialize;
+
+// --- 📸 PUBLISHED STATE (lock-free reads) ---
+// Readers load the latest published value and never block. A writer edits a private copy and
+// swaps it in atomically when the guard drops, so a slow dashboard request can't stall the
+// trading loop. Writers are serialized among themselves so no update is lost.
+// The JSON for the dashboard is rendered once per published value and shared by every poller.
+
+pub struct Published<T> {
+    current: ArcSwap<T>,
+    writer: Mutex<()>,
+    rendered: Mutex<Option<(Arc<T>, Rendered)>>, // Holding the value keeps its address from being reused
+}
+
+/// A published value as JSON, with an ETag derived from the content.
+#[derive(Clone)]
+pub struct Rendered {
+    pub body: Bytes,
+    pub etag: String,
+}
+
+impl<T: Clone> Published<T> {
+    pub fn new(value: T) -> Self {
+        Published { current: ArcSwap::from_pointee(value), writer: Mutex::new(()), rendered: Mutex::new(None) }
+    }
+
+    /// The latest published value.
+    pub fn read(&self) -> Guard<Arc<T>> {
+        self.current.load()
+    }
+
+    /// Draft of the latest value, published when the guard drops.
+    pub fn write(&self) -> Draft<'_, T> {
+        self.writer_with(self.writer.lock())
+    }
+
+    /// Like `write`, but gives up if another writer holds on for longer than `timeout`.
+    pub fn try_write_for(&self, timeout: Duration) -> Option<Draft<'_, T>> {
+        self.writer.try_lock_for(timeout).map(|lock| self.writer_with(lock))
+    }
+
+    fn writer_with<'a>(&'a self, lock: MutexGuard<'a, ()>) -> Draft<'a, T> {
+        let draft = T::clone(&self.current.load());
+        Draft { _lock: lock, target: &self.current, draft: Some(draft) }
+    }
+}
+
+impl<T: Serialize> Published<T> {
+    /// JSON of the latest value, serialized again only after a new publish.
+    pub fn json(&self) -> Rendered {
+        let current = self.current.load_full();
+        let mut cache = self.rendered.lock();
+        if let Some((value, rendered)) = &*cache {
+            if Arc::ptr_eq(value, &current) { return rendered.clone(); }
+        }
+        let body = serde_json::to_vec(&*current).unwrap_or_default();
+        let mut hasher = DefaultHasher::new();
+        body.hash(&mut hasher);
+        let rendered = Rendered { etag: format!("\"{:016x}\"", hasher.finish()), body: Bytes::from(body) };
+        *cache = Some((current, rendered.clone()));
+        rendered
+    }
+}
+
+pub struct Draft<'a, T> {
+    _lock: MutexGuard<'a, ()>,
+    target: &'a ArcSwap<T>,
+    draft: Option<T>, // Only None while being published
+}
+
+impl<T> Deref for Draft<'_, T> {
+    type Target = T;
+    fn deref(&self) -> &T {
+        self.draft.as_ref().unwrap()
+    }
+}
+
+impl<T> DerefMut for Draft<'_, T> {
+    fn deref_mut(&mut self) -> &mut T {
+        self.draft.as_mut().unwrap()
+    }
+}
+
+impl<T> Drop for Draft<'_, T> {
+    fn drop(&mut self) {
+        // A half-edited draft from a panicking writer is discarded, readers keep the last good value
+        if let Some(draft) = self.draft.take().filter(|_| !thread::panicking()) {
+            self.target.store(Arc::new(draft));
+        }
+    }
+}
@@ -0,0 +1,370 @@
+use std::collections::HashMap;
+use std::sync::OnceLock;
+use std::time::Duration;
+use parking_lot::Mutex;
+use pyo3::exceptions::{PyIOError, PyRuntimeError, PyValueError};
+use pyo3::prelude::*;
+use pyo3::types::PyDict;
+use rust_decimal::Decimal;
+
+use crate::config::Config;
+use crate::fills::SimFills;
+use crate::indicators::IndicatorPipeline;
+use crate::replay::SimulationConfig;
+use crate::risk::{self, Holding, RiskConfig};
+use crate::strategy::{self, Indicators, Strategy, StrategyParams};
+use crate::{backtest, bybit, money, okx, symbols, Candle, CONFIG_FILE, FEE_RATE};
+
+// --- 🐍 PYTHON BINDINGS ---
+// Behind the `python` feature; built into a wheel with maturin (see pyproject.toml). Python gets
+// the selected venue's candles and price, the indicator pipeline, the backtester driven by a
+// Python strategy object, and a paper account that sizes, checks and fills orders the way the
+// bot does (risk limits, the venue's steps and minimums, simulated slippage, fees). Nothing here
+// sends a real order: a strategy prototyped in Python is ported to the Strategy trait to go live.
+
+static RUNTIME: OnceLock<tokio::runtime::Runtime> = OnceLock::new();
+// Strategy reasons are &'static str; each distinct reason a Python strategy returns is kept once
+static REASONS: Mutex<Option<HashMap<String, &'static str>>> = parking_lot::const_mutex(None);
+
+fn block_on<T>(py: Python<'_>, work: impl std::future::Future<Output = T> + Send) -> T
+where
+    T: Send,
+{
+    let rt = RUNTIME.get_or_init(|| {
+        tokio::runtime::Builder::new_current_thread().enable_all().build().expect("Python runtime")
+    });
+    py.allow_threads(|| rt.block_on(work))
+}
+
+fn reason(text: String) -> &'static str {
+    let mut reasons = REASONS.lock();
+    let reasons = reasons.get_or_insert_with(HashMap::new);
+    if let Some(known) = reasons.get(&text) { return known; }
+    let leaked: &'static str = Box::leak(text.clone().into_boxed_str());
+    reasons.insert(text, leaked);
+    leaked
+}
+
+fn load_config(path: &str) -> PyResult<Config> {
+    let raw = std::fs::read_to_string(path).map_err(|e| PyIOError::new_err(format!("{}: {}", path, e)))?;
+    Config::parse(&raw).map_err(PyValueError::new_err)
+}
+
+#[pyclass(name = "Candle", get_all, set_all)]
+#[derive(Clone)]
+struct PyCandle {
+    time: i64, // Unix millis of the open
+    open: f64,
+    high: f64,
+    low: f64,
+    close: f64,
+}
+
+#[pymethods]
+impl PyCandle {
+    #[new]
+    fn new(time: i64, open: f64, high: f64, low: f64, close: f64) -> Self {
+        PyCandle { time, open, high, low, close }
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Candle(time={}, open={}, high={}, low={}, close={})", self.time, self.open, self.high, self.low, self.close)
+    }
+}
+
+impl From<Candle> for PyCandle {
+    fn from(c: Candle) -> Self {
+        PyCandle { time: c.time, open: c.open, high: c.high, low: c.low, close: c.close }
+    }
+}
+
+impl From<PyCandle> for Candle {
+    fn from(c: PyCandle) -> Self {
+        Candle { time: c.time, open: c.open, high: c.high, low: c.low, close: c.close }
+    }
+}
+
+#[pyclass(name = "Indicators", get_all, frozen)]
+#[derive(Clone)]
+struct PyIndicators {
+    price: f64,
+    rsi: f64,
+    bb_lower: f64,
+    venue_premium_pct: Option<f64>,
+    last_exit_price: Option<f64>, // While the re-entry band holds entries
+}
+
+#[pymethods]
+impl PyIndicators {
+    fn __repr__(&self) -> String {
+        format!("Indicators(price={}, rsi={:.2}, bb_lower={:.2})", self.price, self.rsi, self.bb_lower)
+    }
+}
+
+impl From<Indicators> for PyIndicators {
+    fn from(ind: Indicators) -> Self {
+        PyIndicators {
+            price: ind.price, rsi: ind.rsi, bb_lower: ind.bb_lower, venue_premium_pct: ind.venue_premium_pct,
+            last_exit_price: ind.last_exit.map(|exit| exit.price),
+        }
+    }
+}
+
+#[pyclass(name = "IndicatorPipeline")]
+struct PyIndicatorPipeline(IndicatorPipeline);
+
+#[pymethods]
+impl PyIndicatorPipeline {
+    #[new]
+    fn new() -> Self {
+        PyIndicatorPipeline(IndicatorPipeline::new())
+    }
+
+    /// Adds the next close and returns the readings including it.
+    fn next(&mut self, close: f64) -> PyIndicators {
+        self.0.next(close).into()
+    }
+
+    /// Whether enough closes have gone in for the readings to be traded on.
+    #[getter]
+    fn ready(&self) -> bool {
+        self.0.ready()
+    }
+}
+
+// A Python object with entry_reason(ind) and optionally exit_reason(ind, highest_price), both
+// returning a reason string or None. Without exit_reason the built-in trailing stop and RSI exit
+// apply. The first exception stops the strategy from trading and is raised after the run.
+struct PyStrategy {
+    object: PyObject,
+    params: StrategyParams,
+    fallback: Box<dyn Strategy>,
+    error: Mutex<Option<PyErr>>,
+}
+
+impl PyStrategy {
+    // entry_reason(ind), or exit_reason(ind, highest_price) when there is a highest price
+    fn call(&self, method: &str, ind: &Indicators, highest_price: Option<f64>) -> Option<&'static str> {
+        if self.error.lock().is_some() { return None; }
+        Python::with_gil(|py| {
+            let ind = PyIndicators::from(*ind);
+            let result = match highest_price {
+                Some(highest) => self.object.call_method1(py, method, (ind, highest)),
+                None => self.object.call_method1(py, method, (ind,)),
+            };
+            let result = result.and_then(|value| value.extract::<Option<String>>(py));
+            match result {
+                Ok(value) => value.map(reason),
+                Err(e) => {
+                    *self.error.lock() = Some(e);
+                    None
+                }
+            }
+        })
+    }
+}
+
+impl Strategy for PyStrategy {
+    fn name(&self) -> &'static str { "python" }
+    fn params(&self) -> &StrategyParams { &self.params }
+
+    fn entry_reason(&self, ind: &Indicators) -> Option<&'static str> {
+        self.call("entry_reason", ind, None)
+    }
+
+    fn exit_reason(&self, ind: &Indicators, highest_price: f64) -> Option<&'static str> {
+        let has_exit = Python::with_gil(|py| self.object.bind(py).hasattr("exit_reason").unwrap_or(false));
+        if !has_exit { return self.fallback.exit_reason(ind, highest_price); }
+        self.call("exit_reason", ind, Some(highest_price))
+    }
+}
+
+/// An account that trades on paper through the bot's own sizing, risk and fill model.
+#[pyclass(name = "Account")]
+struct PyAccount {
+    risk: RiskConfig,
+    fills: SimFills,
+    wallet: Decimal,      // Quote currency
+    quantity: Decimal,    // Base currency held
+    entry_price: Decimal, // Average fill of the open position
+    realized_pl: Decimal,
+    fees_paid: Decimal,
+}
+
+#[pymethods]
+impl PyAccount {
+    /// Risk limits and the fill model come from the config file ([risk], [simulation]).
+    #[new]
+    #[pyo3(signature = (wallet, config_path=CONFIG_FILE.to_string()))]
+    fn new(wallet: f64, config_path: String) -> PyResult<Self> {
+        let config = if std::path::Path::new(&config_path).exists() { load_config(&config_path)? } else { Config::default() };
+        Ok(PyAccount {
+            risk: config.risk,
+            fills: SimFills::new(config.simulation.seed, config.simulation.slippage_bps, config.simulation.spread_bps, None, config.simulation.matching),
+            wallet: money::dec(wallet),
+            quantity: Decimal::ZERO,
+            entry_price: Decimal::ZERO,
+            realized_pl: Decimal::ZERO,
+            fees_paid: Decimal::ZERO,
+        })
+    }
+
+    /// Buys `capital` worth (quote currency) at `price`; returns the filled quantity. Raises
+    /// ValueError when the risk limits or the venue's minimums reject the order.
+    fn buy(&mut self, price: f64, capital: f64) -> PyResult<f64> {
+        let market = symbols::active();
+        let price = market.order_price("buy", money::dec(price));
+        let holding = Holding { pair: market.pair.to_string(), positions: u32::from(self.quantity > Decimal::ZERO), notional: self.quantity * price };
+        let wanted = money::dec(capital).checked_div(price).unwrap_or_default();
+        let qty = risk::check_entry(&self.risk, market.pair, price, wanted, self.wallet, &[holding], &[])
+            .and_then(|qty| market.order_qty(price, qty))
+            .map_err(PyValueError::new_err)?;
+        let fill = self.fills.fill("buy", price);
+        let fee = fill * qty * FEE_RATE;
+        self.entry_price = (self.entry_price * self.quantity + fill * qty) / (self.quantity + qty);
+        self.quantity += qty;
+        self.wallet -= fill * qty + fee;
+        self.fees_paid += fee;
+        Ok(money::float(qty))
+    }
+
+    /// Sells the whole position at `price`; returns the realized profit after fees.
+    fn sell(&mut self, price: f64) -> PyResult<f64> {
+        if self.quantity <= Decimal::ZERO { return Err(PyValueError::new_err("no open position")); }
+        let fill = self.fills.fill("sell", money::dec(price));
+        let fee = fill * self.quantity * FEE_RATE;
+        let profit = (fill - self.entry_price) * self.quantity - fee;
+        self.wallet += fill * self.quantity - fee;
+        self.fees_paid += fee;
+        self.realized_pl += profit;
+        self.quantity = Decimal::ZERO;
+        self.entry_price = Decimal::ZERO;
+        Ok(money::float(profit))
+    }
+
+    #[getter]
+    fn position(&self) -> Option<(f64, f64)> {
+        (self.quantity > Decimal::ZERO).then(|| (money::float(self.quantity), money::float(self.entry_price)))
+    }
+
+    #[getter]
+    fn wallet(&self) -> f64 { money::float(self.wallet) }
+
+    #[getter]
+    fn realized_pl(&self) -> f64 { money::float(self.realized_pl) }
+
+    #[getter]
+    fn fees_paid(&self) -> f64 { money::float(self.fees_paid) }
+
+    fn unrealized_pl(&self, price: f64) -> f64 {
+        money::float((money::dec(price) - self.entry_price) * self.quantity)
+    }
+}
+
+/// Selects the venue and pair the way the bot does, from the config file ([bybit], [okx]).
+#[pyfunction]
+#[pyo3(signature = (config_path=CONFIG_FILE.to_string()))]
+fn configure(config_path: String) -> PyResult<()> {
+    let config = load_config(&config_path)?;
+    bybit::configure(config.bybit);
+    okx::configure(config.okx);
+    symbols::configure().map_err(PyValueError::new_err)
+}
+
+/// The traded pair's latest candles from the selected venue, oldest first.
+#[pyfunction]
+#[pyo3(signature = (limit=500))]
+fn fetch_candles(py: Python<'_>, limit: u32) -> PyResult<Vec<PyCandle>> {
+    let client = reqwest::Client::new();
+    let mut candles = block_on(py, crate::fetch_historical_candles(&client, limit, Duration::from_secs(10)))
+        .map_err(PyRuntimeError::new_err)?;
+    candles.sort_by_key(|c| c.time);
+    Ok(candles.into_iter().map(PyCandle::from).collect())
+}
+
+#[pyfunction]
+fn latest_price(py: Python<'_>) -> PyResult<Option<f64>> {
+    let client = reqwest::Client::new();
+    block_on(py, crate::get_latest_price(&client, Duration::from_secs(5))).map_err(PyRuntimeError::new_err)
+}
+
+/// Runs candles (oldest first) through a Python strategy, or a built-in one by name, on the same
+/// backtester as the dashboard, crossing `spread_bps` ([simulation] default when omitted). Returns
+/// a dict with metrics, trades and the equity curve; P&L is net with the gross alongside.
+#[pyfunction]
+#[pyo3(signature = (candles, strategy, params=None, spread_bps=None))]
+fn run_backtest(py: Python<'_>, candles: Vec<PyCandle>, strategy: PyObject, params: Option<HashMap<String, f64>>, spread_bps: Option<f64>) -> PyResult<PyObject> {
+    let mut parsed = StrategyParams::default();
+    for (key, value) in params.unwrap_or_default() {
+        match key.as_str() {
+            "rsi_buy" => parsed.rsi_buy = value,
+            "rsi_sell" => parsed.rsi_sell = value,
+            "rsi_crash" => parsed.rsi_crash = value,
+            "trailing_stop_pct" => parsed.trailing_stop_pct = value,
+            "trade_capital" => parsed.trade_capital = value,
+            "hard_stop_pct" => parsed.hard_stop_pct = value,
+            "break_even_pct" => parsed.break_even_pct = value,
+            "max_hold_mins" => parsed.max_hold_mins = value,
+            "reentry_pct" => parsed.reentry_pct = value,
+            "reentry_rsi" => parsed.reentry_rsi = value,
+            other => return Err(PyValueError::new_err(format!("unknown parameter {}", other))),
+        }
+    }
+    let candles: Vec<Candle> = candles.into_iter().map(Candle::from).collect();
+    let spread_bps = spread_bps.unwrap_or(SimulationConfig::default().spread_bps);
+    let run = if let Ok(name) = strategy.extract::<String>(py) {
+        strategy::validate(&name, &parsed).map_err(PyValueError::new_err)?;
+        backtest::run(&candles, strategy::build(&name, parsed).as_ref(), spread_bps)
+    } else {
+        let adapter = PyStrategy {
+            object: strategy,
+            params: parsed,
+            fallback: strategy::build(strategy::DEFAULT_STRATEGY, parsed),
+            error: Mutex::new(None),
+        };
+        let run = backtest::run(&candles, &adapter, spread_bps);
+        if let Some(e) = adapter.error.lock().take() { return Err(e); }
+        run
+    };
+
+    let metrics = PyDict::new(py);
+    metrics.set_item("candles", run.metrics.candles)?;
+    metrics.set_item("trades", run.metrics.trades)?;
+    metrics.set_item("wins", run.metrics.wins)?;
+    metrics.set_item("win_rate", run.metrics.win_rate)?;
+    metrics.set_item("net_pnl", run.metrics.net_pnl)?;
+    metrics.set_item("gross_pnl", run.metrics.gross_pnl)?;
+    metrics.set_item("avg_trade", run.metrics.avg_trade)?;
+    metrics.set_item("max_drawdown", run.metrics.max_drawdown)?;
+    let trades = run.trades.iter().map(|t| {
+        let trade = PyDict::new(py);
+        trade.set_item("entry_time", t.entry_time)?;
+        trade.set_item("exit_time", t.exit_time)?;
+        trade.set_item("entry_price", t.entry_price)?;
+        trade.set_item("exit_price", t.exit_price)?;
+        trade.set_item("quantity", t.quantity)?;
+        trade.set_item("profit", t.profit)?;
+        trade.set_item("gross_profit", t.gross_profit)?;
+        trade.set_item("reason", &t.reason)?;
+        Ok(trade)
+    }).collect::<PyResult<Vec<_>>>()?;
+    let result = PyDict::new(py);
+    result.set_item("strategy", &run.strategy)?;
+    result.set_item("metrics", metrics)?;
+    result.set_item("trades", trades)?;
+    result.set_item("equity", run.equity)?;
+    Ok(result.into())
+}
+
+#[pymodule]
+fn coindcx_scalper(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_class::<PyCandle>()?;
+    m.add_class::<PyIndicators>()?;
+    m.add_class::<PyIndicatorPipeline>()?;
+    m.add_class::<PyAccount>()?;
+    m.add_function(wrap_pyfunction!(configure, m)?)?;
+    m.add_function(wrap_pyfunction!(fetch_candles, m)?)?;
+    m.add_function(wrap_pyfunction!(latest_price, m)?)?;
+    m.add_function(wrap_pyfunction!(run_backtest, m)?)?;
+    Ok(())
+}
@@ -0,0 +1,85 @@
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, Instant};
+use parking_lot::Mutex;
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::StatusCode;
+use tokio::time::sleep;
+
+// --- 🚥 EXCHANGE RATE LIMIT THROTTLE ---
+// Shared by every task that calls the exchange. A 429 blocks all calls for its Retry-After,
+// then market data stays paused for an exponential backoff while orders may go again.
+// A nearly exhausted budget (x-ratelimit-remaining) pauses market data only, keeping room for orders.
+
+const MARKET_DATA_RESERVE: u64 = 5;   // Requests left in the window that are kept for orders
+const MAX_BACKOFF_SECS: u64 = 120;
+const MAX_ORDER_WAIT: Duration = Duration::from_secs(3);
+
+struct Throttle {
+    blocked_until: Option<Instant>,        // Everything waits (Retry-After of the last 429)
+    market_paused_until: Option<Instant>,  // Market data waits (backoff / tight budget)
+    consecutive_429: u32,
+}
+
+static THROTTLE: Mutex<Throttle> = Mutex::new(Throttle { blocked_until: None, market_paused_until: None, consecutive_429: 0 });
+static HITS: AtomicU64 = AtomicU64::new(0);
+
+fn header_secs(headers: &HeaderMap, name: &str) -> Option<u64> {
+    headers.get(name)?.to_str().ok()?.trim().parse::<f64>().ok().map(|v| v.ceil() as u64)
+}
+
+fn remaining_until(until: Option<Instant>) -> Option<Duration> {
+    until.and_then(|t| t.checked_duration_since(Instant::now())).filter(|d| !d.is_zero())
+}
+
+/// Feeds a response's status and rate-limit headers into the throttle.
+pub fn observe(status: StatusCode, headers: &HeaderMap) {
+    let mut t = THROTTLE.lock();
+    let now = Instant::now();
+
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        HITS.fetch_add(1, Ordering::Relaxed);
+        t.consecutive_429 += 1;
+        let retry_after = header_secs(headers, RETRY_AFTER.as_str()).unwrap_or(1).max(1);
+        let backoff = (retry_after << t.consecutive_429.min(7)).min(MAX_BACKOFF_SECS);
+        t.blocked_until = Some(now + Duration::from_secs(retry_after));
+        t.market_paused_until = Some(now + Duration::from_secs(backoff));
+        eprintln!("Rate limited (HTTP 429 #{}): all calls wait {}s, market data {}s", t.consecutive_429, retry_after, backoff);
+        return;
+    }
+
+    t.consecutive_429 = 0;
+    if let Some(remaining) = header_secs(headers, "x-ratelimit-remaining") {
+        if remaining <= MARKET_DATA_RESERVE {
+            // Reset is either seconds until the window resets or an epoch timestamp
+            let reset = match header_secs(headers, "x-ratelimit-reset").unwrap_or(1) {
+                secs if secs > 1_000_000_000 => secs.saturating_sub(chrono::Utc::now().timestamp() as u64),
+                secs => secs,
+            };
+            t.market_paused_until = Some(now + Duration::from_secs(reset.clamp(1, MAX_BACKOFF_SECS)));
+        }
+    }
+}
+
+/// Why market-data polling should be skipped right now, if it should.
+pub fn market_data_paused() -> Option<String> {
+    let t = THROTTLE.lock();
+    remaining_until(t.market_paused_until).map(|d| format!("rate limited ({}s)", d.as_secs() + 1))
+}
+
+/// Orders wait out a short 429 block; a longer one fails the order instead of risking a ban.
+pub async fn order_permit() -> Result<(), String> {
+    let wait = remaining_until(THROTTLE.lock().blocked_until);
+    match wait {
+        None => Ok(()),
+        Some(d) if d <= MAX_ORDER_WAIT => {
+            sleep(d).await;
+            Ok(())
+        },
+        Some(d) => Err(format!("rate limited, retry in {}s", d.as_secs() + 1)),
+    }
+}
+
+/// Total HTTP 429 responses since start.
+pub fn hits() -> u64 {
+    HITS.load(Ordering::Relaxed)
+}
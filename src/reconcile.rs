@@ -0,0 +1,112 @@
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+use crate::executor::Fill;
+use crate::symbols::{self, Venue};
+use crate::{bybit, money, okx, ratelimit, signed_headers};
+
+// --- 🔍 EXTERNAL TRADE RECONCILIATION ---
+// A sell from the exchange app (or anything else on the same account) while the bot holds a
+// position would leave it managing coins that are gone. In live mode every balance sync compares
+// the base balance with the previous one. The comparison restarts after each of the bot's own
+// orders, so a change in between was made outside the bot; the account's fills since the previous
+// sync say what traded and at what price. When the balance no longer covers the open position, the
+// position is cut to what is left (closed when that is below the minimum order) and the difference
+// is booked as an EXTERNAL SELL at those fills' average price, or the last price when there are
+// none (a withdrawal). External buys and changes to coins the bot doesn't hold are only reported.
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct ReconcileConfig {
+    pub enabled: bool,
+    pub tolerance_pct: f64, // Of the position; smaller gaps (fees taken in the base coin, rounding) are ignored
+}
+
+impl Default for ReconcileConfig {
+    fn default() -> Self {
+        ReconcileConfig { enabled: true, tolerance_pct: 1.0 }
+    }
+}
+
+/// A fill on the account that the bot did not place.
+pub struct Execution {
+    pub sell: bool,
+    pub fill: Fill,
+}
+
+/// What changed on the account outside the bot since the previous balance sync.
+pub struct Change {
+    pub delta: Decimal,         // Base balance change
+    pub sold: Decimal,          // To take off the open position
+    pub price: Option<Decimal>, // Average of the external sells
+    pub fills: usize,
+}
+
+#[derive(Default)]
+pub struct Reconciler {
+    baseline: Option<(Decimal, i64)>, // Base balance and when it was read (millis)
+}
+
+impl Reconciler {
+    /// Forgets the last balance: one of the bot's own orders may have changed it.
+    pub fn reset(&mut self) {
+        self.baseline = None;
+    }
+
+    /// Compares a fresh base balance with the previous one and with the open position (`held`).
+    pub async fn check(&mut self, client: &reqwest::Client, cfg: &ReconcileConfig, base: Decimal, held: Decimal, timeout: Duration) -> Option<Change> {
+        let now_ms = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis() as i64;
+        let previous = self.baseline.replace((base, now_ms));
+        let market = symbols::active();
+        let delta = previous.map_or(Decimal::ZERO, |(before, _)| base - before);
+        let tolerance = (held * money::dec(cfg.tolerance_pct / 100.0)).max(market.min_qty);
+        let short = held - base > tolerance;
+        if !short && delta.abs() < market.min_qty { return None; }
+
+        // Without a previous sync there is no window that excludes the bot's own fills
+        let external = match previous {
+            Some((_, since)) => fills(client, since, timeout).await.unwrap_or_else(|e| {
+                eprintln!("Reconcile Error: fills: {}", e);
+                Vec::new()
+            }),
+            None => Vec::new(),
+        };
+        let (sold_qty, sold_value) = external.iter().filter(|e| e.sell)
+            .fold((Decimal::ZERO, Decimal::ZERO), |(q, v), e| (q + e.fill.qty, v + e.fill.qty * e.fill.price));
+        let price = (sold_qty > Decimal::ZERO).then(|| sold_value / sold_qty);
+        let sold = match short {
+            false => Decimal::ZERO,
+            // What is left can't be sold on its own
+            true if base < market.min_qty => held,
+            true => held - base,
+        };
+        Some(Change { delta, sold, price, fills: external.len() })
+    }
+}
+
+/// The account's fills on the traded pair since `since_ms`, without the bot's own orders where the
+/// venue tells them apart.
+async fn fills(client: &reqwest::Client, since_ms: i64, timeout: Duration) -> Result<Vec<Execution>, String> {
+    match symbols::venue() {
+        Venue::Bybit => bybit::fills(client, since_ms, timeout).await,
+        Venue::Okx => okx::fills(client, since_ms, timeout).await,
+        Venue::CoinDcx => coindcx_fills(client, since_ms, timeout).await,
+    }
+}
+
+async fn coindcx_fills(client: &reqwest::Client, since_ms: i64, timeout: Duration) -> Result<Vec<Execution>, String> {
+    let timestamp = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_millis();
+    let body_str = serde_json::json!({
+        "symbol": symbols::active().order_code, "from_timestamp": since_ms, "limit": 500, "timestamp": timestamp,
+    }).to_string();
+    let res = client.post("https://api.coindcx.com/exchange/v1/orders/trade_history").headers(signed_headers(&body_str)).body(body_str)
+        .timeout(timeout).send().await.map_err(|e| e.to_string())?;
+    ratelimit::observe(res.status(), res.headers());
+    let trades = res.json::<Vec<Value>>().await.map_err(|e| e.to_string())?;
+    Ok(trades.iter().map(|t| Execution {
+        sell: t["side"] == "sell",
+        fill: Fill { price: money::from_value(&t["price"]), qty: money::from_value(&t["quantity"]) },
+    }).collect())
+}
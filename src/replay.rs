@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+
+use crate::fills::Matching;
+use crate::ticks::{self, Tick};
+use crate::Candle;
+
+// --- ⏩ ACCELERATED SIMULATION (historical candles on a virtual clock) ---
+// With speed > 1 in SIMULATION_MODE the live loop trades through recent exchange history
+// instead of waiting for real ticks, e.g. a day of 1m candles in a couple of minutes. The replay
+// subcommand drives the same loop from recorded data instead: the tick recorder's trades, each at
+// its own time, or the exchange's candles for a past range.
+
+const CANDLE_MS: i64 = 60_000;
+pub const WARMUP_CANDLES: usize = crate::candles::WARMUP; // Seeded into the DB for the indicators, not traded
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SimulationConfig {
+    pub speed: f64,           // 1 = live ticks on the wall clock
+    pub history_candles: u32, // Candles fetched for an accelerated run (warm-up included)
+    pub seed: u64,            // Fill model RNG seed; same seed + same data = same trades
+    pub slippage_bps: f64,    // Max adverse slippage per simulated fill, 0 = fill at the signal price
+    pub spread_bps: f64,      // Bid/ask spread crossed by a simulated fill when the live book can't be used
+    pub matching: Matching,   // book = rest until the live market trades through, instant = fill at the signal
+}
+
+impl Default for SimulationConfig {
+    fn default() -> Self {
+        SimulationConfig { speed: 1.0, history_candles: 1440, seed: 1, slippage_bps: 0.0, spread_bps: 2.0, matching: Matching::Book }
+    }
+}
+
+pub struct CandleReplay {
+    candles: Vec<Candle>, // Oldest first, warm-up removed
+    ticks: Vec<Tick>,     // Recorded trades, replayed instead of the candles' synthetic ticks
+}
+
+impl CandleReplay {
+    /// Splits off the warm-up candles; None if there is nothing left to replay.
+    pub fn new(mut candles: Vec<Candle>) -> Option<(Self, Vec<Candle>)> {
+        candles.sort_by_key(|c| c.time);
+        if candles.len() <= WARMUP_CANDLES { return None; }
+        let rest = candles.split_off(WARMUP_CANDLES);
+        Some((CandleReplay { candles: rest, ticks: Vec::new() }, candles))
+    }
+
+    /// Recorded trades (oldest first) from `start` on; the ones before it become the warm-up
+    /// candles. None if nothing was recorded from `start`.
+    pub fn from_ticks(mut recorded: Vec<Tick>, start: i64) -> Option<(Self, Vec<Candle>)> {
+        let split = recorded.partition_point(|t| t.time < start);
+        let ticks = recorded.split_off(split);
+        if ticks.is_empty() { return None; }
+        Some((CandleReplay { candles: ticks::candles(&ticks), ticks }, ticks::candles(&recorded)))
+    }
+
+    pub fn start_ms(&self) -> i64 {
+        self.ticks.first().map_or(self.candles[0].time, |t| t.time)
+    }
+
+    /// When the next recorded trade after `ts` happened, so the loop wakes for each one. None for
+    /// a candle replay, which steps on the loop's own grid.
+    pub fn next_tick(&self, ts: i64) -> Option<i64> {
+        self.ticks.get(self.ticks.partition_point(|t| t.time <= ts)).map(|t| t.time)
+    }
+
+    pub fn candle_count(&self) -> usize {
+        self.candles.len()
+    }
+
+    /// Synthetic tick inside the candle covering `ts`: open -> first extreme -> second extreme -> close,
+    /// or the last recorded trade in a tick replay. None once the replay has run past the end.
+    pub fn price_at(&self, ts: i64) -> Option<f64> {
+        if !self.ticks.is_empty() {
+            // The last recorded trade, until the one after the last
+            let idx = self.ticks.partition_point(|t| t.time <= ts).checked_sub(1)?;
+            return (idx + 1 < self.ticks.len() || ts == self.ticks[idx].time).then_some(self.ticks[idx].price);
+        }
+        let idx = self.candles.partition_point(|c| c.time <= ts).checked_sub(1)?;
+        let c = &self.candles[idx];
+        let offset = ts - c.time;
+        if offset >= CANDLE_MS {
+            // Gap in the history (no trades that minute): hold the last close until the next candle
+            return (idx + 1 < self.candles.len()).then_some(c.close);
+        }
+
+        // Bullish candles tend to print the low first, bearish ones the high
+        let (first, second) = if c.close >= c.open { (c.low, c.high) } else { (c.high, c.low) };
+        let path = [c.open, first, second, c.close];
+        let pos = offset as f64 / CANDLE_MS as f64 * 3.0;
+        let leg = (pos as usize).min(2);
+        let frac = pos - leg as f64;
+        Some(path[leg] + (path[leg + 1] - path[leg]) * frac)
+    }
+}
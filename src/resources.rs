@@ -0,0 +1,92 @@
+use std::fs;
+use std::time::{Duration, Instant};
+use serde::Serialize;
+use tokio::time::sleep;
+
+use crate::notify::{Event, EventKind, Notifier};
+use crate::{add_log, SharedState, DB_FILE, PAIR};
+
+// --- 🌡️ RESOURCE USAGE (Linux /proc + Raspberry Pi sysfs) ---
+
+const SAMPLE_SECS: u64 = 10;
+const CLOCK_TICKS: f64 = 100.0;  // USER_HZ on Linux (utime/stime units)
+const TEMP_ALERT_C: f64 = 80.0;  // Pi firmware starts soft-throttling around here
+const THROTTLED_FILE: &str = "/sys/devices/platform/soc/soc:firmware/get_throttled";
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct ResourceUsage {
+    pub cpu_pct: f64,             // Of one core
+    pub rss_mb: f64,
+    pub open_fds: usize,
+    pub db_size_mb: f64,          // Including the WAL file
+    pub soc_temp_c: Option<f64>,
+    pub throttled: bool,          // Firmware throttle flag, or temperature above TEMP_ALERT_C
+}
+
+fn cpu_ticks() -> Option<u64> {
+    // Fields after the "(comm)" part; utime and stime are the 12th and 13th of those
+    let stat = fs::read_to_string("/proc/self/stat").ok()?;
+    let rest = stat.rsplit_once(')')?.1;
+    let fields: Vec<&str> = rest.split_whitespace().collect();
+    Some(fields.get(11)?.parse::<u64>().ok()? + fields.get(12)?.parse::<u64>().ok()?)
+}
+
+fn rss_mb() -> f64 {
+    fs::read_to_string("/proc/self/status").ok()
+        .and_then(|s| s.lines().find(|l| l.starts_with("VmRSS:")).map(str::to_string))
+        .and_then(|l| l.split_whitespace().nth(1)?.parse::<f64>().ok())
+        .map(|kb| kb / 1024.0)
+        .unwrap_or(0.0)
+}
+
+fn open_fds() -> usize {
+    fs::read_dir("/proc/self/fd").map(|d| d.count()).unwrap_or(0)
+}
+
+fn db_size_mb() -> f64 {
+    let size = |p: String| fs::metadata(p).map(|m| m.len()).unwrap_or(0);
+    (size(DB_FILE.to_string()) + size(format!("{}-wal", DB_FILE))) as f64 / 1024.0 / 1024.0
+}
+
+fn soc_temp_c() -> Option<f64> {
+    let raw = fs::read_to_string("/sys/class/thermal/thermal_zone0/temp").ok()?;
+    Some(raw.trim().parse::<f64>().ok()? / 1000.0)
+}
+
+// Bits 0-3 are the "currently" flags: under-voltage, freq capped, throttled, soft temp limit
+fn firmware_throttled() -> bool {
+    fs::read_to_string(THROTTLED_FILE).ok()
+        .and_then(|s| u32::from_str_radix(s.trim().trim_start_matches("0x"), 16).ok())
+        .is_some_and(|flags| flags & 0xF != 0)
+}
+
+pub async fn monitor(state: SharedState) {
+    let notifier = Notifier::from_env();
+    let mut last = (Instant::now(), cpu_ticks().unwrap_or(0));
+    let mut was_throttled = false;
+
+    loop {
+        sleep(Duration::from_secs(SAMPLE_SECS)).await;
+
+        let now = (Instant::now(), cpu_ticks().unwrap_or(0));
+        let wall = now.0.duration_since(last.0).as_secs_f64();
+        let cpu_pct = if wall > 0.0 { (now.1.saturating_sub(last.1)) as f64 / CLOCK_TICKS / wall * 100.0 } else { 0.0 };
+        last = now;
+
+        let soc_temp_c = soc_temp_c();
+        let throttled = firmware_throttled() || soc_temp_c.is_some_and(|t| t >= TEMP_ALERT_C);
+        let usage = ResourceUsage { cpu_pct, rss_mb: rss_mb(), open_fds: open_fds(), db_size_mb: db_size_mb(), soc_temp_c, throttled };
+
+        if throttled != was_throttled {
+            let msg = if throttled {
+                format!("🌡️ Pi is throttling (SoC {:.1}°C), tick latency will suffer", soc_temp_c.unwrap_or(0.0))
+            } else {
+                "🌡️ Throttling cleared".to_string()
+            };
+            add_log(&state, msg.clone());
+            notifier.send(Event::new(EventKind::Alert, PAIR, state.read().price, 0.0, 0.0, &msg));
+            was_throttled = throttled;
+        }
+        state.write().resources = usage;
+    }
+}
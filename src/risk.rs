@@ -0,0 +1,230 @@
+use std::collections::HashSet;
+use chrono::{NaiveDate, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::money;
+
+// --- 🚦 RISK LIMITS (kill switches) ---
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskConfig {
+    pub daily_loss_limit: f64,     // USDT, 0 = disabled
+    pub daily_loss_limit_pct: f64, // % of trade capital, 0 = disabled
+    pub max_drawdown: f64,         // USDT below peak equity, 0 = disabled
+    pub max_drawdown_pct: f64,     // % of trade capital + peak profit, 0 = disabled
+    pub flatten_on_halt: bool,     // Close the open position when a limit trips
+    pub max_position_notional: f64, // USDT per position, 0 = disabled
+    pub max_wallet_pct: f64,       // % of available quote balance per trade, 0 = disabled
+    pub max_open_positions: u32,   // Across all pairs, 0 = disabled
+    pub max_total_notional: f64,   // USDT across all pairs, 0 = disabled
+    pub correlation_groups: Vec<Vec<String>>, // Pairs that count as one bucket: one of them open at a time
+    pub max_locked_pct: f64,       // % of the quote across all wallets locked (margin, open orders) before entries stop, 0 = disabled
+}
+
+pub fn validate(cfg: &RiskConfig) -> Result<(), String> {
+    let mut seen = HashSet::new();
+    for pair in cfg.correlation_groups.iter().flatten() {
+        if !seen.insert(pair.to_uppercase()) {
+            return Err(format!("[risk] {} is in more than one correlation group", pair));
+        }
+    }
+    Ok(())
+}
+
+/// Open positions in one pair, as the portfolio limits count them.
+#[derive(Clone, Debug, Default)]
+pub struct Holding {
+    pub pair: String,
+    pub positions: u32,
+    pub notional: Decimal, // At the last price
+}
+
+/// The quote currency in one of the account's wallets (spot, futures, funding), from the last
+/// balance sync. A wallet that could not be read carries the error and counts for nothing.
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct WalletBalance {
+    pub wallet: &'static str,
+    pub available: Decimal,
+    pub locked: Decimal, // In open orders or held as margin
+    pub error: Option<String>,
+}
+
+impl WalletBalance {
+    pub fn new(wallet: &'static str, available: Decimal, locked: Decimal) -> Self {
+        WalletBalance { wallet, available, locked, error: None }
+    }
+
+    pub fn failed(wallet: &'static str, error: String) -> Self {
+        WalletBalance { wallet, error: Some(error), ..Default::default() }
+    }
+}
+
+/// The correlation group `pair` is in, if any.
+fn group_of<'a>(cfg: &'a RiskConfig, pair: &str) -> Option<&'a Vec<String>> {
+    cfg.correlation_groups.iter().find(|group| group.iter().any(|p| p.eq_ignore_ascii_case(pair)))
+}
+
+/// Single gate for every new position, whatever the strategy asked for. `portfolio` is what is
+/// already open, in this pair and any other, and `wallets` the quote in every wallet of the
+/// account. Returns the (possibly reduced) quantity, or why the entry is rejected.
+pub fn check_entry(
+    cfg: &RiskConfig, pair: &str, price: Decimal, qty: Decimal, wallet_quote: Decimal, portfolio: &[Holding], wallets: &[WalletBalance],
+) -> Result<Decimal, String> {
+    let open = portfolio.iter().filter(|h| h.positions > 0);
+    let open_positions: u32 = open.clone().map(|h| h.positions).sum();
+    if cfg.max_open_positions > 0 && open_positions >= cfg.max_open_positions {
+        return Err(format!("{} open positions (max {})", open_positions, cfg.max_open_positions));
+    }
+    if let Some(group) = group_of(cfg, pair) {
+        let correlated = open.clone().find(|h| !h.pair.eq_ignore_ascii_case(pair) && group.iter().any(|p| p.eq_ignore_ascii_case(&h.pair)));
+        if let Some(other) = correlated {
+            return Err(format!("{} is open, correlated with {} ({})", other.pair, pair, group.join(", ")));
+        }
+    }
+    if cfg.max_locked_pct > 0.0 {
+        let locked: Decimal = wallets.iter().map(|w| w.locked).sum();
+        let total = locked + wallets.iter().map(|w| w.available).sum::<Decimal>();
+        if total > Decimal::ZERO && locked * Decimal::ONE_HUNDRED / total > money::dec(cfg.max_locked_pct) {
+            return Err(format!("{:.2} of {:.2} locked across wallets (max {}%)", locked, total, cfg.max_locked_pct));
+        }
+    }
+
+    let mut notional = price * qty;
+    if cfg.max_position_notional > 0.0 {
+        notional = notional.min(money::dec(cfg.max_position_notional));
+    }
+    if cfg.max_total_notional > 0.0 {
+        let exposed: Decimal = open.map(|h| h.notional).sum();
+        let room = money::dec(cfg.max_total_notional) - exposed;
+        if room <= Decimal::ZERO {
+            return Err(format!("${:.2} open across all pairs (max ${:.2})", exposed, cfg.max_total_notional));
+        }
+        notional = notional.min(room);
+    }
+    if cfg.max_wallet_pct > 0.0 {
+        notional = notional.min(wallet_quote * money::dec(cfg.max_wallet_pct) / Decimal::ONE_HUNDRED);
+    }
+    if notional <= Decimal::ZERO || price <= Decimal::ZERO {
+        return Err(format!("no size left within limits (wallet ${:.2})", wallet_quote));
+    }
+    Ok(notional / price)
+}
+
+/// Halt state shared with the API; entries stay blocked until someone re-arms.
+/// Persisted in the DB so a restart doesn't quietly resume trading.
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RiskState {
+    pub halted: Option<String>,    // Reason
+    pub halted_at: Option<String>, // RFC3339
+    pub day: Option<String>,       // Trading day (YYYY-MM-DD) the day figures below belong to
+    pub day_pnl: f64,              // Realized + unrealized for the current trading day
+    pub day_realized: f64,         // Realized part of day_pnl, carried into the loop after a restart
+    pub rearm_offset: f64,         // Day P&L at the last re-arm, losses before it are not counted twice
+    pub equity: f64,               // Cumulative P&L across restarts (realized + unrealized)
+    pub peak_equity: f64,
+    pub realized_total: f64,       // Cumulative realized P&L across restarts
+    #[serde(skip)]
+    realized_base: f64,            // Realized P&L carried over from previous runs
+}
+
+impl RiskState {
+    pub fn halt(&mut self, reason: String) {
+        self.halted = Some(reason);
+        self.halted_at = Some(Utc::now().to_rfc3339());
+    }
+
+    pub fn rearm(&mut self) {
+        self.halted = None;
+        self.halted_at = None;
+        self.rearm_offset = self.day_pnl;
+        self.peak_equity = self.equity;
+    }
+
+    /// Restores a persisted state; the day figures are kept if they are from `today`'s trading day.
+    pub fn restore(mut saved: RiskState, today: NaiveDate) -> Self {
+        saved.realized_base = saved.realized_total;
+        if !saved.is_day(today) {
+            saved.new_day(today);
+        }
+        saved
+    }
+
+    pub fn is_day(&self, day: NaiveDate) -> bool {
+        self.day.as_deref() == Some(day.to_string().as_str())
+    }
+
+    pub fn new_day(&mut self, day: NaiveDate) {
+        self.day = Some(day.to_string());
+        self.day_pnl = 0.0;
+        self.day_realized = 0.0;
+        self.rearm_offset = 0.0;
+    }
+
+    /// Updates the day P&L and returns the halt reason the first time the daily loss limit is breached.
+    pub fn check_daily_loss(&mut self, cfg: &RiskConfig, day_realized: f64, unrealized: f64, capital: f64) -> Option<String> {
+        let day_pnl = day_realized + unrealized;
+        self.day_realized = day_realized;
+        self.day_pnl = day_pnl;
+        if self.halted.is_some() { return None; }
+
+        // The tighter of the two limits applies
+        let limit = [cfg.daily_loss_limit, capital * cfg.daily_loss_limit_pct / 100.0]
+            .into_iter()
+            .filter(|l| *l > 0.0)
+            .reduce(f64::min)?;
+
+        let loss = self.rearm_offset.min(0.0) - day_pnl;
+        if loss >= limit {
+            let reason = format!("Daily loss limit hit (-${:.2} >= ${:.2})", loss, limit);
+            self.halt(reason.clone());
+            Some(reason)
+        } else {
+            None
+        }
+    }
+
+    /// Updates equity / peak and returns the halt reason the first time the drawdown limit is breached.
+    pub fn check_drawdown(&mut self, cfg: &RiskConfig, realized: f64, unrealized: f64, capital: f64) -> Option<String> {
+        self.realized_total = self.realized_base + realized;
+        self.equity = self.realized_total + unrealized;
+        self.peak_equity = self.peak_equity.max(self.equity);
+        if self.halted.is_some() { return None; }
+
+        let limit = [cfg.max_drawdown, (capital + self.peak_equity.max(0.0)) * cfg.max_drawdown_pct / 100.0]
+            .into_iter()
+            .filter(|l| *l > 0.0)
+            .reduce(f64::min)?;
+
+        let drawdown = self.peak_equity - self.equity;
+        if drawdown >= limit {
+            let reason = format!("Max drawdown hit (-${:.2} from peak ${:.2})", drawdown, self.peak_equity);
+            self.halt(reason.clone());
+            Some(reason)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn saved_on(day: NaiveDate) -> RiskState {
+        RiskState { day: Some(day.to_string()), day_pnl: -150.0, day_realized: -120.0, rearm_offset: -80.0, ..RiskState::default() }
+    }
+
+    #[test]
+    fn restart_keeps_the_days_losses_until_the_next_day() {
+        let today = NaiveDate::from_ymd_opt(2024, 3, 1).unwrap();
+        let same_day = RiskState::restore(saved_on(today), today);
+        assert_eq!((same_day.day_pnl, same_day.day_realized, same_day.rearm_offset), (-150.0, -120.0, -80.0));
+
+        let next_day = RiskState::restore(saved_on(today), today.succ_opt().unwrap());
+        assert_eq!((next_day.day_pnl, next_day.day_realized, next_day.rearm_offset), (0.0, 0.0, 0.0));
+        assert!(next_day.is_day(today.succ_opt().unwrap()));
+    }
+}
@@ -0,0 +1,143 @@
+use std::future::Future;
+use std::io;
+use std::sync::OnceLock;
+use std::thread;
+use serde::{Deserialize, Serialize};
+use tokio::runtime::{Builder, Runtime};
+
+// --- 🧵 RUNTIME TOPOLOGY ---
+// The async runtime is built from config before anything else starts. SQLite calls run on their
+// own small set of blocking threads, so a slow query never occupies an async worker (on a
+// current-thread runtime that worker is also the trading loop).
+// Optionally (Linux) the trading loop gets a core of its own with a realtime priority, and every
+// other thread (web server, exporters, DB) is kept off that core.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RuntimeFlavor {
+    CurrentThread, // Everything on one thread: most predictable latency on a small board
+    #[default]
+    MultiThread,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RuntimeConfig {
+    pub flavor: RuntimeFlavor,
+    pub worker_threads: usize, // multi_thread only, 0 = one per core
+    pub db_threads: usize,     // Dedicated SQLite threads
+    pub pin_trading: bool,     // Give the trading loop (feed, strategy, executor) a core of its own
+    pub trading_core: usize,   // That core, counted from 0
+    pub trading_priority: u8,  // SCHED_FIFO priority 1-99 for it (needs CAP_SYS_NICE), 0 = normal
+}
+
+impl Default for RuntimeConfig {
+    fn default() -> Self {
+        RuntimeConfig {
+            flavor: RuntimeFlavor::MultiThread,
+            worker_threads: 0,
+            db_threads: 2,
+            pin_trading: false,
+            trading_core: 3, // Last core of a Pi 4/5
+            trading_priority: 10,
+        }
+    }
+}
+
+// Never driven itself: only its blocking pool is used, as the dedicated SQLite threads
+static DB_RUNTIME: OnceLock<Runtime> = OnceLock::new();
+// Set once every other thread is kept off it
+static TRADING_CORE: OnceLock<usize> = OnceLock::new();
+
+pub fn build(cfg: &RuntimeConfig) -> io::Result<Runtime> {
+    // Threads inherit the affinity of the thread that starts them, so this covers every runtime thread
+    if cfg.pin_trading {
+        let cores = thread::available_parallelism().map_or(1, |n| n.get());
+        let others = (0..cores).filter(|c| *c != cfg.trading_core).collect::<Vec<_>>();
+        if cfg.trading_core >= cores || others.is_empty() {
+            eprintln!("⚠️ Not pinning the trading loop: core {} of {} cannot be reserved", cfg.trading_core, cores);
+        } else {
+            match set_affinity(&others) {
+                Ok(()) => { let _ = TRADING_CORE.set(cfg.trading_core); },
+                Err(e) => eprintln!("⚠️ Not pinning the trading loop: could not keep other threads off core {}: {}", cfg.trading_core, e),
+            }
+        }
+    }
+
+    let db = Builder::new_current_thread().max_blocking_threads(cfg.db_threads.max(1)).thread_name("scalper-db").build()?;
+    let _ = DB_RUNTIME.set(db);
+
+    let mut builder = match cfg.flavor {
+        RuntimeFlavor::CurrentThread => Builder::new_current_thread(),
+        RuntimeFlavor::MultiThread => {
+            let mut builder = Builder::new_multi_thread();
+            if cfg.worker_threads > 0 { builder.worker_threads(cfg.worker_threads); }
+            builder
+        },
+    };
+    builder.enable_all().thread_name("scalper-worker").build()
+}
+
+/// Runs SQLite work on the dedicated DB threads (inline if the runtime was not built from config).
+pub async fn db<T: Send + 'static>(work: impl FnOnce() -> T + Send + 'static) -> T {
+    match DB_RUNTIME.get() {
+        Some(rt) => rt.spawn_blocking(work).await.expect("DB task panicked"),
+        None => work(),
+    }
+}
+
+/// Runs the trading loop: on the shared runtime, or pinned to its own core on a dedicated thread.
+pub fn spawn_trading(cfg: &RuntimeConfig, task: impl Future<Output = ()> + Send + 'static) {
+    let Some(&core) = TRADING_CORE.get() else {
+        tokio::spawn(task);
+        return;
+    };
+    let priority = cfg.trading_priority;
+    let started = thread::Builder::new().name("scalper-trading".to_string()).spawn(move || {
+        match set_affinity(&[core]) {
+            Ok(()) => println!("📌 Trading loop pinned to core {}", core),
+            Err(e) => eprintln!("⚠️ Could not pin the trading loop to core {}: {}", core, e),
+        }
+        if priority > 0 {
+            if let Err(e) = raise_priority(priority) {
+                eprintln!("⚠️ Could not raise the trading loop priority: {}", e);
+            }
+        }
+        match Builder::new_current_thread().enable_all().build() {
+            Ok(rt) => rt.block_on(task),
+            Err(e) => eprintln!("❌ Could not start the trading runtime: {}", e),
+        }
+    });
+    if let Err(e) = started {
+        eprintln!("❌ Could not start the trading thread: {}", e);
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn set_affinity(cores: &[usize]) -> Result<(), String> {
+    // SAFETY: cpu_set_t is plain data; pid 0 is the calling thread
+    let rc = unsafe {
+        let mut set: libc::cpu_set_t = std::mem::zeroed();
+        for core in cores { libc::CPU_SET(*core, &mut set); }
+        libc::sched_setaffinity(0, std::mem::size_of::<libc::cpu_set_t>(), &set)
+    };
+    if rc == 0 { Ok(()) } else { Err(io::Error::last_os_error().to_string()) }
+}
+
+#[cfg(target_os = "linux")]
+fn raise_priority(priority: u8) -> Result<(), String> {
+    let param = libc::sched_param { sched_priority: i32::from(priority.min(99)) };
+    // SAFETY: pid 0 is the calling thread
+    let rc = unsafe { libc::sched_setscheduler(0, libc::SCHED_FIFO, &param) };
+    if rc == 0 { Ok(()) } else { Err(io::Error::last_os_error().to_string()) }
+}
+
+#[cfg(not(target_os = "linux"))]
+fn set_affinity(_cores: &[usize]) -> Result<(), String> {
+    Err("only supported on Linux".to_string())
+}
+
+#[cfg(not(target_os = "linux"))]
+fn raise_priority(_priority: u8) -> Result<(), String> {
+    Err("only supported on Linux".to_string())
+}
@@ -0,0 +1,82 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::{Duration, Instant};
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use tokio::time::sleep;
+
+use crate::config::SharedConfig;
+use crate::symbols;
+
+// --- 🧭 PRICE SANITY CHECK ---
+// A wrong decimal, a stuck feed or a bad print on the trading venue would otherwise go straight
+// into an order. A second, independent source (another exchange's ticker or an aggregator) is
+// polled in the background, and the trading loop refuses to act on a price that is more than
+// max_deviation_pct away from it. The loop only compares against the last reference it has, it
+// never waits for one. Not used in replays: their prices are historical.
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SanityConfig {
+    pub enabled: bool,
+    pub url: String,     // {base} and {quote} are replaced by the pair's currencies
+    pub pointer: String, // JSON pointer to the price in the response
+    pub max_deviation_pct: f64,
+    pub max_age_secs: u64, // An older reference is ignored
+    pub poll_secs: u64,
+    pub require_reference: bool, // Also refuse to trade while there is no fresh reference
+}
+
+impl Default for SanityConfig {
+    fn default() -> Self {
+        SanityConfig {
+            enabled: false,
+            url: "https://api.binance.com/api/v3/ticker/price?symbol={base}{quote}".to_string(),
+            pointer: "/price".to_string(),
+            max_deviation_pct: 2.0,
+            max_age_secs: 30,
+            poll_secs: 5,
+            require_reference: false,
+        }
+    }
+}
+
+static RUNNING: AtomicBool = AtomicBool::new(false);
+static REFERENCE: Mutex<Option<(f64, Instant)>> = parking_lot::const_mutex(None);
+
+/// Why `price` should not be acted on, if the reference disagrees with it.
+pub fn reject_reason(cfg: &SanityConfig, price: f64) -> Option<String> {
+    if !cfg.enabled || !RUNNING.load(Ordering::Relaxed) { return None; }
+    let fresh = REFERENCE.lock().filter(|(_, at)| at.elapsed() <= Duration::from_secs(cfg.max_age_secs));
+    let Some((reference, _)) = fresh else {
+        return cfg.require_reference.then(|| "no fresh reference price".to_string());
+    };
+    let deviation = (price - reference).abs() / reference * 100.0;
+    (deviation > cfg.max_deviation_pct)
+        .then(|| format!("price {:.2} is {:.1}% off the reference {:.2}", price, deviation, reference))
+}
+
+async fn fetch(client: &reqwest::Client, cfg: &SanityConfig) -> Result<f64, String> {
+    let market = symbols::active();
+    let url = cfg.url.replace("{base}", market.base()).replace("{quote}", market.quote());
+    let res = client.get(&url).send().await.map_err(|e| e.to_string())?;
+    let body: Value = res.json().await.map_err(|e| e.to_string())?;
+    let value = body.pointer(&cfg.pointer).ok_or_else(|| format!("no {} in the response", cfg.pointer))?;
+    let price = value.as_str().and_then(|s| s.parse().ok()).or_else(|| value.as_f64()).unwrap_or(0.0);
+    if price > 0.0 { Ok(price) } else { Err(format!("bad price {}", value)) }
+}
+
+pub async fn monitor(config: SharedConfig) {
+    let client = reqwest::Client::builder().timeout(Duration::from_secs(5)).build().unwrap();
+    RUNNING.store(true, Ordering::Relaxed);
+    loop {
+        let cfg = config.read().sanity.clone();
+        if cfg.enabled {
+            match fetch(&client, &cfg).await {
+                Ok(price) => *REFERENCE.lock() = Some((price, Instant::now())),
+                Err(e) => eprintln!("Price Reference Error: {}", e),
+            }
+        }
+        sleep(Duration::from_secs(cfg.poll_secs.max(1))).await;
+    }
+}
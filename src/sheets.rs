@@ -0,0 +1,192 @@
+use std::fs;
+use std::sync::OnceLock;
+use std::time::{Duration, Instant};
+use base64::engine::general_purpose::URL_SAFE_NO_PAD;
+use base64::Engine;
+use chrono::{DateTime, NaiveDate, Utc};
+use rsa::pkcs1v15::SigningKey;
+use rsa::pkcs8::DecodePrivateKey;
+use rsa::signature::{SignatureEncoding, Signer};
+use rsa::RsaPrivateKey;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use sha2::Sha256;
+use tokio::sync::mpsc;
+use tokio::time::sleep;
+
+use crate::executor::Fill;
+use crate::{money, PAIR};
+
+// --- 📊 GOOGLE SHEETS EXPORT ---
+// Each closed trade, and a summary row at the end of each day, is appended to a Google Sheet as a
+// service account. Rows go through a background task; if the API is unreachable they are held and
+// retried every minute, so a row is late rather than lost (until the process exits).
+
+const SCOPE: &str = "https://www.googleapis.com/auth/spreadsheets";
+const RETRY_AFTER: Duration = Duration::from_secs(60);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SheetsConfig {
+    pub spreadsheet_id: String, // From the sheet's URL, empty = off
+    pub credentials: String,    // Service account key (JSON); share the sheet with its client_email
+    pub trades_sheet: String,   // Tab for closed trades
+    pub summary_sheet: String,  // Tab for daily summaries
+}
+
+impl Default for SheetsConfig {
+    fn default() -> Self {
+        SheetsConfig {
+            spreadsheet_id: String::new(),
+            credentials: "service_account.json".to_string(),
+            trades_sheet: "Trades".to_string(),
+            summary_sheet: "Daily".to_string(),
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq)]
+enum Tab {
+    Trades,
+    Summary,
+}
+
+struct Row {
+    tab: Tab,
+    values: Vec<Value>,
+}
+
+static QUEUE: OnceLock<mpsc::UnboundedSender<Row>> = OnceLock::new();
+
+pub fn start(cfg: SheetsConfig) {
+    if cfg.spreadsheet_id.is_empty() { return; }
+    let auth = match ServiceAccount::load(&cfg.credentials) {
+        Ok(auth) => auth,
+        Err(e) => return eprintln!("❌ Google Sheets export disabled: {}: {}", cfg.credentials, e),
+    };
+    let (tx, rx) = mpsc::unbounded_channel();
+    if QUEUE.set(tx).is_err() { return; }
+    tokio::spawn(write_loop(rx, cfg, auth));
+}
+
+fn submit(tab: Tab, values: Vec<Value>) {
+    if let Some(queue) = QUEUE.get() {
+        let _ = queue.send(Row { tab, values });
+    }
+}
+
+/// Time, pair, entry, exit, quantity, P&L, P&L %, reason.
+pub fn trade_closed(at: DateTime<Utc>, entry_price: Decimal, fill: Fill, profit: Decimal, gross: Decimal, reason: &str) {
+    let pct = if entry_price > Decimal::ZERO { money::float((fill.price - entry_price) / entry_price * Decimal::ONE_HUNDRED) } else { 0.0 };
+    submit(Tab::Trades, vec![
+        json!(at.format("%Y-%m-%d %H:%M:%S").to_string()), json!(PAIR), json!(entry_price), json!(fill.price),
+        json!(fill.qty), json!(profit), json!(pct), json!(reason), json!(gross),
+    ]);
+}
+
+/// Date, pair, trades closed, P&L.
+pub fn daily_summary(day: NaiveDate, trades: u32, pnl: f64) {
+    submit(Tab::Summary, vec![json!(day.to_string()), json!(PAIR), json!(trades), json!(pnl)]);
+}
+
+#[derive(Deserialize)]
+struct KeyFile {
+    client_email: String,
+    private_key: String,
+    token_uri: String,
+}
+
+/// OAuth for a service account: a self-signed JWT exchanged for an access token, reused until it expires.
+struct ServiceAccount {
+    email: String,
+    token_uri: String,
+    key: SigningKey<Sha256>,
+    token: Option<(String, Instant)>,
+}
+
+impl ServiceAccount {
+    fn load(path: &str) -> Result<Self, String> {
+        let raw = fs::read_to_string(path).map_err(|e| e.to_string())?;
+        let file: KeyFile = serde_json::from_str(&raw).map_err(|e| e.to_string())?;
+        let key = RsaPrivateKey::from_pkcs8_pem(&file.private_key).map_err(|e| e.to_string())?;
+        Ok(ServiceAccount { email: file.client_email, token_uri: file.token_uri, key: SigningKey::new(key), token: None })
+    }
+
+    fn assertion(&self) -> String {
+        let now = Utc::now().timestamp();
+        let header = URL_SAFE_NO_PAD.encode(r#"{"alg":"RS256","typ":"JWT"}"#);
+        let claims = json!({ "iss": self.email, "scope": SCOPE, "aud": self.token_uri, "iat": now, "exp": now + 3600 });
+        let signed = format!("{}.{}", header, URL_SAFE_NO_PAD.encode(claims.to_string()));
+        let signature = self.key.sign(signed.as_bytes()).to_bytes();
+        format!("{}.{}", signed, URL_SAFE_NO_PAD.encode(signature))
+    }
+
+    async fn token(&mut self, client: &reqwest::Client) -> Result<String, String> {
+        if let Some((token, expires)) = &self.token {
+            if Instant::now() < *expires { return Ok(token.clone()); }
+        }
+        #[derive(Deserialize)]
+        struct Granted {
+            access_token: String,
+            expires_in: u64,
+        }
+        let assertion = self.assertion();
+        let form = [("grant_type", "urn:ietf:params:oauth:grant-type:jwt-bearer"), ("assertion", assertion.as_str())];
+        let res = client.post(&self.token_uri).form(&form).send().await.map_err(|e| e.to_string())?;
+        if !res.status().is_success() {
+            return Err(format!("token request: HTTP {}: {}", res.status(), res.text().await.unwrap_or_default()));
+        }
+        let granted: Granted = res.json().await.map_err(|e| e.to_string())?;
+        // Renewed a minute early so a token never expires mid-request
+        let expires = Instant::now() + Duration::from_secs(granted.expires_in.saturating_sub(60));
+        self.token = Some((granted.access_token.clone(), expires));
+        Ok(granted.access_token)
+    }
+}
+
+async fn append(client: &reqwest::Client, token: &str, spreadsheet_id: &str, sheet: &str, rows: Vec<Vec<Value>>) -> Result<(), String> {
+    let mut url = reqwest::Url::parse("https://sheets.googleapis.com/v4/spreadsheets").map_err(|e| e.to_string())?;
+    if let Ok(mut path) = url.path_segments_mut() {
+        path.push(spreadsheet_id).push("values").push(&format!("{}!A1:append", sheet));
+    }
+    url.query_pairs_mut().append_pair("valueInputOption", "USER_ENTERED").append_pair("insertDataOption", "INSERT_ROWS");
+    let res = client.post(url).bearer_auth(token).json(&json!({ "values": rows })).send().await.map_err(|e| e.to_string())?;
+    if !res.status().is_success() {
+        return Err(format!("append to {}: HTTP {}: {}", sheet, res.status(), res.text().await.unwrap_or_default()));
+    }
+    Ok(())
+}
+
+/// Appends everything held, one request per tab; rows of a tab that failed stay in `pending`.
+async fn flush(client: &reqwest::Client, auth: &mut ServiceAccount, cfg: &SheetsConfig, pending: &mut Vec<Row>) -> Result<(), String> {
+    let token = auth.token(client).await?;
+    for (tab, sheet) in [(Tab::Trades, &cfg.trades_sheet), (Tab::Summary, &cfg.summary_sheet)] {
+        let rows: Vec<Vec<Value>> = pending.iter().filter(|r| r.tab == tab).map(|r| r.values.clone()).collect();
+        if rows.is_empty() { continue; }
+        append(client, &token, &cfg.spreadsheet_id, sheet, rows).await?;
+        pending.retain(|r| r.tab != tab);
+    }
+    Ok(())
+}
+
+async fn write_loop(mut rx: mpsc::UnboundedReceiver<Row>, cfg: SheetsConfig, mut auth: ServiceAccount) {
+    let client = reqwest::Client::builder().timeout(Duration::from_secs(20)).build().unwrap();
+    let mut pending: Vec<Row> = Vec::new();
+    loop {
+        if pending.is_empty() {
+            match rx.recv().await {
+                Some(row) => pending.push(row),
+                None => return,
+            }
+        } else {
+            sleep(RETRY_AFTER).await;
+        }
+        while let Ok(row) = rx.try_recv() {
+            pending.push(row);
+        }
+        if let Err(e) = flush(&client, &mut auth, &cfg, &mut pending).await {
+            eprintln!("Google Sheets Error: {} ({} rows held, retrying)", e, pending.len());
+        }
+    }
+}
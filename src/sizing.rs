@@ -0,0 +1,176 @@
+use serde::{Deserialize, Serialize};
+
+use crate::stats::Performance;
+
+// --- 📐 POSITION SIZING ---
+// How much quote currency the next entry puts in. trade_capital (a strategy parameter) is the
+// notional of every entry with the fixed method, and the starting equity for the others; equity
+// is that plus the realized P&L so far, so sizes grow and shrink with the results. With equity =
+// "wallet" it is the account itself instead, from the last balance sync: the quote balance plus
+// the base balance at the price, so deposits, withdrawals and trades outside the bot count too.
+// The risk limits ([risk] max_position_notional, max_wallet_pct) still cap whatever comes out of here.
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SizingMethod {
+    #[default]
+    Fixed,      // trade_capital per entry
+    EquityPct,  // equity_pct of equity
+    Volatility, // Loses risk_pct of equity if the trailing stop is hit right away
+    Kelly,      // Fraction of the Kelly bet from the trade history, capped
+}
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EquitySource {
+    #[default]
+    Capital, // trade_capital + realized P&L
+    Wallet,  // The exchange balances, refreshed at every balance sync
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SizingConfig {
+    pub method: SizingMethod,
+    pub equity: EquitySource,    // What the non-fixed methods take a share of
+    pub equity_pct: f64,         // equity_pct: % of equity per entry
+    pub risk_pct: f64,           // volatility: % of equity at risk down to the stop
+    pub kelly_fraction: f64,     // kelly: 0.5 = half Kelly
+    pub kelly_max_pct: f64,      // kelly: cap, % of equity
+    pub kelly_min_trades: usize, // kelly: fixed sizing until this many closed trades
+}
+
+impl Default for SizingConfig {
+    fn default() -> Self {
+        SizingConfig {
+            method: SizingMethod::Fixed,
+            equity: EquitySource::Capital,
+            equity_pct: 10.0,
+            risk_pct: 1.0,
+            kelly_fraction: 0.5,
+            kelly_max_pct: 25.0,
+            kelly_min_trades: 30,
+        }
+    }
+}
+
+pub fn validate(cfg: &SizingConfig) -> Result<(), String> {
+    let pct = |key: &str, value: f64| match value > 0.0 && value <= 100.0 {
+        true => Ok(()),
+        false => Err(format!("[sizing] {} must be above 0 and at most 100", key)),
+    };
+    pct("equity_pct", cfg.equity_pct)?;
+    pct("risk_pct", cfg.risk_pct)?;
+    pct("kelly_max_pct", cfg.kelly_max_pct)?;
+    if !(cfg.kelly_fraction > 0.0 && cfg.kelly_fraction <= 1.0) {
+        return Err("[sizing] kelly_fraction must be above 0 and at most 1".to_string());
+    }
+    Ok(())
+}
+
+/// What a sizer can look at.
+pub struct Account<'a> {
+    pub capital: f64,       // trade_capital
+    pub equity: f64,        // trade_capital + realized P&L, or the wallet
+    pub stop_distance: f64, // Fraction of the price down to the trailing stop
+    pub record: &'a Performance,
+}
+
+pub trait PositionSizer: Send + Sync {
+    fn name(&self) -> &'static str;
+    /// Notional for the next entry, or why there should be none.
+    fn notional(&self, account: &Account) -> Result<f64, String>;
+}
+
+/// trade_capital on every entry.
+pub struct FixedNotional;
+
+impl PositionSizer for FixedNotional {
+    fn name(&self) -> &'static str { "fixed" }
+
+    fn notional(&self, account: &Account) -> Result<f64, String> {
+        Ok(account.capital)
+    }
+}
+
+/// A fixed share of equity.
+pub struct FixedFractional {
+    pct: f64,
+}
+
+impl PositionSizer for FixedFractional {
+    fn name(&self) -> &'static str { "equity_pct" }
+
+    fn notional(&self, account: &Account) -> Result<f64, String> {
+        positive_equity(account).map(|equity| equity * self.pct / 100.0)
+    }
+}
+
+/// Risk per trade over the stop distance: a wider stop buys less.
+pub struct VolatilityTarget {
+    risk_pct: f64,
+}
+
+impl PositionSizer for VolatilityTarget {
+    fn name(&self) -> &'static str { "volatility" }
+
+    fn notional(&self, account: &Account) -> Result<f64, String> {
+        let equity = positive_equity(account)?;
+        if account.stop_distance <= 0.0 { return Err("no stop distance to size against".to_string()); }
+        // A tight stop means a big position; the risk limits are what keep it sane
+        Ok(equity * self.risk_pct / 100.0 / account.stop_distance)
+    }
+}
+
+/// A fraction of the Kelly bet, from the win rate and payoff of the closed trades.
+pub struct Kelly {
+    fraction: f64,
+    max_pct: f64,
+    min_trades: usize,
+}
+
+impl PositionSizer for Kelly {
+    fn name(&self) -> &'static str { "kelly" }
+
+    fn notional(&self, account: &Account) -> Result<f64, String> {
+        let record = account.record;
+        // Too few trades to estimate the edge from
+        if record.closed_trades < self.min_trades { return Ok(account.capital); }
+        let equity = positive_equity(account)?;
+        let win = record.win_rate / 100.0;
+        // f = W - (1 - W) / R, R the average win over the average loss
+        let kelly = match record.avg_loss < 0.0 {
+            true => win - (1.0 - win) / (record.avg_win / -record.avg_loss),
+            false => 1.0, // Never lost
+        };
+        if kelly <= 0.0 {
+            return Err(format!("no edge: win rate {:.0}%, avg win ${:.2} / loss ${:.2}", record.win_rate, record.avg_win, record.avg_loss));
+        }
+        Ok(equity * (kelly * self.fraction).min(self.max_pct / 100.0))
+    }
+}
+
+/// Equity from the configured source; `wallet` is the account valued at the current price.
+pub fn equity(cfg: &SizingConfig, capital: f64, realized: f64, wallet: f64) -> f64 {
+    match cfg.equity {
+        EquitySource::Capital => capital + realized,
+        EquitySource::Wallet => wallet,
+    }
+}
+
+fn positive_equity(account: &Account) -> Result<f64, String> {
+    match account.equity > 0.0 {
+        true => Ok(account.equity),
+        false => Err(format!("equity is ${:.2}", account.equity)),
+    }
+}
+
+/// Builds the configured sizer.
+pub fn build(cfg: &SizingConfig) -> Box<dyn PositionSizer> {
+    match cfg.method {
+        SizingMethod::Fixed => Box::new(FixedNotional),
+        SizingMethod::EquityPct => Box::new(FixedFractional { pct: cfg.equity_pct }),
+        SizingMethod::Volatility => Box::new(VolatilityTarget { risk_pct: cfg.risk_pct }),
+        SizingMethod::Kelly => Box::new(Kelly { fraction: cfg.kelly_fraction, max_pct: cfg.kelly_max_pct, min_trades: cfg.kelly_min_trades }),
+    }
+}
@@ -0,0 +1,114 @@
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+
+use crate::risk::Holding;
+use crate::BotState;
+
+// --- 🧺 POSITION SLOTS ---
+// Up to `count` independent positions (tranches) in the traded pair. Each slot has its own entry,
+// peak and trailing stop and exits on its own. Another slot only opens on a fresh entry signal at
+// least add_below_pct under the lowest open entry, so a deeper dip adds a tranche instead of the
+// same signal filling every slot. Whatever looks at the account as a whole (unrealized P&L, the
+// risk limits, reconciliation, flattening, the panic guard, the dashboard) sees the slots combined.
+
+pub const MAX_SLOTS: usize = 4;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SlotConfig {
+    pub count: usize,       // 1-MAX_SLOTS; 1 is a single position
+    pub add_below_pct: f64, // Under the lowest open entry, for another slot to open
+}
+
+impl Default for SlotConfig {
+    fn default() -> Self {
+        SlotConfig { count: 1, add_below_pct: 1.0 }
+    }
+}
+
+pub fn validate(cfg: &SlotConfig) -> Result<(), String> {
+    if !(1..=MAX_SLOTS).contains(&cfg.count) {
+        return Err(format!("[slots] count must be between 1 and {}", MAX_SLOTS));
+    }
+    if !(cfg.add_below_pct >= 0.0 && cfg.add_below_pct < 100.0) {
+        return Err("[slots] add_below_pct must be at least 0 and below 100".to_string());
+    }
+    Ok(())
+}
+
+/// The positions of every slot; fixed size, so publishing them never allocates.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+pub struct Slots(pub [BotState; MAX_SLOTS]);
+
+impl Slots {
+    /// A single position in the first slot (a record saved before there were slots).
+    pub fn single(position: BotState) -> Self {
+        let mut slots = Slots::default();
+        slots.0[0] = position;
+        slots
+    }
+
+    pub fn open(&self) -> usize {
+        self.0.iter().filter(|p| matches!(p, BotState::InPosition { .. })).count()
+    }
+
+    /// The first free slot among the first `count`.
+    pub fn free(&self, count: usize) -> Option<usize> {
+        self.0.iter().take(count).position(|p| *p == BotState::Idle)
+    }
+
+    pub fn lowest_entry(&self) -> Option<Decimal> {
+        self.0.iter().filter_map(|p| match p {
+            BotState::InPosition { entry_price, .. } => Some(*entry_price),
+            BotState::Idle => None,
+        }).min()
+    }
+
+    /// All open slots as one position: total quantity at the average entry (and signal) price. The
+    /// peak is the lowest of the slots' peaks, so a stop on the whole never fires before a slot's own,
+    /// and the entry time the oldest known one.
+    pub fn combined(&self) -> BotState {
+        let (mut quantity, mut cost, mut signalled, mut peak) = (Decimal::ZERO, Decimal::ZERO, Decimal::ZERO, f64::INFINITY);
+        let mut oldest = i64::MAX;
+        for position in &self.0 {
+            if let BotState::InPosition { entry_price, highest_price, quantity: q, entry_signal, entered_at } = *position {
+                quantity += q;
+                cost += entry_price * q;
+                signalled += entry_signal * q;
+                peak = peak.min(highest_price);
+                if entered_at > 0 { oldest = oldest.min(entered_at); }
+            }
+        }
+        let entered_at = if oldest == i64::MAX { 0 } else { oldest };
+        match quantity > Decimal::ZERO {
+            true => BotState::InPosition { entry_price: cost / quantity, highest_price: peak, quantity, entry_signal: signalled / quantity, entered_at },
+            false => BotState::Idle,
+        }
+    }
+
+    /// The open slots as the portfolio limits count them, valued at `price`.
+    pub fn holding(&self, pair: &str, price: Decimal) -> Holding {
+        let notional = match self.combined() {
+            BotState::InPosition { quantity, .. } => quantity * price,
+            BotState::Idle => Decimal::ZERO,
+        };
+        Holding { pair: pair.to_string(), positions: self.open() as u32, notional }
+    }
+
+    /// How a sold quantity comes off the slots: all of it from `slot`, or from the open slots in
+    /// order when the sell was for all of them (a flatten, an external sell).
+    pub fn allocate(&self, qty: Decimal, slot: Option<usize>) -> Vec<(usize, Decimal)> {
+        if let Some(slot) = slot { return vec![(slot, qty)]; }
+        let mut left = qty;
+        let mut shares = Vec::new();
+        for (slot, position) in self.0.iter().enumerate() {
+            if let BotState::InPosition { quantity, .. } = *position {
+                if left <= Decimal::ZERO { break; }
+                let share = quantity.min(left);
+                shares.push((slot, share));
+                left -= share;
+            }
+        }
+        shares
+    }
+}
@@ -0,0 +1,149 @@
+use std::fs;
+use std::time::Duration;
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::sync::{mpsc, oneshot};
+use tokio::time::timeout;
+
+use crate::candles::LiveCandles;
+use crate::executor::{OrderKind, OrderRequest};
+use crate::{simulating, BotState, PAIR};
+
+// --- ⚡ WARM RESTART SNAPSHOT ---
+// The trading loop's in-memory state (indicator internals, the candle being built, position, the
+// order in flight) is written to a small bincode file on a timer and at shutdown. A recent one is
+// restored at startup instead of re-syncing history and replaying it into the indicators.
+// The position record in the DB stays authoritative: it is written on every change, a snapshot
+// only every few seconds.
+
+pub const SNAPSHOT_FILE: &str = "bot_state.bin";
+const VERSION: u32 = 5; // Bump when anything in the snapshot changes shape
+// Long enough for one polling pass of the trading loop, which answers between passes
+const CAPTURE_WAIT: Duration = Duration::from_secs(6);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SnapshotConfig {
+    pub enabled: bool,
+    pub interval_secs: u64, // How often the trading loop writes one
+    pub max_age_secs: u64,  // Older snapshots are ignored at startup: the candles would have a gap
+}
+
+impl Default for SnapshotConfig {
+    fn default() -> Self {
+        SnapshotConfig { enabled: true, interval_secs: 10, max_age_secs: 120 }
+    }
+}
+
+// bincode can't read BotState's tagged JSON form, so the position is stored as plain fields
+// (and Decimals as text, which bincode can read back)
+#[derive(Clone, Copy, Serialize, Deserialize)]
+struct Held {
+    #[serde(with = "rust_decimal::serde::str")]
+    entry_price: Decimal,
+    highest_price: f64,
+    #[serde(with = "rust_decimal::serde::str")]
+    quantity: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    entry_signal: Decimal,
+    entered_at: i64,
+}
+
+/// An order that was handed to the executor but had not reported back.
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct PendingOrder {
+    pub side: String,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub price: Decimal,
+    #[serde(with = "rust_decimal::serde::str")]
+    pub qty: Decimal,
+    pub reason: String,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Snapshot {
+    version: u32,
+    pair: String,
+    simulation: bool,
+    pub saved_at: i64, // Millis
+    pub candles: LiveCandles,
+    held: Option<Held>,
+    pub pending: Option<PendingOrder>,
+}
+
+impl Snapshot {
+    pub fn new(saved_at: i64, candles: &LiveCandles, position: BotState, pending: Option<&OrderRequest>) -> Self {
+        let held = match position {
+            BotState::InPosition { entry_price, highest_price, quantity, entry_signal, entered_at } => Some(Held { entry_price, highest_price, quantity, entry_signal, entered_at }),
+            BotState::Idle => None,
+        };
+        let pending = pending.map(|r| {
+            let (OrderKind::Entry { reason } | OrderKind::Exit { reason } | OrderKind::Flatten { reason }) = r.kind;
+            PendingOrder { side: r.side.to_string(), price: r.price, qty: r.qty, reason: reason.to_string() }
+        });
+        Snapshot { version: VERSION, pair: PAIR.to_string(), simulation: simulating(), saved_at, candles: candles.clone(), held, pending }
+    }
+
+    pub fn position(&self) -> BotState {
+        match self.held {
+            Some(Held { entry_price, highest_price, quantity, entry_signal, entered_at }) => BotState::InPosition { entry_price, highest_price, quantity, entry_signal, entered_at },
+            None => BotState::Idle,
+        }
+    }
+}
+
+/// Written to a temporary file and renamed over the old one, so a crash mid-write never leaves half a snapshot.
+pub fn save(snapshot: &Snapshot) -> Result<(), String> {
+    let bytes = bincode::serialize(snapshot).map_err(|e| e.to_string())?;
+    let tmp = format!("{}.tmp", SNAPSHOT_FILE);
+    fs::write(&tmp, bytes).map_err(|e| e.to_string())?;
+    fs::rename(&tmp, SNAPSHOT_FILE).map_err(|e| e.to_string())
+}
+
+/// The snapshot from the last run, if there is one and it is usable here (`None` when there is no file).
+pub fn load(now_ms: i64, max_age: Duration) -> Result<Option<Snapshot>, String> {
+    let bytes = match fs::read(SNAPSHOT_FILE) {
+        Ok(bytes) => bytes,
+        Err(_) => return Ok(None),
+    };
+    let snapshot: Snapshot = bincode::deserialize(&bytes).map_err(|e| format!("unreadable ({})", e))?;
+    if snapshot.version != VERSION {
+        return Err(format!("written by an older version ({})", snapshot.version));
+    }
+    if snapshot.pair != PAIR || snapshot.simulation != simulating() {
+        return Err(format!("taken for {} in {} mode", snapshot.pair, if snapshot.simulation { "simulation" } else { "live" }));
+    }
+    let age = Duration::from_millis((now_ms - snapshot.saved_at).max(0) as u64);
+    if age > max_age {
+        return Err(format!("{}s old, limit is {}s", age.as_secs(), max_age.as_secs()));
+    }
+    Ok(Some(snapshot))
+}
+
+// Handed from startup to the first trading task; a restarted task rebuilds from the DB instead
+static RESTORED: Mutex<Option<Snapshot>> = Mutex::new(None);
+// The running trading task's inbox for shutdown captures
+static CAPTURE: Mutex<Option<mpsc::UnboundedSender<oneshot::Sender<Snapshot>>>> = Mutex::new(None);
+
+pub fn restore(snapshot: Snapshot) {
+    *RESTORED.lock() = Some(snapshot);
+}
+
+pub fn take() -> Option<Snapshot> {
+    RESTORED.lock().take()
+}
+
+/// Called by the trading task: shutdown captures arrive on the returned channel.
+pub fn listen() -> mpsc::UnboundedReceiver<oneshot::Sender<Snapshot>> {
+    let (tx, rx) = mpsc::unbounded_channel();
+    *CAPTURE.lock() = Some(tx);
+    rx
+}
+
+/// Asks the trading task for its current state (`None` if it doesn't answer in time).
+pub async fn capture() -> Option<Snapshot> {
+    let (reply, answer) = oneshot::channel();
+    CAPTURE.lock().as_ref()?.send(reply).ok()?;
+    timeout(CAPTURE_WAIT, answer).await.ok()?.ok()
+}
@@ -0,0 +1,132 @@
+use std::time::{Duration, Instant};
+use chrono::Utc;
+use parking_lot::Mutex;
+use serde::{Deserialize, Serialize};
+use tokio::time::sleep;
+
+use crate::config::SharedConfig;
+use crate::notify::{Event, EventKind, Notifier};
+use crate::symbols::{self, Venue};
+use crate::{add_log, bybit, coindcx, okx, runtime, DbManager, SharedState, PAIR};
+
+// --- ↔️ CROSS-EXCHANGE SPREAD MONITOR ---
+// Polls the pair's last price on every listed venue at the same moment and records each venue's
+// spread against the one being traded (the spreads table, pruned after retention_days). When the
+// widest spread between any two venues passes alert_pct it is logged and sent to the webhooks, once
+// until it narrows again. With strategy_input, the traded venue's premium over the median of the
+// others is handed to the strategy (Indicators::venue_premium_pct).
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct SpreadConfig {
+    pub enabled: bool,
+    pub venues: Vec<Venue>, // The traded venue is always included
+    pub poll_secs: u64,
+    pub alert_pct: f64,
+    pub retention_days: u32,
+    pub strategy_input: bool,
+}
+
+impl Default for SpreadConfig {
+    fn default() -> Self {
+        SpreadConfig {
+            enabled: false,
+            venues: vec![Venue::CoinDcx, Venue::Bybit, Venue::Okx],
+            poll_secs: 10,
+            alert_pct: 0.5,
+            retention_days: 7,
+            strategy_input: false,
+        }
+    }
+}
+
+static PREMIUM: Mutex<Option<(f64, Instant)>> = parking_lot::const_mutex(None);
+
+/// The traded venue's premium (%) over the other venues' median, when it is a strategy input and
+/// was measured within the last three polls.
+pub fn premium(cfg: &SpreadConfig) -> Option<f64> {
+    if !cfg.enabled || !cfg.strategy_input { return None; }
+    let max_age = Duration::from_secs(cfg.poll_secs.max(1) * 3);
+    PREMIUM.lock().filter(|(_, at)| at.elapsed() <= max_age).map(|(premium, _)| premium)
+}
+
+fn name(venue: Venue) -> &'static str {
+    match venue {
+        Venue::CoinDcx => "coindcx",
+        Venue::Bybit => "bybit",
+        Venue::Okx => "okx",
+    }
+}
+
+async fn last_price(client: &reqwest::Client, venue: Venue) -> Result<f64, String> {
+    let timeout = Duration::from_secs(5);
+    let market = symbols::lookup(PAIR, venue).ok_or_else(|| format!("{} is not listed for {}", PAIR, name(venue)))?;
+    let price = match venue {
+        Venue::CoinDcx => coindcx::last_price(client, market.data_code, timeout).await?,
+        Venue::Bybit => bybit::last_price(client, market.data_code, timeout).await?,
+        Venue::Okx => okx::last_price(client, market.data_code, timeout).await?,
+    };
+    price.ok_or_else(|| "no price".to_string())
+}
+
+fn median(mut values: Vec<f64>) -> Option<f64> {
+    if values.is_empty() { return None; }
+    values.sort_by(f64::total_cmp);
+    let mid = values.len() / 2;
+    Some(if values.len().is_multiple_of(2) { (values[mid - 1] + values[mid]) / 2.0 } else { values[mid] })
+}
+
+pub async fn monitor(state: SharedState, config: SharedConfig) {
+    let client = reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap();
+    let notifier = Notifier::from_env();
+    let mut alerted = false;
+    loop {
+        let cfg = config.read().spread.clone();
+        if cfg.enabled {
+            let traded = symbols::venue();
+            let mut venues: Vec<Venue> = Vec::new();
+            for venue in cfg.venues.iter().copied().chain([traded]) {
+                if !venues.contains(&venue) { venues.push(venue); }
+            }
+            let fetched = futures_util::future::join_all(venues.iter().map(|v| last_price(&client, *v))).await;
+            let mut prices = Vec::new();
+            for (venue, result) in venues.iter().zip(fetched) {
+                match result {
+                    Ok(price) => prices.push((*venue, price)),
+                    Err(e) => eprintln!("Spread Error ({}): {}", name(*venue), e),
+                }
+            }
+
+            let base = prices.iter().find(|(v, _)| *v == traded).map(|(_, p)| *p);
+            if let (Some(base), true) = (base, prices.len() >= 2) {
+                let others = median(prices.iter().filter(|(v, _)| *v != traded).map(|(_, p)| *p).collect());
+                if let Some(others) = others {
+                    *PREMIUM.lock() = Some(((base - others) / others * 100.0, Instant::now()));
+                }
+                let rows: Vec<(String, f64, f64)> = prices.iter()
+                    .map(|(v, p)| (name(*v).to_string(), *p, (p - base) / base * 100.0))
+                    .collect();
+                let (retention, now) = (cfg.retention_days, Utc::now().timestamp_millis());
+                if let Err(e) = runtime::db(move || DbManager::save_spreads(now, &rows, retention)).await {
+                    eprintln!("Spread DB Error: {}", e);
+                }
+
+                // Widest gap between any two venues
+                let low = prices.iter().min_by(|a, b| a.1.total_cmp(&b.1)).copied().unwrap_or((traded, base));
+                let high = prices.iter().max_by(|a, b| a.1.total_cmp(&b.1)).copied().unwrap_or((traded, base));
+                let widest = (high.1 - low.1) / low.1 * 100.0;
+                if (widest > cfg.alert_pct) != alerted {
+                    alerted = widest > cfg.alert_pct;
+                    let msg = if alerted {
+                        format!("↔️ {} spread {:.2}%: {} {:.2} vs {} {:.2}", PAIR, widest, name(high.0), high.1, name(low.0), low.1)
+                    } else {
+                        format!("↔️ {} spread back to {:.2}%", PAIR, widest)
+                    };
+                    add_log(&state, msg.clone());
+                    notifier.send(Event::new(EventKind::Alert, PAIR, base, 0.0, 0.0, &msg));
+                }
+            }
+        }
+        sleep(Duration::from_secs(cfg.poll_secs.max(1))).await;
+    }
+}
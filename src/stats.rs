@@ -0,0 +1,160 @@
+use std::collections::HashMap;
+use chrono::DateTime;
+use rust_decimal::Decimal;
+use serde::Serialize;
+
+use crate::{money, TradeRecord};
+
+// --- 📈 PERFORMANCE ANALYTICS ---
+// Computed from the trades table. Besides the totals, the most recent trades and the current
+// streak are reported on their own: a strategy that stops working shows there long before it
+// moves the all-time numbers.
+
+pub const ROLLING_TRADES: usize = 20; // Window of the rolling win rate and expectancy
+
+/// A buy matched with the sell that closed it.
+#[derive(Clone, Debug)]
+pub struct ClosedTrade {
+    pub profit: f64,
+    pub return_pct: f64,
+    pub holding_secs: i64,
+}
+
+#[derive(Clone, Debug, Default, Serialize)]
+pub struct Performance {
+    pub closed_trades: usize,
+    pub wins: usize,
+    pub losses: usize,
+    pub win_rate: f64,
+    pub net_pnl: f64,
+    pub gross_profit: f64,
+    pub gross_loss: f64,
+    pub profit_factor: Option<f64>, // None when there are no losing trades
+    pub expectancy: f64,            // Average P&L per closed trade (USDT)
+    pub avg_win: f64,
+    pub avg_loss: f64,
+    pub sharpe: Option<f64>,        // Per-trade, not annualized
+    pub sortino: Option<f64>,       // Per-trade, not annualized
+    pub max_drawdown: f64,          // USDT, peak-to-trough of the equity curve
+    pub avg_holding_secs: f64,
+    pub rolling_win_rate: f64,   // Over the last ROLLING_TRADES closed trades
+    pub rolling_expectancy: f64, // Same window
+    pub streak: i64,             // Consecutive wins (positive) or losses (negative) up to the last trade
+}
+
+fn parse_ts(ts: &str) -> Option<i64> {
+    DateTime::parse_from_rfc3339(ts).ok().map(|t| t.timestamp())
+}
+
+/// Pairs each sell with the preceding buy in the same position slot. Expects trades oldest first.
+pub fn closed_trades(trades: &[TradeRecord]) -> Vec<ClosedTrade> {
+    let mut open: HashMap<Option<usize>, &TradeRecord> = HashMap::new();
+    let mut closed = Vec::new();
+
+    for t in trades {
+        match t.action.as_str() {
+            "buy" => { open.insert(t.slot, t); },
+            "sell" => {
+                if let Some(entry) = open.remove(&t.slot) {
+                    let notional = entry.price * entry.quantity;
+                    let holding_secs = match (parse_ts(&entry.timestamp), parse_ts(&t.timestamp)) {
+                        (Some(a), Some(b)) => b - a,
+                        _ => 0,
+                    };
+                    closed.push(ClosedTrade {
+                        profit: money::float(t.profit),
+                        return_pct: if notional > Decimal::ZERO { money::float(t.profit / notional * Decimal::ONE_HUNDRED) } else { 0.0 },
+                        holding_secs,
+                    });
+                }
+            },
+            _ => {},
+        }
+    }
+    closed
+}
+
+fn mean(xs: &[f64]) -> f64 {
+    if xs.is_empty() { 0.0 } else { xs.iter().sum::<f64>() / xs.len() as f64 }
+}
+
+/// Mean over standard deviation of per-trade returns; None when they don't vary.
+pub fn sharpe(returns: &[f64]) -> Option<f64> {
+    let avg = mean(returns);
+    let std_dev = (returns.iter().map(|r| (r - avg).powi(2)).sum::<f64>() / returns.len() as f64).sqrt();
+    (std_dev > 0.0).then(|| avg / std_dev)
+}
+
+/// Gross profit over gross loss; None when there are no losing trades.
+pub fn profit_factor(profits: &[f64]) -> Option<f64> {
+    let gross_profit: f64 = profits.iter().filter(|p| **p > 0.0).sum();
+    let gross_loss: f64 = -profits.iter().filter(|p| **p <= 0.0).sum::<f64>();
+    (gross_loss > 0.0).then(|| gross_profit / gross_loss)
+}
+
+/// Max peak-to-trough decline of an equity series.
+pub fn max_drawdown(equity: impl IntoIterator<Item = f64>) -> f64 {
+    let mut peak = f64::MIN;
+    let mut max_dd: f64 = 0.0;
+    for e in equity {
+        peak = peak.max(e);
+        max_dd = max_dd.max(peak - e);
+    }
+    max_dd
+}
+
+/// Length of the run of same-sign results ending with the last one: +3 after three wins in a row,
+/// -2 after two losses. A break-even trade counts as a loss, as in the win rate.
+fn streak(profits: &[f64]) -> i64 {
+    let Some(last) = profits.last() else { return 0 };
+    let won = *last > 0.0;
+    let run = profits.iter().rev().take_while(|p| (**p > 0.0) == won).count() as i64;
+    if won { run } else { -run }
+}
+
+/// `equity` is the sampled equity curve (oldest first); if empty, the curve is rebuilt from closed trades.
+pub fn compute(trades: &[TradeRecord], equity: &[(i64, f64)]) -> Performance {
+    let closed = closed_trades(trades);
+    if closed.is_empty() { return Performance::default(); }
+
+    let profits: Vec<f64> = closed.iter().map(|c| c.profit).collect();
+    let returns: Vec<f64> = closed.iter().map(|c| c.return_pct).collect();
+    let wins: Vec<f64> = profits.iter().copied().filter(|p| *p > 0.0).collect();
+    let losses: Vec<f64> = profits.iter().copied().filter(|p| *p <= 0.0).collect();
+
+    let gross_profit: f64 = wins.iter().sum();
+    let gross_loss: f64 = -losses.iter().sum::<f64>();
+    let net_pnl = gross_profit - gross_loss;
+
+    let avg_ret = mean(&returns);
+    let downside_dev = (returns.iter().map(|r| r.min(0.0).powi(2)).sum::<f64>() / returns.len() as f64).sqrt();
+
+    let max_dd = if equity.is_empty() {
+        let mut cum = 0.0;
+        max_drawdown(std::iter::once(0.0).chain(profits.iter().map(|p| { cum += p; cum })))
+    } else {
+        max_drawdown(equity.iter().map(|e| e.1))
+    };
+    let recent = &profits[profits.len().saturating_sub(ROLLING_TRADES)..];
+
+    Performance {
+        closed_trades: closed.len(),
+        wins: wins.len(),
+        losses: losses.len(),
+        win_rate: wins.len() as f64 / closed.len() as f64 * 100.0,
+        net_pnl,
+        gross_profit,
+        gross_loss,
+        profit_factor: profit_factor(&profits),
+        expectancy: net_pnl / closed.len() as f64,
+        avg_win: mean(&wins),
+        avg_loss: mean(&losses),
+        sharpe: sharpe(&returns),
+        sortino: (downside_dev > 0.0).then(|| avg_ret / downside_dev),
+        max_drawdown: max_dd,
+        avg_holding_secs: closed.iter().map(|c| c.holding_secs as f64).sum::<f64>() / closed.len() as f64,
+        rolling_win_rate: recent.iter().filter(|p| **p > 0.0).count() as f64 / recent.len() as f64 * 100.0,
+        rolling_expectancy: mean(recent),
+        streak: streak(&profits),
+    }
+}
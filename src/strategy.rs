@@ -0,0 +1,303 @@
+use serde::{Deserialize, Serialize};
+
+use crate::{money, BREAK_EVEN_PCT, FEE_RATE, HARD_STOP_PCT, MAX_HOLD_MINS, REENTRY_PCT, REENTRY_RSI, RSI_BUY, RSI_CRASH, RSI_SELL, TRADE_CAPITAL, TRAILING_STOP_PCT};
+
+// --- 🎯 STRATEGIES ---
+// Shared by the live loop and the backtester so both trade identical logic.
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct StrategyParams {
+    pub rsi_buy: f64,
+    pub rsi_sell: f64,
+    pub rsi_crash: f64,
+    pub trailing_stop_pct: f64,
+    pub trade_capital: f64,
+    pub hard_stop_pct: f64, // Under the entry price, 0 = off
+    pub break_even_pct: f64, // Peak above the entry price that arms the break-even stop, 0 = off
+    pub max_hold_mins: f64,  // Minutes a position may stay open, 0 = no limit
+    pub reentry_pct: f64,    // Price move away from the last exit before entries re-arm, 0 = off
+    pub reentry_rsi: f64,    // RSI points away from the last exit, the other way to re-arm, 0 = off
+}
+
+impl Default for StrategyParams {
+    fn default() -> Self {
+        StrategyParams {
+            rsi_buy: RSI_BUY,
+            rsi_sell: RSI_SELL,
+            rsi_crash: RSI_CRASH,
+            trailing_stop_pct: TRAILING_STOP_PCT,
+            trade_capital: TRADE_CAPITAL,
+            hard_stop_pct: HARD_STOP_PCT,
+            break_even_pct: BREAK_EVEN_PCT,
+            max_hold_mins: MAX_HOLD_MINS,
+            reentry_pct: REENTRY_PCT,
+            reentry_rsi: REENTRY_RSI,
+        }
+    }
+}
+
+impl StrategyParams {
+    pub fn get(&self, key: &str) -> Option<f64> {
+        match key {
+            "rsi_buy" => Some(self.rsi_buy),
+            "rsi_sell" => Some(self.rsi_sell),
+            "rsi_crash" => Some(self.rsi_crash),
+            "trailing_stop_pct" => Some(self.trailing_stop_pct),
+            "trade_capital" => Some(self.trade_capital),
+            "hard_stop_pct" => Some(self.hard_stop_pct),
+            "break_even_pct" => Some(self.break_even_pct),
+            "max_hold_mins" => Some(self.max_hold_mins),
+            "reentry_pct" => Some(self.reentry_pct),
+            "reentry_rsi" => Some(self.reentry_rsi),
+            _ => None,
+        }
+    }
+
+    /// Sets a parameter by its key; false for an unknown key.
+    pub fn set(&mut self, key: &str, value: f64) -> bool {
+        let field = match key {
+            "rsi_buy" => &mut self.rsi_buy,
+            "rsi_sell" => &mut self.rsi_sell,
+            "rsi_crash" => &mut self.rsi_crash,
+            "trailing_stop_pct" => &mut self.trailing_stop_pct,
+            "trade_capital" => &mut self.trade_capital,
+            "hard_stop_pct" => &mut self.hard_stop_pct,
+            "break_even_pct" => &mut self.break_even_pct,
+            "max_hold_mins" => &mut self.max_hold_mins,
+            "reentry_pct" => &mut self.reentry_pct,
+            "reentry_rsi" => &mut self.reentry_rsi,
+            _ => return false,
+        };
+        *field = value;
+        true
+    }
+}
+
+/// Indicator values a strategy sees on each tick / candle.
+#[derive(Clone, Copy, Debug)]
+pub struct Indicators {
+    pub price: f64,
+    pub rsi: f64,
+    pub bb_lower: f64,
+    pub venue_premium_pct: Option<f64>, // Traded venue over the others ([spread] strategy_input), else None
+    pub last_exit: Option<LastExit>,    // While still inside its re-entry band, else None (see ReentryBand)
+}
+
+/// Where the last position was closed.
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct LastExit {
+    pub price: f64,
+    pub rsi: f64,
+}
+
+/// Hysteresis against churn around one level: after an exit, entries stay held until the price has
+/// moved reentry_pct or the RSI reentry_rsi points away from the exit, in either direction. Once
+/// out of the band it stays out, so wandering back to the exit level does not hold entries again.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ReentryBand {
+    exit: Option<LastExit>,
+}
+
+impl ReentryBand {
+    pub fn exited(&mut self, price: f64, rsi: f64) {
+        self.exit = Some(LastExit { price, rsi });
+    }
+
+    /// Fills in `ind.last_exit` for this tick, dropping the exit once the tick is out of its band.
+    pub fn apply(&mut self, params: &StrategyParams, ind: &mut Indicators) {
+        let left = |exit: &LastExit| {
+            let moved = params.reentry_pct > 0.0 && (ind.price - exit.price).abs() >= exit.price * params.reentry_pct;
+            let turned = params.reentry_rsi > 0.0 && (ind.rsi - exit.rsi).abs() >= params.reentry_rsi;
+            moved || turned
+        };
+        let off = params.reentry_pct <= 0.0 && params.reentry_rsi <= 0.0;
+        if off || self.exit.as_ref().is_some_and(left) {
+            self.exit = None;
+        }
+        ind.last_exit = self.exit;
+    }
+}
+
+/// Describes one tunable parameter for the dashboard form.
+#[derive(Clone, Debug, Serialize)]
+pub struct ParamSpec {
+    pub key: &'static str,
+    pub label: &'static str,
+    pub min: f64,
+    pub max: f64,
+    pub step: f64,
+}
+
+#[derive(Clone, Debug, Serialize)]
+pub struct StrategyInfo {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub params: Vec<ParamSpec>,
+}
+
+pub trait Strategy: Send + Sync {
+    fn name(&self) -> &'static str;
+    fn params(&self) -> &StrategyParams;
+    /// Returns the entry reason if the tick qualifies for a LONG.
+    fn entry_reason(&self, ind: &Indicators) -> Option<&'static str>;
+    /// The entry to act on: entry_reason, held back while the last exit's re-entry band is.
+    fn entry_signal(&self, ind: &Indicators) -> Option<&'static str> {
+        if ind.last_exit.is_some() { return None; }
+        self.entry_reason(ind)
+    }
+    /// Returns the exit reason if an open position should be closed.
+    fn exit_reason(&self, ind: &Indicators, highest_price: f64) -> Option<&'static str> {
+        let p = self.params();
+        let stop_price = highest_price * (1.0 - p.trailing_stop_pct);
+        if ind.price < stop_price {
+            Some("STOP LOSS")
+        } else if ind.rsi > p.rsi_sell {
+            Some("PROFIT TAKE")
+        } else {
+            None
+        }
+    }
+}
+
+/// Mean reversion: oversold RSI below the lower band, or a deep RSI crash.
+pub struct RsiBollinger {
+    params: StrategyParams,
+}
+
+impl Strategy for RsiBollinger {
+    fn name(&self) -> &'static str { "rsi_bb" }
+    fn params(&self) -> &StrategyParams { &self.params }
+
+    fn entry_reason(&self, ind: &Indicators) -> Option<&'static str> {
+        if ind.rsi < self.params.rsi_crash {
+            Some("CRASH CATCH")
+        } else if ind.rsi < self.params.rsi_buy && ind.price < ind.bb_lower {
+            Some("RSI+BB")
+        } else {
+            None
+        }
+    }
+}
+
+/// Plain RSI oversold entry, ignoring Bollinger Bands.
+pub struct RsiOnly {
+    params: StrategyParams,
+}
+
+impl Strategy for RsiOnly {
+    fn name(&self) -> &'static str { "rsi_only" }
+    fn params(&self) -> &StrategyParams { &self.params }
+
+    fn entry_reason(&self, ind: &Indicators) -> Option<&'static str> {
+        if ind.rsi < self.params.rsi_buy { Some("RSI OVERSOLD") } else { None }
+    }
+}
+
+// Stops that hold whatever the strategy's own exits say (a custom exit_reason included). The hard
+// stop, hard_stop_pct under the entry, bounds a gap down right after an entry. The break-even stop
+// arms once the peak has been break_even_pct above the entry and sits at the entry plus the fees
+// of both sides, so a winner can no longer turn into a loser. Both are worked out from the entry
+// and the peak, so they need no state of their own and survive a restart with the position.
+
+/// Which stop a fall in price hits first.
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StopKind {
+    Trailing,
+    BreakEven,
+    Hard,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize)]
+pub struct Stop {
+    pub price: f64,
+    pub kind: StopKind,
+}
+
+fn hard_stop_level(params: &StrategyParams, entry_price: f64) -> Option<f64> {
+    (params.hard_stop_pct > 0.0).then_some(entry_price * (1.0 - params.hard_stop_pct))
+}
+
+fn break_even_level(params: &StrategyParams, entry_price: f64, highest_price: f64) -> Option<f64> {
+    let armed = params.break_even_pct > 0.0 && highest_price >= entry_price * (1.0 + params.break_even_pct);
+    armed.then_some(entry_price * (1.0 + 2.0 * money::float(FEE_RATE)))
+}
+
+/// The exit the hard or break-even stop calls for at `price`, if any.
+pub fn protective_exit(params: &StrategyParams, price: f64, entry_price: f64, highest_price: f64) -> Option<&'static str> {
+    if hard_stop_level(params, entry_price).is_some_and(|level| price <= level) {
+        Some("HARD STOP")
+    } else if break_even_level(params, entry_price, highest_price).is_some_and(|level| price <= level) {
+        Some("BREAK EVEN")
+    } else {
+        None
+    }
+}
+
+/// The time exit: a scalp still open after max_hold_mins has had its chance, whatever the
+/// indicators say.
+pub fn time_exit(params: &StrategyParams, held_ms: i64) -> Option<&'static str> {
+    (params.max_hold_mins > 0.0 && held_ms as f64 >= params.max_hold_mins * 60_000.0).then_some("TIME EXIT")
+}
+
+/// The active stop of a position: the highest of the trailing, hard and break-even stops.
+pub fn stop_level(params: &StrategyParams, entry_price: f64, highest_price: f64) -> Stop {
+    let trailing = Stop { price: highest_price * (1.0 - params.trailing_stop_pct), kind: StopKind::Trailing };
+    [
+        hard_stop_level(params, entry_price).map(|price| Stop { price, kind: StopKind::Hard }),
+        break_even_level(params, entry_price, highest_price).map(|price| Stop { price, kind: StopKind::BreakEven }),
+    ]
+    .into_iter()
+    .flatten()
+    .fold(trailing, |best, stop| if stop.price > best.price { stop } else { best })
+}
+
+pub const DEFAULT_STRATEGY: &str = "rsi_bb";
+
+/// Builds a strategy by name, falling back to the default for unknown names.
+pub fn build(name: &str, params: StrategyParams) -> Box<dyn Strategy> {
+    match name {
+        "rsi_only" => Box::new(RsiOnly { params }),
+        _ => Box::new(RsiBollinger { params }),
+    }
+}
+
+/// Checks the strategy exists and every parameter it uses is within its schema range.
+pub fn validate(name: &str, params: &StrategyParams) -> Result<(), String> {
+    let info = available().into_iter().find(|s| s.name == name)
+        .ok_or_else(|| format!("Unknown strategy '{}'", name))?;
+    for spec in &info.params {
+        let val = params.get(spec.key).unwrap_or(f64::NAN);
+        if !(spec.min..=spec.max).contains(&val) {
+            return Err(format!("{} must be between {} and {}", spec.key, spec.min, spec.max));
+        }
+    }
+    Ok(())
+}
+
+pub fn available() -> Vec<StrategyInfo> {
+    let rsi_buy = ParamSpec { key: "rsi_buy", label: "RSI Buy", min: 1.0, max: 50.0, step: 1.0 };
+    let rsi_sell = ParamSpec { key: "rsi_sell", label: "RSI Sell", min: 50.0, max: 99.0, step: 1.0 };
+    let rsi_crash = ParamSpec { key: "rsi_crash", label: "RSI Crash", min: 1.0, max: 50.0, step: 1.0 };
+    let stop = ParamSpec { key: "trailing_stop_pct", label: "Trailing Stop", min: 0.001, max: 0.1, step: 0.001 };
+    let capital = ParamSpec { key: "trade_capital", label: "Capital (USDT)", min: 10.0, max: 1_000_000.0, step: 10.0 };
+    let hard_stop = ParamSpec { key: "hard_stop_pct", label: "Hard Stop", min: 0.0, max: 0.2, step: 0.001 };
+    let break_even = ParamSpec { key: "break_even_pct", label: "Break-Even At", min: 0.0, max: 0.1, step: 0.001 };
+    let max_hold = ParamSpec { key: "max_hold_mins", label: "Max Hold (min)", min: 0.0, max: 1440.0, step: 1.0 };
+    let reentry_pct = ParamSpec { key: "reentry_pct", label: "Re-entry Move", min: 0.0, max: 0.1, step: 0.001 };
+    let reentry_rsi = ParamSpec { key: "reentry_rsi", label: "Re-entry RSI", min: 0.0, max: 50.0, step: 1.0 };
+
+    vec![
+        StrategyInfo {
+            name: "rsi_bb",
+            description: "Mean reversion: RSI oversold below lower Bollinger Band, or RSI crash catch",
+            params: vec![rsi_buy.clone(), rsi_sell.clone(), rsi_crash, stop.clone(), capital.clone(), hard_stop.clone(), break_even.clone(), max_hold.clone(), reentry_pct.clone(), reentry_rsi.clone()],
+        },
+        StrategyInfo {
+            name: "rsi_only",
+            description: "RSI oversold entry without Bollinger confirmation",
+            params: vec![rsi_buy, rsi_sell, stop, capital, hard_stop, break_even, max_hold, reentry_pct, reentry_rsi],
+        },
+    ]
+}
@@ -0,0 +1,208 @@
+use parking_lot::RwLock;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+use crate::{bybit, okx, PAIR};
+
+// --- 🏷️ SYMBOL REGISTRY ---
+// PAIR is the canonical BASE/QUOTE name; every venue calls the same market something else, and
+// CoinDCX uses two names (B-BTC_USDT for market data, BTCUSDT for orders; I-BTC_INR and BTCINR for
+// its INR markets). The registry maps the
+// canonical pair to each venue's codes, order size and price steps and minimum order, so the venue
+// modules and the order path ask it instead of carrying their own strings. The built-in steps are
+// replaced by the venue's instrument info when preflight reads it.
+
+#[derive(Clone, Copy, Debug, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Venue {
+    CoinDcx,
+    Bybit,
+    Okx,
+}
+
+/// Which of a venue's deployments to trade on: the real exchange, or its sandbox (test funds, own
+/// API keys) where the venue has one.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Environment {
+    #[default]
+    Mainnet,
+    Testnet,
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Market {
+    pub venue: Venue,
+    pub pair: &'static str,       // Canonical, BASE/QUOTE
+    pub data_code: &'static str,  // Candles, trades and the order book
+    pub order_code: &'static str, // Orders, balances and positions
+    pub qty_step: Decimal,
+    pub price_step: Decimal,
+    pub min_qty: Decimal,
+    pub min_notional: Decimal, // In the quote currency; 0 = none
+}
+
+const fn market(venue: Venue, pair: &'static str, data_code: &'static str, order_code: &'static str, steps: [Decimal; 4]) -> Market {
+    Market { venue, pair, data_code, order_code, qty_step: steps[0], price_step: steps[1], min_qty: steps[2], min_notional: steps[3] }
+}
+
+// [qty step, price step, min qty, min notional]
+const MARKETS: [Market; 9] = [
+    market(Venue::CoinDcx, "BTC/USDT", "B-BTC_USDT", "BTCUSDT", [dec!(0.00001), dec!(0.01), dec!(0.00001), dec!(1)]),
+    market(Venue::CoinDcx, "ETH/USDT", "B-ETH_USDT", "ETHUSDT", [dec!(0.0001), dec!(0.01), dec!(0.0001), dec!(1)]),
+    // INR markets: CoinDCX's own book, I- codes for market data
+    market(Venue::CoinDcx, "BTC/INR", "I-BTC_INR", "BTCINR", [dec!(0.00001), dec!(1), dec!(0.00001), dec!(100)]),
+    market(Venue::CoinDcx, "ETH/INR", "I-ETH_INR", "ETHINR", [dec!(0.0001), dec!(1), dec!(0.0001), dec!(100)]),
+    market(Venue::CoinDcx, "USDT/INR", "I-USDT_INR", "USDTINR", [dec!(0.01), dec!(0.01), dec!(0.01), dec!(100)]),
+    market(Venue::Bybit, "BTC/USDT", "BTCUSDT", "BTCUSDT", [dec!(0.001), dec!(0.1), dec!(0.001), dec!(5)]),
+    market(Venue::Bybit, "ETH/USDT", "ETHUSDT", "ETHUSDT", [dec!(0.01), dec!(0.01), dec!(0.01), dec!(5)]),
+    market(Venue::Okx, "BTC/USDT", "BTC-USDT", "BTC-USDT", [dec!(0.00000001), dec!(0.1), dec!(0.00001), Decimal::ZERO]),
+    market(Venue::Okx, "ETH/USDT", "ETH-USDT", "ETH-USDT", [dec!(0.000001), dec!(0.01), dec!(0.0001), Decimal::ZERO]),
+];
+
+static ACTIVE: RwLock<Option<Market>> = parking_lot::const_rwlock(None);
+
+/// The venue selected in config (CoinDCX unless [bybit] or [okx] is enabled).
+pub fn venue() -> Venue {
+    if bybit::enabled() { Venue::Bybit } else if okx::enabled() { Venue::Okx } else { Venue::CoinDcx }
+}
+
+/// The selected venue's environment; CoinDCX has no sandbox.
+pub fn environment() -> Environment {
+    match venue() {
+        Venue::Bybit => bybit::environment(),
+        Venue::Okx => okx::environment(),
+        Venue::CoinDcx => Environment::Mainnet,
+    }
+}
+
+pub fn lookup(pair: &str, venue: Venue) -> Option<Market> {
+    MARKETS.iter().find(|m| m.venue == venue && m.pair.eq_ignore_ascii_case(pair)).copied()
+}
+
+/// A market by either its canonical pair (BTC/USDT) or its market data code (B-BTC_USDT).
+pub fn find(name: &str, venue: Venue) -> Option<Market> {
+    lookup(name, venue).or_else(|| MARKETS.iter().find(|m| m.venue == venue && m.data_code.eq_ignore_ascii_case(name)).copied())
+}
+
+/// Resolves PAIR on the selected venue; called once the venue modules are configured.
+pub fn configure() -> Result<(), String> {
+    let market = lookup(PAIR, venue()).ok_or_else(|| format!("{} is not in the symbol registry for {:?}", PAIR, venue()))?;
+    *ACTIVE.write() = Some(market);
+    Ok(())
+}
+
+/// The traded market. Before configure() (and in tools that never call it) PAIR on CoinDCX.
+pub fn active() -> Market {
+    ACTIVE.read().or_else(|| lookup(PAIR, Venue::CoinDcx)).unwrap_or(MARKETS[0])
+}
+
+/// Replaces the built-in steps and minimums with the venue's (zeros are ignored).
+pub fn refine(qty_step: Decimal, price_step: Decimal, min_qty: Decimal, min_notional: Decimal) {
+    let mut active = ACTIVE.write();
+    let Some(market) = active.as_mut() else { return };
+    if qty_step > Decimal::ZERO { market.qty_step = qty_step; }
+    if price_step > Decimal::ZERO { market.price_step = price_step; }
+    if min_qty > Decimal::ZERO { market.min_qty = min_qty; }
+    if min_notional > Decimal::ZERO { market.min_notional = min_notional; }
+}
+
+fn floor_to(value: Decimal, step: Decimal) -> Decimal {
+    if step <= Decimal::ZERO { value } else { ((value / step).floor() * step).normalize() }
+}
+
+fn ceil_to(value: Decimal, step: Decimal) -> Decimal {
+    if step <= Decimal::ZERO { value } else { ((value / step).ceil() * step).normalize() }
+}
+
+fn decimals(step: Decimal) -> usize {
+    if step <= Decimal::ZERO { 8 } else { step.normalize().scale() as usize }
+}
+
+impl Market {
+    pub fn base(&self) -> &'static str {
+        self.pair.split_once('/').map_or(self.pair, |(base, _)| base)
+    }
+
+    pub fn quote(&self) -> &'static str {
+        self.pair.split_once('/').map_or("", |(_, quote)| quote)
+    }
+
+    /// Quantity as sent to the venue: rounded down to the step.
+    pub fn qty(&self, qty: Decimal) -> String {
+        format!("{:.*}", decimals(self.qty_step), floor_to(qty, self.qty_step))
+    }
+
+    /// Price as sent to the venue: rounded down to the tick.
+    pub fn price(&self, price: Decimal) -> String {
+        format!("{:.*}", decimals(self.price_step), floor_to(price, self.price_step))
+    }
+
+    pub fn floor_qty(&self, qty: Decimal) -> Decimal {
+        floor_to(qty, self.qty_step)
+    }
+
+    pub fn floor_price(&self, price: Decimal) -> Decimal {
+        floor_to(price, self.price_step)
+    }
+
+    /// A limit price on the tick: a buy's rounded up and a sell's down, so the order is never less
+    /// marketable than the price it was signalled at.
+    pub fn order_price(&self, side: &str, price: Decimal) -> Decimal {
+        if side == "buy" { ceil_to(price, self.price_step) } else { floor_to(price, self.price_step) }
+    }
+
+    /// An order as the venue will take it, price on the tick and quantity down to the step, or why
+    /// it can't be placed.
+    pub fn round_order(&self, side: &str, price: Decimal, qty: Decimal) -> Result<(Decimal, Decimal), String> {
+        let price = self.order_price(side, price);
+        if price <= Decimal::ZERO {
+            return Err(format!("no valid {} price ({})", side, price));
+        }
+        Ok((price, self.order_qty(price, qty)?))
+    }
+
+    /// Rounds an order down to the step, or says why it is too small to place.
+    pub fn order_qty(&self, price: Decimal, qty: Decimal) -> Result<Decimal, String> {
+        let qty = self.floor_qty(qty);
+        if qty < self.min_qty {
+            return Err(format!("{} {} below the minimum {}", self.qty(qty), self.base(), self.min_qty));
+        }
+        if qty * price < self.min_notional {
+            return Err(format!("{:.2} {} below the minimum order of {}", qty * price, self.quote(), self.min_notional));
+        }
+        Ok(qty)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Steps 0.001 BTC and 0.1 USDT, minimums 0.001 BTC and 5 USDT
+    fn bybit_btc() -> Market {
+        lookup("BTC/USDT", Venue::Bybit).unwrap()
+    }
+
+    #[test]
+    fn buys_round_the_price_up_and_sells_down() {
+        let market = bybit_btc();
+        assert_eq!(market.round_order("buy", dec!(60000.04), dec!(0.0019)), Ok((dec!(60000.1), dec!(0.001))));
+        assert_eq!(market.round_order("sell", dec!(60000.06), dec!(0.0029)), Ok((dec!(60000.0), dec!(0.002))));
+        // Already on the steps, nothing moves
+        assert_eq!(market.round_order("buy", dec!(60000.1), dec!(0.002)), Ok((dec!(60000.1), dec!(0.002))));
+        assert_eq!(market.round_order("sell", dec!(60000.1), dec!(0.002)), Ok((dec!(60000.1), dec!(0.002))));
+    }
+
+    #[test]
+    fn refuses_orders_under_the_minimums() {
+        let market = bybit_btc();
+        let small = market.order_qty(dec!(60000), dec!(0.0009)).unwrap_err();
+        assert!(small.contains("below the minimum 0.001"), "{}", small);
+        // 0.001 BTC at 4000 is 4 USDT, under the 5 USDT minimum
+        let cheap = market.round_order("sell", dec!(4000), dec!(0.001)).unwrap_err();
+        assert!(cheap.contains("below the minimum order of 5"), "{}", cheap);
+        assert!(market.round_order("buy", Decimal::ZERO, dec!(0.01)).is_err());
+    }
+}
@@ -0,0 +1,43 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use sd_notify::NotifyState;
+
+// --- 🐧 SYSTEMD INTEGRATION (Type=notify + WatchdogSec) ---
+// All calls are no-ops when the bot is not started by systemd (no NOTIFY_SOCKET).
+
+static READY_SENT: AtomicBool = AtomicBool::new(false);
+
+fn send(states: &[NotifyState]) {
+    if let Err(e) = sd_notify::notify(false, states) {
+        eprintln!("sd_notify Error: {}", e);
+    }
+}
+
+/// READY=1, sent once: the first time the DB is initialized and a price tick came through.
+pub fn ready() {
+    if !READY_SENT.swap(true, Ordering::SeqCst) {
+        send(&[NotifyState::Ready, NotifyState::Status("Trading")]);
+    }
+}
+
+/// Watchdog keep-alive with a short status line for `systemctl status`.
+pub fn watchdog(status: &str) {
+    send(&[NotifyState::Watchdog, NotifyState::Status(status)]);
+}
+
+pub fn stopping() {
+    send(&[NotifyState::Stopping]);
+}
+
+/// RELOADING=1 with the timestamp newer systemd versions require.
+pub fn reloading() {
+    match NotifyState::monotonic_usec_now() {
+        Ok(now) => send(&[NotifyState::Reloading, now]),
+        Err(e) => eprintln!("sd_notify Error: {}", e),
+    }
+}
+
+pub fn ready_again() {
+    if READY_SENT.load(Ordering::SeqCst) {
+        send(&[NotifyState::Ready]);
+    }
+}
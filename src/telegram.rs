@@ -0,0 +1,144 @@
+use std::env;
+use std::time::Duration;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::time::sleep;
+
+use crate::config::SharedConfig;
+use crate::feed::MAX_BACKOFF_SECS;
+use crate::{control, money, runtime, BotState, DbManager, SharedState, PAIR};
+
+// --- 💬 TELEGRAM COMMANDS ---
+// Long-polls the Bot API for messages and answers /status, /pnl, /pause, /resume and /close from
+// whitelisted chats, through the same controls as the dashboard. The token comes from the
+// TELEGRAM_BOT_TOKEN env var; messages from any other chat are ignored. Control commands are recorded in
+// the activity log as telegram:<chat id>.
+
+const POLL_SECS: u64 = 50; // Long poll: the API holds the request until a message arrives or this passes
+const HELP: &str = "/status - state, price and position\n/pnl - realized and unrealized P&L\n/pause - stop new entries\n/resume - clear a pause or halt\n/close - sell the open position";
+
+#[derive(Clone, Debug, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TelegramConfig {
+    pub chat_ids: Vec<i64>, // Chats allowed to send commands; empty = commands off
+}
+
+#[derive(Deserialize)]
+struct Updates {
+    result: Vec<Update>,
+}
+
+#[derive(Deserialize)]
+struct Update {
+    update_id: i64,
+    message: Option<Message>,
+}
+
+#[derive(Deserialize)]
+struct Message {
+    chat: Chat,
+    text: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct Chat {
+    id: i64,
+}
+
+fn status(state: &SharedState) -> String {
+    let data = state.read();
+    let position = match data.position {
+        BotState::InPosition { entry_price, quantity, .. } => format!("{:.6} @ ${:.2} ({:+.2}%)", quantity, entry_price, data.unrealized_pl),
+        BotState::Idle => "flat".to_string(),
+    };
+    let halted = data.risk.halted.as_ref().map(|r| format!("\nHalted: {}", r)).unwrap_or_default();
+    format!("{}\nStatus: {}\nPrice: ${:.2}\nPosition: {}{}", PAIR, data.status, data.price, position, halted)
+}
+
+fn pnl(state: &SharedState) -> String {
+    let data = state.read();
+    let unrealized = match data.position {
+        BotState::InPosition { entry_price, quantity, .. } => (money::dec(data.price) - entry_price) * quantity,
+        BotState::Idle => Decimal::ZERO,
+    };
+    let s = &data.session;
+    format!("Realized: ${:.2} net, ${:.2} gross\nUnrealized: ${:.2}\nToday: ${:.2}\nTrades: {} ({} wins, {} losses)\nFees: ${:.2}",
+        data.realized_pl, data.gross_pl, unrealized, data.risk.day_pnl, s.trades, s.wins, s.losses, s.fees_paid)
+}
+
+// "/pause@my_bot extra" -> "/pause"
+fn command_of(text: &str) -> &str {
+    text.split_whitespace().next().unwrap_or("").split('@').next().unwrap_or("")
+}
+
+async fn run_command(state: &SharedState, chat_id: i64, command: &str) -> String {
+    let by = format!("telegram:{}", chat_id);
+    match command {
+        "/status" => status(state),
+        "/pnl" => pnl(state),
+        "/pause" => match control::pause(state, &by).await {
+            Ok(()) => "⏸️ Paused, no new entries until /resume".to_string(),
+            Err(e) => format!("Not paused: {}", e),
+        },
+        "/resume" => match control::resume(state).await {
+            Some(reason) => format!("▶️ Resumed (was: {})", reason),
+            None => "Not halted, nothing to resume".to_string(),
+        },
+        "/close" => match control::close(state, &by) {
+            Ok(()) => "Closing the position on the next tick".to_string(),
+            Err(e) => format!("Not closed: {}", e),
+        },
+        _ => HELP.to_string(),
+    }
+}
+
+async fn reply(client: &reqwest::Client, token: &str, chat_id: i64, text: &str) {
+    let url = format!("https://api.telegram.org/bot{}/sendMessage", token);
+    if let Err(e) = client.post(url).json(&serde_json::json!({ "chat_id": chat_id, "text": text })).send().await {
+        eprintln!("Telegram Error: {}", e.without_url());
+    }
+}
+
+/// Runs for the life of the process when TELEGRAM_BOT_TOKEN is set. The whitelist is re-read for
+/// every message, so config reloads apply.
+pub async fn poll_loop(state: SharedState, config: SharedConfig) {
+    let Ok(token) = env::var("TELEGRAM_BOT_TOKEN") else { return };
+    let client = reqwest::Client::builder().timeout(Duration::from_secs(POLL_SECS + 10)).build().unwrap();
+    let mut offset = 0;
+    let mut backoff = 1;
+    loop {
+        let url = format!("https://api.telegram.org/bot{}/getUpdates?timeout={}&offset={}", token, POLL_SECS, offset);
+        let updates = match client.get(url).send().await {
+            Ok(r) if r.status().is_success() => r.json::<Updates>().await.map_err(|e| e.without_url().to_string()),
+            Ok(r) => Err(format!("HTTP {}", r.status())),
+            Err(e) => Err(e.without_url().to_string()),
+        };
+        let updates = match updates {
+            Ok(updates) => updates,
+            Err(e) => {
+                eprintln!("Telegram Error: {}", e);
+                sleep(Duration::from_secs(backoff)).await;
+                backoff = (backoff * 2).min(MAX_BACKOFF_SECS);
+                continue;
+            },
+        };
+        backoff = 1;
+        for update in updates.result {
+            offset = offset.max(update.update_id + 1);
+            let Some(Message { chat, text: Some(text) }) = update.message else { continue };
+            if !text.starts_with('/') { continue; }
+            if !config.read().telegram.chat_ids.contains(&chat.id) {
+                eprintln!("Telegram: ignored {} from chat {} (not in chat_ids)", text, chat.id);
+                continue;
+            }
+            let command = command_of(&text);
+            let answer = run_command(&state, chat.id, command).await;
+            // Like the dashboard, only actions are recorded, not reads
+            if matches!(command, "/pause" | "/resume" | "/close") {
+                let (username, action) = (format!("telegram:{}", chat.id), format!("{} -> {}", command, answer));
+                let _ = runtime::db(move || DbManager::log_activity(&username, &action)).await;
+            }
+            reply(&client, &token, chat.id, &answer).await;
+        }
+    }
+}
@@ -0,0 +1,175 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, RecvTimeoutError, SyncSender};
+use std::sync::OnceLock;
+use std::thread;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, NaiveDate};
+use serde::{Deserialize, Serialize};
+
+use crate::{calendar, symbols, Candle};
+
+// --- 🎞️ TICK RECORDER ---
+// Every streamed trade is appended to a daily binary file as a fixed 16-byte record (time received
+// in millis, price; both little-endian). Appends are buffered on a writer thread and flushed about
+// once a second, so recording costs the feed one non-blocking send and SQLite never sees a tick.
+// The backtester reads the files back as 1-minute candles.
+
+const RECORD_SIZE: usize = 16;
+const QUEUE_SIZE: usize = 8192;
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RecorderConfig {
+    pub enabled: bool,
+    pub dir: String,
+    pub flush_ms: u64,       // Longest a tick sits in the write buffer
+    pub retention_days: u32, // Older day files are deleted, 0 = keep all
+}
+
+impl Default for RecorderConfig {
+    fn default() -> Self {
+        RecorderConfig { enabled: false, dir: "ticks".to_string(), flush_ms: 1000, retention_days: 30 }
+    }
+}
+
+#[derive(Clone, Copy, Debug)]
+pub struct Tick {
+    pub time: i64,
+    pub price: f64,
+}
+
+impl Tick {
+    fn encode(&self) -> [u8; RECORD_SIZE] {
+        let mut record = [0; RECORD_SIZE];
+        record[..8].copy_from_slice(&self.time.to_le_bytes());
+        record[8..].copy_from_slice(&self.price.to_le_bytes());
+        record
+    }
+
+    fn decode(record: &[u8]) -> Self {
+        let (time, price) = record.split_at(8);
+        Tick { time: i64::from_le_bytes(time.try_into().unwrap()), price: f64::from_le_bytes(price.try_into().unwrap()) }
+    }
+}
+
+static QUEUE: OnceLock<SyncSender<Tick>> = OnceLock::new();
+static DROPPED: AtomicU64 = AtomicU64::new(0);
+
+pub fn start(cfg: RecorderConfig) {
+    if !cfg.enabled { return; }
+    let (tx, rx) = mpsc::sync_channel(QUEUE_SIZE);
+    if QUEUE.set(tx).is_err() { return; }
+    let started = thread::Builder::new().name("scalper-ticks".to_string()).spawn(move || write_loop(rx, cfg));
+    if let Err(e) = started {
+        eprintln!("❌ Could not start the tick recorder: {}", e);
+    }
+}
+
+/// Called by the feed for every trade; never waits (a full queue drops the tick and counts it).
+pub fn record(time: i64, price: f64) {
+    if let Some(queue) = QUEUE.get() {
+        if queue.try_send(Tick { time, price }).is_err() {
+            DROPPED.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+fn day_of(time: i64) -> NaiveDate {
+    DateTime::from_timestamp_millis(time).unwrap_or_default().date_naive()
+}
+
+fn day_file(dir: &str, day: NaiveDate) -> PathBuf {
+    Path::new(dir).join(format!("{}-{}.bin", symbols::active().data_code, day))
+}
+
+fn open_day(dir: &str, day: NaiveDate) -> io::Result<BufWriter<File>> {
+    fs::create_dir_all(dir)?;
+    let file = OpenOptions::new().create(true).append(true).open(day_file(dir, day))?;
+    // A crash can leave half a record at the end; cut it so new records stay aligned
+    let len = file.metadata()?.len();
+    file.set_len(len - len % RECORD_SIZE as u64)?;
+    Ok(BufWriter::with_capacity(64 * 1024, file))
+}
+
+fn prune(dir: &str, today: NaiveDate, retention_days: u32) {
+    if retention_days == 0 { return; }
+    let Ok(entries) = fs::read_dir(dir) else { return };
+    let cutoff = today - chrono::Duration::days(i64::from(retention_days));
+    let prefix = format!("{}-", symbols::active().data_code);
+    for entry in entries.flatten() {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        let day = name.strip_prefix(&prefix).and_then(|rest| rest.strip_suffix(".bin"))
+            .and_then(|d| NaiveDate::parse_from_str(d, "%Y-%m-%d").ok());
+        if day.is_some_and(|d| d < cutoff) {
+            let _ = fs::remove_file(entry.path());
+        }
+    }
+}
+
+fn write_loop(rx: mpsc::Receiver<Tick>, cfg: RecorderConfig) {
+    let flush_every = Duration::from_millis(cfg.flush_ms.max(1));
+    let mut out: Option<(NaiveDate, BufWriter<File>)> = None;
+    let mut last_flush = Instant::now();
+    loop {
+        match rx.recv_timeout(flush_every) {
+            Ok(tick) => {
+                let day = day_of(tick.time);
+                if out.as_ref().map(|(d, _)| *d) != Some(day) {
+                    if let Some((_, mut w)) = out.take() { let _ = w.flush(); }
+                    match open_day(&cfg.dir, day) {
+                        Ok(w) => out = Some((day, w)),
+                        Err(e) => eprintln!("Tick Recorder Error: {}", e),
+                    }
+                    prune(&cfg.dir, day, cfg.retention_days);
+                }
+                if let Some((_, w)) = &mut out {
+                    if let Err(e) = w.write_all(&tick.encode()) { eprintln!("Tick Recorder Error: {}", e); }
+                }
+            },
+            Err(RecvTimeoutError::Timeout) => {},
+            Err(RecvTimeoutError::Disconnected) => break,
+        }
+        if last_flush.elapsed() >= flush_every {
+            if let Some((_, w)) = &mut out {
+                if let Err(e) = w.flush() { eprintln!("Tick Recorder Error: {}", e); }
+            }
+            let dropped = DROPPED.swap(0, Ordering::Relaxed);
+            if dropped > 0 { eprintln!("Tick Recorder: {} ticks dropped (writer behind)", dropped); }
+            last_flush = Instant::now();
+        }
+    }
+}
+
+/// Recorded ticks between `from` and `to` (millis, inclusive), oldest first.
+pub fn read(dir: &str, from: i64, to: i64) -> io::Result<Vec<Tick>> {
+    let mut ticks = Vec::new();
+    let last = day_of(to);
+    for day in day_of(from).iter_days().take_while(|d| *d <= last) {
+        match fs::read(day_file(dir, day)) {
+            Ok(bytes) => ticks.extend(bytes.chunks_exact(RECORD_SIZE).map(Tick::decode).filter(|t| t.time >= from && t.time <= to)),
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {},
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(ticks)
+}
+
+/// Aggregates ticks (oldest first) into 1-minute candles, the same way the live loop builds them.
+pub fn candles(ticks: &[Tick]) -> Vec<Candle> {
+    let mut candles: Vec<Candle> = Vec::new();
+    for tick in ticks {
+        let start = calendar::candle_start(tick.time);
+        match candles.last_mut() {
+            Some(c) if c.time == start => {
+                c.close = tick.price;
+                if tick.price > c.high { c.high = tick.price; }
+                if tick.price < c.low { c.low = tick.price; }
+            },
+            _ => candles.push(Candle { open: tick.price, high: tick.price, low: tick.price, close: tick.price, time: start }),
+        }
+    }
+    candles
+}
@@ -0,0 +1,41 @@
+use std::time::Duration;
+use serde::{Deserialize, Serialize};
+
+// --- ⏱️ PER-ENDPOINT TIMEOUTS ---
+// Every exchange call gets its own budget, so a slow public endpoint cannot hold up an order.
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeoutConfig {
+    pub tick_ms: u64,     // Latest price; a late tick is worthless, the next one comes in 5s
+    pub candles_ms: u64,  // History sync (500 candles) and backtest fetches
+    pub orders_ms: u64,
+    pub balances_ms: u64,
+    pub keep_warm_secs: u64, // Ping the order endpoint's host this often so orders reuse a live TLS connection, 0 = off
+}
+
+impl Default for TimeoutConfig {
+    fn default() -> Self {
+        TimeoutConfig { tick_ms: 800, candles_ms: 3000, orders_ms: 5000, balances_ms: 5000, keep_warm_secs: 30 }
+    }
+}
+
+impl TimeoutConfig {
+    pub fn tick(&self) -> Duration { Duration::from_millis(self.tick_ms) }
+    pub fn candles(&self) -> Duration { Duration::from_millis(self.candles_ms) }
+    pub fn orders(&self) -> Duration { Duration::from_millis(self.orders_ms) }
+    pub fn balances(&self) -> Duration { Duration::from_millis(self.balances_ms) }
+    pub fn keep_warm(&self) -> Option<Duration> { (self.keep_warm_secs > 0).then(|| Duration::from_secs(self.keep_warm_secs)) }
+}
+
+/// Dedicated connection pool for order placement, never queued behind market-data polling.
+/// Idle connections are kept (and TCP keepalives sent) well past the warm-up interval.
+pub fn order_client() -> reqwest::Client {
+    reqwest::Client::builder()
+        .tcp_nodelay(true)
+        .tcp_keepalive(Duration::from_secs(60))
+        .pool_idle_timeout(Duration::from_secs(300))
+        .pool_max_idle_per_host(2)
+        .build()
+        .unwrap()
+}
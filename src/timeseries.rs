@@ -0,0 +1,223 @@
+use std::collections::VecDeque;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::OnceLock;
+use std::time::Duration;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use tokio::sync::mpsc;
+use tokio::time::{interval, MissedTickBehavior};
+use tokio_postgres::NoTls;
+
+use crate::candles::CandleClosed;
+use crate::{latency, PAIR};
+
+// --- 📈 TIME-SERIES SINK ---
+// Closed candles with their indicators, the equity curve and hot-path latency are shipped to
+// InfluxDB (line protocol over HTTP) or TimescaleDB (Postgres tables, turned into hypertables when
+// the extension is installed) for long-term Grafana dashboards, so SQLite only keeps what the bot
+// itself reads. Points are batched on a background task; while the database is unreachable they
+// are held (up to queue_size) and retried. The trading loop never waits on it.
+
+const CHANNEL_SIZE: usize = 1024;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Backend {
+    #[default]
+    None,
+    Influx,
+    Timescale,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct TimeSeriesConfig {
+    pub backend: Backend,
+    pub url: String,       // Influx write URL (…/api/v2/write?org=o&bucket=b) or Postgres connection string
+    pub token: String,     // Influx API token (optional)
+    pub prefix: String,    // Measurement / table name prefix
+    pub flush_secs: u64,
+    pub queue_size: usize, // Points held while the database is unreachable; the oldest are dropped first
+}
+
+impl Default for TimeSeriesConfig {
+    fn default() -> Self {
+        TimeSeriesConfig {
+            backend: Backend::None,
+            url: String::new(),
+            token: String::new(),
+            prefix: "scalper".to_string(),
+            flush_secs: 10,
+            queue_size: 10_000,
+        }
+    }
+}
+
+enum Point {
+    Candle { time: i64, open: f64, high: f64, low: f64, close: f64, rsi: f64, bb_lower: f64, bb_upper: f64 },
+    Equity { time: i64, equity: f64 },
+    Latency { time: i64, stage: &'static str, quantiles: [f64; 3] }, // p50, p95, p99 in seconds
+}
+
+static QUEUE: OnceLock<mpsc::Sender<Point>> = OnceLock::new();
+static DROPPED: AtomicU64 = AtomicU64::new(0);
+
+pub fn start(cfg: TimeSeriesConfig) {
+    if cfg.backend == Backend::None { return; }
+    let (tx, rx) = mpsc::channel(CHANNEL_SIZE);
+    if QUEUE.set(tx).is_err() { return; }
+    tokio::spawn(write_loop(rx, cfg));
+}
+
+/// Called for every closed candle with the equity at its close; never waits.
+pub fn candle_closed(closed: &CandleClosed, equity: f64) {
+    let Some(queue) = QUEUE.get() else { return };
+    let (c, r) = (&closed.candle, &closed.readings);
+    let points = [
+        Point::Candle { time: c.time, open: c.open, high: c.high, low: c.low, close: c.close, rsi: r.rsi, bb_lower: r.bb_lower, bb_upper: r.bb_upper },
+        Point::Equity { time: c.time, equity },
+    ];
+    for point in points {
+        if queue.try_send(point).is_err() {
+            DROPPED.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+// Influx rejects NaN and infinities (indicators before warm-up), so those fields are left out
+fn line(out: &mut String, measurement: &str, tags: &str, fields: &[(&str, f64)], time: i64) {
+    let fields: Vec<String> = fields.iter().filter(|(_, v)| v.is_finite()).map(|(k, v)| format!("{}={}", k, v)).collect();
+    if fields.is_empty() { return; }
+    let _ = writeln!(out, "{},pair={}{} {} {}", measurement, PAIR, tags, fields.join(","), time * 1_000_000);
+}
+
+fn render_influx(prefix: &str, points: &[Point]) -> String {
+    let mut out = String::new();
+    for point in points {
+        match point {
+            Point::Candle { time, open, high, low, close, rsi, bb_lower, bb_upper } => line(&mut out, &format!("{}_candle", prefix), "",
+                &[("open", *open), ("high", *high), ("low", *low), ("close", *close), ("rsi", *rsi), ("bb_lower", *bb_lower), ("bb_upper", *bb_upper)], *time),
+            Point::Equity { time, equity } => line(&mut out, &format!("{}_equity", prefix), "", &[("equity", *equity)], *time),
+            Point::Latency { time, stage, quantiles: [p50, p95, p99] } => line(&mut out, &format!("{}_latency", prefix), &format!(",stage={}", stage),
+                &[("p50", *p50), ("p95", *p95), ("p99", *p99)], *time),
+        }
+    }
+    out
+}
+
+fn timescale_schema(prefix: &str) -> String {
+    format!("CREATE TABLE IF NOT EXISTS {p}_candles (
+        time TIMESTAMPTZ NOT NULL, pair TEXT NOT NULL,
+        open DOUBLE PRECISION, high DOUBLE PRECISION, low DOUBLE PRECISION, close DOUBLE PRECISION,
+        rsi DOUBLE PRECISION, bb_lower DOUBLE PRECISION, bb_upper DOUBLE PRECISION,
+        PRIMARY KEY (pair, time));
+    CREATE TABLE IF NOT EXISTS {p}_equity (
+        time TIMESTAMPTZ NOT NULL, pair TEXT NOT NULL, equity DOUBLE PRECISION,
+        PRIMARY KEY (pair, time));
+    CREATE TABLE IF NOT EXISTS {p}_latency (
+        time TIMESTAMPTZ NOT NULL, pair TEXT NOT NULL, stage TEXT NOT NULL,
+        p50 DOUBLE PRECISION, p95 DOUBLE PRECISION, p99 DOUBLE PRECISION);", p = prefix)
+}
+
+async fn connect_timescale(cfg: &TimeSeriesConfig) -> Result<tokio_postgres::Client, String> {
+    let (client, connection) = tokio_postgres::connect(&cfg.url, NoTls).await.map_err(|e| e.to_string())?;
+    tokio::spawn(async move {
+        if let Err(e) = connection.await { eprintln!("Time-Series Error: connection: {}", e); }
+    });
+    client.batch_execute(&timescale_schema(&cfg.prefix)).await.map_err(|e| e.to_string())?;
+    // Without the timescaledb extension these fail and the tables stay plain Postgres tables
+    for table in ["candles", "equity", "latency"] {
+        let _ = client.execute(&format!("SELECT create_hypertable('{}_{}', 'time', if_not_exists => TRUE)", cfg.prefix, table), &[]).await;
+    }
+    Ok(client)
+}
+
+fn at(time: i64) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(time).unwrap_or_default()
+}
+
+async fn write_timescale(client: &mut tokio_postgres::Client, prefix: &str, points: &[Point]) -> Result<(), tokio_postgres::Error> {
+    let tx = client.transaction().await?;
+    let candle = tx.prepare(&format!("INSERT INTO {}_candles (time, pair, open, high, low, close, rsi, bb_lower, bb_upper) \
+        VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9) ON CONFLICT DO NOTHING", prefix)).await?;
+    let equity = tx.prepare(&format!("INSERT INTO {}_equity (time, pair, equity) VALUES ($1, $2, $3) ON CONFLICT DO NOTHING", prefix)).await?;
+    let latency = tx.prepare(&format!("INSERT INTO {}_latency (time, pair, stage, p50, p95, p99) VALUES ($1, $2, $3, $4, $5, $6)", prefix)).await?;
+    for point in points {
+        match point {
+            Point::Candle { time, open, high, low, close, rsi, bb_lower, bb_upper } =>
+                tx.execute(&candle, &[&at(*time), &PAIR, open, high, low, close, rsi, bb_lower, bb_upper]).await?,
+            Point::Equity { time, equity: value } => tx.execute(&equity, &[&at(*time), &PAIR, value]).await?,
+            Point::Latency { time, stage, quantiles: [p50, p95, p99] } =>
+                tx.execute(&latency, &[&at(*time), &PAIR, stage, p50, p95, p99]).await?,
+        };
+    }
+    tx.commit().await
+}
+
+enum Sink {
+    Influx(reqwest::Client),
+    Timescale(Option<tokio_postgres::Client>), // Connected on first use and after a failure
+}
+
+impl Sink {
+    async fn write(&mut self, cfg: &TimeSeriesConfig, points: &[Point]) -> Result<(), String> {
+        match self {
+            Sink::Influx(client) => {
+                let mut req = client.post(&cfg.url).body(render_influx(&cfg.prefix, points));
+                if !cfg.token.is_empty() {
+                    req = req.header("Authorization", format!("Token {}", cfg.token));
+                }
+                match req.send().await {
+                    Ok(r) if r.status().is_success() => Ok(()),
+                    Ok(r) => Err(format!("HTTP {}", r.status())),
+                    Err(e) => Err(e.to_string()),
+                }
+            },
+            Sink::Timescale(conn) => {
+                if conn.as_ref().is_none_or(|c| c.is_closed()) {
+                    *conn = Some(connect_timescale(cfg).await?);
+                }
+                let Some(client) = conn else { return Ok(()) };
+                let result = write_timescale(client, &cfg.prefix, points).await.map_err(|e| e.to_string());
+                if result.is_err() { *conn = None; }
+                result
+            },
+        }
+    }
+}
+
+async fn write_loop(mut rx: mpsc::Receiver<Point>, cfg: TimeSeriesConfig) {
+    let mut sink = match cfg.backend {
+        Backend::Influx => Sink::Influx(reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap()),
+        Backend::Timescale => Sink::Timescale(None),
+        Backend::None => return,
+    };
+    let mut pending: VecDeque<Point> = VecDeque::new();
+    let mut flush = interval(Duration::from_secs(cfg.flush_secs.max(1)));
+    flush.set_missed_tick_behavior(MissedTickBehavior::Delay);
+    loop {
+        tokio::select! {
+            point = rx.recv() => match point {
+                Some(point) => pending.push_back(point),
+                None => break,
+            },
+            _ = flush.tick() => {
+                let now = Utc::now().timestamp_millis();
+                pending.extend(latency::summaries().into_iter().filter(|s| s.count > 0)
+                    .map(|s| Point::Latency { time: now, stage: s.stage, quantiles: s.quantiles }));
+                while pending.len() > cfg.queue_size {
+                    pending.pop_front();
+                    DROPPED.fetch_add(1, Ordering::Relaxed);
+                }
+                let dropped = DROPPED.swap(0, Ordering::Relaxed);
+                if dropped > 0 { eprintln!("Time-Series: {} points dropped", dropped); }
+                if pending.is_empty() { continue; }
+                match sink.write(&cfg, pending.make_contiguous()).await {
+                    Ok(()) => pending.clear(),
+                    Err(e) => eprintln!("Time-Series Error ({:?}): {} ({} points held)", cfg.backend, e, pending.len()),
+                }
+            },
+        }
+    }
+}
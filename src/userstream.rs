@@ -0,0 +1,194 @@
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+use parking_lot::Mutex;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{json, Value};
+use tokio::sync::oneshot;
+use tokio::time::{sleep, timeout_at, Instant};
+
+use crate::executor::Fill;
+use crate::feed::{self, Flow, MAX_BACKOFF_SECS};
+use crate::{add_log, bybit, get_api_credentials, money, okx, sign_payload, simulating, symbols, SharedState};
+
+// --- 📬 USER STREAM (private WebSocket) ---
+// The account's own events pushed by the exchange over its authenticated WebSocket: order updates
+// and balance changes. While it is connected, an order's final state (filled, partly filled then
+// canceled, canceled, rejected) comes from the stream instead of polling the order. A balance
+// change updates the wallet right away and brings the next balance sync (and with it the external
+// trade check) forward. When the stream is down, or says nothing about an order in time,
+// everything falls back to polling; a CoinDCX order still working at its deadline is canceled and
+// its status read back, never taken as filled. CoinDCX joins the private "coindcx" channel, Bybit
+// subscribes to order, wallet and position, OKX to orders and account. Live mode only; read at
+// startup.
+
+// What is left of an order's wait for polling it, when the stream has not reported by then
+const FALLBACK: Duration = Duration::from_secs(1);
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+#[serde(default)]
+pub struct UserStreamConfig {
+    pub enabled: bool,
+}
+
+impl Default for UserStreamConfig {
+    fn default() -> Self {
+        UserStreamConfig { enabled: true }
+    }
+}
+
+/// Something the exchange pushed about the account.
+pub enum AccountEvent {
+    Ready, // Authenticated and subscribed
+    Order { client_id: String, outcome: Result<Fill, String> }, // Final state of an order
+    Balances { quote: Option<Decimal>, base: Option<Decimal> },
+}
+
+type Waiter = (String, oneshot::Sender<Result<Fill, String>>);
+
+static CONNECTED: AtomicBool = AtomicBool::new(false);
+static BALANCE_CHANGED: AtomicBool = AtomicBool::new(false);
+static WAITERS: Mutex<Vec<Waiter>> = parking_lot::const_mutex(Vec::new());
+
+/// Starts the stream task (live mode only).
+pub fn start(cfg: UserStreamConfig, state: SharedState) {
+    if !cfg.enabled || simulating() { return; }
+    tokio::spawn(run(state));
+}
+
+pub fn connected() -> bool {
+    CONNECTED.load(Ordering::Relaxed)
+}
+
+/// Waits for the final state of `client_id` on the stream; registered before the order is sent so
+/// a fast fill is not missed. None while the stream is down.
+pub fn watch(client_id: &str) -> Option<oneshot::Receiver<Result<Fill, String>>> {
+    if !connected() { return None; }
+    let (tx, rx) = oneshot::channel();
+    let mut waiters = WAITERS.lock();
+    // Orders that were given up on
+    waiters.retain(|(_, tx)| !tx.is_closed());
+    waiters.push((client_id.to_string(), tx));
+    Some(rx)
+}
+
+/// The order's outcome from the stream, or None to poll for it: no stream, the stream dropped, or
+/// nothing until shortly before `deadline`.
+pub async fn report(watch: Option<oneshot::Receiver<Result<Fill, String>>>, deadline: Instant) -> Option<Result<Fill, String>> {
+    let until = deadline.checked_sub(FALLBACK)?;
+    match timeout_at(until, watch?).await {
+        Ok(Ok(outcome)) => Some(outcome),
+        _ => None,
+    }
+}
+
+/// Whether the stream saw the balances change since the last call; the trading loop syncs them now if so.
+pub fn take_balance_change() -> bool {
+    BALANCE_CHANGED.swap(false, Ordering::Relaxed)
+}
+
+fn apply(state: &SharedState, event: AccountEvent) {
+    match event {
+        AccountEvent::Ready => {
+            CONNECTED.store(true, Ordering::Relaxed);
+            add_log(state, "📬 User stream connected, fills and balances pushed by the exchange".to_string());
+        },
+        AccountEvent::Order { client_id, outcome } => {
+            let mut waiters = WAITERS.lock();
+            if let Some(i) = waiters.iter().position(|(id, _)| *id == client_id) {
+                let (_, tx) = waiters.swap_remove(i);
+                let _ = tx.send(outcome);
+            }
+        },
+        AccountEvent::Balances { quote, base } => {
+            let market = symbols::active();
+            let mut data = state.write();
+            if let Some(quote) = quote {
+                data.wallet_usdt = quote;
+                Arc::make_mut(&mut data.balances).insert(market.quote().to_string(), quote);
+            }
+            if let Some(base) = base {
+                data.wallet_btc = base;
+                Arc::make_mut(&mut data.balances).insert(market.base().to_string(), base);
+            }
+            BALANCE_CHANGED.store(true, Ordering::Relaxed);
+        },
+    }
+}
+
+async fn run(state: SharedState) {
+    let mut backoff = 1;
+    loop {
+        let connected_at = Instant::now();
+        let mut on_event = |event: AccountEvent| apply(&state, event);
+        let streamed = if bybit::enabled() {
+            bybit::stream_account(&mut on_event).await
+        } else if okx::enabled() {
+            okx::stream_account(&mut on_event).await
+        } else {
+            stream_account(&mut on_event).await
+        };
+        // Whoever is still waiting polls instead
+        CONNECTED.store(false, Ordering::Relaxed);
+        WAITERS.lock().clear();
+        let err = streamed.err().unwrap_or_else(|| "stopped".to_string());
+        add_log(&state, format!("⚠️ User stream down ({}), polling orders and balances", err));
+        if connected_at.elapsed() > Duration::from_secs(60) { backoff = 1; }
+        sleep(Duration::from_secs(backoff)).await;
+        backoff = (backoff * 2).min(MAX_BACKOFF_SECS);
+    }
+}
+
+// CoinDCX: the "coindcx" channel, joined with the key and a signature of {"channel":"coindcx"}.
+// Events carry their payload as a JSON string in data.
+async fn stream_account(on_event: &mut impl FnMut(AccountEvent)) -> Result<(), String> {
+    let (key, secret) = get_api_credentials();
+    let body = json!({ "channel": "coindcx" }).to_string();
+    let join = json!({ "channelName": "coindcx", "authSignature": sign_payload(&body, &secret), "apiKey": key });
+    // No acknowledgement for a private join; a bad signature only shows as silence
+    on_event(AccountEvent::Ready);
+    feed::subscribe_with(join, |event| {
+        let event: Value = serde_json::from_str(event).unwrap_or_default();
+        let data = &event[1]["data"];
+        let items: Value = match data.as_str() {
+            Some(raw) => serde_json::from_str(raw).unwrap_or_default(),
+            None => data.clone(),
+        };
+        let items = match items {
+            Value::Array(items) => items,
+            item => vec![item],
+        };
+        match event[0].as_str() {
+            Some("order-update") => for order in &items {
+                let client_id = order["client_order_id"].as_str().unwrap_or("");
+                if client_id.is_empty() { continue; }
+                if let Some(outcome) = order_outcome(order) {
+                    on_event(AccountEvent::Order { client_id: client_id.to_string(), outcome });
+                }
+            },
+            Some("balance-update") => {
+                let market = symbols::active();
+                let balance = |currency: &str| items.iter().find(|b| b["currency_short_name"] == currency).map(|b| money::from_value(&b["balance"]));
+                let (quote, base) = (balance(market.quote()), balance(market.base()));
+                if quote.is_some() || base.is_some() { on_event(AccountEvent::Balances { quote, base }); }
+            },
+            _ => {},
+        }
+        Flow::Continue
+    }).await
+}
+
+/// A CoinDCX order's outcome once it is done, from its status and what is left of it (the same in
+/// the stream and from the REST order status).
+pub fn order_outcome(order: &Value) -> Option<Result<Fill, String>> {
+    let filled = money::from_value(&order["total_quantity"]) - money::from_value(&order["remaining_quantity"]);
+    let fill = Fill { price: money::from_value(&order["avg_price"]), qty: filled };
+    match order["status"].as_str().unwrap_or("") {
+        "filled" => Some(Ok(fill)),
+        "cancelled" | "partially_cancelled" if filled > Decimal::ZERO => Some(Ok(fill)),
+        "cancelled" | "partially_cancelled" => Some(Err("not filled (canceled)".to_string())),
+        "rejected" => Some(Err("order rejected".to_string())),
+        _ => None, // Open or partly filled, still working
+    }
+}
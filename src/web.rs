@@ -0,0 +1,537 @@
+use std::time::Duration;
+use axum::{
+    extract::{FromRef, Path, Query, State},
+    http::{header, StatusCode},
+    middleware,
+    response::{Html, IntoResponse, Redirect, Response},
+    Extension, Form,
+    routing::{get, post, put},
+    Router,
+    Json,
+};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use tokio::time::sleep;
+
+use crate::alerts::{AlertCondition, AlertRule};
+use crate::auth::{self, Session, SharedSessions};
+use crate::backtest::{self, BacktestRun};
+use crate::config::{Config, SharedConfig};
+use crate::orderbook::{DepthView, SharedBook};
+use crate::risk::{RiskConfig, RiskState};
+use crate::stats::{self, Performance};
+use crate::strategy::{self, StrategyInfo, StrategyParams};
+use crate::{
+    assets, control, fetch_historical_candles, latency, metrics, money, runtime, simulating, ticks, ActivityRecord, Candle,
+    DashboardData, DbManager, SharedState, TradeRecord, BACKTEST_CANDLES, BOOK_DEPTH, PAIR, PORT, PUBLIC_PORT, TIMEFRAME,
+};
+
+// --- 🖥️ DASHBOARD ---
+// The web UI and its JSON API (behind the `web` feature, on by default): the dashboard, login,
+// backtests, strategy and alert editing, risk controls, and the optional read-only public page.
+// Everything here reads the shared state and config or goes through control, like Telegram and
+// gRPC do.
+
+// Everything needed to debug or monitor the bot in a single document
+#[derive(Serialize)]
+struct Snapshot {
+    generated_at: String,
+    mode: &'static str,
+    pair: &'static str,
+    timeframe: &'static str,
+    config: Config,
+    state: DashboardData,
+    recent_candles: Vec<Candle>,
+    recent_trades: Vec<TradeRecord>,
+    performance: Performance,
+}
+
+// Sanitized view for the public dashboard: no balances, sizes or logs
+#[derive(Serialize)]
+struct PublicStats {
+    pair: &'static str,
+    price: f64,
+    rsi: f64,
+    status: PublicStatus,
+    unrealized_pl: f64,     // %
+    realized_pl_pct: f64,   // % of trade capital
+}
+
+// The loop's status text can carry balances and limits ("IDLE (Blocked: no size left within
+// limits (wallet $...))"), so the public page only gets what it amounts to
+#[derive(Serialize)]
+#[serde(rename_all = "UPPERCASE")]
+enum PublicStatus {
+    Idle,
+    Holding,
+    Pending,
+    Halted,
+}
+
+impl PublicStatus {
+    fn of(data: &DashboardData) -> Self {
+        if data.status.starts_with("HALTED") || data.risk.halted.is_some() {
+            PublicStatus::Halted
+        } else if data.status.contains("PENDING") {
+            PublicStatus::Pending
+        } else if data.slots.open() > 0 {
+            PublicStatus::Holding
+        } else {
+            PublicStatus::Idle
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SessionSummary {
+    uptime_secs: i64,
+    mode: &'static str,
+    pair: &'static str,
+    strategy: String,
+    status: String,
+    signals: u32,
+    trades: u32,
+    unfilled: u32,   // Simulated limit orders that expired, for the fill rate
+    closed_trades: u32,
+    win_rate: f64,
+    fees_paid: Decimal,
+    realized_pl: Decimal, // Net of the spread, slippage and fees
+    gross_pl: Decimal,    // At the signal prices
+    restarts: u32,
+    breaker: String,
+    breaker_trips: u32,
+    last_error: Option<String>,
+}
+
+#[derive(Clone)]
+pub struct AppState {
+    dashboard: SharedState,
+    pub config: SharedConfig, // Read by the login gate
+    book: SharedBook,
+    pub sessions: SharedSessions,
+}
+
+impl FromRef<AppState> for SharedState {
+    fn from_ref(app: &AppState) -> Self { app.dashboard.clone() }
+}
+
+impl FromRef<AppState> for SharedConfig {
+    fn from_ref(app: &AppState) -> Self { app.config.clone() }
+}
+
+impl FromRef<AppState> for SharedBook {
+    fn from_ref(app: &AppState) -> Self { app.book.clone() }
+}
+
+#[derive(Debug, Deserialize)]
+struct TradesQuery {
+    limit: Option<usize>,
+}
+
+#[derive(Debug, Deserialize)]
+struct StrategyRequest {
+    name: Option<String>,
+    #[serde(flatten)]
+    params: StrategyParams,
+}
+
+#[derive(Debug, Deserialize)]
+struct BacktestRequest {
+    name: Option<String>,
+    #[serde(flatten)]
+    params: StrategyParams,
+    recorded_days: Option<u32>, // Run on the tick recorder's files instead of exchange history
+}
+
+#[derive(Debug, Deserialize)]
+struct AlertRuleRequest {
+    condition: AlertCondition,
+    enabled: Option<bool>,
+}
+
+#[derive(Debug, Deserialize)]
+struct LoginForm {
+    username: String,
+    password: String,
+}
+
+#[derive(Serialize)]
+struct WhoAmI {
+    auth_enabled: bool,
+    username: Option<String>,
+    role: Option<auth::Role>,
+}
+
+#[derive(Serialize)]
+struct StrategyResponse {
+    active: String,
+    params: StrategyParams,     // With the pair's overrides
+    overrides: toml::Table,     // [pairs."<pair>".strategy], what differs from [strategy]
+    in_position: bool,
+    available: Vec<StrategyInfo>,
+}
+
+#[derive(Serialize)]
+struct RiskResponse {
+    config: RiskConfig,     // With the pair's overrides
+    overrides: toml::Table, // [pairs."<pair>".risk]
+    state: RiskState,
+}
+
+async fn dashboard_handler(headers: axum::http::HeaderMap) -> Response {
+    assets::page("dashboard.html", &headers)
+}
+
+async fn asset_handler(Path(file): Path<String>, headers: axum::http::HeaderMap) -> Response {
+    assets::file(&file, &headers)
+}
+
+async fn backtests_page_handler(headers: axum::http::HeaderMap) -> Response {
+    assets::page("backtests.html", &headers)
+}
+
+async fn public_dashboard_handler(headers: axum::http::HeaderMap) -> Response {
+    assets::page("public.html", &headers)
+}
+
+// Realized P&L as a % of the strategy's trade_capital, the capital the risk limits are set against
+async fn public_api_handler(State(state): State<SharedState>, State(config): State<SharedConfig>) -> Json<PublicStats> {
+    let capital = config.read().strategy.params.trade_capital;
+    let data = state.read();
+    Json(PublicStats {
+        pair: PAIR,
+        price: data.price,
+        rsi: data.rsi,
+        status: PublicStatus::of(&data),
+        unrealized_pl: data.unrealized_pl,
+        realized_pl_pct: if capital > 0.0 { money::float(data.realized_pl) / capital * 100.0 } else { 0.0 },
+    })
+}
+
+async fn api_handler(State(state): State<SharedState>, headers: axum::http::HeaderMap) -> Response {
+    let rendered = state.json();
+    // Nothing published since this viewer's last poll
+    if headers.get(header::IF_NONE_MATCH).is_some_and(|tag| tag.as_bytes() == rendered.etag.as_bytes()) {
+        return (StatusCode::NOT_MODIFIED, [(header::ETAG, rendered.etag)]).into_response();
+    }
+    let headers = [
+        (header::CONTENT_TYPE, "application/json".to_string()),
+        (header::CACHE_CONTROL, "no-cache".to_string()),
+        (header::ETAG, rendered.etag),
+    ];
+    (headers, rendered.body).into_response()
+}
+
+async fn session_handler(State(state): State<SharedState>) -> Json<SessionSummary> {
+    let data = state.read();
+    let s = &data.session;
+    let closed_trades = s.wins + s.losses;
+    Json(SessionSummary {
+        uptime_secs: (Utc::now().timestamp_millis() - s.started_at) / 1000,
+        mode: if simulating() { "simulation" } else { "live" },
+        pair: PAIR,
+        strategy: data.strategy.clone(),
+        status: data.status.clone(),
+        signals: s.signals,
+        trades: s.trades,
+        unfilled: s.unfilled,
+        closed_trades,
+        win_rate: if closed_trades == 0 { 0.0 } else { s.wins as f64 / closed_trades as f64 * 100.0 },
+        fees_paid: s.fees_paid,
+        realized_pl: data.realized_pl,
+        gross_pl: data.gross_pl,
+        restarts: s.restarts,
+        breaker: data.breaker.clone(),
+        breaker_trips: s.breaker_trips,
+        last_error: s.last_error.clone(),
+    })
+}
+
+async fn performance_handler() -> Json<Performance> {
+    let (trades, equity) = runtime::db(|| (DbManager::get_all_trades(), DbManager::get_equity_curve())).await;
+    Json(stats::compute(&trades.unwrap_or_default(), &equity.unwrap_or_default()))
+}
+
+async fn snapshot_handler(State(app): State<AppState>) -> Json<Snapshot> {
+    let (trades, equity, candles) = runtime::db(|| {
+        (DbManager::get_all_trades(), DbManager::get_equity_curve(), DbManager::get_recent_candles(50))
+    }).await;
+    let trades = trades.unwrap_or_default();
+    let performance = stats::compute(&trades, &equity.unwrap_or_default());
+    let recent_trades = trades.into_iter().rev().take(50).collect();
+
+    Json(Snapshot {
+        generated_at: Utc::now().to_rfc3339(),
+        mode: if simulating() { "simulation" } else { "live" },
+        pair: PAIR,
+        timeframe: TIMEFRAME,
+        config: app.config.read().redacted(),
+        state: DashboardData::clone(&app.dashboard.read()),
+        recent_candles: candles.unwrap_or_default(),
+        recent_trades,
+        performance,
+    })
+}
+
+async fn login_page_handler(headers: axum::http::HeaderMap) -> Response {
+    assets::page("login.html", &headers)
+}
+
+async fn login_handler(State(app): State<AppState>, Form(form): Form<LoginForm>) -> Response {
+    match auth::login(&app, &form.username, &form.password) {
+        Some((token, session)) => {
+            let username = session.username.clone();
+            let _ = runtime::db(move || DbManager::log_activity(&username, "LOGIN")).await;
+            ([(header::SET_COOKIE, auth::new_session_cookie(&token))], Redirect::to("/")).into_response()
+        },
+        None => {
+            let _ = runtime::db(move || DbManager::log_activity(&form.username, "LOGIN FAILED")).await;
+            (StatusCode::UNAUTHORIZED, Html("Invalid credentials. <a href=\"/login\">Try again</a>")).into_response()
+        },
+    }
+}
+
+async fn logout_handler(State(app): State<AppState>, req: axum::extract::Request) -> Response {
+    let session = auth::token_from_request(&req).and_then(|token| app.sessions.write().remove(&token));
+    if let Some(session) = session {
+        let _ = runtime::db(move || DbManager::log_activity(&session.username, "LOGOUT")).await;
+    }
+    ([(header::SET_COOKIE, auth::session_cookie("", 0))], Redirect::to("/login")).into_response()
+}
+
+async fn whoami_handler(State(app): State<AppState>, session: Option<Extension<Session>>) -> Json<WhoAmI> {
+    Json(WhoAmI {
+        auth_enabled: !app.config.read().users.is_empty(),
+        username: session.as_ref().map(|s| s.username.clone()),
+        role: session.map(|s| s.role),
+    })
+}
+
+async fn activity_handler() -> Json<Vec<ActivityRecord>> {
+    Json(runtime::db(|| DbManager::get_activity(50)).await.unwrap_or_default())
+}
+
+async fn metrics_handler(State(app): State<AppState>) -> String {
+    let prefix = app.config.read().metrics.prefix.clone();
+    let samples = metrics::collect(&app.dashboard.read());
+    metrics::render_prometheus(&prefix, &samples) + &latency::render_prometheus(&prefix)
+}
+
+async fn orderbook_handler(State(book): State<SharedBook>) -> Json<DepthView> {
+    Json(book.read().view(BOOK_DEPTH))
+}
+
+async fn backtests_list_handler() -> Json<Vec<BacktestRun>> {
+    Json(runtime::db(|| DbManager::get_backtests(None, false)).await.unwrap_or_default())
+}
+
+async fn backtest_detail_handler(Path(id): Path<i64>) -> Result<Json<BacktestRun>, StatusCode> {
+    let mut runs = runtime::db(move || DbManager::get_backtests(Some(id), true)).await.map_err(|_| StatusCode::INTERNAL_SERVER_ERROR)?;
+    runs.pop().map(Json).ok_or(StatusCode::NOT_FOUND)
+}
+
+async fn backtest_run_handler(State(config): State<SharedConfig>, Json(req): Json<BacktestRequest>) -> Result<Json<BacktestRun>, (StatusCode, String)> {
+    let name = req.name.unwrap_or_else(|| strategy::DEFAULT_STRATEGY.to_string());
+    strategy::validate(&name, &req.params).map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+
+    let candles = match req.recorded_days {
+        Some(days) => {
+            let dir = config.read().recorder.dir.clone();
+            let to = Utc::now().timestamp_millis();
+            let from = to - i64::from(days) * 86_400_000;
+            let recorded = tokio::task::spawn_blocking(move || ticks::read(&dir, from, to)).await
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?
+                .map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Reading recorded ticks failed: {}", e)))?;
+            let candles = ticks::candles(&recorded);
+            if candles.is_empty() {
+                return Err((StatusCode::NOT_FOUND, format!("No recorded ticks in the last {} day(s)", days)));
+            }
+            candles
+        },
+        None => {
+            let client = reqwest::Client::builder().timeout(Duration::from_secs(10)).build().unwrap();
+            // User-initiated and not latency sensitive, so it keeps the generous client timeout
+            let mut candles = fetch_historical_candles(&client, BACKTEST_CANDLES, Duration::from_secs(10)).await
+                .map_err(|e| (StatusCode::BAD_GATEWAY, format!("History fetch failed: {}", e)))?;
+            candles.sort_by_key(|c| c.time);
+            candles
+        },
+    };
+
+    let spread_bps = config.read().simulation.spread_bps;
+    let mut run = backtest::run(&candles, strategy::build(&name, req.params).as_ref(), spread_bps);
+    run.created = Utc::now().to_rfc3339();
+    let (saved, mut run) = runtime::db(move || (DbManager::save_backtest(&run), run)).await;
+    run.id = saved.map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()))?;
+    Ok(Json(run))
+}
+
+fn strategy_response(dashboard: &SharedState, config: &SharedConfig) -> StrategyResponse {
+    let (cfg, overrides) = { let c = config.read(); (c.strategy.clone(), c.pair_overrides().strategy) };
+    StrategyResponse {
+        active: cfg.name,
+        params: cfg.params,
+        overrides,
+        in_position: !dashboard.read().entry_price.is_zero(),
+        available: strategy::available(),
+    }
+}
+
+async fn strategy_get_handler(State(app): State<AppState>) -> Json<StrategyResponse> {
+    Json(strategy_response(&app.dashboard, &app.config))
+}
+
+async fn strategy_update_handler(
+    State(app): State<AppState>,
+    Json(req): Json<StrategyRequest>,
+) -> Result<Json<StrategyResponse>, (StatusCode, String)> {
+    control::set_strategy(&app.dashboard, &app.config, req.name, req.params)?;
+    Ok(Json(strategy_response(&app.dashboard, &app.config)))
+}
+
+fn save_config(config: &SharedConfig) -> Result<(), (StatusCode, String)> {
+    config.write().save().map_err(|e| (StatusCode::INTERNAL_SERVER_ERROR, format!("Config save failed: {}", e)))
+}
+
+async fn alerts_list_handler(State(config): State<SharedConfig>) -> Json<Vec<AlertRule>> {
+    Json(config.read().alerts.clone())
+}
+
+async fn alert_create_handler(
+    State(config): State<SharedConfig>,
+    Json(req): Json<AlertRuleRequest>,
+) -> Result<Json<AlertRule>, (StatusCode, String)> {
+    req.condition.validate().map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let rule = {
+        let mut cfg = config.write();
+        let id = cfg.alerts.iter().map(|r| r.id).max().unwrap_or(0) + 1;
+        let rule = AlertRule { id, condition: req.condition, enabled: req.enabled.unwrap_or(true) };
+        cfg.alerts.push(rule.clone());
+        rule
+    };
+    save_config(&config)?;
+    Ok(Json(rule))
+}
+
+async fn alert_update_handler(
+    State(config): State<SharedConfig>,
+    Path(id): Path<u32>,
+    Json(req): Json<AlertRuleRequest>,
+) -> Result<Json<AlertRule>, (StatusCode, String)> {
+    req.condition.validate().map_err(|e| (StatusCode::BAD_REQUEST, e))?;
+    let rule = {
+        let mut cfg = config.write();
+        let rule = cfg.alerts.iter_mut().find(|r| r.id == id)
+            .ok_or((StatusCode::NOT_FOUND, format!("No alert rule {}", id)))?;
+        rule.condition = req.condition;
+        if let Some(enabled) = req.enabled { rule.enabled = enabled; }
+        rule.clone()
+    };
+    save_config(&config)?;
+    Ok(Json(rule))
+}
+
+async fn alert_delete_handler(
+    State(config): State<SharedConfig>,
+    Path(id): Path<u32>,
+) -> Result<StatusCode, (StatusCode, String)> {
+    {
+        let mut cfg = config.write();
+        let before = cfg.alerts.len();
+        cfg.alerts.retain(|r| r.id != id);
+        if cfg.alerts.len() == before {
+            return Err((StatusCode::NOT_FOUND, format!("No alert rule {}", id)));
+        }
+    }
+    save_config(&config)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn risk_handler(State(app): State<AppState>) -> Json<RiskResponse> {
+    let (config, overrides) = { let c = app.config.read(); (c.risk.clone(), c.pair_overrides().risk) };
+    Json(RiskResponse { config, overrides, state: app.dashboard.read().risk.clone() })
+}
+
+// Explicit re-arm after a kill switch tripped (or a pause)
+async fn risk_rearm_handler(State(app): State<AppState>) -> Json<RiskResponse> {
+    control::resume(&app.dashboard).await;
+    risk_handler(State(app)).await
+}
+
+// Manual halt: no new entries until re-armed
+async fn risk_pause_handler(State(app): State<AppState>, session: Option<Extension<Session>>) -> Result<Json<RiskResponse>, (StatusCode, String)> {
+    let by = session.map_or_else(|| "dashboard".to_string(), |s| s.username.clone());
+    control::pause(&app.dashboard, &by).await.map_err(|e| (StatusCode::CONFLICT, e))?;
+    Ok(risk_handler(State(app)).await)
+}
+
+async fn position_close_handler(State(app): State<AppState>, session: Option<Extension<Session>>) -> Result<StatusCode, (StatusCode, String)> {
+    let by = session.map_or_else(|| "dashboard".to_string(), |s| s.username.clone());
+    control::close(&app.dashboard, &by).map_err(|e| (StatusCode::CONFLICT, e))?;
+    Ok(StatusCode::ACCEPTED)
+}
+
+async fn trades_handler(Query(query): Query<TradesQuery>) -> Json<Vec<TradeRecord>> {
+    let limit = query.limit.unwrap_or(200).min(1000);
+    Json(runtime::db(move || DbManager::get_recent_trades(limit)).await.unwrap_or_default())
+}
+
+/// Serves the dashboard on PORT (and the public page on PUBLIC_PORT); runs until the process exits.
+pub async fn run(shared_state: SharedState, shared_config: SharedConfig, shared_book: SharedBook, shared_sessions: SharedSessions) {
+    let app_state = AppState {
+        dashboard: shared_state,
+        config: shared_config,
+        book: shared_book,
+        sessions: shared_sessions,
+    };
+
+    let app = Router::new()
+        .route("/", get(dashboard_handler))
+        .route("/assets/:file", get(asset_handler))
+        .route("/api/stats", get(api_handler))
+        .route("/api/trades", get(trades_handler))
+        .route("/api/session", get(session_handler))
+        .route("/api/performance", get(performance_handler))
+        .route("/api/snapshot", get(snapshot_handler))
+        .route("/api/orderbook", get(orderbook_handler))
+        .route("/metrics", get(metrics_handler))
+        .route("/login", get(login_page_handler).post(login_handler))
+        .route("/logout", post(logout_handler))
+        .route("/api/me", get(whoami_handler))
+        .route("/api/activity", get(activity_handler))
+        .route("/api/risk", get(risk_handler))
+        .route("/api/risk/rearm", post(risk_rearm_handler))
+        .route("/api/risk/pause", post(risk_pause_handler))
+        .route("/api/position/close", post(position_close_handler))
+        .route("/backtests", get(backtests_page_handler))
+        .route("/api/backtests", get(backtests_list_handler).post(backtest_run_handler))
+        .route("/api/backtests/:id", get(backtest_detail_handler))
+        .route("/api/strategy", get(strategy_get_handler).post(strategy_update_handler))
+        .route("/api/alerts", get(alerts_list_handler).post(alert_create_handler))
+        .route("/api/alerts/:id", put(alert_update_handler).delete(alert_delete_handler))
+        .layer(middleware::from_fn_with_state(app_state.clone(), auth::require_login))
+        .with_state(app_state.clone());
+
+    // Optional read-only listener, safe to share publicly (no balances, no controls)
+    if let Some(public_port) = PUBLIC_PORT {
+        let public_app = Router::new()
+            .route("/", get(public_dashboard_handler))
+            .route("/assets/:file", get(asset_handler))
+            .route("/api/public", get(public_api_handler))
+            .with_state(app_state);
+        tokio::spawn(serve(public_app, public_port, "Public Dashboard"));
+    }
+
+    serve(app, PORT, "Dashboard").await;
+}
+
+async fn serve(app: Router, port: u16, name: &str) {
+    let listener = loop {
+        match tokio::net::TcpListener::bind(format!("0.0.0.0:{}", port)).await {
+            Ok(l) => { println!("🌍 {}: http://0.0.0.0:{}", name, port); break l; },
+            Err(_) => { eprintln!("⚠️ Port {} busy, retrying...", port); sleep(Duration::from_secs(5)).await; }
+        }
+    };
+    axum::serve(listener, app).await.unwrap();
+}